@@ -1,3 +1,4 @@
+use super::slashing_protection::{get_genesis_validators_root, import_interchange_file};
 use crate::wallet::create::{PASSWORD_FLAG, STDIN_INPUTS_FLAG};
 use account_utils::{
     eth2_keystore::Keystore,
@@ -9,16 +10,20 @@ use account_utils::{
     ZeroizeString,
 };
 use clap::{App, Arg, ArgMatches};
+use environment::Environment;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread::sleep;
 use std::time::Duration;
+use types::EthSpec;
 
 pub const CMD: &str = "import";
 pub const KEYSTORE_FLAG: &str = "keystore";
 pub const DIR_FLAG: &str = "directory";
 pub const REUSE_PASSWORD_FLAG: &str = "reuse-password";
+pub const PASSWORD_DIR_FLAG: &str = "password-dir";
+pub const SLASHING_PROTECTION_FLAG: &str = "slashing-protection";
 
 pub const PASSWORD_PROMPT: &str = "Enter the keystore password, or press enter to omit it:";
 pub const KEYSTORE_REUSE_WARNING: &str = "DO NOT USE THE ORIGINAL KEYSTORES TO VALIDATE WITH \
@@ -78,15 +83,47 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(PASSWORD_DIR_FLAG)
+                .long(PASSWORD_DIR_FLAG)
+                .value_name("PASSWORDS_DIRECTORY")
+                .help(
+                    "Path to a directory containing a password file for each keystore, in the \
+                    layout used by Teku: the password for `NAME.json` is read from `NAME.txt`. \
+                    When used with `--directory`, every `.json` file in the directory which \
+                    has a password file is imported, regardless of its name.",
+                )
+                .conflicts_with(PASSWORD_FLAG)
+                .conflicts_with(REUSE_PASSWORD_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SLASHING_PROTECTION_FLAG)
+                .long(SLASHING_PROTECTION_FLAG)
+                .value_name("INTERCHANGE_FILE")
+                .help(
+                    "Path to a slashing protection interchange file (EIP-3076) exported from \
+                    the client which previously used the keystores. It is imported before the \
+                    keystores and no keystores are imported if it fails.",
+                )
+                .takes_value(true),
+        )
 }
 
-pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
     let keystore: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORE_FLAG)?;
     let keystores_dir: Option<PathBuf> = clap_utils::parse_optional(matches, DIR_FLAG)?;
     let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
     let reuse_password = matches.is_present(REUSE_PASSWORD_FLAG);
     let keystore_password_path: Option<PathBuf> =
         clap_utils::parse_optional(matches, PASSWORD_FLAG)?;
+    let password_dir: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_DIR_FLAG)?;
+    let interchange_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, SLASHING_PROTECTION_FLAG)?;
 
     let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
         .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
@@ -115,8 +152,12 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
         (None, Some(keystores_dir)) => {
             let mut keystores = vec![];
 
-            recursively_find_voting_keystores(&keystores_dir, &mut keystores)
-                .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+            if let Some(password_dir) = password_dir.as_ref() {
+                find_keystores_with_passwords(&keystores_dir, password_dir, &mut keystores)?;
+            } else {
+                recursively_find_voting_keystores(&keystores_dir, &mut keystores)
+                    .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+            }
 
             if keystores.is_empty() {
                 eprintln!("No keystores found in {:?}", keystores_dir);
@@ -133,6 +174,23 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
         }
     };
 
+    // Import the slashing protection history before any keystores, so that we never have a
+    // keystore enabled without its history.
+    if let Some(interchange_path) = interchange_path {
+        let genesis_validators_root = get_genesis_validators_root(&env)?;
+
+        eprintln!(
+            "Importing slashing protection data from {:?}",
+            interchange_path
+        );
+        import_interchange_file(
+            &slashing_protection,
+            &interchange_path,
+            genesis_validators_root,
+        )?;
+        eprintln!();
+    }
+
     eprintln!("WARNING: {}", KEYSTORE_REUSE_WARNING);
 
     // For each keystore:
@@ -163,46 +221,64 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
             CONFIG_FILENAME
         );
 
-        let password_opt = loop {
-            if let Some(password) = previous_password.clone() {
-                eprintln!("Reuse previous password.");
-                break Some(password);
-            }
-            eprintln!();
-            eprintln!("{}", PASSWORD_PROMPT);
-
-            let password = match keystore_password_path.as_ref() {
-                Some(path) => {
-                    let password_from_file: ZeroizeString = fs::read_to_string(&path)
-                        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
-                        .into();
-                    password_from_file.without_newlines()
+        let password_opt = if let Some(password_dir) = password_dir.as_ref() {
+            let password_path = password_path(src_keystore, password_dir)?;
+            let password: ZeroizeString = fs::read_to_string(&password_path)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", password_path, e))?
+                .into();
+            let password = password.without_newlines();
+
+            keystore.decrypt_keypair(password.as_ref()).map_err(|e| {
+                format!(
+                    "Unable to decrypt {:?} with the password in {:?}: {:?}",
+                    src_keystore, password_path, e
+                )
+            })?;
+            eprintln!("Password read from {:?}.", password_path);
+
+            Some(password)
+        } else {
+            loop {
+                if let Some(password) = previous_password.clone() {
+                    eprintln!("Reuse previous password.");
+                    break Some(password);
                 }
-                None => {
-                    let password_from_user = read_password_from_user(stdin_inputs)?;
-                    if password_from_user.as_ref().is_empty() {
-                        eprintln!("Continuing without password.");
+                eprintln!();
+                eprintln!("{}", PASSWORD_PROMPT);
+
+                let password = match keystore_password_path.as_ref() {
+                    Some(path) => {
+                        let password_from_file: ZeroizeString = fs::read_to_string(&path)
+                            .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+                            .into();
+                        password_from_file.without_newlines()
+                    }
+                    None => {
+                        let password_from_user = read_password_from_user(stdin_inputs)?;
+                        if password_from_user.as_ref().is_empty() {
+                            eprintln!("Continuing without password.");
+                            sleep(Duration::from_secs(1)); // Provides nicer UX.
+                            break None;
+                        }
+                        password_from_user
+                    }
+                };
+
+                match keystore.decrypt_keypair(password.as_ref()) {
+                    Ok(_) => {
+                        eprintln!("Password is correct.");
+                        eprintln!();
                         sleep(Duration::from_secs(1)); // Provides nicer UX.
-                        break None;
+                        if reuse_password {
+                            previous_password = Some(password.clone());
+                        }
+                        break Some(password);
                     }
-                    password_from_user
-                }
-            };
-
-            match keystore.decrypt_keypair(password.as_ref()) {
-                Ok(_) => {
-                    eprintln!("Password is correct.");
-                    eprintln!();
-                    sleep(Duration::from_secs(1)); // Provides nicer UX.
-                    if reuse_password {
-                        previous_password = Some(password.clone());
+                    Err(eth2_keystore::Error::InvalidPassword) => {
+                        eprintln!("Invalid password");
                     }
-                    break Some(password);
-                }
-                Err(eth2_keystore::Error::InvalidPassword) => {
-                    eprintln!("Invalid password");
+                    Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
                 }
-                Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
             }
         };
 
@@ -271,3 +347,46 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     Ok(())
 }
+
+/// Returns the path of the password file for the keystore at `keystore_path`, using the layout of
+/// Teku's `--validator-keys` flag (`NAME.json` has its password in `password_dir/NAME.txt`).
+fn password_path(keystore_path: &Path, password_dir: &Path) -> Result<PathBuf, String> {
+    keystore_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| password_dir.join(format!("{}.txt", stem)))
+        .ok_or_else(|| format!("Badly formatted file name: {:?}", keystore_path))
+}
+
+/// Finds each `.json` file in `keystores_dir` which has a password file in `password_dir`.
+///
+/// Unlike `recursively_find_voting_keystores`, the name of the keystore is not checked since
+/// other clients do not require a particular naming scheme.
+fn find_keystores_with_passwords(
+    keystores_dir: &Path,
+    password_dir: &Path,
+    matches: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let dir_entries = fs::read_dir(keystores_dir)
+        .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+
+    for dir_entry in dir_entries {
+        let path = dir_entry
+            .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?
+            .path();
+
+        if !path.is_file() || path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+
+        if password_path(&path, password_dir)?.exists() {
+            matches.push(path);
+        } else {
+            eprintln!("Skipping {:?} which has no password file", path);
+        }
+    }
+
+    matches.sort();
+
+    Ok(())
+}
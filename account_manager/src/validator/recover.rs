@@ -1,4 +1,5 @@
 use super::create::STORE_WITHDRAW_FLAG;
+use super::exit::BEACON_SERVER_FLAG;
 use crate::common::read_mnemonic_from_cli;
 use crate::validator::create::COUNT_FLAG;
 use crate::wallet::create::STDIN_INPUTS_FLAG;
@@ -8,13 +9,25 @@ use account_utils::random_password;
 use clap::{App, Arg, ArgMatches};
 use directory::ensure_dir_exists;
 use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Timeouts, Url,
+};
 use eth2_wallet::bip39::Seed;
 use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType, ValidatorKeystores};
+use ssz::Decode;
+use std::collections::HashSet;
+use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+use types::{BeaconState, EthSpec, PublicKeyBytes};
 use validator_dir::Builder as ValidatorDirBuilder;
 pub const CMD: &str = "recover";
 pub const FIRST_INDEX_FLAG: &str = "first-index";
 pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const GAP_LIMIT_FLAG: &str = "gap-limit";
+pub const BEACON_STATE_FLAG: &str = "beacon-state";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
@@ -74,9 +87,98 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long(STDIN_INPUTS_FLAG)
                 .help("If present, read all user inputs from stdin instead of tty."),
         )
+        .arg(
+            Arg::with_name(GAP_LIMIT_FLAG)
+                .long(GAP_LIMIT_FLAG)
+                .value_name("GAP_LIMIT")
+                .help(
+                    "If present, only the keys of validators which are in the validator registry \
+                    are recovered. Indices are scanned consecutively from `--first-index` until \
+                    this many consecutive indices are not found in the registry, and `--count` is \
+                    ignored. Requires either `--beacon-node` or `--beacon-state`.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help(
+                    "Address of a beacon node HTTP API from which the validator registry is read \
+                    when using `--gap-limit`. Deposits which are yet to be processed by the beacon \
+                    chain are not included.",
+                )
+                .requires(GAP_LIMIT_FLAG)
+                .conflicts_with(BEACON_STATE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_STATE_FLAG)
+                .long(BEACON_STATE_FLAG)
+                .value_name("STATE_PATH")
+                .help(
+                    "Path to an SSZ-encoded beacon state from which the validator registry is read \
+                    when using `--gap-limit`.",
+                )
+                .requires(GAP_LIMIT_FLAG)
+                .takes_value(true),
+        )
 }
 
-pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+/// The source of the validator registry used to determine which keys have been deposited.
+enum ValidatorRegistry {
+    BeaconNode(BeaconNodeHttpClient),
+    State(HashSet<PublicKeyBytes>),
+}
+
+impl ValidatorRegistry {
+    fn from_cli<E: EthSpec>(matches: &ArgMatches, env: &Environment<E>) -> Result<Self, String> {
+        let server_url: Option<String> = clap_utils::parse_optional(matches, BEACON_SERVER_FLAG)?;
+        let state_path: Option<PathBuf> = clap_utils::parse_optional(matches, BEACON_STATE_FLAG)?;
+
+        match (server_url, state_path) {
+            (Some(server_url), None) => {
+                let client = BeaconNodeHttpClient::new(
+                    Url::parse(&server_url)
+                        .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+                    Timeouts::set_all(Duration::from_secs(env.eth2_config().spec.seconds_per_slot)),
+                );
+                Ok(ValidatorRegistry::BeaconNode(client))
+            }
+            (None, Some(state_path)) => {
+                let bytes = fs::read(&state_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", state_path, e))?;
+                let state = BeaconState::<E>::from_ssz_bytes(&bytes)
+                    .map_err(|e| format!("Unable to decode beacon state: {:?}", e))?;
+                Ok(ValidatorRegistry::State(
+                    state.validators.iter().map(|v| v.pubkey).collect(),
+                ))
+            }
+            _ => Err(format!(
+                "--{} requires either --{} or --{}",
+                GAP_LIMIT_FLAG, BEACON_SERVER_FLAG, BEACON_STATE_FLAG
+            )),
+        }
+    }
+
+    /// Returns `true` if a validator with `pubkey` is in the registry.
+    async fn contains(&self, pubkey: PublicKeyBytes) -> Result<bool, String> {
+        match self {
+            ValidatorRegistry::BeaconNode(client) => client
+                .get_beacon_states_validator_id(StateId::Head, &ValidatorId::PublicKey(pubkey))
+                .await
+                .map(|response| response.is_some())
+                .map_err(|e| format!("Failed to get validator details: {:?}", e)),
+            ValidatorRegistry::State(pubkeys) => Ok(pubkeys.contains(&pubkey)),
+        }
+    }
+}
+
+pub fn cli_run<E: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<E>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
     let secrets_dir = if matches.value_of("datadir").is_some() {
         let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
         path.join(DEFAULT_SECRET_DIR)
@@ -87,6 +189,10 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
     let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
     let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let gap_limit: Option<u32> = clap_utils::parse_optional(matches, GAP_LIMIT_FLAG)?;
+    let registry = gap_limit
+        .map(|_| ValidatorRegistry::from_cli(matches, &env))
+        .transpose()?;
 
     eprintln!("secrets-dir path: {:?}", secrets_dir);
 
@@ -101,7 +207,41 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     let seed = Seed::new(&mnemonic, "");
 
-    for index in first_index..first_index + count {
+    let indices: Box<dyn Iterator<Item = u32>> = if gap_limit.is_some() {
+        Box::new(first_index..)
+    } else {
+        Box::new(first_index..first_index + count)
+    };
+    let mut unused_indices = 0;
+
+    for index in indices {
+        if let (Some(gap_limit), Some(registry)) = (gap_limit, registry.as_ref()) {
+            let (secret, _) =
+                recover_validator_secret_from_mnemonic(seed.as_bytes(), index, KeyType::Voting)
+                    .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
+            let voting_pubkey = keypair_from_secret(secret.as_bytes())
+                .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?
+                .pk
+                .compress();
+
+            if !env.runtime().block_on(registry.contains(voting_pubkey))? {
+                unused_indices += 1;
+                eprintln!(
+                    "Index: {}\t{:?} not found in the validator registry",
+                    index, voting_pubkey
+                );
+                if unused_indices >= gap_limit {
+                    eprintln!(
+                        "Stopping after {} consecutive indices not found in the registry",
+                        unused_indices
+                    );
+                    break;
+                }
+                continue;
+            }
+            unused_indices = 0;
+        }
+
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
@@ -134,13 +274,17 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
-        println!(
-            "{}/{}\tIndex: {}\t0x{}",
-            index - first_index,
-            count - first_index,
-            index,
-            voting_pubkey
-        );
+        if gap_limit.is_some() {
+            println!("Index: {}\t0x{}", index, voting_pubkey);
+        } else {
+            println!(
+                "{}/{}\tIndex: {}\t0x{}",
+                index - first_index,
+                count - first_index,
+                index,
+                voting_pubkey
+            );
+        }
     }
 
     Ok(())
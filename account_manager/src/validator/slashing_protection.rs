@@ -5,8 +5,8 @@ use slashing_protection::{
     SLASHING_PROTECTION_FILENAME,
 };
 use std::fs::File;
-use std::path::PathBuf;
-use types::{BeaconState, Epoch, EthSpec, Slot};
+use std::path::{Path, PathBuf};
+use types::{BeaconState, Epoch, EthSpec, Hash256, Slot};
 
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
@@ -47,34 +47,11 @@ pub fn cli_run<T: EthSpec>(
 ) -> Result<(), String> {
     let slashing_protection_db_path = validator_base_dir.join(SLASHING_PROTECTION_FILENAME);
 
-    let testnet_config = env
-        .testnet
-        .ok_or("Unable to get testnet configuration from the environment")?;
-
-    let genesis_validators_root = testnet_config
-        .beacon_state::<T>()
-        .map(|state: BeaconState<T>| state.genesis_validators_root)
-        .map_err(|e| {
-            format!(
-                "Unable to get genesis state, has genesis occurred? Detail: {:?}",
-                e
-            )
-        })?;
+    let genesis_validators_root = get_genesis_validators_root(&env)?;
 
     match matches.subcommand() {
         (IMPORT_CMD, Some(matches)) => {
             let import_filename: PathBuf = clap_utils::parse_required(&matches, IMPORT_FILE_ARG)?;
-            let import_file = File::open(&import_filename).map_err(|e| {
-                format!(
-                    "Unable to open import file at {}: {:?}",
-                    import_filename.display(),
-                    e
-                )
-            })?;
-
-            let interchange = Interchange::from_json_reader(&import_file)
-                .map_err(|e| format!("Error parsing file for import: {:?}", e))?;
-
             let slashing_protection_database =
                 SlashingDatabase::open_or_create(&slashing_protection_db_path).map_err(|e| {
                     format!(
@@ -84,66 +61,11 @@ pub fn cli_run<T: EthSpec>(
                     )
                 })?;
 
-            let outcomes = slashing_protection_database
-                .import_interchange_info(interchange, genesis_validators_root)
-                .map_err(|e| {
-                    format!(
-                        "Error during import: {:?}\n\
-                         IT IS NOT SAFE TO START VALIDATING",
-                        e
-                    )
-                })?;
-
-            let display_slot = |slot: Option<Slot>| {
-                slot.map_or("none".to_string(), |slot| format!("{}", slot.as_u64()))
-            };
-            let display_epoch = |epoch: Option<Epoch>| {
-                epoch.map_or("?".to_string(), |epoch| format!("{}", epoch.as_u64()))
-            };
-            let display_attestation = |source, target| match (source, target) {
-                (None, None) => "none".to_string(),
-                (source, target) => format!("{}=>{}", display_epoch(source), display_epoch(target)),
-            };
-
-            let mut num_failed = 0;
-
-            for outcome in &outcomes {
-                match outcome {
-                    InterchangeImportOutcome::Success { pubkey, summary } => {
-                        eprintln!("- {:?} SUCCESS min block: {}, max block: {}, min attestation: {}, max attestation: {}",
-                            pubkey,
-                            display_slot(summary.min_block_slot),
-                            display_slot(summary.max_block_slot),
-                            display_attestation(summary.min_attestation_source, summary.min_attestation_target),
-                            display_attestation(summary.max_attestation_source,
-                            summary.max_attestation_target),
-                        );
-                    }
-                    InterchangeImportOutcome::Failure { pubkey, error } => {
-                        eprintln!("- {:?} ERROR: {:?}", pubkey, error);
-                        num_failed += 1;
-                    }
-                }
-            }
-
-            if num_failed == 0 {
-                eprintln!("Import completed successfully.");
-                eprintln!(
-                    "Please double-check that the minimum and maximum blocks and slots above \
-                     match your expectations."
-                );
-            } else {
-                eprintln!(
-                    "WARNING: history was NOT imported for {} of {} records",
-                    num_failed,
-                    outcomes.len()
-                );
-                eprintln!("IT IS NOT SAFE TO START VALIDATING");
-                eprintln!("Please see https://lighthouse-book.sigmaprime.io/slashing-protection.html#slashable-data-in-import");
-                return Err("Partial import".to_string());
-            }
-
-            Ok(())
+            import_interchange_file(
+                &slashing_protection_database,
+                &import_filename,
+                genesis_validators_root,
+            )
         }
         (EXPORT_CMD, Some(matches)) => {
             let export_filename: PathBuf = clap_utils::parse_required(&matches, EXPORT_FILE_ARG)?;
@@ -183,3 +105,97 @@ pub fn cli_run<T: EthSpec>(
         (command, _) => Err(format!("No such subcommand `{}`", command)),
     }
 }
+
+/// Returns the genesis validators root of the network in `env`, which is required to import or
+/// export an interchange file.
+pub fn get_genesis_validators_root<T: EthSpec>(env: &Environment<T>) -> Result<Hash256, String> {
+    let testnet_config = env
+        .testnet
+        .as_ref()
+        .ok_or("Unable to get testnet configuration from the environment")?;
+
+    testnet_config
+        .beacon_state::<T>()
+        .map(|state: BeaconState<T>| state.genesis_validators_root)
+        .map_err(|e| {
+            format!(
+                "Unable to get genesis state, has genesis occurred? Detail: {:?}",
+                e
+            )
+        })
+}
+
+/// Imports the interchange file at `path` into `slashing_protection_database`, printing a summary
+/// of the history imported for each validator.
+///
+/// Returns an error if the history of any validator could not be imported.
+pub fn import_interchange_file(
+    slashing_protection_database: &SlashingDatabase,
+    path: &Path,
+    genesis_validators_root: Hash256,
+) -> Result<(), String> {
+    let import_file = File::open(&path)
+        .map_err(|e| format!("Unable to open import file at {}: {:?}", path.display(), e))?;
+
+    let interchange = Interchange::from_json_reader(&import_file)
+        .map_err(|e| format!("Error parsing file for import: {:?}", e))?;
+
+    let outcomes = slashing_protection_database
+        .import_interchange_info(interchange, genesis_validators_root)
+        .map_err(|e| {
+            format!(
+                "Error during import: {:?}\n\
+                 IT IS NOT SAFE TO START VALIDATING",
+                e
+            )
+        })?;
+
+    let display_slot =
+        |slot: Option<Slot>| slot.map_or("none".to_string(), |slot| format!("{}", slot.as_u64()));
+    let display_epoch =
+        |epoch: Option<Epoch>| epoch.map_or("?".to_string(), |epoch| format!("{}", epoch.as_u64()));
+    let display_attestation = |source, target| match (source, target) {
+        (None, None) => "none".to_string(),
+        (source, target) => format!("{}=>{}", display_epoch(source), display_epoch(target)),
+    };
+
+    let mut num_failed = 0;
+
+    for outcome in &outcomes {
+        match outcome {
+            InterchangeImportOutcome::Success { pubkey, summary } => {
+                eprintln!("- {:?} SUCCESS min block: {}, max block: {}, min attestation: {}, max attestation: {}",
+                    pubkey,
+                    display_slot(summary.min_block_slot),
+                    display_slot(summary.max_block_slot),
+                    display_attestation(summary.min_attestation_source, summary.min_attestation_target),
+                    display_attestation(summary.max_attestation_source,
+                    summary.max_attestation_target),
+                );
+            }
+            InterchangeImportOutcome::Failure { pubkey, error } => {
+                eprintln!("- {:?} ERROR: {:?}", pubkey, error);
+                num_failed += 1;
+            }
+        }
+    }
+
+    if num_failed == 0 {
+        eprintln!("Import completed successfully.");
+        eprintln!(
+            "Please double-check that the minimum and maximum blocks and slots above \
+             match your expectations."
+        );
+    } else {
+        eprintln!(
+            "WARNING: history was NOT imported for {} of {} records",
+            num_failed,
+            outcomes.len()
+        );
+        eprintln!("IT IS NOT SAFE TO START VALIDATING");
+        eprintln!("Please see https://lighthouse-book.sigmaprime.io/slashing-protection.html#slashable-data-in-import");
+        return Err("Partial import".to_string());
+    }
+
+    Ok(())
+}
@@ -165,6 +165,16 @@ pub enum Error {
     ///
     /// The peer has sent an invalid message.
     NoCommitteeForSlotAndIndex { slot: Slot, index: CommitteeIndex },
+    /// The committee index of the attestation is not less than the number of committees per slot
+    /// in the attestation's target epoch.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    BadCommitteeIndex {
+        index: CommitteeIndex,
+        committees_per_slot: u64,
+    },
     /// The unaggregated attestation doesn't have only one aggregation bit set.
     ///
     /// ## Peer scoring
@@ -1086,6 +1096,15 @@ where
         .with_committee_cache(target.root, attestation_epoch, |committee_cache, _| {
             let committees_per_slot = committee_cache.committees_per_slot();
 
+            // Check the committee index against the spec limit prior to looking up the committee,
+            // so a bad index is distinguishable from other reasons for a missing committee.
+            if attestation.data.index >= committees_per_slot {
+                return Ok(Err(Error::BadCommitteeIndex {
+                    index: attestation.data.index,
+                    committees_per_slot,
+                }));
+            }
+
             Ok(committee_cache
                 .get_beacon_committee(attestation.data.slot, attestation.data.index)
                 .map(|committee| map_fn((committee, committees_per_slot)))
@@ -0,0 +1,123 @@
+//! An optional database index from a beacon block root to the attestations included on-chain which
+//! voted for it (i.e., with `data.beacon_block_root` equal to the root).
+//!
+//! The index allows the support for a fork, or the vote weight behind a block, to be determined
+//! after the fact without replaying blocks. It is enabled with
+//! `ChainConfig::index_attestations_by_block_root`.
+//!
+//! To keep the index bounded, entries are deleted by the migrator when the block they voted for is
+//! either pruned (i.e., it does not descend from finality) or becomes a strict ancestor of the
+//! finalized block, after which no attestation can be included which votes for it.
+
+use eth2::lighthouse::IncludedAttestation;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashMap;
+use store::{
+    DBColumn, Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp, StoreItem, StoreOp,
+};
+use types::{EthSpec, Hash256, IndexedAttestation, Slot};
+
+/// The attestations which voted for a single block root.
+#[derive(Encode, Decode)]
+pub struct AttestationsByBlockRoot<E: EthSpec> {
+    pub attestations: Vec<IncludedAttestation<E>>,
+}
+
+impl<E: EthSpec> StoreItem for AttestationsByBlockRoot<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconAttestationsByBlockRoot
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
+/// Returns the attestations included on-chain which voted for `voted_root`, in the order they
+/// were imported.
+pub fn attestations_for_block_root<E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    voted_root: &Hash256,
+) -> Result<Vec<IncludedAttestation<E>>, StoreError>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    Ok(store
+        .get_item::<AttestationsByBlockRoot<E>>(voted_root)?
+        .map_or_else(Vec::new, |item| item.attestations))
+}
+
+/// Returns the database operations which add the `indexed_attestations` of the block with
+/// `block_root` and `block_slot` to the index.
+///
+/// The index is read-modify-write, so the caller must ensure that blocks are not indexed
+/// concurrently. Indexing the same block twice has no effect.
+pub fn index_block_attestations<'a, E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    block_root: Hash256,
+    block_slot: Slot,
+    indexed_attestations: &[IndexedAttestation<E>],
+) -> Result<Vec<StoreOp<'a, E>>, StoreError>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let mut by_voted_root: HashMap<Hash256, Vec<&IndexedAttestation<E>>> = HashMap::new();
+    for attestation in indexed_attestations {
+        by_voted_root
+            .entry(attestation.data.beacon_block_root)
+            .or_default()
+            .push(attestation);
+    }
+
+    let mut ops = Vec::with_capacity(by_voted_root.len());
+    for (voted_root, attestations) in by_voted_root {
+        let mut item = store
+            .get_item::<AttestationsByBlockRoot<E>>(&voted_root)?
+            .unwrap_or_else(|| AttestationsByBlockRoot {
+                attestations: vec![],
+            });
+
+        if item
+            .attestations
+            .iter()
+            .any(|included| included.block_root == block_root)
+        {
+            continue;
+        }
+
+        item.attestations.extend(
+            attestations
+                .into_iter()
+                .map(|attestation| IncludedAttestation {
+                    block_root,
+                    block_slot,
+                    attestation: attestation.clone(),
+                }),
+        );
+
+        ops.push(StoreOp::KeyValueOp(item.as_kv_store_op(voted_root)));
+    }
+
+    Ok(ops)
+}
+
+/// Returns the database operations which delete the index entries of the attestations which voted
+/// for any of `voted_roots`.
+///
+/// Roots without an entry (e.g., because indexing is disabled) are ignored.
+pub fn delete_index_entries(
+    voted_roots: impl IntoIterator<Item = Hash256>,
+) -> impl Iterator<Item = KeyValueStoreOp> {
+    voted_roots.into_iter().map(|voted_root| {
+        KeyValueStoreOp::delete(DBColumn::BeaconAttestationsByBlockRoot, &voted_root)
+    })
+}
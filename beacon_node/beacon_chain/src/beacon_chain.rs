@@ -2,6 +2,7 @@ use crate::attestation_verification::{
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
 };
+use crate::attestations_by_block_root::{attestations_for_block_root, index_block_attestations};
 use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::block_verification::{
     check_block_is_finalized_descendant, check_block_relevancy, get_block_root,
@@ -9,6 +10,9 @@ use crate::block_verification::{
     IntoFullyVerifiedBlock,
 };
 use crate::chain_config::ChainConfig;
+use crate::deferred_block_indexing::{
+    attestation_deadline, DeferredBlockIndexing, MAX_DEFERRED_BLOCKS,
+};
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::events::ServerSentEventHandler;
@@ -17,12 +21,16 @@ use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{Error as NaiveAggregationError, NaiveAggregationPool};
 use crate::observed_attestations::{Error as AttestationObservationError, ObservedAttestations};
 use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
+use crate::observed_block_headers::ObservedBlockHeaders;
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::proposal_timings::ProposalTimings;
+use crate::quarantine::Quarantine;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::SnapshotCache;
+use crate::state_provider::StateProvider;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_monitor::{
     get_block_delay_ms, get_slot_delay_ms, timestamp_now, ValidatorMonitor,
@@ -32,8 +40,9 @@ use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::BeaconForkChoiceStore;
 use crate::BeaconSnapshot;
 use crate::{metrics, BeaconChainError};
+use eth2::lighthouse::{CacheMemoryEstimates, IncludedAttestation};
 use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
-use fork_choice::ForkChoice;
+use fork_choice::{ForkChoice, ForkChoiceStore};
 use futures::channel::mpsc::Sender;
 use itertools::process_results;
 use operation_pool::{OperationPool, PersistedOperationPool};
@@ -217,6 +226,9 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) observed_aggregators: RwLock<ObservedAggregators<T::EthSpec>>,
     /// Maintains a record of which validators have proposed blocks for each slot.
     pub(crate) observed_block_producers: RwLock<ObservedBlockProducers<T::EthSpec>>,
+    /// Maintains a record of all signature-verified block headers for each slot and proposer,
+    /// including those which are not canonical.
+    pub(crate) observed_block_headers: RwLock<ObservedBlockHeaders<T::EthSpec>>,
     /// Maintains a record of which validators have submitted voluntary exits.
     pub(crate) observed_voluntary_exits: Mutex<ObservedOperations<SignedVoluntaryExit, T::EthSpec>>,
     /// Maintains a record of which validators we've seen proposer slashings for.
@@ -244,6 +256,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) head_tracker: Arc<HeadTracker>,
     /// A cache dedicated to block processing.
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
+    /// Caches states recently loaded from the database.
+    pub(crate) state_provider: StateProvider<T::EthSpec>,
     /// Caches the attester shuffling for a given epoch and shuffling key root.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
     /// Caches the beacon block proposer shuffling for a given epoch and shuffling key root.
@@ -263,6 +277,12 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
     pub validator_monitor: RwLock<ValidatorMonitor<T::EthSpec>>,
+    /// Retains recently rejected gossip objects for debugging.
+    pub quarantine: Quarantine<T::EthSpec, T::HotStore, T::ColdStore>,
+    /// Timings of the block proposals made via this node.
+    pub proposal_timings: ProposalTimings,
+    /// Imported blocks whose contents are yet to be indexed.
+    pub(crate) deferred_block_indexing: Mutex<Vec<DeferredBlockIndexing<T::EthSpec>>>,
 }
 
 type BeaconBlockAndState<T> = (BeaconBlock<T>, BeaconState<T>);
@@ -545,6 +565,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Returns the state at the given root, if any.
     ///
+    /// The state is read from the snapshot cache used for block processing or the state provider
+    /// if possible, falling back to the database. States read from the database are added to the
+    /// state provider, so that they may be reused by the HTTP API and block import.
+    ///
     /// ## Errors
     ///
     /// May return a database error.
@@ -553,7 +577,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         state_root: &Hash256,
         slot: Option<Slot>,
     ) -> Result<Option<BeaconState<T::EthSpec>>, Error> {
-        Ok(self.store.get_state(state_root, slot)?)
+        if let Some(state) = self
+            .snapshot_cache
+            .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .and_then(|snapshot_cache| {
+                snapshot_cache.get_state_cloned(state_root, CloneConfig::committee_caches_only())
+            })
+        {
+            metrics::inc_counter(&metrics::STATE_PROVIDER_SNAPSHOT_CACHE_HITS);
+            return Ok(Some(state));
+        }
+
+        if let Some(state) = self.state_provider.get(state_root) {
+            metrics::inc_counter(&metrics::STATE_PROVIDER_HITS);
+            return Ok(Some(state));
+        }
+
+        metrics::inc_counter(&metrics::STATE_PROVIDER_MISSES);
+        let state = self.store.get_state(state_root, slot)?;
+        if let Some(state) = &state {
+            self.state_provider.insert(*state_root, state);
+        }
+
+        Ok(state)
     }
 
     /// Returns a `Checkpoint` representing the head block and state. Contains the "best block";
@@ -628,6 +674,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Returns the head which should be built upon by a block proposed at `slot`.
+    ///
+    /// Duties should use this function (or `Self::get_head_for_attestation`) rather than reading
+    /// the canonical head directly. Fork choice only applies some updates when the slot changes
+    /// (e.g., a justified checkpoint which could not be adopted within
+    /// `SAFE_SLOTS_TO_UPDATE_JUSTIFIED` of an epoch boundary). If the per-slot timer has not yet
+    /// run for the current slot, the canonical head may not reflect those updates.
+    pub fn get_head_for_proposal(&self, slot: Slot) -> Result<HeadInfo, Error> {
+        self.fork_choice_at_slot(slot)?;
+        self.head_info()
+    }
+
+    /// Returns the head which should be attested to by an attestation at `slot`.
+    ///
+    /// Returns an error if the head is later than `slot`. See `Self::get_head_for_proposal`.
+    pub fn get_head_for_attestation(&self, slot: Slot) -> Result<HeadInfo, Error> {
+        self.fork_choice_at_slot(slot)?;
+        let head_info = self.head_info()?;
+
+        if head_info.slot > slot {
+            return Err(Error::AttestingPriorToHead {
+                head_slot: head_info.slot,
+                request_slot: slot,
+            });
+        }
+
+        Ok(head_info)
+    }
+
+    /// Runs fork choice if it has not yet been run at the current slot, or at `slot` if that is
+    /// earlier. Fork choice is never advanced beyond the slot clock.
+    fn fork_choice_at_slot(&self, slot: Slot) -> Result<(), Error> {
+        let current_slot = std::cmp::min(slot, self.slot()?);
+        let fork_choice_slot = self.fork_choice.read().fc_store().get_current_slot();
+
+        if fork_choice_slot < current_slot {
+            self.fork_choice()?;
+        }
+
+        Ok(())
+    }
+
     /// Returns the current heads of the `BeaconChain`. For the canonical head, see `Self::head`.
     ///
     /// Returns `(block_root, block_slot)`.
@@ -761,6 +849,56 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(pubkey_cache.get_index(pubkey))
     }
 
+    /// Returns the index of the validator with `pubkey` in `state`, if any.
+    ///
+    /// The `validator_pubkey_cache` is used to avoid scanning the validator registry. Only the
+    /// validators in `state` which are not yet known to the cache (e.g., if `state` is ahead of the
+    /// head) are scanned.
+    ///
+    /// ## Errors
+    ///
+    /// May return an error if acquiring a read-lock on the `validator_pubkey_cache` times out.
+    pub fn validator_index_in_state(
+        &self,
+        pubkey: &PublicKeyBytes,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Option<usize>, Error> {
+        self.validator_index_in_registry(pubkey, state.validators.len(), |index| {
+            state
+                .validators
+                .get(index)
+                .map(|validator| validator.pubkey)
+        })
+    }
+
+    /// As per `Self::validator_index_in_state`, but for a registry of `num_validators` validators
+    /// where the public key of each validator is provided by `pubkey_at`.
+    pub fn validator_index_in_registry<F>(
+        &self,
+        pubkey: &PublicKeyBytes,
+        num_validators: usize,
+        pubkey_at: F,
+    ) -> Result<Option<usize>, Error>
+    where
+        F: Fn(usize) -> Option<PublicKeyBytes>,
+    {
+        let pubkey_cache = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or(Error::ValidatorPubkeyCacheLockTimeout)?;
+
+        // Validator indices never change, so the index from the cache is valid for any state
+        // which is long enough to include it.
+        if let Some(index) = pubkey_cache.get_index(pubkey) {
+            return Ok(Some(index).filter(|index| pubkey_at(*index) == Some(*pubkey)));
+        }
+
+        let num_known = pubkey_cache.len();
+        drop(pubkey_cache);
+
+        Ok((num_known..num_validators).find(|index| pubkey_at(*index) == Some(*pubkey)))
+    }
+
     /// Returns the validator pubkey (if any) for the given validator index.
     ///
     /// ## Notes
@@ -915,6 +1053,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         index: CommitteeIndex,
     ) -> Result<Attestation<T::EthSpec>, Error> {
+        // Ensure that the canonical head is safe to attest to at `slot`.
+        self.get_head_for_attestation(slot)?;
+
         // Note: we're taking a lock on the head. The work involved here should be trivial enough
         // that the lock should not be held for long.
         let head = self
@@ -1581,6 +1722,59 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Checks `block` against the block already observed from the same proposer at the same slot.
+    /// Returns the root of the previously observed block if it differs from `block_root`, in which
+    /// case publishing `block` would be a proposer equivocation.
+    ///
+    /// If no block has been observed, `block` is recorded only if its proposal signature is valid,
+    /// since `ObservedBlockProducers` must only contain signature verified blocks. Otherwise a
+    /// forged block could prevent the genuine block from the same proposer and slot from being
+    /// accepted. The final check and the record happen under a single write lock, so that two
+    /// conflicting valid blocks submitted concurrently cannot both pass. Blocks from the HTTP API
+    /// are not gossip verified, so this should be called before they are published.
+    ///
+    /// Returns an error if `block` is too old to be checked against the observed proposals.
+    pub fn check_and_observe_proposal(
+        &self,
+        block_root: Hash256,
+        block: &SignedBeaconBlock<T::EthSpec>,
+    ) -> Result<Option<Hash256>, Error> {
+        let conflicting_root = |observed_root: Hash256| {
+            Some(observed_root).filter(|observed_root| *observed_root != block_root)
+        };
+
+        if let Some(observed_root) = self
+            .observed_block_producers
+            .read()
+            .observed_block_root(&block.message)?
+        {
+            return Ok(conflicting_root(observed_root));
+        }
+
+        let signature_is_valid =
+            match self.validator_pubkey(block.message.proposer_index as usize)? {
+                Some(pubkey) => block.verify_signature(
+                    Some(block_root),
+                    &pubkey,
+                    &self.head_info()?.fork,
+                    self.genesis_validators_root,
+                    &self.spec,
+                ),
+                None => false,
+            };
+        if !signature_is_valid {
+            return Ok(None);
+        }
+
+        let mut observed_block_producers = self.observed_block_producers.write();
+        if let Some(observed_root) = observed_block_producers.observed_block_root(&block.message)? {
+            return Ok(conflicting_root(observed_root));
+        }
+
+        observed_block_producers.observe_proposer(block_root, &block.message)?;
+        Ok(None)
+    }
+
     /// Accepts a fully-verified block and imports it into the chain without performing any
     /// additional verification.
     ///
@@ -1618,18 +1812,6 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::stop_timer(attestation_observation_timer);
 
-        // If a slasher is configured, provide the attestations from the block.
-        if let Some(slasher) = self.slasher.as_ref() {
-            for attestation in &signed_block.message.body.attestations {
-                let committee =
-                    state.get_beacon_committee(attestation.data.slot, attestation.data.index)?;
-                let indexed_attestation =
-                    get_indexed_attestation(&committee.committee, attestation)
-                        .map_err(|e| BlockError::BeaconChainError(e.into()))?;
-                slasher.accept_attestation(indexed_attestation);
-            }
-        }
-
         // If there are new validators in this block, update our pubkey cache.
         //
         // We perform this _before_ adding the block to fork choice because the pubkey cache is
@@ -1713,9 +1895,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.validator_monitor
             .write()
             .process_valid_state(current_slot.epoch(T::EthSpec::slots_per_epoch()), &state);
-        let validator_monitor = self.validator_monitor.read();
 
         // Register each attestation in the block with the fork choice service.
+        let mut indexed_attestations = Vec::with_capacity(block.body.attestations.len());
         for attestation in &block.body.attestations[..] {
             let _fork_choice_attestation_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_ATTESTATION_TIMES);
@@ -1733,34 +1915,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 Err(e) => Err(BlockError::BeaconChainError(e.into())),
             }?;
 
-            // Only register this with the validator monitor when the block is sufficiently close to
-            // the current slot.
-            if VALIDATOR_MONITOR_HISTORIC_EPOCHS as u64 * T::EthSpec::slots_per_epoch()
-                + block.slot.as_u64()
-                >= current_slot.as_u64()
-            {
-                validator_monitor.register_attestation_in_block(
-                    &indexed_attestation,
-                    &block,
-                    &self.spec,
-                );
-            }
+            indexed_attestations.push(indexed_attestation);
         }
 
-        for exit in &block.body.voluntary_exits {
-            validator_monitor.register_block_voluntary_exit(&exit.message)
-        }
-
-        for slashing in &block.body.attester_slashings {
-            validator_monitor.register_block_attester_slashing(slashing)
-        }
-
-        for slashing in &block.body.proposer_slashings {
-            validator_monitor.register_block_proposer_slashing(slashing)
-        }
-
-        drop(validator_monitor);
-
         metrics::observe(
             &metrics::OPERATIONS_PER_BLOCK_ATTESTATION,
             block.body.attestations.len() as f64,
@@ -1778,6 +1935,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             Box::new(signed_block.clone()),
         ));
         ops.push(StoreOp::PutState(block.state_root, &state));
+        if self.config.index_attestations_by_block_root {
+            ops.extend(
+                index_block_attestations(
+                    &self.store,
+                    block_root,
+                    block.slot,
+                    &indexed_attestations,
+                )
+                .map_err(|e| BlockError::BeaconChainError(e.into()))?,
+            );
+        }
         let txn_lock = self.store.hot_db.begin_rw_transaction();
 
         if let Err(e) = self.store.do_atomically(ops) {
@@ -1819,6 +1987,35 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let parent_root = block.parent_root;
         let slot = block.slot;
 
+        // A block from the current slot may become the head which our validators attest to, so
+        // defer any work which isn't required to compute the head.
+        if slot == current_slot {
+            if attestation_deadline(&self.slot_clock, slot)
+                .map_or(false, |deadline| timestamp_now() > deadline)
+            {
+                metrics::inc_counter(&metrics::BLOCK_IMPORTED_AFTER_ATTESTATION_DEADLINE);
+                debug!(
+                    self.log,
+                    "Block imported after attestation deadline";
+                    "block_root" => ?block_root,
+                    "slot" => slot,
+                );
+            }
+
+            let mut deferred = self.deferred_block_indexing.lock();
+            if deferred.len() >= MAX_DEFERRED_BLOCKS {
+                for work in deferred.drain(..) {
+                    self.index_block_contents(&work.block, work.indexed_attestations);
+                }
+            }
+            deferred.push(DeferredBlockIndexing {
+                block: signed_block.message.clone(),
+                indexed_attestations,
+            });
+        } else {
+            self.index_block_contents(&signed_block.message, indexed_attestations);
+        }
+
         self.snapshot_cache
             .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
             .ok_or(Error::SnapshotCacheLockTimeout)
@@ -1861,6 +2058,60 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(block_root)
     }
 
+    /// Provide the contents of an imported block to the slasher and validator monitor.
+    fn index_block_contents(
+        &self,
+        block: &BeaconBlock<T::EthSpec>,
+        indexed_attestations: Vec<IndexedAttestation<T::EthSpec>>,
+    ) {
+        let current_slot = self.slot().unwrap_or(block.slot);
+        let validator_monitor = self.validator_monitor.read();
+
+        // Only register attestations with the validator monitor when the block is sufficiently
+        // close to the current slot.
+        if VALIDATOR_MONITOR_HISTORIC_EPOCHS as u64 * T::EthSpec::slots_per_epoch()
+            + block.slot.as_u64()
+            >= current_slot.as_u64()
+        {
+            for indexed_attestation in &indexed_attestations {
+                validator_monitor.register_attestation_in_block(
+                    indexed_attestation,
+                    block,
+                    &self.spec,
+                );
+            }
+        }
+
+        for exit in &block.body.voluntary_exits {
+            validator_monitor.register_block_voluntary_exit(&exit.message)
+        }
+
+        for slashing in &block.body.attester_slashings {
+            validator_monitor.register_block_attester_slashing(slashing)
+        }
+
+        for slashing in &block.body.proposer_slashings {
+            validator_monitor.register_block_proposer_slashing(slashing)
+        }
+
+        drop(validator_monitor);
+
+        // If a slasher is configured, provide the attestations from the block.
+        if let Some(slasher) = self.slasher.as_ref() {
+            for indexed_attestation in indexed_attestations {
+                slasher.accept_attestation(indexed_attestation);
+            }
+        }
+    }
+
+    /// Index the contents of any blocks which were deferred by `Self::import_block`.
+    fn process_deferred_block_indexing(&self) {
+        let deferred = std::mem::take(&mut *self.deferred_block_indexing.lock());
+        for work in deferred {
+            self.index_block_contents(&work.block, work.indexed_attestations);
+        }
+    }
+
     /// Produce a new block at the given `slot`.
     ///
     /// The produced block will not be inherently valid, it must be signed by a block producer.
@@ -1881,7 +2132,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // fall back to getting the head from `slot - 1`.
         let state_load_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES);
         let head_info = self
-            .head_info()
+            .get_head_for_proposal(slot)
             .map_err(BlockProductionError::UnableToGetHeadInfo)?;
         let (state, state_root_opt) = if head_info.slot < slot {
             // Normal case: proposing a block atop the current head. Use the snapshot cache.
@@ -2100,6 +2351,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             metrics::inc_counter(&metrics::FORK_CHOICE_ERRORS);
         }
 
+        self.process_deferred_block_indexing();
+
         result
     }
 
@@ -2399,6 +2652,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Returns the attestations included on-chain which voted for `block_root`.
+    ///
+    /// Returns `Ok(None)` if `ChainConfig::index_attestations_by_block_root` is disabled. Only the
+    /// finalized block and its descendants are retained in the index, so an empty list is
+    /// returned for any other block.
+    pub fn attestations_for_block_root(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<Vec<IncludedAttestation<T::EthSpec>>>, Error> {
+        if !self.config.index_attestations_by_block_root {
+            return Ok(None);
+        }
+        Ok(Some(attestations_for_block_root(&self.store, block_root)?))
+    }
+
+    /// Returns all signature-verified block headers which have been observed at `slot`, including
+    /// those which are not canonical.
+    ///
+    /// Only slots after the latest finalized slot are retained.
+    pub fn observed_block_headers_at_slot(&self, slot: Slot) -> Vec<SignedBeaconBlockHeader> {
+        self.observed_block_headers.read().headers_at_slot(slot)
+    }
+
+    /// Returns estimates of the memory used by the larger in-memory caches.
+    ///
+    /// Caches which are locked for longer than the usual lock timeouts are omitted, rather than
+    /// blocking the caller.
+    pub fn cache_memory_estimates(&self) -> CacheMemoryEstimates {
+        CacheMemoryEstimates {
+            snapshot_cache_bytes: self
+                .snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .map(|cache| cache.estimated_memory_bytes() as u64),
+            validator_pubkey_cache_bytes: self
+                .validator_pubkey_cache
+                .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+                .map(|cache| cache.estimated_memory_bytes() as u64),
+            op_pool_bytes: self.op_pool.estimated_memory_bytes() as u64,
+            state_provider_bytes: self.state_provider.estimated_memory_bytes() as u64,
+        }
+    }
+
     /// Called by the timer on every slot.
     ///
     /// Performs slot-based pruning.
@@ -2420,11 +2715,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.fork_choice.write().prune()?;
         let new_finalized_checkpoint = head_state.finalized_checkpoint;
 
-        self.observed_block_producers.write().prune(
-            new_finalized_checkpoint
-                .epoch
-                .start_slot(T::EthSpec::slots_per_epoch()),
-        );
+        let new_finalized_slot = new_finalized_checkpoint
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+        self.observed_block_producers
+            .write()
+            .prune(new_finalized_slot);
+        self.observed_block_headers
+            .write()
+            .prune(new_finalized_slot);
 
         self.snapshot_cache
             .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
@@ -2440,6 +2739,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 );
             });
 
+        self.shuffling_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .map(|mut shuffling_cache| {
+                shuffling_cache.prune(new_finalized_checkpoint.epoch);
+            })
+            .unwrap_or_else(|| {
+                error!(
+                    self.log,
+                    "Failed to obtain cache write lock";
+                    "lock" => "shuffling_cache",
+                    "task" => "prune"
+                );
+            });
+        self.beacon_proposer_cache
+            .lock()
+            .prune(new_finalized_checkpoint.epoch);
+
         self.op_pool.prune_all(head_state, self.epoch()?);
 
         self.store_migrator.process_finalization(
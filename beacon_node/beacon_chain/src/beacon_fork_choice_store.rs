@@ -350,3 +350,17 @@ pub struct PersistedForkChoiceStore {
     justified_balances: Vec<u64>,
     best_justified_checkpoint: Checkpoint,
 }
+
+impl PersistedForkChoiceStore {
+    pub fn finalized_checkpoint(&self) -> &Checkpoint {
+        &self.finalized_checkpoint
+    }
+
+    pub fn justified_checkpoint(&self) -> &Checkpoint {
+        &self.justified_checkpoint
+    }
+
+    pub fn justified_balances(&self) -> &[u64] {
+        &self.justified_balances
+    }
+}
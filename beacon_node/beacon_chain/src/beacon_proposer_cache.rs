@@ -124,4 +124,19 @@ impl BeaconProposerCache {
 
         Ok(())
     }
+
+    /// Removes the proposers for all epochs prior to `finalized_epoch`, which can no longer have
+    /// blocks imported.
+    pub fn prune(&mut self, finalized_epoch: Epoch) {
+        let expired = self
+            .cache
+            .iter()
+            .map(|(key, _)| *key)
+            .filter(|(epoch, _)| *epoch < finalized_epoch)
+            .collect::<Vec<_>>();
+
+        for key in expired {
+            self.cache.pop(&key);
+        }
+    }
 }
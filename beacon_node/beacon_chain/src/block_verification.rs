@@ -53,7 +53,7 @@ use crate::{
 use fork_choice::{ForkChoice, ForkChoiceStore};
 use parking_lot::RwLockReadGuard;
 use proto_array::Block as ProtoBlock;
-use slog::{debug, error, Logger};
+use slog::{debug, error, trace, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 use state_processing::{
@@ -294,34 +294,70 @@ impl<E: EthSpec> BlockSlashInfo<BlockError<E>> {
     }
 }
 
-/// Process invalid blocks to see if they are suitable for the slasher.
+/// Process invalid blocks to see if they are suitable for the slasher and the
+/// `observed_block_headers` cache.
 ///
-/// If no slasher is configured, this is a no-op.
+/// The proposer signature of an unchecked header is only verified if a slasher is configured, so
+/// that invalid blocks cannot cost a signature verification each otherwise. Repeat proposals whose
+/// header has already been observed (e.g., a replayed gossip block) are not checked either. A
+/// repeat proposal with an invalid proposer signature is reported as
+/// `BlockError::ProposalSignatureInvalid`, so that the peer which sent it is penalised.
 fn process_block_slash_info<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     slash_info: BlockSlashInfo<BlockError<T::EthSpec>>,
 ) -> BlockError<T::EthSpec> {
-    if let Some(slasher) = chain.slasher.as_ref() {
-        let (verified_header, error) = match slash_info {
-            BlockSlashInfo::SignatureNotChecked(header, e) => {
-                if verify_header_signature(chain, &header).is_ok() {
-                    (header, e)
-                } else {
-                    return e;
+    let (verified_header, error) = match slash_info {
+        BlockSlashInfo::SignatureNotChecked(header, e) => {
+            if chain.slasher.is_none() {
+                return e;
+            }
+
+            let is_repeat_proposal = matches!(e, BlockError::RepeatProposal { .. });
+            if is_repeat_proposal && !chain.observed_block_headers.read().is_new_header(&header) {
+                return e;
+            }
+
+            match verify_header_signature(chain, &header) {
+                Ok(()) => (header, e),
+                Err(BlockError::ProposalSignatureInvalid) if is_repeat_proposal => {
+                    return BlockError::ProposalSignatureInvalid;
                 }
+                Err(_) => return e,
             }
-            BlockSlashInfo::SignatureInvalid(e) => return e,
-            BlockSlashInfo::SignatureValid(header, e) => (header, e),
-        };
+        }
+        BlockSlashInfo::SignatureInvalid(e) => return e,
+        BlockSlashInfo::SignatureValid(header, e) => (header, e),
+    };
 
+    observe_block_header(chain, &verified_header);
+    if let Some(slasher) = chain.slasher.as_ref() {
         slasher.accept_block_header(verified_header);
-        error
-    } else {
-        match slash_info {
-            BlockSlashInfo::SignatureNotChecked(_, e)
-            | BlockSlashInfo::SignatureInvalid(e)
-            | BlockSlashInfo::SignatureValid(_, e) => e,
-        }
+    }
+    error
+}
+
+/// Store a signature-verified `header` in the `observed_block_headers` cache, logging if it
+/// reveals an equivocation.
+fn observe_block_header<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    header: &SignedBeaconBlockHeader,
+) {
+    match chain.observed_block_headers.write().observe(header.clone()) {
+        Ok(true) => debug!(
+            chain.log,
+            "Observed competing block";
+            "proposer_index" => header.message.proposer_index,
+            "slot" => header.message.slot,
+            "block_root" => ?header.message.canonical_root(),
+        ),
+        Ok(false) => (),
+        // The header is prior to finalization or otherwise irrelevant, it is safe to ignore.
+        Err(e) => trace!(
+            chain.log,
+            "Unable to observe block header";
+            "error" => ?e,
+            "slot" => header.message.slot,
+        ),
     }
 }
 
@@ -435,9 +471,11 @@ pub trait IntoFullyVerifiedBlock<T: BeaconChainTypes>: Sized {
     ) -> Result<FullyVerifiedBlock<T>, BlockError<T::EthSpec>> {
         self.into_fully_verified_block_slashable(chain)
             .map(|fully_verified| {
+                let header = fully_verified.block.signed_block_header();
+                observe_block_header(chain, &header);
                 // Supply valid block to slasher.
                 if let Some(slasher) = chain.slasher.as_ref() {
-                    slasher.accept_block_header(fully_verified.block.signed_block_header());
+                    slasher.accept_block_header(header);
                 }
                 fully_verified
             })
@@ -639,7 +677,7 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         if chain
             .observed_block_producers
             .write()
-            .observe_proposer(&block.message)
+            .observe_proposer(block_root, &block.message)
             .map_err(|e| BlockError::BeaconChainError(e.into()))?
         {
             return Err(BlockError::RepeatProposal {
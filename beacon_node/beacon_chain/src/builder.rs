@@ -3,8 +3,10 @@ use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::head_tracker::HeadTracker;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::quarantine::Quarantine;
 use crate::shuffling_cache::ShufflingCache;
-use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
+use crate::snapshot_cache::SnapshotCache;
+use crate::state_provider::StateProvider;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_monitor::ValidatorMonitor;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
@@ -52,9 +54,27 @@ where
     type EthSpec = TEthSpec;
 }
 
+/// The method used to initialize the chain, which must happen exactly once per builder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChainInitialization {
+    /// Started a new chain from a genesis state (`BeaconChainBuilder::genesis_state`).
+    Genesis,
+    /// Loaded an existing chain from the store (`BeaconChainBuilder::resume_from_db`).
+    Resume,
+    /// Started a new chain from a finalized checkpoint
+    /// (`BeaconChainBuilder::weak_subjectivity_state`).
+    Checkpoint,
+}
+
 /// Builds a `BeaconChain` by either creating anew from genesis, or, resuming from an existing chain
 /// persisted to `store`.
 ///
+/// Initialization happens in stages: the store, logger and spec must be supplied before the chain
+/// is initialized with exactly one of `Self::genesis_state`, `Self::weak_subjectivity_state` or
+/// `Self::resume_from_db`. Calling
+/// these out of order, or calling both, returns an error rather than producing a chain from a mix
+/// of sources.
+///
 /// Types may be elided and the compiler will infer them if all necessary builder methods have been
 /// called. If type inference errors are being raised, it is likely that not all required methods
 /// have been called.
@@ -85,6 +105,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     graffiti: Graffiti,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
     validator_monitor: Option<ValidatorMonitor<T::EthSpec>>,
+    initialization: Option<ChainInitialization>,
 }
 
 impl<TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>
@@ -122,9 +143,23 @@ where
             graffiti: Graffiti::default(),
             slasher: None,
             validator_monitor: None,
+            initialization: None,
         }
     }
 
+    /// Record that the chain is being initialized via `method`, returning an error if it has
+    /// already been initialized.
+    fn begin_initialization(&mut self, method: ChainInitialization) -> Result<(), String> {
+        if let Some(existing) = self.initialization {
+            return Err(format!(
+                "Cannot initialize the chain via {:?}, it was already initialized via {:?}",
+                method, existing
+            ));
+        }
+        self.initialization = Some(method);
+        Ok(())
+    }
+
     /// Override the default spec (as defined by `TEthSpec`).
     ///
     /// This method should generally be called immediately after `Self::new` to ensure components
@@ -206,6 +241,7 @@ where
     ///
     /// May initialize several components; including the op_pool and finalized checkpoints.
     pub fn resume_from_db(mut self) -> Result<Self, String> {
+        self.begin_initialization(ChainInitialization::Resume)?;
         let log = self.log.as_ref().ok_or("resume_from_db requires a log")?;
 
         info!(
@@ -273,6 +309,7 @@ where
         mut self,
         mut beacon_state: BeaconState<TEthSpec>,
     ) -> Result<Self, String> {
+        self.begin_initialization(ChainInitialization::Genesis)?;
         let store = self.store.clone().ok_or("genesis_state requires a store")?;
 
         let beacon_block = genesis_block(&mut beacon_state, &self.spec)?;
@@ -326,6 +363,116 @@ where
         Ok(self.empty_op_pool())
     }
 
+    /// Starts a new chain from a trusted, finalized `anchor_state` and its `anchor_block`, rather
+    /// than from genesis.
+    ///
+    /// The anchor state must be the post-state of the anchor block, and both must be on the same
+    /// chain as `genesis_state`. Blocks and states prior to the anchor are not downloaded, so the
+    /// chain is unable to serve them.
+    pub fn weak_subjectivity_state(
+        mut self,
+        mut anchor_state: BeaconState<TEthSpec>,
+        anchor_block: SignedBeaconBlock<TEthSpec>,
+        mut genesis_state: BeaconState<TEthSpec>,
+    ) -> Result<Self, String> {
+        self.begin_initialization(ChainInitialization::Checkpoint)?;
+        let store = self
+            .store
+            .clone()
+            .ok_or("weak_subjectivity_state requires a store")?;
+        let log = self
+            .log
+            .as_ref()
+            .ok_or("weak_subjectivity_state requires a log")?;
+
+        let anchor_state_root = anchor_state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Error hashing checkpoint state: {:?}", e))?;
+        if anchor_block.state_root() != anchor_state_root {
+            return Err(format!(
+                "Checkpoint state root {:?} does not match the state root of the checkpoint \
+                 block {:?}",
+                anchor_state_root,
+                anchor_block.state_root()
+            ));
+        }
+        let anchor_block_root = anchor_block.canonical_root();
+        if anchor_state.get_latest_block_root(anchor_state_root) != anchor_block_root {
+            return Err("Checkpoint state is not the post-state of the checkpoint block".into());
+        }
+        if anchor_state.genesis_validators_root != genesis_state.genesis_validators_root {
+            return Err(format!(
+                "Checkpoint state has genesis validators root {:?} but the genesis state has {:?}",
+                anchor_state.genesis_validators_root, genesis_state.genesis_validators_root
+            ));
+        }
+
+        info!(
+            log,
+            "Starting beacon chain";
+            "method" => "checkpoint",
+            "block_root" => ?anchor_block_root,
+            "state_root" => ?anchor_state_root,
+            "slot" => anchor_state.slot,
+        );
+
+        let genesis_block = genesis_block(&mut genesis_state, &self.spec)?;
+        let genesis_state_root = genesis_block.message.state_root;
+        let genesis_block_root = genesis_block.canonical_root();
+
+        anchor_state
+            .build_all_caches(&self.spec)
+            .map_err(|e| format!("Failed to build checkpoint state caches: {:?}", e))?;
+
+        store
+            .init_from_anchor(
+                genesis_state_root,
+                &genesis_state,
+                anchor_state_root,
+                &anchor_state,
+            )
+            .map_err(|e| format!("Failed to store checkpoint state: {:?}", e))?;
+        store
+            .put_item(&genesis_block_root, &genesis_block)
+            .map_err(|e| format!("Failed to store genesis block: {:?}", e))?;
+        // Store the genesis block under the `ZERO_HASH` key.
+        store
+            .put_item(&Hash256::zero(), &genesis_block)
+            .map_err(|e| {
+                format!(
+                    "Failed to store genesis block under 0x00..00 alias: {:?}",
+                    e
+                )
+            })?;
+        store
+            .put_item(&anchor_block_root, &anchor_block)
+            .map_err(|e| format!("Failed to store checkpoint block: {:?}", e))?;
+
+        self.genesis_state_root = Some(genesis_state_root);
+        self.genesis_block_root = Some(genesis_block_root);
+        self.genesis_time = Some(genesis_state.genesis_time);
+
+        let anchor = BeaconSnapshot {
+            beacon_block_root: anchor_block_root,
+            beacon_block: anchor_block,
+            beacon_state: anchor_state,
+        };
+
+        let fc_store = BeaconForkChoiceStore::get_forkchoice_store(store, &anchor);
+
+        let fork_choice = ForkChoice::from_genesis(
+            fc_store,
+            anchor.beacon_block_root,
+            &anchor.beacon_block.message,
+            &anchor.beacon_state,
+        )
+        .map_err(|e| format!("Unable to build initialize ForkChoice: {:?}", e))?;
+
+        self.fork_choice = Some(fork_choice);
+
+        Ok(self.empty_op_pool())
+    }
+
     /// Sets the `BeaconChain` eth1 backend.
     pub fn eth1_backend(mut self, backend: Option<TEth1Backend>) -> Self {
         self.eth1_chain = backend.map(Eth1Chain::new);
@@ -402,6 +549,12 @@ where
         BeaconChain<Witness<TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>>,
         String,
     > {
+        if self.initialization.is_none() {
+            return Err(
+                "Cannot build before initializing the chain with genesis_state or resume_from_db"
+                    .into(),
+            );
+        }
         let log = self.log.ok_or("Cannot build without a logger")?;
         let slot_clock = self
             .slot_clock
@@ -461,11 +614,17 @@ where
         let fc_finalized = fork_choice.finalized_checkpoint();
         let head_finalized = canonical_head.beacon_state.finalized_checkpoint;
         if fc_finalized != head_finalized {
+            let is_anchor = store.get_anchor_info().map_or(false, |anchor_info| {
+                fc_finalized.epoch == anchor_info.anchor_slot.epoch(TEthSpec::slots_per_epoch())
+            });
             if head_finalized.root == Hash256::zero()
                 && head_finalized.epoch == fc_finalized.epoch
                 && fc_finalized.root == genesis_block_root
             {
                 // This is a legal edge-case encountered during genesis.
+            } else if is_anchor {
+                // Fork choice is finalized at the checkpoint the chain was started from, which is
+                // ahead of the finalized checkpoint of the checkpoint state itself.
             } else {
                 return Err(format!(
                     "Database corrupt: fork choice is finalized at {:?} whilst head is finalized at \
@@ -495,6 +654,12 @@ where
             );
         }
 
+        let snapshot_cache_size = self.chain_config.snapshot_cache_size;
+        let shuffling_cache_size = self.chain_config.shuffling_cache_size;
+        let state_provider_cache_size = self.chain_config.state_provider_cache_size;
+        let quarantine = Quarantine::new(self.chain_config.quarantine_size, store.clone())
+            .map_err(|e| format!("Unable to open quarantine: {:?}", e))?;
+
         let beacon_chain = BeaconChain {
             spec: self.spec,
             config: self.chain_config,
@@ -512,6 +677,7 @@ where
             observed_aggregators: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_block_producers: <_>::default(),
+            observed_block_headers: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_voluntary_exits: <_>::default(),
             observed_proposer_slashings: <_>::default(),
@@ -525,10 +691,11 @@ where
             event_handler: self.event_handler,
             head_tracker: Arc::new(self.head_tracker.unwrap_or_default()),
             snapshot_cache: TimeoutRwLock::new(SnapshotCache::new(
-                DEFAULT_SNAPSHOT_CACHE_SIZE,
+                snapshot_cache_size,
                 canonical_head,
             )),
-            shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
+            state_provider: StateProvider::new(state_provider_cache_size),
+            shuffling_cache: TimeoutRwLock::new(ShufflingCache::new(shuffling_cache_size)),
             beacon_proposer_cache: <_>::default(),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             disabled_forks: self.disabled_forks,
@@ -539,6 +706,9 @@ where
             graffiti: self.graffiti,
             slasher: self.slasher.clone(),
             validator_monitor: RwLock::new(validator_monitor),
+            deferred_block_indexing: <_>::default(),
+            quarantine,
+            proposal_timings: <_>::default(),
         };
 
         let head = beacon_chain
@@ -744,6 +914,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn initialization_happens_once() {
+        let log = get_logger();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log.clone())
+            .unwrap();
+        let spec = MinimalEthSpec::default_spec();
+        let genesis_state =
+            interop_genesis_state(&generate_deterministic_keypairs(1), 42, &spec).unwrap();
+
+        let builder = BeaconChainBuilder::<
+            Witness<
+                TestingSlotClock,
+                CachingEth1Backend<MinimalEthSpec>,
+                MinimalEthSpec,
+                MemoryStore<MinimalEthSpec>,
+                MemoryStore<MinimalEthSpec>,
+            >,
+        >::new(MinimalEthSpec)
+        .logger(log)
+        .store(Arc::new(store))
+        .genesis_state(genesis_state.clone())
+        .expect("should initialize from genesis");
+
+        assert!(
+            builder.genesis_state(genesis_state).is_err(),
+            "should not initialize twice"
+        );
+    }
+
     #[test]
     fn interop_state() {
         let validator_count = 16;
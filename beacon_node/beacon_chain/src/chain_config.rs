@@ -1,3 +1,6 @@
+use crate::shuffling_cache::DEFAULT_SHUFFLING_CACHE_SIZE;
+use crate::snapshot_cache::DEFAULT_SNAPSHOT_CACHE_SIZE;
+use crate::state_provider::DEFAULT_STATE_PROVIDER_CACHE_SIZE;
 use serde_derive::{Deserialize, Serialize};
 use types::Checkpoint;
 
@@ -12,6 +15,18 @@ pub struct ChainConfig {
     ///
     /// If `None`, there is no weak subjectivity verification.
     pub weak_subjectivity_checkpoint: Option<Checkpoint>,
+    /// The maximum number of block/state snapshots held in the snapshot cache.
+    pub snapshot_cache_size: usize,
+    /// The maximum number of committee caches held in the shuffling cache.
+    pub shuffling_cache_size: usize,
+    /// The maximum number of states loaded from the database held in the state provider cache.
+    pub state_provider_cache_size: usize,
+    /// The maximum number of rejected gossip objects held in the quarantine. Zero disables the
+    /// quarantine.
+    pub quarantine_size: usize,
+    /// Maintain a database index from each block root to the attestations included on-chain which
+    /// voted for it.
+    pub index_attestations_by_block_root: bool,
 }
 
 impl Default for ChainConfig {
@@ -19,6 +34,11 @@ impl Default for ChainConfig {
         Self {
             import_max_skip_slots: None,
             weak_subjectivity_checkpoint: None,
+            snapshot_cache_size: DEFAULT_SNAPSHOT_CACHE_SIZE,
+            shuffling_cache_size: DEFAULT_SHUFFLING_CACHE_SIZE,
+            state_provider_cache_size: DEFAULT_STATE_PROVIDER_CACHE_SIZE,
+            quarantine_size: 0,
+            index_attestations_by_block_root: false,
         }
     }
 }
@@ -0,0 +1,105 @@
+//! Provides `DebugArchive`, a snapshot of the in-memory state which influences head selection and
+//! gossip filtering.
+//!
+//! An archive can be requested from a running node via the HTTP API and loaded into a test harness
+//! to reproduce head-selection anomalies offline.
+
+use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use operation_pool::PersistedOperationPool;
+use slot_clock::SlotClock;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use types::{Epoch, EthSpec, Hash256, Slot};
+
+/// Incremented whenever the format of `DebugArchive` changes.
+pub const DEBUG_ARCHIVE_VERSION: u64 = 1;
+
+#[derive(Encode, Decode)]
+pub struct DebugArchive<T: EthSpec> {
+    /// Always `DEBUG_ARCHIVE_VERSION` for archives produced by this version of Lighthouse.
+    pub version: u64,
+    /// The slot of the slot clock when the archive was created.
+    pub slot: Slot,
+    /// The head block root when the archive was created.
+    pub head_block_root: Hash256,
+    pub fork_choice: PersistedForkChoice,
+    pub op_pool: PersistedOperationPool<T>,
+    /// The proposers of the blocks observed at each slot.
+    pub observed_block_producers: Vec<(Slot, Vec<u64>)>,
+    /// The roots of the aggregate attestations observed at each slot.
+    pub observed_aggregates: Vec<(Slot, Vec<Hash256>)>,
+    /// The validators whose unaggregated attestations were observed in each epoch.
+    pub observed_attesters: Vec<(Epoch, Vec<u64>)>,
+    /// The validators whose aggregates were observed in each epoch.
+    pub observed_aggregators: Vec<(Epoch, Vec<u64>)>,
+}
+
+impl<T: EthSpec> DebugArchive<T> {
+    /// Creates an archive from the current state of `chain`.
+    pub fn from_chain<U: BeaconChainTypes<EthSpec = T>>(
+        chain: &BeaconChain<U>,
+    ) -> Result<Self, BeaconChainError> {
+        let head_block_root = chain.head_info()?.block_root;
+        let slot = chain
+            .slot_clock
+            .now()
+            .unwrap_or_else(|| chain.spec.genesis_slot);
+
+        let fork_choice = {
+            let fork_choice = chain.fork_choice.read();
+            PersistedForkChoice {
+                fork_choice: fork_choice.to_persisted(),
+                fork_choice_store: fork_choice.fc_store().to_persisted(),
+            }
+        };
+
+        Ok(Self {
+            version: DEBUG_ARCHIVE_VERSION,
+            slot,
+            head_block_root,
+            fork_choice,
+            op_pool: PersistedOperationPool::from_operation_pool(&chain.op_pool),
+            observed_block_producers: chain.observed_block_producers.read().observed_proposers(),
+            observed_aggregates: chain.observed_attestations.read().observed_roots(),
+            observed_attesters: chain.observed_attesters.read().observed_validators(),
+            observed_aggregators: chain.observed_aggregators.read().observed_validators(),
+        })
+    }
+
+    /// Decodes an archive, checking that it was produced with a compatible format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let archive = Self::from_ssz_bytes(bytes)
+            .map_err(|e| format!("Unable to decode debug archive: {:?}", e))?;
+
+        if archive.version != DEBUG_ARCHIVE_VERSION {
+            return Err(format!(
+                "Unsupported debug archive version {}, expected {}",
+                archive.version, DEBUG_ARCHIVE_VERSION
+            ));
+        }
+
+        Ok(archive)
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    /// Runs the fork choice head-finding algorithm against the archived fork choice.
+    ///
+    /// Queued attestations are not applied and the fork choice store is not advanced to a later
+    /// slot, so the result should match `self.head_block_root` unless the head was not updated
+    /// prior to the archive being created.
+    pub fn find_head(&self) -> Result<Hash256, String> {
+        let mut proto_array = self.fork_choice.fork_choice.proto_array()?;
+        let store = &self.fork_choice.fork_choice_store;
+
+        proto_array.find_head(
+            store.justified_checkpoint().epoch,
+            store.justified_checkpoint().root,
+            store.finalized_checkpoint().epoch,
+            store.justified_balances(),
+        )
+    }
+}
@@ -0,0 +1,119 @@
+//! Provides a queue for the parts of block import which are not required to compute the head.
+//!
+//! A block which arrives late in its slot must be imported, and the head recomputed, before the
+//! attestation deadline (one third of the way through the slot). Otherwise our validators will
+//! attest to its parent. For blocks of the current slot, feeding the block's attestations to the
+//! slasher and registering its contents with the validator monitor is therefore deferred until
+//! after the next run of fork choice.
+
+use slot_clock::SlotClock;
+use std::time::Duration;
+use types::{BeaconBlock, EthSpec, IndexedAttestation, Slot};
+
+/// The number of intervals per slot. Attestations are produced at the end of the first interval.
+pub const INTERVALS_PER_SLOT: u32 = 3;
+
+/// The maximum number of blocks which may be awaiting indexing. If fork choice is not run after
+/// this many blocks (e.g., in tests), the queue is processed as part of block import.
+pub const MAX_DEFERRED_BLOCKS: usize = 8;
+
+/// The contents of an imported block which are yet to be indexed.
+pub struct DeferredBlockIndexing<E: EthSpec> {
+    pub block: BeaconBlock<E>,
+    pub indexed_attestations: Vec<IndexedAttestation<E>>,
+}
+
+/// Returns the duration since the UNIX epoch at which attestations for `slot` should be produced.
+pub fn attestation_deadline<S: SlotClock>(slot_clock: &S, slot: Slot) -> Option<Duration> {
+    slot_clock
+        .start_of(slot)
+        .map(|slot_start| slot_start + slot_clock.slot_duration() / INTERVALS_PER_SLOT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+    use slot_clock::ManualSlotClock;
+    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+
+    const VALIDATOR_COUNT: usize = 16;
+
+    #[test]
+    fn deadline_is_one_third_of_slot() {
+        let slot_clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(100),
+            Duration::from_secs(12),
+        );
+
+        assert_eq!(
+            attestation_deadline(&slot_clock, Slot::new(0)),
+            Some(Duration::from_secs(104))
+        );
+        assert_eq!(
+            attestation_deadline(&slot_clock, Slot::new(2)),
+            Some(Duration::from_secs(128))
+        );
+    }
+
+    #[test]
+    fn current_slot_block_indexed_after_fork_choice() {
+        let harness = BeaconChainHarness::new(
+            MinimalEthSpec,
+            generate_deterministic_keypairs(VALIDATOR_COUNT),
+        );
+        harness.advance_slot();
+        harness.extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        for validator_index in 0..VALIDATOR_COUNT as u64 {
+            harness
+                .chain
+                .validator_monitor
+                .write()
+                .auto_register_local_validator(validator_index);
+        }
+
+        harness.advance_slot();
+        let slot = harness.get_current_slot();
+        let (block, state) = harness.make_block(harness.get_current_state(), slot);
+        let attestation = block
+            .message
+            .body
+            .attestations
+            .first()
+            .expect("block should include attestations")
+            .clone();
+        let attester = attestation
+            .aggregation_bits
+            .iter()
+            .position(|bit| bit)
+            .expect("attestation should have an attester");
+        let committee = state
+            .get_beacon_committee(attestation.data.slot, attestation.data.index)
+            .unwrap()
+            .committee
+            .to_vec();
+        let attester = committee[attester] as u64;
+        let inclusions = || {
+            harness
+                .chain
+                .validator_monitor
+                .read()
+                .attestation_block_inclusions(attester, attestation.data.target.epoch)
+        };
+
+        // The block is imported, but its contents are yet to be indexed.
+        harness.chain.process_block(block).unwrap();
+        assert_eq!(harness.chain.deferred_block_indexing.lock().len(), 1);
+        assert_eq!(inclusions(), 0);
+
+        // Running fork choice indexes the deferred block.
+        harness.chain.fork_choice().unwrap();
+        assert!(harness.chain.deferred_block_indexing.lock().is_empty());
+        assert_ne!(inclusions(), 0);
+    }
+}
@@ -2,14 +2,18 @@ use crate::metrics;
 use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
 use eth2::lighthouse::Eth1SyncStatusData;
 use eth2_hashing::hash;
+use parking_lot::RwLock;
 use slog::{debug, error, trace, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
-use state_processing::per_block_processing::get_new_eth1_data;
+use state_processing::per_block_processing::{
+    get_new_eth1_data, verify_deposit_merkle_proof_against_root,
+};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::iter::DoubleEndedIterator;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use task_executor::TaskExecutor;
@@ -20,6 +24,7 @@ use types::{
 
 type BlockNumber = u64;
 type Eth1DataVoteCount = HashMap<(Eth1Data, BlockNumber), u64>;
+type VotesToConsider = HashMap<Eth1Data, BlockNumber>;
 
 /// We will declare ourself synced with the Eth1 chain, even if we are this many blocks behind.
 ///
@@ -35,6 +40,11 @@ pub enum Error {
     /// The deposit index of the state is higher than the deposit contract. This is a critical
     /// consensus error.
     DepositIndexTooHigh,
+    /// The root of our deposit tree does not match the deposit root of the eth1 data that the
+    /// deposits must be proven against. The deposit cache is likely corrupt.
+    DepositRootMismatch { local: Hash256, expected: Hash256 },
+    /// A deposit proof generated from our deposit tree was invalid.
+    InvalidDepositProof { deposit_index: u64 },
     /// The current state was unable to return the root for the state at the start of the eth1
     /// voting period.
     UnableToGetPreviousStateRoot(BeaconStateError),
@@ -417,10 +427,21 @@ impl<T: EthSpec> Default for DummyEth1ChainBackend<T> {
 #[derive(Clone)]
 pub struct CachingEth1Backend<T: EthSpec> {
     pub core: HttpService,
+    votes_cache: Arc<RwLock<Option<(VotesCacheKey, Arc<VotesToConsider>)>>>,
     log: Logger,
     _phantom: PhantomData<T>,
 }
 
+/// Identifies the inputs used to compute the votes to consider, so that they can be reused for each
+/// block produced in the same voting period whilst the block cache is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct VotesCacheKey {
+    voting_period_start_seconds: u64,
+    min_deposit_count: u64,
+    earliest_block_hash: Option<Hash256>,
+    latest_block_hash: Option<Hash256>,
+}
+
 impl<T: EthSpec> CachingEth1Backend<T> {
     /// Instantiates `self` with empty caches.
     ///
@@ -428,6 +449,7 @@ impl<T: EthSpec> CachingEth1Backend<T> {
     pub fn new(config: Eth1Config, log: Logger, spec: ChainSpec) -> Self {
         Self {
             core: HttpService::new(config, log.clone(), spec),
+            votes_cache: Arc::new(RwLock::new(None)),
             log,
             _phantom: PhantomData,
         }
@@ -438,11 +460,49 @@ impl<T: EthSpec> CachingEth1Backend<T> {
         HttpService::auto_update(self.core.clone(), handle);
     }
 
+    /// Returns the votes to consider for the voting period starting at
+    /// `voting_period_start_seconds`.
+    ///
+    /// The result is cached and reused until the voting period, the minimum deposit count or the
+    /// contents of the block cache change.
+    fn votes_to_consider(
+        &self,
+        voting_period_start_seconds: u64,
+        min_deposit_count: u64,
+        spec: &ChainSpec,
+    ) -> Arc<VotesToConsider> {
+        let blocks = self.core.blocks().read();
+
+        let key = VotesCacheKey {
+            voting_period_start_seconds,
+            min_deposit_count,
+            earliest_block_hash: blocks.earliest_block().map(|block| block.hash),
+            latest_block_hash: blocks.latest_block().map(|block| block.hash),
+        };
+
+        if let Some((cached_key, votes)) = self.votes_cache.read().as_ref() {
+            if *cached_key == key {
+                return votes.clone();
+            }
+        }
+
+        let votes = Arc::new(get_votes_to_consider(
+            blocks.iter(),
+            voting_period_start_seconds,
+            min_deposit_count,
+            spec,
+        ));
+        *self.votes_cache.write() = Some((key, votes.clone()));
+
+        votes
+    }
+
     /// Instantiates `self` from an existing service.
     pub fn from_service(service: HttpService) -> Self {
         Self {
             log: service.log.clone(),
             core: service,
+            votes_cache: Arc::new(RwLock::new(None)),
             _phantom: PhantomData,
         }
     }
@@ -458,10 +518,11 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
             voting_period_start_slot,
         );
 
-        let blocks = self.core.blocks().read();
-
-        let votes_to_consider =
-            get_votes_to_consider(blocks.iter(), voting_period_start_seconds, spec);
+        let votes_to_consider = self.votes_to_consider(
+            voting_period_start_seconds,
+            state.eth1_data.deposit_count,
+            spec,
+        );
 
         trace!(
             self.log,
@@ -520,14 +581,14 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         &self,
         state: &BeaconState<T>,
         eth1_data_vote: &Eth1Data,
-        _spec: &ChainSpec,
+        spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error> {
         let deposit_index = state.eth1_deposit_index;
-        let deposit_count = if let Some(new_eth1_data) = get_new_eth1_data(state, eth1_data_vote)? {
-            new_eth1_data.deposit_count
-        } else {
-            state.eth1_data.deposit_count
-        };
+        // The deposits are proven against the eth1 data which will be in the state when the block
+        // is processed.
+        let eth1_data =
+            get_new_eth1_data(state, eth1_data_vote)?.unwrap_or_else(|| state.eth1_data.clone());
+        let deposit_count = eth1_data.deposit_count;
 
         match deposit_index.cmp(&deposit_count) {
             Ordering::Greater => Err(Error::DepositIndexTooHigh),
@@ -536,13 +597,32 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
                 let next = deposit_index;
                 let last = std::cmp::min(deposit_count, next + T::MaxDeposits::to_u64());
 
-                self.core
+                let (deposit_root, deposits) = self
+                    .core
                     .deposits()
                     .read()
                     .cache
                     .get_deposits(next, last, deposit_count, DEPOSIT_TREE_DEPTH)
-                    .map_err(|e| Error::BackendError(format!("Failed to get deposits: {:?}", e)))
-                    .map(|(_deposit_root, deposits)| deposits)
+                    .map_err(|e| Error::BackendError(format!("Failed to get deposits: {:?}", e)))?;
+
+                if deposit_root != eth1_data.deposit_root {
+                    return Err(Error::DepositRootMismatch {
+                        local: deposit_root,
+                        expected: eth1_data.deposit_root,
+                    });
+                }
+
+                for (deposit, deposit_index) in deposits.iter().zip(next..) {
+                    verify_deposit_merkle_proof_against_root(
+                        deposit,
+                        deposit_index,
+                        eth1_data.deposit_root,
+                        spec,
+                    )
+                    .map_err(|_| Error::InvalidDepositProof { deposit_index })?;
+                }
+
+                Ok(deposits)
             }
         }
     }
@@ -570,6 +650,7 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         let inner = HttpService::from_bytes(bytes, config, log.clone(), spec)?;
         Ok(Self {
             core: inner,
+            votes_cache: Arc::new(RwLock::new(None)),
             log,
             _phantom: PhantomData,
         })
@@ -579,12 +660,16 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
 /// Get all votes from eth1 blocks which are in the list of candidate blocks for the
 /// current eth1 voting period.
 ///
+/// Blocks with a deposit count lower than `min_deposit_count` (i.e., the deposit count in the
+/// state) are not considered, since voting for them would revert deposits.
+///
 /// Returns a hashmap of `Eth1Data` to its associated eth1 `block_number`.
 fn get_votes_to_consider<'a, I>(
     blocks: I,
     voting_period_start_seconds: u64,
+    min_deposit_count: u64,
     spec: &ChainSpec,
-) -> HashMap<Eth1Data, u64>
+) -> VotesToConsider
 where
     I: DoubleEndedIterator<Item = &'a Eth1Block> + Clone,
 {
@@ -598,6 +683,7 @@ where
                 .eth1_data()
                 .map(|eth1_data| (eth1_data, eth1_block.number))
         })
+        .filter(|(eth1_data, _)| eth1_data.deposit_count >= min_deposit_count)
         .collect()
 }
 
@@ -605,7 +691,7 @@ where
 /// Return hashmap with count of each vote cast.
 fn collect_valid_votes<T: EthSpec>(
     state: &BeaconState<T>,
-    votes_to_consider: &HashMap<Eth1Data, BlockNumber>,
+    votes_to_consider: &VotesToConsider,
 ) -> Eth1DataVoteCount {
     let mut valid_votes = HashMap::new();
     state
@@ -668,6 +754,7 @@ fn is_candidate_block(block: &Eth1Block, period_start: u64, spec: &ChainSpec) ->
 mod test {
     use super::*;
     use environment::null_logger;
+    use std::ops::Range;
     use types::{test_utils::DepositTestTask, MinimalEthSpec};
 
     type E = MinimalEthSpec;
@@ -838,6 +925,15 @@ mod test {
 
                 (initial_deposit_index..deposits.len()).for_each(|i| {
                     state.eth1_data.deposit_count = i as u64;
+                    state.eth1_data.deposit_root = eth1_chain
+                        .backend
+                        .core
+                        .deposits()
+                        .read()
+                        .cache
+                        .get_deposits(0, 0, i as u64, DEPOSIT_TREE_DEPTH)
+                        .expect("should get deposit root")
+                        .0;
 
                     let deposits_for_inclusion = eth1_chain
                         .deposits_for_block_inclusion(&state, &Eth1Data::default(), spec)
@@ -870,7 +966,19 @@ mod test {
                         i
                     );
                 });
-            })
+            });
+
+            state.eth1_deposit_index = 0;
+            state.eth1_data.deposit_count = 1;
+            state.eth1_data.deposit_root = Hash256::repeat_byte(42);
+
+            assert!(
+                matches!(
+                    eth1_chain.deposits_for_block_inclusion(&state, &Eth1Data::default(), spec),
+                    Err(Error::DepositRootMismatch { .. })
+                ),
+                "should not include deposits which do not match the eth1 data deposit root"
+            );
         }
 
         #[test]
@@ -948,6 +1056,148 @@ mod test {
         }
     }
 
+    mod eth1_data_votes {
+        use super::*;
+        use types::MainnetEthSpec;
+
+        fn get_eth1_chain() -> Eth1Chain<CachingEth1Backend<E>, E> {
+            let log = null_logger().unwrap();
+            Eth1Chain::new(CachingEth1Backend::new(
+                Eth1Config::default(),
+                log,
+                MainnetEthSpec::default_spec(),
+            ))
+        }
+
+        fn insert_blocks(eth1_chain: &Eth1Chain<CachingEth1Backend<E>, E>, numbers: Range<u64>) {
+            numbers.for_each(|i| {
+                eth1_chain
+                    .backend
+                    .core
+                    .blocks()
+                    .write()
+                    .insert_root_or_child(get_eth1_block(i, i))
+                    .expect("should add blocks to cache");
+            });
+        }
+
+        fn eth1_data(i: u64) -> Eth1Data {
+            get_eth1_block(i, i)
+                .eth1_data()
+                .expect("should have eth1 data")
+        }
+
+        /// Returns a state in a voting period with the candidate eth1 blocks `start..=end`.
+        fn get_state(spec: &ChainSpec) -> (BeaconState<E>, u64, u64) {
+            let slots_per_eth1_voting_period = <E as EthSpec>::SlotsPerEth1VotingPeriod::to_u64();
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), spec);
+            state.slot = Slot::from(slots_per_eth1_voting_period * 10);
+
+            let follow_distance_seconds = spec.eth1_follow_distance * spec.seconds_per_eth1_block;
+            let voting_period_start = get_voting_period_start_seconds(&state, spec);
+
+            (
+                state,
+                voting_period_start - follow_distance_seconds * 2,
+                voting_period_start - follow_distance_seconds,
+            )
+        }
+
+        #[test]
+        fn invalid_majority_votes() {
+            let spec = &E::default_spec();
+            let eth1_chain = get_eth1_chain();
+            let (mut state, start, end) = get_state(spec);
+
+            // Include some blocks which are too recent to be candidates.
+            insert_blocks(&eth1_chain, start..end + 10);
+
+            // Blocks before `start + 1` have fewer deposits than the state.
+            state.eth1_data.deposit_count = start + 1;
+
+            let honest_vote = eth1_data(start + 1);
+            let unknown_vote = Eth1Data {
+                deposit_root: Hash256::repeat_byte(1),
+                deposit_count: start + 5,
+                block_hash: Hash256::repeat_byte(2),
+            };
+            let too_recent_vote = eth1_data(end + 5);
+            let too_few_deposits_vote = eth1_data(start);
+
+            // Each invalid vote has more votes than the honest vote.
+            let votes = std::iter::once(honest_vote.clone())
+                .chain(vec![unknown_vote; 4])
+                .chain(vec![too_recent_vote; 4])
+                .chain(vec![too_few_deposits_vote; 4])
+                .collect::<Vec<_>>();
+            state.eth1_data_votes = votes.into();
+
+            assert_eq!(
+                eth1_chain
+                    .eth1_data_for_block_production(&state, spec)
+                    .expect("should produce eth1 data vote"),
+                honest_vote,
+                "should ignore votes which are not for candidate blocks"
+            );
+        }
+
+        #[test]
+        fn majority_vote_wins() {
+            let spec = &E::default_spec();
+            let eth1_chain = get_eth1_chain();
+            let (mut state, start, end) = get_state(spec);
+
+            insert_blocks(&eth1_chain, start..end + 1);
+
+            let majority_vote = eth1_data(start + 1);
+            let votes = vec![eth1_data(end); 3]
+                .into_iter()
+                .chain(vec![eth1_data(start + 2); 3])
+                .chain(vec![majority_vote.clone(); 4])
+                .collect::<Vec<_>>();
+            state.eth1_data_votes = votes.into();
+
+            assert_eq!(
+                eth1_chain
+                    .eth1_data_for_block_production(&state, spec)
+                    .expect("should produce eth1 data vote"),
+                majority_vote,
+                "should follow the majority, even if it is not the latest block"
+            );
+        }
+
+        #[test]
+        fn votes_cache_follows_block_cache() {
+            let spec = &E::default_spec();
+            let eth1_chain = get_eth1_chain();
+            let (state, start, end) = get_state(spec);
+
+            let mid = start + (end - start) / 2;
+            insert_blocks(&eth1_chain, start..mid);
+
+            for _ in 0..2 {
+                assert_eq!(
+                    eth1_chain
+                        .eth1_data_for_block_production(&state, spec)
+                        .expect("should produce eth1 data vote"),
+                    eth1_data(mid - 1),
+                    "should vote for the latest candidate block"
+                );
+            }
+
+            insert_blocks(&eth1_chain, mid..end + 1);
+
+            assert_eq!(
+                eth1_chain
+                    .eth1_data_for_block_production(&state, spec)
+                    .expect("should produce eth1 data vote"),
+                eth1_data(end),
+                "should not use stale votes after the block cache is updated"
+            );
+        }
+    }
+
     mod eth1_data_sets {
         use super::*;
 
@@ -962,6 +1212,7 @@ mod test {
                 get_votes_to_consider(
                     blocks.iter(),
                     get_voting_period_start_seconds(&state, spec),
+                    state.eth1_data.deposit_count,
                     &spec,
                 ),
                 HashMap::new()
@@ -988,7 +1239,7 @@ mod test {
                 .collect::<Vec<_>>();
 
             let votes_to_consider =
-                get_votes_to_consider(blocks.iter(), voting_period_start, &spec);
+                get_votes_to_consider(blocks.iter(), voting_period_start, 0, &spec);
             assert_eq!(
                 votes_to_consider.len() as u64,
                 end_eth1_block - start_eth1_block,
@@ -1006,6 +1257,43 @@ mod test {
                     )
                 });
         }
+
+        #[test]
+        fn lower_deposit_count() {
+            let spec = E::default_spec();
+
+            let slots_per_eth1_voting_period = <E as EthSpec>::SlotsPerEth1VotingPeriod::to_u64();
+            let eth1_follow_distance = spec.eth1_follow_distance;
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), &spec);
+            state.genesis_time = 0;
+            state.slot = Slot::from(slots_per_eth1_voting_period * 10);
+
+            let follow_distance_seconds = eth1_follow_distance * spec.seconds_per_eth1_block;
+            let voting_period_start = get_voting_period_start_seconds(&state, &spec);
+            let start_eth1_block = voting_period_start - follow_distance_seconds * 2;
+            let end_eth1_block = voting_period_start - follow_distance_seconds;
+            let blocks = (start_eth1_block..end_eth1_block)
+                .map(|i| get_eth1_block(i, i))
+                .collect::<Vec<_>>();
+
+            // Each block has a deposit count equal to its number.
+            let min_deposit_count = start_eth1_block + 10;
+
+            let votes_to_consider =
+                get_votes_to_consider(blocks.iter(), voting_period_start, min_deposit_count, &spec);
+            assert_eq!(
+                votes_to_consider.len() as u64,
+                end_eth1_block - min_deposit_count,
+                "blocks with fewer deposits should not be in votes to consider"
+            );
+            assert!(
+                votes_to_consider
+                    .keys()
+                    .all(|eth1_data| eth1_data.deposit_count >= min_deposit_count),
+                "should only consider blocks with at least the minimum deposit count"
+            );
+        }
     }
 
     mod collect_valid_votes {
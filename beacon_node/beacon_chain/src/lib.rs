@@ -1,5 +1,6 @@
 #![recursion_limit = "128"] // For lazy-static
 pub mod attestation_verification;
+pub mod attestations_by_block_root;
 mod beacon_chain;
 mod beacon_fork_choice_store;
 mod beacon_proposer_cache;
@@ -7,6 +8,8 @@ mod beacon_snapshot;
 mod block_verification;
 pub mod builder;
 pub mod chain_config;
+pub mod debug_archive;
+mod deferred_block_indexing;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
@@ -16,14 +19,18 @@ pub mod migrate;
 mod naive_aggregation_pool;
 mod observed_attestations;
 mod observed_attesters;
+pub mod observed_block_headers;
 mod observed_block_producers;
 pub mod observed_operations;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+pub mod proposal_timings;
+pub mod quarantine;
 pub mod schema_change;
 mod shuffling_cache;
 mod snapshot_cache;
 pub mod state_advance_timer;
+mod state_provider;
 pub mod test_utils;
 mod timeout_rw_lock;
 pub mod validator_monitor;
@@ -39,10 +46,12 @@ pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use attestation_verification::Error as AttestationError;
 pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceStoreError};
 pub use block_verification::{BlockError, GossipVerifiedBlock};
+pub use debug_archive::DebugArchive;
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
 pub use events::ServerSentEventHandler;
 pub use metrics::scrape_for_metrics;
 pub use parking_lot;
+pub use persisted_fork_choice::PersistedForkChoice;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
@@ -156,6 +156,14 @@ lazy_static! {
         "beacon_attestation_processing_agg_pool_core_insert",
         "Time spent for the core map.insert() function of agg pool"
     );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_DATA_ROOT_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_agg_pool_data_root_hits_total",
+        "Count of times the root of an attestation data was found in the agg pool root cache"
+    );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_DATA_ROOT_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_agg_pool_data_root_misses_total",
+        "Count of times the root of an attestation data was computed by the agg pool"
+    );
     pub static ref ATTESTATION_PROCESSING_AGG_POOL_AGGREGATION: Result<Histogram> = try_create_histogram(
         "beacon_attestation_processing_agg_pool_aggregation",
         "Time spent doing signature aggregation when adding to the agg poll"
@@ -205,6 +213,16 @@ lazy_static! {
     pub static ref SHUFFLING_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_misses_total", "Count of times shuffling cache fulfils request");
 
+    /*
+     * State Provider
+     */
+    pub static ref STATE_PROVIDER_SNAPSHOT_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_state_provider_snapshot_cache_hits_total", "Count of states read from the snapshot cache rather than the database");
+    pub static ref STATE_PROVIDER_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_state_provider_hits_total", "Count of states read from the state provider cache rather than the database");
+    pub static ref STATE_PROVIDER_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_state_provider_misses_total", "Count of states read from the database");
+
     /*
      * Attestation Production
      */
@@ -326,6 +344,18 @@ lazy_static! {
     pub static ref OP_POOL_NUM_VOLUNTARY_EXITS: Result<IntGauge> =
         try_create_int_gauge("beacon_op_pool_voluntary_exits_total", "Count of voluntary exits in the op pool");
 
+    /*
+     * Cache Memory Estimates
+     */
+    pub static ref SNAPSHOT_CACHE_SIZE_BYTES: Result<IntGauge> =
+        try_create_int_gauge("beacon_snapshot_cache_size_bytes", "Estimated size of the blocks and states in the snapshot cache");
+    pub static ref VALIDATOR_PUBKEY_CACHE_SIZE_BYTES: Result<IntGauge> =
+        try_create_int_gauge("beacon_validator_pubkey_cache_size_bytes", "Estimated size of the validator pubkey cache");
+    pub static ref OP_POOL_SIZE_BYTES: Result<IntGauge> =
+        try_create_int_gauge("beacon_op_pool_size_bytes", "Estimated size of the operations in the op pool");
+    pub static ref STATE_PROVIDER_SIZE_BYTES: Result<IntGauge> =
+        try_create_int_gauge("beacon_state_provider_size_bytes", "Estimated size of the states in the state provider cache");
+
     /*
      * Participation Metrics
      */
@@ -625,6 +655,10 @@ lazy_static! {
         "beacon_block_imported_slot_start_delay_time",
         "Duration between the start of the blocks slot and the current time when it was imported.",
     );
+    pub static ref BLOCK_IMPORTED_AFTER_ATTESTATION_DEADLINE: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_imported_after_attestation_deadline_total",
+        "Count of blocks from the current slot which were imported after the attestation deadline.",
+    );
     pub static ref BEACON_BLOCK_HEAD_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram(
         "beacon_block_head_slot_start_delay_time",
         "Duration between the start of the blocks slot and the current time when it was as head.",
@@ -635,6 +669,22 @@ lazy_static! {
         will result in failed attestations.",
     );
 
+    /*
+     * Database Pruning
+     */
+    pub static ref PRUNED_BLOCKS_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_pruned_blocks_total",
+        "Count of blocks deleted from the database because they did not descend from finality"
+    );
+    pub static ref PRUNED_STATES_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_pruned_states_total",
+        "Count of states deleted from the database because they did not descend from finality"
+    );
+    pub static ref PRUNED_BYTES_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_pruned_bytes_total",
+        "Total size of the blocks and states deleted by pruning, as stored in the database"
+    );
+
     /*
      * General block metrics
      */
@@ -674,6 +724,19 @@ pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
         beacon_chain.op_pool.num_voluntary_exits(),
     );
 
+    let cache_memory = beacon_chain.cache_memory_estimates();
+    if let Some(bytes) = cache_memory.snapshot_cache_bytes {
+        set_gauge(&SNAPSHOT_CACHE_SIZE_BYTES, bytes as i64);
+    }
+    if let Some(bytes) = cache_memory.validator_pubkey_cache_bytes {
+        set_gauge(&VALIDATOR_PUBKEY_CACHE_SIZE_BYTES, bytes as i64);
+    }
+    set_gauge(&OP_POOL_SIZE_BYTES, cache_memory.op_pool_bytes as i64);
+    set_gauge(
+        &STATE_PROVIDER_SIZE_BYTES,
+        cache_memory.state_provider_bytes as i64,
+    );
+
     beacon_chain
         .validator_monitor
         .read()
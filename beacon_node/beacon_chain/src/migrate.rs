@@ -1,6 +1,8 @@
+use crate::attestations_by_block_root::delete_index_entries;
 use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::errors::BeaconChainError;
 use crate::head_tracker::{HeadTracker, SszHeadTracker};
+use crate::metrics;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
@@ -11,7 +13,7 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::{migrate_database, HotColdDBError};
 use store::iter::RootsIterator;
-use store::{Error, ItemStore, StoreItem, StoreOp};
+use store::{DBColumn, DBKey, Error, ItemStore, KeyValueStore, StoreItem, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
     BeaconState, BeaconStateError, BeaconStateHash, Checkpoint, Epoch, EthSpec, Hash256,
@@ -462,6 +464,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker_lock.remove(&head_hash);
         }
 
+        // Attestations can no longer be included which vote for an abandoned block, or for a
+        // strict ancestor of the new finalized block, so their attestation index entries are
+        // deleted alongside the blocks.
+        let unindexed_roots = abandoned_blocks
+            .iter()
+            .copied()
+            .chain(
+                newly_finalized_chain
+                    .values()
+                    .map(|(block_hash, _)| *block_hash)
+                    .filter(|block_hash| *block_hash != new_finalized_block_hash),
+            )
+            .map(Into::into)
+            .collect::<HashSet<Hash256>>();
+
+        let num_pruned_blocks = abandoned_blocks.len();
+        let num_pruned_states = abandoned_states.len();
         let batch: Vec<StoreOp<E>> = abandoned_blocks
             .into_iter()
             .map(Into::into)
@@ -474,6 +493,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             .collect();
 
         let mut kv_batch = store.convert_to_kv_batch(&batch)?;
+        kv_batch.extend(delete_index_entries(unindexed_roots));
 
         // Persist the head in case the process is killed or crashes here. This prevents
         // the head tracker reverting after our mutation above.
@@ -485,17 +505,63 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         drop(head_tracker_lock);
         kv_batch.push(persisted_head.as_kv_store_op(BEACON_CHAIN_DB_KEY));
 
+        // Measure the deleted values only once the head tracker lock has been released, since
+        // this reads every abandoned block and state. The measurement is only used for metrics,
+        // so a read error should not prevent pruning.
+        let pruned_bytes = Self::stored_size(&store, &batch).unwrap_or_else(|e| {
+            debug!(
+                log,
+                "Unable to measure pruned data";
+                "error" => ?e,
+            );
+            0
+        });
+
         // Persist the new finalized checkpoint as the pruning checkpoint.
         kv_batch.push(store.pruning_checkpoint_store_op(new_finalized_checkpoint));
 
         store.hot_db.do_atomically(kv_batch)?;
-        debug!(log, "Database pruning complete");
+
+        metrics::inc_counter_by(&metrics::PRUNED_BLOCKS_TOTAL, num_pruned_blocks as u64);
+        metrics::inc_counter_by(&metrics::PRUNED_STATES_TOTAL, num_pruned_states as u64);
+        metrics::inc_counter_by(&metrics::PRUNED_BYTES_TOTAL, pruned_bytes);
+        debug!(
+            log,
+            "Database pruning complete";
+            "pruned_blocks" => num_pruned_blocks,
+            "pruned_states" => num_pruned_states,
+            "pruned_bytes" => pruned_bytes,
+        );
 
         Ok(PruningOutcome::Successful {
             old_finalized_checkpoint,
         })
     }
 
+    /// Returns the number of bytes occupied in the hot database by the blocks and states which
+    /// `batch` deletes.
+    fn stored_size(store: &HotColdDB<E, Hot, Cold>, batch: &[StoreOp<E>]) -> Result<u64, Error> {
+        let size = |column: DBColumn, key: &Hash256| -> Result<u64, Error> {
+            Ok(store
+                .hot_db
+                .get_bytes(column.into(), &key.as_key_bytes())?
+                .map_or(0, |bytes| bytes.len() as u64))
+        };
+
+        let mut total = 0;
+        for op in batch {
+            total += match op {
+                StoreOp::DeleteBlock(block_root) => size(DBColumn::BeaconBlock, block_root)?,
+                StoreOp::DeleteState(state_root, _) => {
+                    size(DBColumn::BeaconStateSummary, state_root)?
+                        + size(DBColumn::BeaconState, state_root)?
+                }
+                _ => 0,
+            };
+        }
+        Ok(total)
+    }
+
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
     /// was last compacted.
     pub fn run_compaction(
@@ -52,10 +52,16 @@ pub enum Error {
     IncorrectSlot { expected: Slot, attestation: Slot },
 }
 
-/// A collection of `Attestation` objects, keyed by their `attestation.data`. Enforces that all
-/// `attestation` are from the same slot.
+/// A collection of `Attestation` objects, keyed by the tree hash root of their `attestation.data`.
+/// Enforces that all `attestation` are from the same slot.
 struct AggregatedAttestationMap<E: EthSpec> {
     map: HashMap<AttestationDataRoot, Attestation<E>>,
+    /// The roots of each `AttestationData` in `map`, so that they are not re-hashed each time an
+    /// attestation with the same data is inserted or looked up.
+    ///
+    /// Only contains entries for `AttestationData` in `map`, so it is bounded by
+    /// `MAX_ATTESTATIONS_PER_SLOT`.
+    roots: HashMap<AttestationData, AttestationDataRoot>,
 }
 
 impl<E: EthSpec> AggregatedAttestationMap<E> {
@@ -63,6 +69,18 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
     pub fn new(initial_capacity: usize) -> Self {
         Self {
             map: HashMap::with_capacity(initial_capacity),
+            roots: HashMap::with_capacity(initial_capacity),
+        }
+    }
+
+    /// Returns the tree hash root of `data`, using a previously computed root if one is known.
+    fn data_root(&self, data: &AttestationData) -> AttestationDataRoot {
+        if let Some(root) = self.roots.get(data) {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_AGG_POOL_DATA_ROOT_HITS);
+            *root
+        } else {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_AGG_POOL_DATA_ROOT_MISSES);
+            data.tree_hash_root()
         }
     }
 
@@ -89,7 +107,7 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
             return Err(Error::MoreThanOneAggregationBitSet(set_bits.len()));
         }
 
-        let attestation_data_root = a.data.tree_hash_root();
+        let attestation_data_root = self.data_root(&a.data);
 
         if let Some(existing_attestation) = self.map.get_mut(&attestation_data_root) {
             if existing_attestation
@@ -112,6 +130,7 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
             }
 
             self.map.insert(attestation_data_root, a.clone());
+            self.roots.insert(a.data.clone(), attestation_data_root);
             Ok(InsertOutcome::NewAttestationData { committee_index })
         }
     }
@@ -120,7 +139,7 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
     ///
     /// The given `a.data.slot` must match the slot that `self` was initialized with.
     pub fn get(&self, data: &AttestationData) -> Option<Attestation<E>> {
-        self.map.get(&data.tree_hash_root()).cloned()
+        self.map.get(&self.data_root(data)).cloned()
     }
 
     /// Returns an aggregated `Attestation` with the given `root`, if any.
@@ -374,6 +393,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn data_roots_are_cached() {
+        let mut a_0 = get_attestation(Slot::new(0));
+        let mut a_1 = a_0.clone();
+        let mut b = get_attestation(Slot::new(0));
+        b.data.beacon_block_root = Hash256::from_low_u64_be(1);
+
+        let genesis_validators_root = Hash256::random();
+        sign(&mut a_0, 0, genesis_validators_root);
+        sign(&mut a_1, 1, genesis_validators_root);
+        sign(&mut b, 0, genesis_validators_root);
+
+        let mut map = AggregatedAttestationMap::new(4);
+        assert!(map.roots.is_empty(), "should start with no cached roots");
+
+        map.insert(&a_0).expect("should insert a_0");
+        map.insert(&a_1).expect("should insert a_1");
+        assert_eq!(
+            map.roots.len(),
+            1,
+            "should cache one root per attestation data"
+        );
+        assert_eq!(
+            map.roots.get(&a_0.data),
+            Some(&a_0.data.tree_hash_root()),
+            "should cache the tree hash root"
+        );
+
+        map.insert(&b).expect("should insert b");
+        assert_eq!(map.roots.len(), 2, "should cache a root for the new data");
+        assert_eq!(map.data_root(&b.data), b.data.tree_hash_root());
+        assert_eq!(
+            map.get(&a_0.data)
+                .map(|a| a.aggregation_bits.num_set_bits()),
+            Some(2),
+            "should find the aggregate by its data"
+        );
+    }
+
     #[test]
     fn multiple_attestations() {
         let mut a_0 = get_attestation(Slot::new(0));
@@ -160,6 +160,21 @@ impl<E: EthSpec> ObservedAttestations<E> {
             .and_then(|set| set.is_known(a, root))
     }
 
+    /// Returns the roots of the attestations observed at each slot, ordered by slot.
+    pub fn observed_roots(&self) -> Vec<(Slot, Vec<Hash256>)> {
+        let mut observed = self
+            .sets
+            .iter()
+            .map(|set| {
+                let mut roots = set.set.iter().copied().collect::<Vec<_>>();
+                roots.sort_unstable();
+                (set.slot, roots)
+            })
+            .collect::<Vec<_>>();
+        observed.sort_unstable_by_key(|(slot, _)| *slot);
+        observed
+    }
+
     /// The maximum number of slots that attestations are stored for.
     fn max_capacity(&self) -> u64 {
         // We add `2` in order to account for one slot either side of the range due to
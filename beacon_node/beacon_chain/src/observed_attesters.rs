@@ -46,6 +46,9 @@ pub trait Item {
 
     /// Returns `true` if `validator_index` has been stored in `self`.
     fn contains(&self, validator_index: usize) -> bool;
+
+    /// Returns all validator indices stored in `self`, in ascending order.
+    fn validator_indices(&self) -> Vec<usize>;
 }
 
 /// Stores a `BitVec` that represents which validator indices have attested during an epoch.
@@ -97,6 +100,15 @@ impl Item for EpochBitfield {
     fn contains(&self, validator_index: usize) -> bool {
         self.bitfield.get(validator_index).map_or(false, |bit| *bit)
     }
+
+    fn validator_indices(&self) -> Vec<usize> {
+        self.bitfield
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| **bit)
+            .map(|(validator_index, _)| validator_index)
+            .collect()
+    }
 }
 
 /// Stores a `HashSet` of which validator indices have created an aggregate attestation during an
@@ -136,6 +148,12 @@ impl Item for EpochHashSet {
     fn contains(&self, validator_index: usize) -> bool {
         self.set.contains(&validator_index)
     }
+
+    fn validator_indices(&self) -> Vec<usize> {
+        let mut indices = self.set.iter().copied().collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices
+    }
 }
 
 /// A container that stores some number of `T` items.
@@ -233,6 +251,24 @@ impl<T: Item, E: EthSpec> AutoPruningContainer<T, E> {
         self.items.get(&epoch).map(|item| item.validator_count())
     }
 
+    /// Returns the indices of the validators observed in each epoch, ordered by epoch.
+    pub fn observed_validators(&self) -> Vec<(Epoch, Vec<u64>)> {
+        let mut observed = self
+            .items
+            .iter()
+            .map(|(epoch, item)| {
+                let indices = item
+                    .validator_indices()
+                    .into_iter()
+                    .map(|validator_index| validator_index as u64)
+                    .collect();
+                (*epoch, indices)
+            })
+            .collect::<Vec<_>>();
+        observed.sort_unstable_by_key(|(epoch, _)| *epoch);
+        observed
+    }
+
     fn sanitize_request(&self, a: &Attestation<E>, validator_index: usize) -> Result<(), Error> {
         if validator_index > E::ValidatorRegistryLimit::to_usize() {
             return Err(Error::ValidatorIndexTooHigh(validator_index));
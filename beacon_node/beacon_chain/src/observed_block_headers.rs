@@ -0,0 +1,277 @@
+//! Provides the `ObservedBlockHeaders` struct which maintains a rolling record of all
+//! signature-verified block headers seen for each `(slot, proposer)`, including those which never
+//! became canonical (e.g., repeat proposals rejected on gossip).
+//!
+//! This allows competing blocks for a slot to be inspected via the HTTP API and makes it cheap to
+//! determine whether a proposer has equivocated.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use types::{EthSpec, Hash256, SignedBeaconBlockHeader, Slot, Unsigned};
+
+/// The maximum number of distinct headers stored for each `(slot, proposer)`.
+///
+/// A single equivocating header is sufficient to prove a slashable offence, so there is no need
+/// to allow a malicious proposer to fill the cache with an unbounded number of signed headers.
+pub const MAX_HEADERS_PER_PROPOSER: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The slot of the provided header is prior to finalization and should not have been provided
+    /// to this function. This is an internal error.
+    FinalizedBlock { slot: Slot, finalized_slot: Slot },
+    /// The function to obtain a set index failed, this is an internal error.
+    ValidatorIndexTooHigh(u64),
+}
+
+/// Maintains a cache of observed signed block headers, keyed by `(slot, proposer_index)`.
+///
+/// The cache supports pruning based upon the finalized epoch. It does not automatically prune, you
+/// must call `Self::prune` manually.
+///
+/// Only headers with a valid proposer signature should be added to this cache. This bounds its
+/// size to `slots_since_finality * known_distinct_shufflings * MAX_HEADERS_PER_PROPOSER`.
+pub struct ObservedBlockHeaders<E: EthSpec> {
+    finalized_slot: Slot,
+    items: HashMap<Slot, HashMap<u64, Vec<(Hash256, SignedBeaconBlockHeader)>>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Default for ObservedBlockHeaders<E> {
+    /// Instantiates `Self` with `finalized_slot == 0`.
+    fn default() -> Self {
+        Self {
+            finalized_slot: Slot::new(0),
+            items: HashMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> ObservedBlockHeaders<E> {
+    /// Observe the given `header`.
+    ///
+    /// Returns `Ok(true)` if a header for the same slot and proposer *with a different root* has
+    /// been observed previously (i.e., the proposer has equivocated).
+    ///
+    /// The supplied `header` **MUST** be signature verified (see struct-level documentation).
+    ///
+    /// ## Errors
+    ///
+    /// - `header.proposer_index` is greater than `VALIDATOR_REGISTRY_LIMIT`.
+    /// - `header.slot` is equal to or less than the latest pruned `finalized_slot`.
+    pub fn observe(&mut self, header: SignedBeaconBlockHeader) -> Result<bool, Error> {
+        self.sanitize_header(&header)?;
+
+        let root = header.message.canonical_root();
+        let headers = self
+            .items
+            .entry(header.message.slot)
+            .or_insert_with(HashMap::new)
+            .entry(header.message.proposer_index)
+            .or_insert_with(Vec::new);
+
+        let is_equivocation = headers.iter().any(|(known_root, _)| *known_root != root);
+
+        if headers.len() < MAX_HEADERS_PER_PROPOSER
+            && !headers.iter().any(|(known_root, _)| *known_root == root)
+        {
+            headers.push((root, header));
+        }
+
+        Ok(is_equivocation)
+    }
+
+    /// Returns `true` if `header` would be stored by `Self::observe`, i.e. it has not been
+    /// observed before and there is room for another header from its proposer at its slot.
+    ///
+    /// This does not require `header` to be signature verified, so it may be used to avoid
+    /// verifying the signatures of headers which have already been observed.
+    pub fn is_new_header(&self, header: &SignedBeaconBlockHeader) -> bool {
+        if self.sanitize_header(header).is_err() {
+            return false;
+        }
+
+        let root = header.message.canonical_root();
+        self.items
+            .get(&header.message.slot)
+            .and_then(|proposers| proposers.get(&header.message.proposer_index))
+            .map_or(true, |headers| {
+                headers.len() < MAX_HEADERS_PER_PROPOSER
+                    && !headers.iter().any(|(known_root, _)| *known_root == root)
+            })
+    }
+
+    /// Returns all headers observed at `slot`, ordered by proposer index and then by the order in
+    /// which they were observed.
+    pub fn headers_at_slot(&self, slot: Slot) -> Vec<SignedBeaconBlockHeader> {
+        let mut proposers = self
+            .items
+            .get(&slot)
+            .map(|proposers| proposers.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        proposers.sort_unstable_by_key(|(proposer_index, _)| **proposer_index);
+
+        proposers
+            .into_iter()
+            .flat_map(|(_, headers)| headers.iter().map(|(_, header)| header.clone()))
+            .collect()
+    }
+
+    /// Returns `true` if more than one distinct header has been observed from `proposer_index` at
+    /// `slot`.
+    pub fn proposer_has_equivocated(&self, slot: Slot, proposer_index: u64) -> bool {
+        self.items
+            .get(&slot)
+            .and_then(|proposers| proposers.get(&proposer_index))
+            .map_or(false, |headers| headers.len() > 1)
+    }
+
+    /// Returns `Ok(())` if the given `header` is sane.
+    fn sanitize_header(&self, header: &SignedBeaconBlockHeader) -> Result<(), Error> {
+        let slot = header.message.slot;
+        let proposer_index = header.message.proposer_index;
+
+        if proposer_index > E::ValidatorRegistryLimit::to_u64() {
+            return Err(Error::ValidatorIndexTooHigh(proposer_index));
+        }
+
+        let finalized_slot = self.finalized_slot;
+        if finalized_slot > 0 && slot <= finalized_slot {
+            return Err(Error::FinalizedBlock {
+                slot,
+                finalized_slot,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes all observations of headers equal to or earlier than `finalized_slot`.
+    ///
+    /// Stores `finalized_slot` in `self`, so that `self` will reject any header that has a slot
+    /// equal to or less than `finalized_slot`.
+    ///
+    /// No-op if `finalized_slot == 0`.
+    pub fn prune(&mut self, finalized_slot: Slot) {
+        if finalized_slot == 0 {
+            return;
+        }
+
+        self.finalized_slot = finalized_slot;
+        self.items.retain(|slot, _| *slot > finalized_slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{BeaconBlockHeader, MainnetEthSpec, Signature};
+
+    type E = MainnetEthSpec;
+
+    fn get_header(slot: u64, proposer: u64, body: u64) -> SignedBeaconBlockHeader {
+        SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                slot: slot.into(),
+                proposer_index: proposer,
+                parent_root: Hash256::zero(),
+                state_root: Hash256::zero(),
+                body_root: Hash256::from_low_u64_be(body),
+            },
+            signature: Signature::empty(),
+        }
+    }
+
+    #[test]
+    fn equivocation_detection() {
+        let mut cache = ObservedBlockHeaders::<E>::default();
+
+        assert_eq!(cache.observe(get_header(1, 0, 0)), Ok(false));
+        assert_eq!(
+            cache.observe(get_header(1, 0, 0)),
+            Ok(false),
+            "duplicate header is not an equivocation"
+        );
+        assert!(!cache.proposer_has_equivocated(Slot::new(1), 0));
+        assert_eq!(cache.headers_at_slot(Slot::new(1)).len(), 1);
+
+        assert_eq!(
+            cache.observe(get_header(1, 1, 0)),
+            Ok(false),
+            "distinct proposer is not an equivocation"
+        );
+        assert_eq!(
+            cache.observe(get_header(2, 0, 1)),
+            Ok(false),
+            "distinct slot is not an equivocation"
+        );
+
+        assert!(!cache.is_new_header(&get_header(1, 0, 0)));
+        assert!(cache.is_new_header(&get_header(1, 0, 1)));
+        assert_eq!(cache.observe(get_header(1, 0, 1)), Ok(true));
+        assert!(cache.proposer_has_equivocated(Slot::new(1), 0));
+        assert!(!cache.proposer_has_equivocated(Slot::new(1), 1));
+
+        assert_eq!(
+            cache.headers_at_slot(Slot::new(1)),
+            vec![
+                get_header(1, 0, 0),
+                get_header(1, 0, 1),
+                get_header(1, 1, 0)
+            ]
+        );
+        assert_eq!(cache.headers_at_slot(Slot::new(3)), vec![]);
+    }
+
+    #[test]
+    fn headers_per_proposer_are_bounded() {
+        let mut cache = ObservedBlockHeaders::<E>::default();
+
+        for body in 0..MAX_HEADERS_PER_PROPOSER as u64 * 2 {
+            cache.observe(get_header(1, 0, body)).unwrap();
+        }
+
+        assert_eq!(
+            cache.headers_at_slot(Slot::new(1)).len(),
+            MAX_HEADERS_PER_PROPOSER
+        );
+        assert_eq!(
+            cache.observe(get_header(1, 0, 0)),
+            Ok(true),
+            "equivocations are still reported when full"
+        );
+    }
+
+    #[test]
+    fn pruning() {
+        let mut cache = ObservedBlockHeaders::<E>::default();
+
+        cache.observe(get_header(1, 0, 0)).unwrap();
+        cache.observe(get_header(2, 0, 0)).unwrap();
+        cache.observe(get_header(3, 0, 0)).unwrap();
+
+        cache.prune(Slot::new(0));
+        assert_eq!(cache.items.len(), 3, "pruning at slot zero is a no-op");
+
+        cache.prune(Slot::new(2));
+        assert_eq!(cache.finalized_slot, 2);
+        assert_eq!(cache.items.len(), 1);
+        assert_eq!(cache.headers_at_slot(Slot::new(3)).len(), 1);
+
+        assert_eq!(
+            cache.observe(get_header(2, 1, 0)),
+            Err(Error::FinalizedBlock {
+                slot: Slot::new(2),
+                finalized_slot: Slot::new(2),
+            }),
+            "cannot observe finalized header"
+        );
+        assert_eq!(
+            cache.observe(get_header(4, E::ValidatorRegistryLimit::to_u64() + 1, 0)),
+            Err(Error::ValidatorIndexTooHigh(
+                E::ValidatorRegistryLimit::to_u64() + 1
+            )),
+        );
+    }
+}
@@ -1,9 +1,9 @@
 //! Provides the `ObservedBlockProducers` struct which allows for rejecting gossip blocks from
 //! validators that have already produced a block.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::marker::PhantomData;
-use types::{BeaconBlock, EthSpec, Slot, Unsigned};
+use types::{BeaconBlock, EthSpec, Hash256, Slot, Unsigned};
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -14,7 +14,8 @@ pub enum Error {
     ValidatorIndexTooHigh(u64),
 }
 
-/// Maintains a cache of observed `(block.slot, block.proposer)`.
+/// Maintains a cache of observed `(block.slot, block.proposer)`, along with the root of the first
+/// block observed for each pair.
 ///
 /// The cache supports pruning based upon the finalized epoch. It does not automatically prune, you
 /// must call `Self::prune` manually.
@@ -27,7 +28,7 @@ pub enum Error {
 /// known_distinct_shufflings` which is much smaller.
 pub struct ObservedBlockProducers<E: EthSpec> {
     finalized_slot: Slot,
-    items: HashMap<Slot, HashSet<u64>>,
+    items: HashMap<Slot, HashMap<u64, Hash256>>,
     _phantom: PhantomData<E>,
 }
 
@@ -43,8 +44,11 @@ impl<E: EthSpec> Default for ObservedBlockProducers<E> {
 }
 
 impl<E: EthSpec> ObservedBlockProducers<E> {
-    /// Observe that the `block` was produced by `block.proposer_index` at `block.slot`. This will
-    /// update `self` so future calls to it indicate that this block is known.
+    /// Observe that the `block` with `block_root` was produced by `block.proposer_index` at
+    /// `block.slot`. This will update `self` so future calls to it indicate that this block is
+    /// known.
+    ///
+    /// If a block has already been observed for the proposer and slot, its root is retained.
     ///
     /// The supplied `block` **MUST** be signature verified (see struct-level documentation).
     ///
@@ -52,16 +56,24 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
     ///
     /// - `block.proposer_index` is greater than `VALIDATOR_REGISTRY_LIMIT`.
     /// - `block.slot` is equal to or less than the latest pruned `finalized_slot`.
-    pub fn observe_proposer(&mut self, block: &BeaconBlock<E>) -> Result<bool, Error> {
+    pub fn observe_proposer(
+        &mut self,
+        block_root: Hash256,
+        block: &BeaconBlock<E>,
+    ) -> Result<bool, Error> {
         self.sanitize_block(block)?;
 
-        let did_not_exist = self
+        let proposers = self
             .items
             .entry(block.slot)
-            .or_insert_with(|| HashSet::with_capacity(E::SlotsPerEpoch::to_usize()))
-            .insert(block.proposer_index);
+            .or_insert_with(|| HashMap::with_capacity(E::SlotsPerEpoch::to_usize()));
 
-        Ok(!did_not_exist)
+        if proposers.contains_key(&block.proposer_index) {
+            Ok(true)
+        } else {
+            proposers.insert(block.proposer_index, block_root);
+            Ok(false)
+        }
     }
 
     /// Returns `Ok(true)` if the `block` has been observed before, `Ok(false)` if not. Does not
@@ -75,14 +87,30 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
     pub fn proposer_has_been_observed(&self, block: &BeaconBlock<E>) -> Result<bool, Error> {
         self.sanitize_block(block)?;
 
-        let exists = self
-            .items
-            .get(&block.slot)
-            .map_or(false, |set| set.contains(&block.proposer_index));
+        let exists = self.items.get(&block.slot).map_or(false, |proposers| {
+            proposers.contains_key(&block.proposer_index)
+        });
 
         Ok(exists)
     }
 
+    /// Returns the root of the first block observed from `block.proposer_index` at `block.slot`,
+    /// if any. A different root indicates that `block` is an equivocation.
+    ///
+    /// ## Errors
+    ///
+    /// - `block.proposer_index` is greater than `VALIDATOR_REGISTRY_LIMIT`.
+    /// - `block.slot` is equal to or less than the latest pruned `finalized_slot`.
+    pub fn observed_block_root(&self, block: &BeaconBlock<E>) -> Result<Option<Hash256>, Error> {
+        self.sanitize_block(block)?;
+
+        Ok(self
+            .items
+            .get(&block.slot)
+            .and_then(|proposers| proposers.get(&block.proposer_index))
+            .copied())
+    }
+
     /// Returns `Ok(())` if the given `block` is sane.
     fn sanitize_block(&self, block: &BeaconBlock<E>) -> Result<(), Error> {
         if block.proposer_index > E::ValidatorRegistryLimit::to_u64() {
@@ -100,6 +128,21 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
         Ok(())
     }
 
+    /// Returns the indices of the proposers observed at each slot, ordered by slot.
+    pub fn observed_proposers(&self) -> Vec<(Slot, Vec<u64>)> {
+        let mut observed = self
+            .items
+            .iter()
+            .map(|(slot, proposers)| {
+                let mut proposers = proposers.keys().copied().collect::<Vec<_>>();
+                proposers.sort_unstable();
+                (*slot, proposers)
+            })
+            .collect::<Vec<_>>();
+        observed.sort_unstable_by_key(|(slot, _)| *slot);
+        observed
+    }
+
     /// Removes all observations of blocks equal to or earlier than `finalized_slot`.
     ///
     /// Stores `finalized_slot` in `self`, so that `self` will reject any block that has a slot
@@ -141,7 +184,7 @@ mod tests {
         let block_a = &get_block(0, 0);
 
         assert_eq!(
-            cache.observe_proposer(block_a),
+            cache.observe_proposer(block_a.canonical_root(), block_a),
             Ok(false),
             "can observe proposer, indicates proposer unobserved"
         );
@@ -200,7 +243,7 @@ mod tests {
         let block_b = &get_block(E::slots_per_epoch(), 0);
 
         assert_eq!(
-            cache.observe_proposer(block_b),
+            cache.observe_proposer(block_b.canonical_root(), block_b),
             Err(Error::FinalizedBlock {
                 slot: E::slots_per_epoch().into(),
                 finalized_slot: E::slots_per_epoch().into(),
@@ -220,7 +263,7 @@ mod tests {
         let block_b = &get_block(three_epochs, 0);
 
         assert_eq!(
-            cache.observe_proposer(block_b),
+            cache.observe_proposer(block_b.canonical_root(), block_b),
             Ok(false),
             "can insert non-finalized block"
         );
@@ -274,7 +317,7 @@ mod tests {
             "no observation in empty cache"
         );
         assert_eq!(
-            cache.observe_proposer(block_a),
+            cache.observe_proposer(block_a.canonical_root(), block_a),
             Ok(false),
             "can observe proposer, indicates proposer unobserved"
         );
@@ -284,7 +327,7 @@ mod tests {
             "observed block is indicated as true"
         );
         assert_eq!(
-            cache.observe_proposer(block_a),
+            cache.observe_proposer(block_a.canonical_root(), block_a),
             Ok(true),
             "observing again indicates true"
         );
@@ -310,7 +353,7 @@ mod tests {
             "no observation for new slot"
         );
         assert_eq!(
-            cache.observe_proposer(block_b),
+            cache.observe_proposer(block_b.canonical_root(), block_b),
             Ok(false),
             "can observe proposer for new slot, indicates proposer unobserved"
         );
@@ -320,7 +363,7 @@ mod tests {
             "observed block in slot 1 is indicated as true"
         );
         assert_eq!(
-            cache.observe_proposer(block_b),
+            cache.observe_proposer(block_b.canonical_root(), block_b),
             Ok(true),
             "observing slot 1 again indicates true"
         );
@@ -355,7 +398,7 @@ mod tests {
             "no observation for new proposer"
         );
         assert_eq!(
-            cache.observe_proposer(block_c),
+            cache.observe_proposer(block_c.canonical_root(), block_c),
             Ok(false),
             "can observe new proposer, indicates proposer unobserved"
         );
@@ -365,7 +408,7 @@ mod tests {
             "observed new proposer block is indicated as true"
         );
         assert_eq!(
-            cache.observe_proposer(block_c),
+            cache.observe_proposer(block_c.canonical_root(), block_c),
             Ok(true),
             "observing new proposer again indicates true"
         );
@@ -391,4 +434,45 @@ mod tests {
             "only one proposer should be present in slot 1"
         );
     }
+
+    #[test]
+    fn observed_block_roots() {
+        let mut cache = ObservedBlockProducers::default();
+
+        // Slot 0, proposer 0, with two conflicting blocks.
+        let block_a = &get_block(0, 0);
+        let mut block_b = get_block(0, 0);
+        block_b.state_root = Hash256::repeat_byte(42);
+        let block_b = &block_b;
+
+        assert_eq!(
+            cache.observed_block_root(block_a),
+            Ok(None),
+            "no root in empty cache"
+        );
+        assert_eq!(
+            cache.observe_proposer(block_a.canonical_root(), block_a),
+            Ok(false)
+        );
+        assert_eq!(
+            cache.observed_block_root(block_a),
+            Ok(Some(block_a.canonical_root())),
+            "root of the observed block is returned"
+        );
+        assert_eq!(
+            cache.observe_proposer(block_b.canonical_root(), block_b),
+            Ok(true),
+            "conflicting block is indicated as observed"
+        );
+        assert_eq!(
+            cache.observed_block_root(block_b),
+            Ok(Some(block_a.canonical_root())),
+            "root of the first observed block is retained"
+        );
+        assert_eq!(
+            cache.observed_block_root(&get_block(0, 1)),
+            Ok(None),
+            "no root for another proposer"
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! Records the timing of each stage of the block proposals made via this beacon node.
+//!
+//! When a proposal is missed or orphaned, these timings show whether the delay was in block
+//! production, in the validator client (signing), or after the block was published to the
+//! network.
+
+use eth2::lighthouse::ProposalTiming;
+use parking_lot::Mutex;
+use slot_clock::SlotClock;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// The number of slots for which timings are retained.
+pub const PROPOSAL_TIMINGS_CAPACITY: usize = 64;
+
+/// A stage of a block proposal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProposalStage {
+    BlockRequested,
+    BlockProduced,
+    BlockReceived,
+    BlockPublished,
+    BlockImported,
+}
+
+/// See the module-level documentation.
+#[derive(Default)]
+pub struct ProposalTimings {
+    timings: Mutex<BTreeMap<Slot, ProposalTiming>>,
+}
+
+impl ProposalTimings {
+    /// Records that `stage` of the proposal at `slot` occurred at `timestamp` (a duration since
+    /// the UNIX epoch).
+    ///
+    /// If a stage is recorded more than once for the same slot (e.g., the validator client retries
+    /// a request), the latest time is kept.
+    pub fn record<S: SlotClock>(
+        &self,
+        slot: Slot,
+        stage: ProposalStage,
+        timestamp: Duration,
+        block_root: Option<Hash256>,
+        slot_clock: &S,
+    ) {
+        let delay_ms = slot_clock
+            .start_of(slot)
+            .map(|slot_start| timestamp.saturating_sub(slot_start).as_millis() as u64);
+
+        let mut timings = self.timings.lock();
+        let timing = timings.entry(slot).or_insert_with(|| ProposalTiming {
+            slot,
+            ..<_>::default()
+        });
+
+        if block_root.is_some() {
+            timing.block_root = block_root;
+        }
+
+        let field = match stage {
+            ProposalStage::BlockRequested => &mut timing.block_requested_ms,
+            ProposalStage::BlockProduced => &mut timing.block_produced_ms,
+            ProposalStage::BlockReceived => &mut timing.block_received_ms,
+            ProposalStage::BlockPublished => &mut timing.block_published_ms,
+            ProposalStage::BlockImported => &mut timing.block_imported_ms,
+        };
+        *field = delay_ms;
+
+        while timings.len() > PROPOSAL_TIMINGS_CAPACITY {
+            let oldest = *timings.keys().next().expect("timings is not empty");
+            timings.remove(&oldest);
+        }
+    }
+
+    /// Returns the retained timings, ordered by slot.
+    pub fn timings(&self) -> Vec<ProposalTiming> {
+        self.timings.lock().values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+
+    fn clock() -> ManualSlotClock {
+        ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(100),
+            Duration::from_secs(12),
+        )
+    }
+
+    #[test]
+    fn records_delays_from_slot_start() {
+        let timings = ProposalTimings::default();
+        let slot = Slot::new(2);
+        let root = Hash256::from_low_u64_be(1);
+
+        timings.record(
+            slot,
+            ProposalStage::BlockRequested,
+            Duration::from_millis(124_100),
+            None,
+            &clock(),
+        );
+        timings.record(
+            slot,
+            ProposalStage::BlockPublished,
+            Duration::from_millis(125_500),
+            Some(root),
+            &clock(),
+        );
+
+        assert_eq!(
+            timings.timings(),
+            vec![ProposalTiming {
+                slot,
+                block_root: Some(root),
+                block_requested_ms: Some(100),
+                block_published_ms: Some(1_500),
+                ..<_>::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn prunes_oldest_slots() {
+        let timings = ProposalTimings::default();
+
+        for slot in 0..PROPOSAL_TIMINGS_CAPACITY as u64 + 2 {
+            timings.record(
+                Slot::new(slot),
+                ProposalStage::BlockRequested,
+                Duration::from_secs(100),
+                None,
+                &clock(),
+            );
+        }
+
+        let retained = timings.timings();
+        assert_eq!(retained.len(), PROPOSAL_TIMINGS_CAPACITY);
+        assert_eq!(retained[0].slot, Slot::new(2));
+    }
+}
@@ -0,0 +1,255 @@
+//! Retains the SSZ bytes of recently rejected gossip blocks and attestations, alongside the
+//! reason for their rejection.
+//!
+//! A rejection caused by a consensus bug (in Lighthouse or another client) is often hard to
+//! reproduce after the fact, since the offending object is not stored anywhere. The quarantine
+//! keeps the exact bytes that were rejected so they can be retrieved via the HTTP API.
+//!
+//! Objects are persisted to the hot database in `DBColumn::BeaconQuarantine`, keyed by the slot
+//! at which they were rejected, so they survive a restart. The quarantine is bounded, pruning the
+//! objects from the oldest slots once full, and is disabled when its capacity is zero.
+
+use eth2::lighthouse::QuarantinedObject;
+use parking_lot::Mutex;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use store::{
+    DBColumn, Error as StoreError, HotColdDB, ItemStore, KeyValueStore, KeyValueStoreOp,
+    SlotSequenceKey,
+};
+use types::{EthSpec, Slot};
+
+/// The representation of a `QuarantinedObject` in the database. The slot and ID are stored in the
+/// key.
+#[derive(Encode, Decode)]
+struct StoredObject {
+    kind: Vec<u8>,
+    source: Vec<u8>,
+    reason: Vec<u8>,
+    received_at: u64,
+    ssz: Vec<u8>,
+}
+
+impl StoredObject {
+    fn into_object(self, key: SlotSequenceKey) -> QuarantinedObject {
+        QuarantinedObject {
+            id: key.sequence,
+            slot: key.slot,
+            kind: String::from_utf8_lossy(&self.kind).into_owned(),
+            source: String::from_utf8_lossy(&self.source).into_owned(),
+            reason: String::from_utf8_lossy(&self.reason).into_owned(),
+            received_at: self.received_at,
+            ssz: self.ssz,
+        }
+    }
+}
+
+/// See the module-level documentation.
+pub struct Quarantine<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
+    capacity: usize,
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    next_id: u64,
+    len: usize,
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> Quarantine<E, Hot, Cold> {
+    /// Open the quarantine persisted in `store`, pruning it to `capacity` objects.
+    pub fn new(capacity: usize, store: Arc<HotColdDB<E, Hot, Cold>>) -> Result<Self, StoreError> {
+        let mut next_id = 0;
+        let mut len = 0;
+        for result in store
+            .hot_db
+            .iter_column_keys::<SlotSequenceKey>(DBColumn::BeaconQuarantine)?
+        {
+            let (key, _) = result?;
+            next_id = std::cmp::max(next_id, key.sequence + 1);
+            len += 1;
+        }
+
+        let quarantine = Self {
+            capacity,
+            store,
+            inner: Mutex::new(Inner { next_id, len }),
+        };
+        quarantine.prune(&mut quarantine.inner.lock(), vec![])?;
+
+        Ok(quarantine)
+    }
+
+    /// Returns `true` if objects will be retained. Callers may use this to avoid retaining or
+    /// encoding objects unnecessarily.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Adds an object which was rejected at `slot` to the quarantine, pruning the objects from the
+    /// oldest slots if it is full.
+    ///
+    /// - `kind`: the type of object, e.g., `"block"`.
+    /// - `source`: where the object came from, e.g., the ID of the peer which sent it.
+    pub fn insert(
+        &self,
+        slot: Slot,
+        kind: &str,
+        source: String,
+        reason: String,
+        ssz: Vec<u8>,
+    ) -> Result<(), StoreError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let received_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        let mut inner = self.inner.lock();
+        let key = SlotSequenceKey::new(slot, inner.next_id);
+        let stored = StoredObject {
+            kind: kind.as_bytes().to_vec(),
+            source: source.into_bytes(),
+            reason: reason.into_bytes(),
+            received_at,
+            ssz,
+        };
+        let put = KeyValueStoreOp::put(DBColumn::BeaconQuarantine, &key, stored.as_ssz_bytes());
+
+        self.prune(&mut inner, vec![put])?;
+        inner.next_id += 1;
+
+        Ok(())
+    }
+
+    /// Atomically applies `puts` and deletes the objects from the oldest slots until the
+    /// quarantine is within its capacity.
+    fn prune(&self, inner: &mut Inner, puts: Vec<KeyValueStoreOp>) -> Result<(), StoreError> {
+        let len = inner.len + puts.len();
+        let excess = len.saturating_sub(self.capacity);
+
+        let mut ops = puts;
+        for result in self
+            .store
+            .hot_db
+            .iter_column_keys::<SlotSequenceKey>(DBColumn::BeaconQuarantine)?
+            .take(excess)
+        {
+            let (key, _) = result?;
+            ops.push(KeyValueStoreOp::delete(DBColumn::BeaconQuarantine, &key));
+        }
+
+        if !ops.is_empty() {
+            self.store.hot_db.do_atomically(ops)?;
+        }
+        inner.len = len - excess;
+
+        Ok(())
+    }
+
+    /// Returns all quarantined objects, oldest first.
+    pub fn objects(&self) -> Result<Vec<QuarantinedObject>, StoreError> {
+        self.store
+            .hot_db
+            .iter_column_keys::<SlotSequenceKey>(DBColumn::BeaconQuarantine)?
+            .map(|result| {
+                let (key, value) = result?;
+                Ok(StoredObject::from_ssz_bytes(&value)?.into_object(key))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::{MemoryStore, StoreConfig};
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+    type Store = HotColdDB<E, MemoryStore<E>, MemoryStore<E>>;
+
+    fn get_store() -> Arc<Store> {
+        let log = NullLoggerBuilder.build().expect("logger should build");
+        Arc::new(
+            Store::open_ephemeral(StoreConfig::default(), E::default_spec(), log)
+                .expect("store should open"),
+        )
+    }
+
+    #[test]
+    fn bounded() {
+        let quarantine = Quarantine::new(2, get_store()).unwrap();
+
+        for (i, slot) in [3, 1, 2].iter().enumerate() {
+            quarantine
+                .insert(
+                    Slot::new(*slot),
+                    "block",
+                    "peer".into(),
+                    format!("reason {}", i),
+                    vec![i as u8],
+                )
+                .unwrap();
+        }
+
+        let objects = quarantine.objects().unwrap();
+        assert_eq!(
+            objects.iter().map(|o| o.id).collect::<Vec<_>>(),
+            vec![2, 0],
+            "the object from the oldest slot should be pruned"
+        );
+        assert_eq!(objects[1].slot, Slot::new(3));
+        assert_eq!(objects[1].reason, "reason 0");
+        assert_eq!(objects[1].ssz, vec![0]);
+    }
+
+    #[test]
+    fn persisted() {
+        let store = get_store();
+        let quarantine = Quarantine::new(3, store.clone()).unwrap();
+        for slot in 0..3 {
+            quarantine
+                .insert(Slot::new(slot), "block", "peer".into(), "".into(), vec![])
+                .unwrap();
+        }
+        drop(quarantine);
+
+        // Re-opening with a smaller capacity prunes the oldest objects, and IDs are not reused.
+        let quarantine = Quarantine::new(2, store).unwrap();
+        quarantine
+            .insert(Slot::new(3), "block", "peer".into(), "".into(), vec![])
+            .unwrap();
+        assert_eq!(
+            quarantine
+                .objects()
+                .unwrap()
+                .iter()
+                .map(|o| o.id)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let quarantine = Quarantine::new(0, get_store()).unwrap();
+        assert!(!quarantine.is_enabled());
+
+        quarantine
+            .insert(
+                Slot::new(0),
+                "block",
+                "peer".into(),
+                "reason".into(),
+                vec![0],
+            )
+            .unwrap();
+        assert!(quarantine.objects().unwrap().is_empty());
+    }
+}
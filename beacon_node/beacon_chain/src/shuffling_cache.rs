@@ -2,12 +2,12 @@ use crate::metrics;
 use lru::LruCache;
 use types::{beacon_state::CommitteeCache, AttestationShufflingId, Epoch, Hash256};
 
-/// The size of the LRU cache that stores committee caches for quicker verification.
+/// The default size of the LRU cache that stores committee caches for quicker verification.
 ///
 /// Each entry should be `8 + 800,000 = 800,008` bytes in size with 100k validators. (8-byte hash +
 /// 100k indices). Therefore, this cache should be approx `16 * 800,008 = 12.8 MB`. (Note: this
 /// ignores a few extra bytes in the caches that should be insignificant compared to the indices).
-const CACHE_SIZE: usize = 16;
+pub const DEFAULT_SHUFFLING_CACHE_SIZE: usize = 16;
 
 /// Provides an LRU cache for `CommitteeCache`.
 ///
@@ -18,9 +18,9 @@ pub struct ShufflingCache {
 }
 
 impl ShufflingCache {
-    pub fn new() -> Self {
+    pub fn new(cache_size: usize) -> Self {
         Self {
-            cache: LruCache::new(CACHE_SIZE),
+            cache: LruCache::new(cache_size),
         }
     }
 
@@ -45,6 +45,25 @@ impl ShufflingCache {
             self.cache.put(key, committee_cache.clone());
         }
     }
+
+    /// Removes the shufflings for all epochs prior to `finalized_epoch - 1`.
+    ///
+    /// The shuffling of the epoch prior to `finalized_epoch` is retained, since blocks in the
+    /// finalized epoch may include attestations from it.
+    pub fn prune(&mut self, finalized_epoch: Epoch) {
+        let min_epoch = finalized_epoch.saturating_sub(1u64);
+        let expired = self
+            .cache
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| key.shuffling_epoch < min_epoch)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in expired {
+            self.cache.pop(&key);
+        }
+    }
 }
 
 /// Contains the shuffling IDs for a beacon block.
@@ -73,3 +92,32 @@ impl BlockShufflingIds {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shuffling_id(epoch: u64) -> AttestationShufflingId {
+        AttestationShufflingId {
+            shuffling_epoch: Epoch::new(epoch),
+            shuffling_decision_block: Hash256::from_low_u64_be(epoch),
+        }
+    }
+
+    #[test]
+    fn prune() {
+        let mut cache = ShufflingCache::new(DEFAULT_SHUFFLING_CACHE_SIZE);
+        for epoch in 0..8 {
+            cache.insert(shuffling_id(epoch), &CommitteeCache::default());
+        }
+
+        cache.prune(Epoch::new(5));
+
+        for epoch in 0..4 {
+            assert!(!cache.contains(&shuffling_id(epoch)));
+        }
+        for epoch in 4..8 {
+            assert!(cache.contains(&shuffling_id(epoch)));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::BeaconSnapshot;
+use ssz::Encode;
 use std::cmp;
 use types::{
     beacon_state::CloneConfig, BeaconState, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot,
@@ -142,6 +143,24 @@ impl<T: EthSpec> SnapshotCache<T> {
         }
     }
 
+    /// Returns an estimate of the number of bytes used by the blocks and states in the cache.
+    ///
+    /// The estimate is based upon the SSZ size of each object, so it excludes the committee,
+    /// pubkey and tree hash caches stored alongside each state.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        self.snapshots
+            .iter()
+            .map(|item| {
+                item.beacon_block.ssz_bytes_len()
+                    + item.beacon_state.ssz_bytes_len()
+                    + item
+                        .pre_state
+                        .as_ref()
+                        .map_or(0, |pre_state| pre_state.ssz_bytes_len())
+            })
+            .sum()
+    }
+
     /// Insert a snapshot, potentially removing an existing snapshot if `self` is at capacity (see
     /// struct-level documentation for more info).
     pub fn insert(&mut self, snapshot: BeaconSnapshot<T>, pre_state: Option<BeaconState<T>>) {
@@ -215,6 +234,18 @@ impl<T: EthSpec> SnapshotCache<T> {
             })
     }
 
+    /// If there is a snapshot with the post-state `state_root`, return a clone of that state.
+    pub fn get_state_cloned(
+        &self,
+        state_root: &Hash256,
+        clone_config: CloneConfig,
+    ) -> Option<BeaconState<T>> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.beacon_block.state_root() == *state_root)
+            .map(|snapshot| snapshot.beacon_state.clone_with(clone_config))
+    }
+
     /// If there is a snapshot with `block_root`, clone it and return the clone.
     pub fn get_cloned(
         &self,
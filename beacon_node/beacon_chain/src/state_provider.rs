@@ -0,0 +1,95 @@
+//! The `StateProvider` stores the states most recently loaded from the database by
+//! `BeaconChain::get_state`, which serves both the HTTP API and block import.
+//!
+//! Loading a state which is not stored in full requires replaying blocks on top of the prior
+//! epoch-boundary state, which is expensive. Without this cache an API query for a recent state
+//! would repeat the load and replay performed moments earlier for block import (or vice versa).
+//!
+//! States are keyed by state root, so a cached state is never invalidated. The cache is a simple
+//! LRU which is not pruned after finality.
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use ssz::Encode;
+use types::{beacon_state::CloneConfig, BeaconState, EthSpec, Hash256};
+
+/// The default number of states that should be cached. States are large, so this is kept small.
+pub const DEFAULT_STATE_PROVIDER_CACHE_SIZE: usize = 4;
+
+/// See the module-level documentation.
+pub struct StateProvider<E: EthSpec> {
+    cache: Mutex<LruCache<Hash256, BeaconState<E>>>,
+}
+
+impl<E: EthSpec> StateProvider<E> {
+    /// Create a new cache which holds up to `capacity` states.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns a clone of the state with `state_root`, if it is cached.
+    pub fn get(&self, state_root: &Hash256) -> Option<BeaconState<E>> {
+        self.cache
+            .lock()
+            .get(state_root)
+            .map(|state| state.clone_with(CloneConfig::committee_caches_only()))
+    }
+
+    /// Stores a clone of `state`, which must have the root `state_root`.
+    pub fn insert(&self, state_root: Hash256, state: &BeaconState<E>) {
+        self.cache.lock().put(
+            state_root,
+            state.clone_with(CloneConfig::committee_caches_only()),
+        );
+    }
+
+    /// Returns an estimate of the memory used by the cached states, based on their SSZ size.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        self.cache
+            .lock()
+            .iter()
+            .map(|(_, state)| state.ssz_bytes_len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ChainSpec, Eth1Data, MinimalEthSpec, Slot};
+
+    type E = MinimalEthSpec;
+
+    fn state(slot: u64) -> BeaconState<E> {
+        let mut state = BeaconState::new(0, Eth1Data::default(), &ChainSpec::minimal());
+        state.slot = Slot::new(slot);
+        state
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let provider = StateProvider::new(DEFAULT_STATE_PROVIDER_CACHE_SIZE);
+        let root = Hash256::from_low_u64_be;
+        let size = DEFAULT_STATE_PROVIDER_CACHE_SIZE as u64;
+
+        for i in 0..size {
+            provider.insert(root(i), &state(i));
+        }
+        assert_eq!(
+            provider.get(&root(0)).map(|state| state.slot),
+            Some(Slot::new(0))
+        );
+
+        // State 1 is now the least recently used.
+        provider.insert(root(size), &state(size));
+        assert!(provider.get(&root(1)).is_none());
+        assert!(provider.get(&root(0)).is_some());
+        assert!(provider.get(&root(size)).is_some());
+        assert_eq!(
+            provider.estimated_memory_bytes(),
+            size as usize * state(0).ssz_bytes_len()
+        );
+    }
+}
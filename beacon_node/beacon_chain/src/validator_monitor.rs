@@ -436,11 +436,34 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             .and_then(|pubkey| self.validators.get(pubkey))
     }
 
+    /// Returns the number of times attestations by `validator_index` with a target in `epoch` have
+    /// been seen in blocks.
+    #[cfg(test)]
+    pub fn attestation_block_inclusions(&self, validator_index: u64, epoch: Epoch) -> usize {
+        self.get_validator(validator_index)
+            .and_then(|validator| {
+                validator
+                    .summaries
+                    .read()
+                    .get(&epoch)
+                    .map(|summary| summary.attestation_block_inclusions)
+            })
+            .unwrap_or(0)
+    }
+
     /// Returns the number of validators monitored by `self`.
     pub fn num_validators(&self) -> usize {
         self.validators.len()
     }
 
+    /// Returns the indices of the monitored validators which are known to be in the state.
+    pub fn monitored_validator_indices(&self) -> Vec<u64> {
+        self.validators
+            .values()
+            .filter_map(|validator| validator.index)
+            .collect()
+    }
+
     /// If `self.auto_register == true`, add the `validator_index` to `self.monitored_validators`.
     /// Otherwise, do nothing.
     pub fn auto_register_local_validator(&mut self, validator_index: u64) {
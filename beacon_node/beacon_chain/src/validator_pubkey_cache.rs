@@ -187,6 +187,15 @@ impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
     pub fn len(&self) -> usize {
         self.indices.len()
     }
+
+    /// Returns an estimate of the number of bytes used by the cache, excluding the overhead of
+    /// the `HashMap`.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        self.len()
+            * (std::mem::size_of::<PublicKey>()
+                + 2 * std::mem::size_of::<PublicKeyBytes>()
+                + std::mem::size_of::<usize>())
+    }
 }
 
 /// Wrapper for a public key stored in the database.
@@ -636,7 +636,7 @@ fn unaggregated_gossip_verification() {
             a
         },
         subnet_id,
-        AttnError::NoCommitteeForSlotAndIndex { .. }
+        AttnError::BadCommitteeIndex { .. }
     );
 
     /*
@@ -1,8 +1,10 @@
 #![cfg(not(debug_assertions))]
 
 use beacon_chain::attestation_verification::Error as AttnError;
+use beacon_chain::builder::BeaconChainBuilder;
 use beacon_chain::test_utils::{
     test_logger, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
+    MigratorConfig, HARNESS_SLOT_TIME,
 };
 use beacon_chain::BeaconSnapshot;
 use lazy_static::lazy_static;
@@ -13,8 +15,9 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use store::{
+    hot_cold_store::HotColdDBError,
     iter::{BlockRootsIterator, StateRootsIterator},
-    HotColdDB, LevelDB, StoreConfig,
+    BeaconNodeBackend, Error as StoreError, HotColdDB, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tree_hash::TreeHash;
@@ -33,7 +36,7 @@ lazy_static! {
 type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<DiskHarnessType<E>>;
 
-fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>> {
     let spec = MinimalEthSpec::default_spec();
     let hot_path = db_path.path().join("hot_db");
     let cold_path = db_path.path().join("cold_db");
@@ -45,7 +48,7 @@ fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
 }
 
 fn get_harness(
-    store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
     validator_count: usize,
 ) -> TestHarness {
     let harness = BeaconChainHarness::new_with_disk_store(
@@ -1708,7 +1711,7 @@ fn garbage_collect_temp_states_from_failed_block() {
     harness.process_block_result(block).unwrap_err();
 
     assert_eq!(
-        store.iter_temporary_state_roots().count(),
+        store.iter_temporary_state_roots().unwrap().count(),
         block_slot.as_usize() - 1
     );
 
@@ -1717,7 +1720,126 @@ fn garbage_collect_temp_states_from_failed_block() {
 
     // On startup, the store should garbage collect all the temporary states.
     let store = get_store(&db_path);
-    assert_eq!(store.iter_temporary_state_roots().count(), 0);
+    assert_eq!(store.iter_temporary_state_roots().unwrap().count(), 0);
+}
+
+#[test]
+fn weak_subjectivity_sync() {
+    // Build an initial chain on one node.
+    let num_initial_blocks = E::slots_per_epoch() * 7;
+    let num_final_blocks = E::slots_per_epoch() * 4;
+
+    let temp1 = tempdir().unwrap();
+    let full_store = get_store(&temp1);
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_initial_blocks as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let genesis_state = full_store
+        .load_cold_state_by_slot(Slot::new(0))
+        .expect("genesis state should be available");
+    let finalized_checkpoint = harness.chain.head_info().unwrap().finalized_checkpoint;
+    let anchor_block = harness
+        .chain
+        .get_block(&finalized_checkpoint.root)
+        .unwrap()
+        .unwrap();
+    let anchor_state = harness
+        .chain
+        .get_state(&anchor_block.state_root(), Some(anchor_block.slot()))
+        .unwrap()
+        .unwrap();
+
+    // Start a second node from the finalized block and state.
+    let temp2 = tempdir().unwrap();
+    let store = get_store(&temp2);
+    let spec = E::default_spec();
+    let (shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+    let log = test_logger();
+
+    let beacon_chain = BeaconChainBuilder::new(MinimalEthSpec)
+        .logger(log.clone())
+        .custom_spec(spec)
+        .store(store.clone())
+        .store_migrator_config(MigratorConfig::default().blocking())
+        .weak_subjectivity_state(anchor_state, anchor_block.clone(), genesis_state)
+        .unwrap()
+        .dummy_eth1_backend()
+        .unwrap()
+        .testing_slot_clock(HARNESS_SLOT_TIME)
+        .unwrap()
+        .shutdown_sender(shutdown_tx)
+        .monitor_validators(true, vec![], log)
+        .build()
+        .expect("should build");
+
+    assert_eq!(
+        beacon_chain.head_info().unwrap().block_root,
+        finalized_checkpoint.root
+    );
+    assert_eq!(
+        store.get_anchor_info().map(|anchor| anchor.anchor_slot),
+        Some(anchor_block.slot())
+    );
+    assert_eq!(store.get_split_slot(), anchor_block.slot());
+
+    // Genesis is available, but the states between genesis and the anchor are not.
+    store.load_cold_state_by_slot(Slot::new(0)).unwrap();
+    assert!(matches!(
+        store.load_cold_state_by_slot(Slot::new(1)),
+        Err(StoreError::HotColdDBError(
+            HotColdDBError::HistoricalStateUnavailable { .. }
+        ))
+    ));
+
+    // Import the blocks built on top of the anchor by the first node.
+    harness.extend_chain(
+        num_final_blocks as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    beacon_chain
+        .slot_clock
+        .set_slot(harness.get_current_slot().as_u64());
+    for snapshot in harness.chain.chain_dump().unwrap() {
+        if snapshot.beacon_block.slot() > anchor_block.slot() {
+            beacon_chain.process_block(snapshot.beacon_block).unwrap();
+        }
+    }
+    beacon_chain.fork_choice().unwrap();
+
+    let expected_head = harness.chain.head_info().unwrap();
+    let head = beacon_chain.head_info().unwrap();
+    assert_eq!(head.block_root, expected_head.block_root);
+    assert_eq!(
+        head.finalized_checkpoint,
+        expected_head.finalized_checkpoint
+    );
+    assert!(store.get_split_slot() > anchor_block.slot());
+
+    // The first restore point after the anchor, and the states which follow it, have been
+    // frozen and can be loaded.
+    let state_upper_limit = store.get_anchor_info().unwrap().state_upper_limit;
+    assert!(store.get_split_slot() > state_upper_limit + 1);
+    for &slot in &[state_upper_limit, state_upper_limit + 1] {
+        let state = store
+            .load_cold_state_by_slot(slot)
+            .expect("historical state after the anchor should be available");
+        let expected_state = full_store.load_cold_state_by_slot(slot).unwrap();
+        assert_eq!(state.slot, slot);
+        assert_eq!(state.canonical_root(), expected_state.canonical_root());
+    }
+    assert!(matches!(
+        store.load_cold_state_by_slot(state_upper_limit - 1),
+        Err(StoreError::HotColdDBError(
+            HotColdDBError::HistoricalStateUnavailable { .. }
+        ))
+    ));
 }
 
 /// Check that the head state's slot matches `expected_slot`.
@@ -1749,7 +1871,10 @@ fn check_finalization(harness: &TestHarness, expected_slot: u64) {
 }
 
 /// Check that the HotColdDB's split_slot is equal to the start slot of the last finalized epoch.
-fn check_split_slot(harness: &TestHarness, store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>) {
+fn check_split_slot(
+    harness: &TestHarness,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
+) {
     let split_slot = store.get_split_slot();
     assert_eq!(
         harness
@@ -9,11 +9,14 @@ use beacon_chain::{
         AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
         OP_POOL_DB_KEY,
     },
+    BeaconChainError, ChainConfig,
 };
+use fork_choice::ForkChoiceStore;
 use operation_pool::PersistedOperationPool;
 use state_processing::{
     per_slot_processing, per_slot_processing::Error as SlotProcessingError, EpochProcessingError,
 };
+use std::collections::HashSet;
 use store::config::StoreConfig;
 use types::{BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Slot};
 
@@ -609,3 +612,220 @@ fn produces_and_processes_with_genesis_skip_slots() {
         run_skip_slot_test(i)
     }
 }
+
+#[test]
+fn validator_index_in_state() {
+    let validator_count = 8;
+    let harness = get_harness(validator_count);
+    let mut state = harness.chain.head().expect("should get head").beacon_state;
+
+    for (i, keypair) in KEYPAIRS[0..validator_count].iter().enumerate() {
+        assert_eq!(
+            harness
+                .chain
+                .validator_index_in_state(&keypair.pk.compress(), &state)
+                .unwrap(),
+            Some(i),
+            "should find known validator {}",
+            i
+        );
+    }
+
+    let new_pubkey = KEYPAIRS[validator_count].pk.compress();
+    assert_eq!(
+        harness
+            .chain
+            .validator_index_in_state(&new_pubkey, &state)
+            .unwrap(),
+        None,
+        "should not find unknown validator"
+    );
+
+    // A validator which is in the state but not yet in the pubkey cache should be found by
+    // scanning the state.
+    let mut new_validator = state.validators[0].clone();
+    new_validator.pubkey = new_pubkey;
+    state.validators.push(new_validator).unwrap();
+    assert_eq!(
+        harness
+            .chain
+            .validator_index_in_state(&new_pubkey, &state)
+            .unwrap(),
+        Some(validator_count),
+        "should find validator missing from the cache"
+    );
+}
+
+#[test]
+fn head_for_duties_runs_fork_choice() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    harness.advance_slot();
+
+    let fork_choice_slot = || {
+        harness
+            .chain
+            .fork_choice
+            .read()
+            .fc_store()
+            .get_current_slot()
+    };
+    let current_slot = harness.chain.slot().unwrap();
+    assert!(
+        fork_choice_slot() < current_slot,
+        "fork choice should not have run in the new slot"
+    );
+
+    let head = harness.chain.get_head_for_proposal(current_slot).unwrap();
+    assert_eq!(fork_choice_slot(), current_slot);
+    assert_eq!(
+        head.block_root,
+        harness.chain.head_info().unwrap().block_root
+    );
+
+    // Fork choice should not be advanced beyond the slot clock.
+    harness
+        .chain
+        .get_head_for_proposal(current_slot + 10)
+        .unwrap();
+    assert_eq!(fork_choice_slot(), current_slot);
+
+    assert_eq!(
+        harness
+            .chain
+            .get_head_for_attestation(current_slot)
+            .unwrap()
+            .block_root,
+        head.block_root
+    );
+    assert!(matches!(
+        harness.chain.get_head_for_attestation(head.slot - 1),
+        Err(BeaconChainError::AttestingPriorToHead { .. })
+    ));
+}
+
+#[test]
+fn attestations_by_block_root() {
+    let chain_config = ChainConfig {
+        index_attestations_by_block_root: true,
+        ..ChainConfig::default()
+    };
+    let harness = BeaconChainHarness::new_with_chain_config(
+        MinimalEthSpec,
+        KEYPAIRS[..].to_vec(),
+        MinimalEthSpec::default_spec().target_aggregators_per_committee,
+        StoreConfig::default(),
+        chain_config,
+    );
+    harness.advance_slot();
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head().unwrap();
+    let voted_root = head.beacon_block.message.parent_root;
+    let included = harness
+        .chain
+        .attestations_for_block_root(&voted_root)
+        .unwrap()
+        .expect("indexing is enabled");
+
+    assert!(!included.is_empty());
+    for included_attestation in &included {
+        assert_eq!(
+            included_attestation.attestation.data.beacon_block_root,
+            voted_root
+        );
+        assert_eq!(included_attestation.block_root, head.beacon_block_root);
+        assert_eq!(included_attestation.block_slot, head.beacon_block.slot());
+    }
+
+    // All validators attested to the parent of the head, so every validator in the committees of
+    // the parent's slot should be included.
+    let attesters = included
+        .iter()
+        .flat_map(|included| included.attestation.attesting_indices.iter().copied())
+        .collect::<HashSet<_>>();
+    let committee_size = VALIDATOR_COUNT / MinimalEthSpec::slots_per_epoch() as usize;
+    assert_eq!(attesters.len(), committee_size);
+
+    // Indexing is disabled by default.
+    let harness = get_harness(VALIDATOR_COUNT);
+    assert_eq!(
+        harness
+            .chain
+            .attestations_for_block_root(&voted_root)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn attestations_by_block_root_pruned_on_finalization() {
+    let chain_config = ChainConfig {
+        index_attestations_by_block_root: true,
+        ..ChainConfig::default()
+    };
+    let harness = BeaconChainHarness::new_with_chain_config(
+        MinimalEthSpec,
+        KEYPAIRS[..].to_vec(),
+        MinimalEthSpec::default_spec().target_aggregators_per_committee,
+        StoreConfig::default(),
+        chain_config,
+    );
+    harness.advance_slot();
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize * 5,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head().unwrap();
+    let finalized_checkpoint = head.beacon_state.finalized_checkpoint;
+    assert!(
+        finalized_checkpoint.epoch > 0,
+        "chain should have finalized"
+    );
+
+    // The entries for strict ancestors of the finalized block are deleted.
+    let finalized_ancestor = harness
+        .chain
+        .block_root_at_slot(Slot::new(1))
+        .unwrap()
+        .expect("block at slot 1 should exist");
+    assert_ne!(finalized_ancestor, finalized_checkpoint.root);
+    assert_eq!(
+        harness
+            .chain
+            .attestations_for_block_root(&finalized_ancestor)
+            .unwrap(),
+        Some(vec![])
+    );
+    // The HTTP API relies on fork choice to distinguish pruned entries from empty ones.
+    assert!(!harness
+        .chain
+        .fork_choice
+        .read()
+        .contains_block(&finalized_ancestor));
+    assert!(harness
+        .chain
+        .fork_choice
+        .read()
+        .contains_block(&finalized_checkpoint.root));
+
+    // The entries for blocks which descend from finality are retained.
+    assert!(!harness
+        .chain
+        .attestations_for_block_root(&head.beacon_block.message.parent_root)
+        .unwrap()
+        .expect("indexing is enabled")
+        .is_empty());
+}
@@ -1,5 +1,7 @@
 use crate::config::{ClientGenesis, Config as ClientConfig};
+use crate::monitoring::{spawn_monitoring_service, Config as MonitoringConfig};
 use crate::notifier::spawn_notifier;
+use crate::self_check;
 use crate::Client;
 use beacon_chain::schema_change::migrate_schema;
 use beacon_chain::{
@@ -7,11 +9,15 @@ use beacon_chain::{
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     slot_clock::{SlotClock, SystemTimeSlotClock},
     state_advance_timer::spawn_state_advance_timer,
-    store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
+    store::{BeaconNodeBackend, HotColdDB, ItemStore, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, ServerSentEventHandler,
 };
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, Timeouts, Url,
+};
 use eth2_libp2p::NetworkGlobals;
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
@@ -19,17 +25,25 @@ use slasher::Slasher;
 use slasher_service::SlasherService;
 use slog::{debug, info, warn};
 use ssz::Decode;
+use std::fs::File;
+use std::io::BufReader;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use timer::spawn_timer;
 use tokio::sync::{mpsc::UnboundedSender, oneshot};
-use types::{test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec};
+use types::{
+    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec, SignedBeaconBlock,
+};
 
 /// Interval between polling the eth1 node for genesis information.
 pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
 
+/// Timeout for the requests made to the remote beacon node during checkpoint sync. Generous since
+/// the finalized state may be large.
+const CHECKPOINT_SYNC_HTTP_TIMEOUT: Duration = Duration::from_secs(180);
+
 /// Builds a `Client` instance.
 ///
 /// ## Notes
@@ -200,6 +214,85 @@ where
 
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
+            ClientGenesis::WeakSubjSszBytes {
+                genesis_state_bytes,
+                anchor_state_path,
+                anchor_block_bytes,
+            } => {
+                info!(context.log(), "Starting checkpoint sync");
+
+                let anchor_state_file = File::open(&anchor_state_path).map_err(|e| {
+                    format!(
+                        "Unable to read checkpoint file {}: {:?}",
+                        anchor_state_path.display(),
+                        e
+                    )
+                })?;
+                let anchor_state_len = anchor_state_file
+                    .metadata()
+                    .map_err(|e| format!("Unable to read checkpoint file metadata: {:?}", e))?
+                    .len() as usize;
+                let anchor_state = BeaconState::from_ssz_reader(
+                    BufReader::new(anchor_state_file),
+                    anchor_state_len,
+                )
+                .map_err(|e| format!("Unable to parse weak subj state SSZ: {:?}", e))?;
+                let anchor_block = SignedBeaconBlock::from_ssz_bytes(&anchor_block_bytes)
+                    .map_err(|e| format!("Unable to parse weak subj block SSZ: {:?}", e))?;
+                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes)
+                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+
+                builder
+                    .weak_subjectivity_state(anchor_state, anchor_block, genesis_state)
+                    .map(|v| (v, None))?
+            }
+            ClientGenesis::CheckpointSyncUrl {
+                genesis_state_bytes,
+                url,
+            } => {
+                info!(
+                    context.log(),
+                    "Starting checkpoint sync";
+                    "remote_url" => &url,
+                );
+
+                let remote = BeaconNodeHttpClient::new(
+                    Url::parse(&url)
+                        .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))?,
+                    Timeouts::set_all(CHECKPOINT_SYNC_HTTP_TIMEOUT),
+                );
+
+                debug!(context.log(), "Downloading finalized block");
+                let anchor_block = remote
+                    .get_beacon_blocks_ssz::<TEthSpec>(BlockId::Finalized)
+                    .await
+                    .map_err(|e| format!("Error fetching finalized block from remote: {:?}", e))?
+                    .ok_or("Finalized block missing from remote, it returned 404")?;
+
+                debug!(context.log(), "Downloading finalized state");
+                let anchor_state = remote
+                    .get_debug_beacon_states_ssz::<TEthSpec>(StateId::Root(
+                        anchor_block.state_root(),
+                    ))
+                    .await
+                    .map_err(|e| format!("Error loading finalized state from remote: {:?}", e))?
+                    .ok_or("Finalized state missing from remote, it returned 404")?;
+
+                info!(
+                    context.log(),
+                    "Loaded checkpoint block and state";
+                    "slot" => anchor_block.slot(),
+                    "block_root" => ?anchor_block.canonical_root(),
+                    "state_root" => ?anchor_block.state_root(),
+                );
+
+                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes)
+                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+
+                builder
+                    .weak_subjectivity_state(anchor_state, anchor_block, genesis_state)
+                    .map(|v| (v, None))?
+            }
             ClientGenesis::DepositContract => {
                 info!(
                     context.log(),
@@ -234,6 +327,7 @@ where
                         network_tx: None,
                         network_globals: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
+                        log_filter: context.log_filter.clone(),
                         log: context.log().clone(),
                     });
 
@@ -330,13 +424,8 @@ where
             .beacon_chain
             .clone()
             .ok_or("node timer requires a beacon chain")?;
-        let seconds_per_slot = self
-            .chain_spec
-            .as_ref()
-            .ok_or("node timer requires a chain spec")?
-            .seconds_per_slot;
 
-        spawn_timer(context.executor, beacon_chain, seconds_per_slot)
+        spawn_timer(context.executor, beacon_chain)
             .map_err(|e| format!("Unable to start node timer: {}", e))?;
 
         Ok(self)
@@ -406,6 +495,35 @@ where
         Ok(self)
     }
 
+    /// Immediately starts the service that periodically sends a health summary to a remote
+    /// monitoring endpoint, if `config` is `Some`.
+    pub fn monitoring_service(self, config: Option<MonitoringConfig>) -> Result<Self, String> {
+        let config = if let Some(config) = config {
+            config
+        } else {
+            return Ok(self);
+        };
+
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or("monitoring_service requires a runtime_context")?
+            .service_context("monitoring".into());
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or("monitoring_service requires a beacon chain")?;
+        let network_globals = self
+            .network_globals
+            .clone()
+            .ok_or("monitoring_service requires a libp2p network")?;
+
+        spawn_monitoring_service(context.executor, beacon_chain, network_globals, config)
+            .map_err(|e| format!("Unable to start monitoring service: {}", e))?;
+
+        Ok(self)
+    }
+
     /// Consumers the builder, returning a `Client` if all necessary components have been
     /// specified.
     ///
@@ -428,6 +546,7 @@ where
                 network_tx: self.network_send.clone(),
                 network_globals: self.network_globals.clone(),
                 eth1_service: self.eth1_service.clone(),
+                log_filter: runtime_context.log_filter.clone(),
                 log: log.clone(),
             });
 
@@ -531,10 +650,39 @@ where
         // a beacon chain requires a timer
         self.timer()
     }
+
+    /// Checks the database and system clock against the beacon chain, as per
+    /// `self_check::check_beacon_chain`.
+    ///
+    /// Must be called after `build_beacon_chain`.
+    pub fn beacon_chain_self_check(self, client_genesis: &ClientGenesis) -> Result<Self, String> {
+        let chain = self
+            .beacon_chain
+            .as_ref()
+            .ok_or("beacon_chain_self_check requires a beacon chain")?;
+        let expected_genesis_validators_root = client_genesis
+            .genesis_state_bytes()
+            .map(|bytes| {
+                BeaconState::<TEthSpec>::from_ssz_bytes(bytes)
+                    .map(|state| state.genesis_validators_root)
+                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))
+            })
+            .transpose()?;
+        self_check::check_beacon_chain(chain, expected_genesis_validators_root)?;
+        Ok(self)
+    }
 }
 
 impl<TSlotClock, TEth1Backend, TEthSpec>
-    ClientBuilder<Witness<TSlotClock, TEth1Backend, TEthSpec, LevelDB<TEthSpec>, LevelDB<TEthSpec>>>
+    ClientBuilder<
+        Witness<
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            BeaconNodeBackend<TEthSpec>,
+            BeaconNodeBackend<TEthSpec>,
+        >,
+    >
 where
     TSlotClock: SlotClock + 'static,
     TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
@@ -26,6 +26,45 @@ pub enum ClientGenesis {
     /// We include the bytes instead of the `BeaconState<E>` because the `EthSpec` type
     /// parameter would be very annoying.
     SszBytes { genesis_state_bytes: Vec<u8> },
+    /// Starts from a trusted, finalized state and its block, loaded from SSZ-encoded bytes, rather
+    /// than from genesis. The genesis state is still required.
+    ///
+    /// The finalized state is read from the file at `anchor_state_path` whilst it is decoded,
+    /// since it may be too large to comfortably hold in memory twice.
+    WeakSubjSszBytes {
+        genesis_state_bytes: Vec<u8>,
+        anchor_state_path: PathBuf,
+        anchor_block_bytes: Vec<u8>,
+    },
+    /// As per `WeakSubjSszBytes`, but the finalized state and block are downloaded from the
+    /// beacon node HTTP API at `url`.
+    CheckpointSyncUrl {
+        genesis_state_bytes: Vec<u8>,
+        url: String,
+    },
+}
+
+impl ClientGenesis {
+    /// Returns the SSZ-encoded genesis state of the network, if it is known without contacting
+    /// an eth1 node.
+    pub fn genesis_state_bytes(&self) -> Option<&[u8]> {
+        match self {
+            ClientGenesis::SszBytes {
+                genesis_state_bytes,
+            }
+            | ClientGenesis::WeakSubjSszBytes {
+                genesis_state_bytes,
+                ..
+            }
+            | ClientGenesis::CheckpointSyncUrl {
+                genesis_state_bytes,
+                ..
+            } => Some(genesis_state_bytes.as_slice()),
+            ClientGenesis::Interop { .. }
+            | ClientGenesis::FromStore
+            | ClientGenesis::DepositContract => None,
+        }
+    }
 }
 
 impl Default for ClientGenesis {
@@ -67,6 +106,10 @@ pub struct Config {
     pub http_api: http_api::Config,
     pub http_metrics: http_metrics::Config,
     pub slasher: Option<slasher::Config>,
+    /// Periodically send a summary of the node's health to a remote monitoring service.
+    pub monitoring: Option<crate::monitoring::Config>,
+    /// Run the checks in `self_check` whilst starting the node.
+    pub startup_checks: bool,
 }
 
 impl Default for Config {
@@ -88,8 +131,10 @@ impl Default for Config {
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             slasher: None,
+            monitoring: None,
             validator_monitor_auto: false,
             validator_monitor_pubkeys: vec![],
+            startup_checks: true,
         }
     }
 }
@@ -2,7 +2,9 @@ extern crate slog;
 
 pub mod config;
 mod metrics;
+pub mod monitoring;
 mod notifier;
+pub mod self_check;
 
 pub mod builder;
 pub mod error;
@@ -0,0 +1,267 @@
+//! An optional service which periodically sends a summary of the node's health to a remote
+//! monitoring service, for users who are unable to run their own Prometheus stack.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::Health;
+use eth2_libp2p::NetworkGlobals;
+use serde_derive::{Deserialize, Serialize};
+use slog::{debug, error, warn, Logger};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{BeaconState, EthSpec};
+use url::Url;
+
+/// The version of the `MonitoringSummary` format, incremented upon breaking changes.
+pub const MONITORING_VERSION: u64 = 1;
+
+/// The default number of seconds between summaries.
+pub const DEFAULT_UPDATE_PERIOD_SECS: u64 = 60;
+
+/// The timeout for each request to the monitoring endpoint.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration for the monitoring service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The URL to which summaries are POSTed.
+    pub endpoint: String,
+    /// An API key for the monitoring service, sent as the `apikey` query parameter.
+    pub api_key: Option<String>,
+    /// The number of seconds between summaries.
+    pub update_period_secs: u64,
+}
+
+/// A compact summary of the health of the beacon node, sent as JSON to the monitoring endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitoringSummary {
+    pub version: u64,
+    /// Milliseconds since the Unix epoch at which the summary was created.
+    pub timestamp: u64,
+    pub process: String,
+    pub client_name: String,
+    pub client_version: String,
+    pub sync_state: String,
+    pub sync_eth2_synced: bool,
+    pub sync_beacon_head_slot: u64,
+    pub current_slot: Option<u64>,
+    pub finalized_epoch: u64,
+    pub network_peers_connected: usize,
+    pub validators: ValidatorSummary,
+    /// System and process statistics, if they are available on this platform.
+    pub system: Option<Health>,
+}
+
+/// The statuses of the validators registered with the validator monitor, as at the head state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSummary {
+    pub total: usize,
+    pub active: usize,
+    /// Validators which are not yet in the state, or which are awaiting activation.
+    pub pending: usize,
+    pub exited: usize,
+    pub slashed: usize,
+}
+
+impl ValidatorSummary {
+    fn from_state<E: EthSpec>(total: usize, indices: &[u64], state: &BeaconState<E>) -> Self {
+        let epoch = state.current_epoch();
+        let mut summary = Self {
+            total,
+            pending: total.saturating_sub(indices.len()),
+            ..Self::default()
+        };
+
+        for validator in indices
+            .iter()
+            .filter_map(|i| state.validators.get(*i as usize))
+        {
+            if validator.slashed {
+                summary.slashed += 1;
+            }
+
+            if validator.is_exited_at(epoch) {
+                summary.exited += 1;
+            } else if validator.is_active_at(epoch) {
+                summary.active += 1;
+            } else {
+                summary.pending += 1;
+            }
+        }
+
+        summary
+    }
+}
+
+impl MonitoringSummary {
+    fn gather<T: BeaconChainTypes>(
+        beacon_chain: &BeaconChain<T>,
+        network: &NetworkGlobals<T::EthSpec>,
+    ) -> Result<Self, String> {
+        let (total, indices) = {
+            let validator_monitor = beacon_chain.validator_monitor.read();
+            (
+                validator_monitor.num_validators(),
+                validator_monitor.monitored_validator_indices(),
+            )
+        };
+
+        let (head_slot, finalized_epoch, validators) = beacon_chain
+            .with_head(|head| {
+                let state = &head.beacon_state;
+                Ok::<_, beacon_chain::BeaconChainError>((
+                    state.slot,
+                    state.finalized_checkpoint.epoch,
+                    ValidatorSummary::from_state(total, &indices, state),
+                ))
+            })
+            .map_err(|e| format!("Unable to read head: {:?}", e))?;
+
+        let sync_state = network.sync_state();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("System time is before the Unix epoch: {:?}", e))?
+            .as_millis() as u64;
+
+        Ok(Self {
+            version: MONITORING_VERSION,
+            timestamp,
+            process: "beaconnode".to_string(),
+            client_name: "lighthouse".to_string(),
+            client_version: lighthouse_version::VERSION.to_string(),
+            sync_state: sync_state.to_string(),
+            sync_eth2_synced: sync_state.is_synced(),
+            sync_beacon_head_slot: head_slot.as_u64(),
+            current_slot: beacon_chain.slot().ok().map(|slot| slot.as_u64()),
+            finalized_epoch: finalized_epoch.as_u64(),
+            network_peers_connected: network.connected_peers(),
+            validators,
+            system: Health::observe().ok(),
+        })
+    }
+}
+
+/// Spawns a service which POSTs a `MonitoringSummary` to `config.endpoint` every
+/// `config.update_period_secs`.
+pub fn spawn_monitoring_service<T: BeaconChainTypes>(
+    executor: task_executor::TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network: Arc<NetworkGlobals<T::EthSpec>>,
+    config: Config,
+) -> Result<(), String> {
+    let mut url = Url::parse(&config.endpoint)
+        .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?;
+    if let Some(api_key) = &config.api_key {
+        url.query_pairs_mut().append_pair("apikey", api_key);
+    }
+
+    if config.update_period_secs == 0 {
+        return Err("Monitoring update period must be greater than zero".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Unable to build monitoring client: {:?}", e))?;
+
+    let log = executor.log().clone();
+    let mut interval = tokio::time::interval(Duration::from_secs(config.update_period_secs));
+
+    let interval_future = async move {
+        loop {
+            interval.tick().await;
+
+            let summary = match MonitoringSummary::gather(&beacon_chain, &network) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    error!(log, "Unable to gather monitoring summary"; "error" => e);
+                    continue;
+                }
+            };
+
+            send_summary(&client, url.clone(), &summary, &log).await;
+        }
+    };
+
+    executor.spawn(interval_future, "monitoring");
+
+    Ok(())
+}
+
+async fn send_summary(
+    client: &reqwest::Client,
+    url: Url,
+    summary: &MonitoringSummary,
+    log: &Logger,
+) {
+    let result = client
+        .post(url)
+        .json(summary)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    match result {
+        Ok(_) => debug!(
+            log,
+            "Sent monitoring summary";
+            "head_slot" => summary.sync_beacon_head_slot,
+        ),
+        // Avoid logging the error itself, since it may contain the URL and API key.
+        Err(e) => warn!(
+            log,
+            "Failed to send monitoring summary";
+            "status" => ?e.status(),
+            "timeout" => e.is_timeout(),
+            "connect" => e.is_connect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Epoch, Eth1Data, Hash256, MinimalEthSpec, PublicKeyBytes, Validator};
+
+    fn validator(activation_epoch: u64, exit_epoch: Epoch, slashed: bool) -> Validator {
+        Validator {
+            pubkey: PublicKeyBytes::empty(),
+            withdrawal_credentials: Hash256::zero(),
+            effective_balance: 32_000_000_000,
+            slashed,
+            activation_eligibility_epoch: Epoch::new(0),
+            activation_epoch: Epoch::new(activation_epoch),
+            exit_epoch,
+            withdrawable_epoch: Epoch::max_value(),
+        }
+    }
+
+    #[test]
+    fn validator_summary() {
+        let spec = MinimalEthSpec::default_spec();
+        let mut state = BeaconState::<MinimalEthSpec>::new(0, Eth1Data::default(), &spec);
+        state.slot = Epoch::new(4).start_slot(MinimalEthSpec::slots_per_epoch());
+
+        let validators = vec![
+            validator(0, Epoch::max_value(), false),
+            validator(10, Epoch::max_value(), false),
+            validator(0, Epoch::new(2), true),
+            validator(0, Epoch::max_value(), false),
+        ];
+        for validator in validators {
+            state.validators.push(validator).unwrap();
+        }
+
+        // Five monitored validators: three in the state and two which are yet to appear.
+        let summary = ValidatorSummary::from_state(5, &[0, 1, 2], &state);
+
+        assert_eq!(
+            summary,
+            ValidatorSummary {
+                total: 5,
+                active: 1,
+                pending: 3,
+                exited: 1,
+                slashed: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Checks which are run whilst the beacon node is starting, so that common misconfigurations and
+//! database problems cause a clear error at startup instead of a confusing failure later on.
+//!
+//! The checks can be disabled with `ClientConfig::startup_checks`.
+
+use crate::config::Config;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use slot_clock::SlotClock;
+use std::net::{IpAddr, SocketAddr, TcpListener, UdpSocket};
+use std::time::Duration;
+use store::metadata::CURRENT_SCHEMA_VERSION;
+use types::{EthSpec, Hash256};
+
+/// The time allowed for an eth1 endpoint to respond to the connectivity check.
+const ETH1_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The hint appended to every failed check.
+const DISABLE_HINT: &str = "If you are certain this is a false positive, the startup checks can \
+                            be disabled with --disable-startup-checks";
+
+fn check_failed(check: &str, reason: String, fix: &str) -> String {
+    format!(
+        "Startup check \"{}\" failed: {}. {}. {}.",
+        check, reason, fix, DISABLE_HINT
+    )
+}
+
+/// Ensures that each of the ports the node is configured to listen on is available.
+///
+/// Ports set to zero are chosen by the OS and are not checked.
+pub fn check_ports(config: &Config) -> Result<(), String> {
+    let network = &config.network;
+
+    let mut tcp_ports = vec![("libp2p", network.listen_address, network.libp2p_port)];
+    if config.http_api.enabled {
+        tcp_ports.push((
+            "http api",
+            IpAddr::V4(config.http_api.listen_addr),
+            config.http_api.listen_port,
+        ));
+    }
+    if config.http_metrics.enabled {
+        tcp_ports.push((
+            "http metrics",
+            IpAddr::V4(config.http_metrics.listen_addr),
+            config.http_metrics.listen_port,
+        ));
+    }
+
+    for (service, address, port) in tcp_ports {
+        if port == 0 {
+            continue;
+        }
+        TcpListener::bind(SocketAddr::new(address, port)).map_err(|e| {
+            check_failed(
+                "ports",
+                format!(
+                    "unable to listen on TCP {}:{} for {}: {}",
+                    address, port, service, e
+                ),
+                "Ensure no other process (e.g., another beacon node) is using the port, or \
+                 choose a different port",
+            )
+        })?;
+    }
+
+    if !network.disable_discovery && network.discovery_port != 0 {
+        let address = SocketAddr::new(network.listen_address, network.discovery_port);
+        UdpSocket::bind(address).map_err(|e| {
+            check_failed(
+                "ports",
+                format!("unable to listen on UDP {} for discovery: {}", address, e),
+                "Ensure no other process is using the port, or set --discovery-port",
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Ensures that at least one of the configured eth1 endpoints responds.
+///
+/// Unlike the other checks, a failure here is not fatal: the eth1 node may still be starting and
+/// the eth1 service retries its endpoints, so the caller should only warn.
+pub async fn check_eth1_endpoints(config: &Config) -> Result<(), String> {
+    if !config.sync_eth1_chain || config.dummy_eth1_backend {
+        return Ok(());
+    }
+
+    let mut errors = vec![];
+    for endpoint in &config.eth1.endpoints {
+        match eth1::http::get_network_id(endpoint, ETH1_CHECK_TIMEOUT).await {
+            Ok(_) => return Ok(()),
+            Err(e) => errors.push(format!("{}: {}", endpoint, e)),
+        }
+    }
+
+    Err(format!(
+        "no eth1 endpoint is reachable ({})",
+        errors.join(", ")
+    ))
+}
+
+/// Checks the integrity of the database and the system clock against the loaded beacon chain:
+///
+/// - The database schema version is the one expected by this version of Lighthouse.
+/// - The database was created for the network with `expected_genesis_validators_root`, if known.
+/// - The finalized block is an ancestor of the head block.
+/// - The head block is not in the future according to the system clock.
+pub fn check_beacon_chain<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    expected_genesis_validators_root: Option<Hash256>,
+) -> Result<(), String> {
+    let schema_version = chain
+        .store
+        .load_schema_version()
+        .map_err(|e| format!("Unable to read schema version: {:?}", e))?;
+    if schema_version != Some(CURRENT_SCHEMA_VERSION) {
+        return Err(check_failed(
+            "database schema",
+            format!(
+                "the database schema version is {:?}, expected {}",
+                schema_version.map(|v| v.as_u64()),
+                CURRENT_SCHEMA_VERSION.as_u64()
+            ),
+            "Run a version of Lighthouse which supports this database or re-sync with --purge-db",
+        ));
+    }
+
+    if let Some(expected) = expected_genesis_validators_root {
+        if chain.genesis_validators_root != expected {
+            return Err(check_failed(
+                "network",
+                format!(
+                    "the database has genesis validators root {:?}, this network has {:?}",
+                    chain.genesis_validators_root, expected
+                ),
+                "Ensure --network (or --testnet-dir) matches the network of the database, or use \
+                 a different --datadir",
+            ));
+        }
+    }
+
+    let head = chain
+        .head_info()
+        .map_err(|e| format!("Unable to read head: {:?}", e))?;
+
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let finalized_slot = head.finalized_checkpoint.epoch.start_slot(slots_per_epoch);
+    let finalized_root = if head.finalized_checkpoint.epoch == 0 {
+        chain.genesis_block_root
+    } else {
+        head.finalized_checkpoint.root
+    };
+    let mut finalized_is_ancestor = false;
+    let iter = chain
+        .rev_iter_block_roots_from(head.block_root)
+        .map_err(|e| {
+            check_failed(
+                "database integrity",
+                format!("unable to load the head block or state: {:?}", e),
+                "The database may be corrupt, re-sync with --purge-db",
+            )
+        })?;
+    for result in iter {
+        let (root, slot) = result.map_err(|e| {
+            check_failed(
+                "database integrity",
+                format!("unable to read the ancestors of the head block: {:?}", e),
+                "The database may be corrupt, re-sync with --purge-db",
+            )
+        })?;
+        if root == finalized_root {
+            finalized_is_ancestor = true;
+            break;
+        }
+        if slot < finalized_slot {
+            break;
+        }
+    }
+    if !finalized_is_ancestor {
+        return Err(check_failed(
+            "database integrity",
+            format!(
+                "the finalized block {:?} is not an ancestor of the head block {:?}",
+                finalized_root, head.block_root
+            ),
+            "The database may be corrupt, re-sync with --purge-db",
+        ));
+    }
+
+    let clock_slot = chain.slot_clock.now();
+    if head.slot > clock_slot.unwrap_or_else(|| chain.spec.genesis_slot) {
+        return Err(check_failed(
+            "clock",
+            format!(
+                "the head block is at slot {} but the system clock is at {}",
+                head.slot,
+                clock_slot.map_or_else(
+                    || "a time prior to genesis".to_string(),
+                    |slot| { format!("slot {}", slot) }
+                )
+            ),
+            "Ensure the system clock is correct, e.g. by running an NTP client",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        test_logger, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
+    };
+    use store::{metadata::SchemaVersion, HotColdDB, StoreConfig};
+    use tempfile::{tempdir, TempDir};
+    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn get_harness(db_path: &TempDir) -> BeaconChainHarness<DiskHarnessType<E>> {
+        let store = HotColdDB::open(
+            &db_path.path().join("hot_db"),
+            &db_path.path().join("cold_db"),
+            |_, _, _| Ok(()),
+            StoreConfig::default(),
+            E::default_spec(),
+            test_logger(),
+        )
+        .expect("disk store should initialize");
+        let harness = BeaconChainHarness::new_with_disk_store(
+            MinimalEthSpec,
+            store,
+            generate_deterministic_keypairs(8),
+        );
+        harness.advance_slot();
+        harness.extend_chain(
+            E::slots_per_epoch() as usize * 4,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        harness
+    }
+
+    fn config_with_libp2p_port(port: u16) -> Config {
+        let mut config = Config::default();
+        config.network.listen_address = "127.0.0.1".parse().unwrap();
+        config.network.libp2p_port = port;
+        config.network.disable_discovery = true;
+        config
+    }
+
+    #[test]
+    fn zero_ports_are_not_checked() {
+        assert!(check_ports(&config_with_libp2p_port(0)).is_ok());
+    }
+
+    #[test]
+    fn port_in_use() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let error = check_ports(&config_with_libp2p_port(port)).unwrap_err();
+        assert!(error.contains("libp2p"), "{}", error);
+
+        drop(listener);
+        assert!(check_ports(&config_with_libp2p_port(port)).is_ok());
+    }
+
+    #[test]
+    fn beacon_chain_passes() {
+        let db_path = tempdir().unwrap();
+        let harness = get_harness(&db_path);
+        let chain = &harness.chain;
+
+        assert!(chain.head_info().unwrap().finalized_checkpoint.epoch > 0);
+        assert_eq!(check_beacon_chain(chain, None), Ok(()));
+        assert_eq!(
+            check_beacon_chain(chain, Some(chain.genesis_validators_root)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn beacon_chain_wrong_network() {
+        let db_path = tempdir().unwrap();
+        let harness = get_harness(&db_path);
+
+        let error =
+            check_beacon_chain(&harness.chain, Some(Hash256::repeat_byte(0xff))).unwrap_err();
+        assert!(error.contains("\"network\""), "{}", error);
+    }
+
+    #[test]
+    fn beacon_chain_wrong_schema_version() {
+        let db_path = tempdir().unwrap();
+        let harness = get_harness(&db_path);
+
+        harness
+            .chain
+            .store
+            .store_schema_version(SchemaVersion(CURRENT_SCHEMA_VERSION.as_u64() + 1))
+            .unwrap();
+        let error = check_beacon_chain(&harness.chain, None).unwrap_err();
+        assert!(error.contains("\"database schema\""), "{}", error);
+    }
+
+    #[test]
+    fn beacon_chain_head_in_the_future() {
+        let db_path = tempdir().unwrap();
+        let harness = get_harness(&db_path);
+
+        harness.chain.slot_clock.set_slot(1);
+        let error = check_beacon_chain(&harness.chain, None).unwrap_err();
+        assert!(error.contains("\"clock\""), "{}", error);
+    }
+}
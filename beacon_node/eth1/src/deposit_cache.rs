@@ -235,15 +235,26 @@ impl DepositCache {
                 .get(start as usize..end as usize)
                 .ok_or_else(|| Error::InternalError("Unable to get known log".into()))?
                 .iter()
-                .map(|deposit_log| {
+                .zip(start..)
+                .map(|(deposit_log, expected_index)| {
+                    // The logs are inserted in order, so this should never fail. It guards against
+                    // producing a proof for the wrong deposit index, which would invalidate the
+                    // block.
+                    if deposit_log.index != expected_index {
+                        return Err(Error::NonConsecutive {
+                            log_index: deposit_log.index,
+                            expected: expected_index as usize,
+                        });
+                    }
+
                     let (_leaf, proof) = tree.generate_proof(deposit_log.index as usize);
 
-                    Deposit {
+                    Ok(Deposit {
                         proof: proof.into(),
                         data: deposit_log.deposit_data.clone(),
-                    }
+                    })
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
 
             Ok((tree.root(), deposits))
         }
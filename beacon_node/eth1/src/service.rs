@@ -60,6 +60,7 @@ pub struct EndpointsCache {
     pub fallback: Fallback<EndpointWithState>,
     pub config_network_id: Eth1Id,
     pub config_chain_id: Eth1Id,
+    pub timeout_multiplier: u32,
     pub log: Logger,
 }
 
@@ -79,6 +80,7 @@ impl EndpointsCache {
             &endpoint.0,
             &self.config_network_id,
             &self.config_chain_id,
+            self.timeout_multiplier,
             &self.log,
         )
         .await;
@@ -134,8 +136,10 @@ async fn endpoint_state(
     endpoint: &str,
     config_network_id: &Eth1Id,
     config_chain_id: &Eth1Id,
+    timeout_multiplier: u32,
     log: &Logger,
 ) -> EndpointState {
+    let timeout = Duration::from_millis(STANDARD_TIMEOUT_MILLIS) * timeout_multiplier;
     let error_connecting = |_| {
         warn!(
             log,
@@ -145,7 +149,7 @@ async fn endpoint_state(
         );
         EndpointError::NotReachable
     };
-    let network_id = get_network_id(endpoint, Duration::from_millis(STANDARD_TIMEOUT_MILLIS))
+    let network_id = get_network_id(endpoint, timeout)
         .await
         .map_err(error_connecting)?;
     if &network_id != config_network_id {
@@ -159,7 +163,7 @@ async fn endpoint_state(
         );
         return Err(EndpointError::WrongNetworkId);
     }
-    let chain_id = get_chain_id(endpoint, Duration::from_millis(STANDARD_TIMEOUT_MILLIS))
+    let chain_id = get_chain_id(endpoint, timeout)
         .await
         .map_err(error_connecting)?;
     // Eth1 nodes return chain_id = 0 if the node is not synced
@@ -256,10 +260,10 @@ async fn relevant_new_block_numbers_from_endpoint(
     service: &Service,
     head_type: HeadType,
 ) -> Result<Option<RangeInclusive<u64>>, SingleEndpointError> {
-    let remote_highest_block =
-        get_block_number(endpoint, Duration::from_millis(BLOCK_NUMBER_TIMEOUT_MILLIS))
-            .map_err(SingleEndpointError::GetBlockNumberFailed)
-            .await?;
+    let timeout = service.config().timeout(BLOCK_NUMBER_TIMEOUT_MILLIS);
+    let remote_highest_block = get_block_number(endpoint, timeout)
+        .map_err(SingleEndpointError::GetBlockNumberFailed)
+        .await?;
     service.relevant_new_block_numbers(remote_highest_block, head_type)
 }
 
@@ -353,9 +357,16 @@ pub struct Config {
     pub max_blocks_per_update: Option<usize>,
     /// If set to true, the eth1 caches are wiped clean when the eth1 service starts.
     pub purge_cache: bool,
+    /// Multiplies the timeout of each request to the eth1 node, for slow hardware or eth1 nodes.
+    pub timeout_multiplier: u32,
 }
 
 impl Config {
+    /// Returns the timeout for a request with a default timeout of `millis`.
+    fn timeout(&self, millis: u64) -> Duration {
+        Duration::from_millis(millis) * self.timeout_multiplier
+    }
+
     /// Sets the block cache to a length that is suitable for the given `EthSpec` and `ChainSpec`.
     pub fn set_block_cache_truncation<E: EthSpec>(&mut self, spec: &ChainSpec) {
         // Compute the number of eth1 blocks in an eth1 voting period.
@@ -397,6 +408,7 @@ impl Default for Config {
             max_log_requests_per_update: Some(100),
             max_blocks_per_update: Some(8_192),
             purge_cache: false,
+            timeout_multiplier: 1,
         }
     }
 }
@@ -596,6 +608,7 @@ impl Service {
         let endpoints = self.config().endpoints.clone();
         let config_network_id = self.config().network_id.clone();
         let config_chain_id = self.config().chain_id.clone();
+        let timeout_multiplier = self.config().timeout_multiplier;
         EndpointsCache {
             fallback: Fallback::new(
                 endpoints
@@ -605,6 +618,7 @@ impl Service {
             ),
             config_network_id,
             config_chain_id,
+            timeout_multiplier,
             log: self.log.clone(),
         }
     }
@@ -833,6 +847,7 @@ impl Service {
 
         let mut logs_imported: usize = 0;
         let deposit_contract_address_ref: &str = &deposit_contract_address;
+        let deposit_log_timeout = self.config().timeout(GET_DEPOSIT_LOG_TIMEOUT_MILLIS);
         for block_range in block_number_chunks.into_iter() {
             if block_range.is_empty() {
                 debug!(
@@ -852,7 +867,7 @@ impl Service {
                         e,
                         &deposit_contract_address_ref,
                         block_range_ref.clone(),
-                        Duration::from_millis(GET_DEPOSIT_LOG_TIMEOUT_MILLIS),
+                        deposit_log_timeout,
                     )
                     .await
                     .map_err(SingleEndpointError::GetDepositLogsFailed)
@@ -1157,13 +1172,15 @@ async fn download_eth1_block(
             .get_deposit_count_from_cache(block_number)
     });
 
+    let timeout = cache.config.read().timeout(GET_BLOCK_TIMEOUT_MILLIS);
+
     // Performs a `get_blockByNumber` call to an eth1 node.
     let http_block = get_block(
         endpoint,
         block_number_opt
             .map(BlockQuery::Number)
             .unwrap_or_else(|| BlockQuery::Latest),
-        Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+        timeout,
     )
     .map_err(SingleEndpointError::BlockDownloadFailed)
     .await?;
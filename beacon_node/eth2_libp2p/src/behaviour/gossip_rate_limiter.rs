@@ -0,0 +1,54 @@
+use crate::rpc::rate_limiter::{Limiter, Quota};
+use crate::types::{GossipKind, GossipRateLimit};
+use libp2p::gossipsub::TopicHash;
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Limits the rate of gossip messages received from each peer on each topic, according to the
+/// `GossipRateLimit`s in the network config.
+///
+/// Messages on topics without a configured limit are always allowed.
+pub struct GossipRateLimiter {
+    /// Creation time of the rate limiter.
+    init_time: Instant,
+    /// A limiter for each limited topic kind.
+    limiters: HashMap<String, Limiter<(PeerId, TopicHash)>>,
+}
+
+impl GossipRateLimiter {
+    pub fn new(limits: &[GossipRateLimit]) -> Result<Self, String> {
+        let limiters = limits
+            .iter()
+            .map(|limit| {
+                let limiter = Limiter::from_quota(Quota::n_every(limit.messages, limit.period))
+                    .map_err(|e| format!("Invalid rate limit for {}: {}", limit.kind, e))?;
+                Ok((limit.kind.clone(), limiter))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            init_time: Instant::now(),
+            limiters,
+        })
+    }
+
+    /// Returns `true` if `peer_id` may send another message on `topic`.
+    pub fn allows(&mut self, peer_id: &PeerId, topic: &TopicHash, kind: &GossipKind) -> bool {
+        let time_since_start = self.init_time.elapsed();
+        match self.limiters.get_mut(kind.as_ref()) {
+            Some(limiter) => limiter
+                .allows(time_since_start, &(*peer_id, topic.clone()), 1)
+                .is_ok(),
+            None => true,
+        }
+    }
+
+    /// Removes peers which have not sent messages recently.
+    pub fn prune(&mut self) {
+        let time_since_start = self.init_time.elapsed();
+        for limiter in self.limiters.values_mut() {
+            limiter.prune(time_since_start);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::behaviour::gossip_rate_limiter::GossipRateLimiter;
 use crate::behaviour::gossipsub_scoring_parameters::PeerScoreSettings;
 use crate::peer_manager::{
     score::{PeerAction, ReportSource},
@@ -6,8 +7,8 @@ use crate::peer_manager::{
 use crate::rpc::*;
 use crate::service::METADATA_FILENAME;
 use crate::types::{
-    subnet_id_from_topic_hash, GossipEncoding, GossipKind, GossipTopic, SnappyTransform,
-    SubnetDiscovery,
+    subnet_id_from_topic_hash, GossipEncoding, GossipKind, GossipSizeLimits, GossipTopic,
+    SnappyTransform, SubnetDiscovery,
 };
 use crate::Eth2Enr;
 use crate::{error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
@@ -45,6 +46,7 @@ use std::{
 };
 use types::{ChainSpec, EnrForkId, EthSpec, SignedBeaconBlock, Slot, SubnetId};
 
+mod gossip_rate_limiter;
 mod gossipsub_scoring_parameters;
 mod handler;
 
@@ -138,6 +140,9 @@ pub struct Behaviour<TSpec: EthSpec> {
 
     /// The interval for updating gossipsub scores
     update_gossipsub_scores: tokio::time::Interval,
+
+    /// Limits the rate of messages each peer may send on each gossip topic.
+    gossip_rate_limiter: GossipRateLimiter,
 }
 
 /// Implements the combined behaviour for the libp2p service.
@@ -178,7 +183,13 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         };
 
         // Initialize the compression transform.
-        let snappy_transform = SnappyTransform::new(net_conf.gs_config.max_transmit_size());
+        let max_transmit_size = net_conf.gs_config.max_transmit_size();
+        let snappy_transform = SnappyTransform::new(
+            max_transmit_size,
+            GossipSizeLimits::new::<TSpec>(max_transmit_size),
+        );
+
+        let gossip_rate_limiter = GossipRateLimiter::new(&net_conf.gossip_rate_limits)?;
 
         let mut gossipsub = Gossipsub::new_with_subscription_filter_and_transform(
             MessageAuthenticity::Anonymous,
@@ -233,6 +244,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             log: behaviour_log,
             score_settings,
             update_gossipsub_scores,
+            gossip_rate_limiter,
         })
     }
 
@@ -638,6 +650,35 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             } => {
                 // Note: We are keeping track here of the peer that sent us the message, not the
                 // peer that originally published the message.
+                if let Ok(topic) = GossipTopic::decode(gs_msg.topic.as_str()) {
+                    if !self.gossip_rate_limiter.allows(
+                        &propagation_source,
+                        &gs_msg.topic,
+                        topic.kind(),
+                    ) {
+                        debug!(self.log, "Peer exceeded gossip rate limit"; "peer_id" => %propagation_source, "topic" => %topic.kind());
+                        metrics::inc_counter_vec(
+                            &metrics::GOSSIP_RATE_LIMITED_MESSAGES_PER_KIND,
+                            &[topic.kind().as_ref()],
+                        );
+                        // Ignore rather than reject the message, since it may be valid. The peer
+                        // is penalized separately.
+                        if let Err(e) = self.gossipsub.report_message_validation_result(
+                            &id,
+                            &propagation_source,
+                            MessageAcceptance::Ignore,
+                        ) {
+                            warn!(self.log, "Failed to report message validation"; "message_id" => %id, "peer_id" => %propagation_source, "error" => ?e);
+                        }
+                        self.peer_manager.report_peer(
+                            &propagation_source,
+                            PeerAction::HighToleranceError,
+                            ReportSource::Gossipsub,
+                        );
+                        return;
+                    }
+                }
+
                 match PubsubMessage::decode(&gs_msg.topic, &gs_msg.data) {
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "error" => e);
@@ -895,6 +936,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         // perform gossipsub score updates when necessary
         while self.update_gossipsub_scores.poll_tick(cx).is_ready() {
             self.peer_manager.update_gossipsub_scores(&self.gossipsub);
+            self.gossip_rate_limiter.prune();
         }
 
         Poll::Pending
@@ -915,7 +957,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                     info.listen_addrs.truncate(MAX_IDENTIFY_ADDRESSES);
                 }
                 // send peer info to the peer manager.
-                self.peer_manager.identify(&peer_id, &info);
+                self.peer_manager.identify(&peer_id, &info, &observed_addr);
 
                 debug!(self.log, "Identified Peer"; "peer" => %peer_id,
                     "protocol_version" => info.protocol_version,
@@ -1,4 +1,4 @@
-use crate::types::GossipKind;
+use crate::types::{GossipKind, GossipRateLimit};
 use crate::{Enr, PeerIdSerialized};
 use directory::{
     DEFAULT_BEACON_NODE_DIR, DEFAULT_HARDCODED_NETWORK, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR,
@@ -93,6 +93,9 @@ pub struct Config {
 
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
+
+    /// Limits on the rate of messages each peer may send on each kind of gossip topic.
+    pub gossip_rate_limits: Vec<GossipRateLimit>,
 }
 
 impl Default for Config {
@@ -189,6 +192,7 @@ impl Default for Config {
             subscribe_all_subnets: false,
             import_all_attestations: false,
             topics: Vec::new(),
+            gossip_rate_limits: Vec::new(),
         }
     }
 }
@@ -409,6 +409,24 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Ok(())
     }
 
+    /// Updates the IP address in the local ENR, leaving the ports unchanged.
+    ///
+    /// This is used when our peers agree on an external address which differs from the one
+    /// discovered (or not discovered) by discv5, e.g., an IPv6 address.
+    pub fn update_enr_ip(&mut self, ip: IpAddr) -> Result<(), String> {
+        match ip {
+            IpAddr::V4(ip) => self.discv5.enr_insert("ip", &ip.octets()),
+            IpAddr::V6(ip) => self.discv5.enr_insert("ip6", &ip.octets()),
+        }
+        .map_err(|e| format!("{:?}", e))?;
+
+        // replace the global version
+        *self.network_globals.local_enr.write() = self.discv5.local_enr();
+        // persist modified enr to disk
+        enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
+        Ok(())
+    }
+
     /// Adds/Removes a subnet from the ENR Bitfield
     pub fn update_enr_bitfield(&mut self, subnet_id: SubnetId, value: bool) -> Result<(), String> {
         let id = *subnet_id as usize;
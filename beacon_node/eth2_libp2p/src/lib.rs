@@ -75,4 +75,7 @@ pub use peer_manager::{
     score::{PeerAction, ReportSource},
     ConnectionDirection, PeerConnectionStatus, PeerDB, PeerInfo, PeerSyncStatus, SyncInfo,
 };
-pub use service::{load_private_key, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
+pub use service::{
+    keypair_from_hex, load_private_key, load_private_key_from_disk, save_private_key_to_disk,
+    Libp2pEvent, Service, NETWORK_KEY_FILENAME,
+};
@@ -45,6 +45,18 @@ lazy_static! {
             "Failed attestation publishes per subnet",
             &["subnet"]
         );
+    pub static ref GOSSIP_OVERSIZED_MESSAGES_PER_KIND: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_oversized_messages_per_kind_total",
+            "Count of gossip messages rejected for exceeding the maximum size of their topic",
+            &["kind"]
+        );
+    pub static ref GOSSIP_RATE_LIMITED_MESSAGES_PER_KIND: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_rate_limited_messages_per_kind_total",
+            "Count of gossip messages ignored because the sending peer exceeded the rate limit",
+            &["kind"]
+        );
     pub static ref FAILED_PUBLISHES_PER_MAIN_TOPIC: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "gossipsub_failed_publishes_per_main_topic",
         "Failed gossip publishes",
@@ -11,10 +11,10 @@ use futures::Stream;
 use hashset_delay::HashSetDelay;
 use libp2p::core::multiaddr::Protocol as MProtocol;
 use libp2p::identify::IdentifyInfo;
-use slog::{crit, debug, error, trace, warn};
+use slog::{crit, debug, error, info, trace, warn};
 use smallvec::SmallVec;
 use std::{
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -25,12 +25,14 @@ use types::{EthSpec, SubnetId};
 pub use libp2p::core::{identity::Keypair, Multiaddr};
 
 pub mod client;
+mod observed_addrs;
 mod peer_info;
 mod peer_sync_status;
 #[allow(clippy::mutable_key_type)] // PeerId in hashmaps are no longer permitted by clippy
 mod peerdb;
 pub(crate) mod score;
 
+use observed_addrs::ObservedAddresses;
 pub use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo};
 pub use peer_sync_status::{PeerSyncStatus, SyncInfo};
 use score::{PeerAction, ReportSource, ScoreState};
@@ -78,6 +80,11 @@ pub struct PeerManager<TSpec: EthSpec> {
     max_peers: usize,
     /// The discovery service.
     discovery: Discovery<TSpec>,
+    /// Our external IP address as reported by our peers.
+    observed_addresses: ObservedAddresses,
+    /// If `true`, the IP address in our ENR is updated from `observed_addresses`. Disabled when
+    /// the user provides an ENR address explicitly or disables ENR auto-updates.
+    update_enr_from_observed_addresses: bool,
     /// The heartbeat interval to perform routine maintenance.
     heartbeat: tokio::time::Interval,
     /// The logger associated with the `PeerManager`.
@@ -125,6 +132,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             target_peers: config.target_peers,
             max_peers: (config.target_peers as f32 * (1.0 + PEER_EXCESS_FACTOR)).ceil() as usize,
             discovery,
+            observed_addresses: ObservedAddresses::default(),
+            update_enr_from_observed_addresses: config.enr_address.is_none()
+                && config.discv5_config.enr_update,
             heartbeat,
             log: log.clone(),
         })
@@ -317,6 +327,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.inbound_ping_peers.remove(peer_id);
         self.outbound_ping_peers.remove(peer_id);
         self.status_peers.remove(peer_id);
+        self.observed_addresses.remove(peer_id);
     }
 
     /// A dial attempt has failed.
@@ -360,7 +371,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     }
 
     /// Updates `PeerInfo` with `identify` information.
-    pub fn identify(&mut self, peer_id: &PeerId, info: &IdentifyInfo) {
+    ///
+    /// The `observed_addr` is our own address, as seen by the peer.
+    pub fn identify(&mut self, peer_id: &PeerId, info: &IdentifyInfo, observed_addr: &Multiaddr) {
         if let Some(peer_info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
             let previous_kind = peer_info.client.kind.clone();
             peer_info.client = client::Client::from_identify_info(info);
@@ -383,6 +396,11 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             }
         } else {
             crit!(self.log, "Received an Identify response from an unknown peer"; "peer_id" => peer_id.to_string());
+            return;
+        }
+
+        if self.update_enr_from_observed_addresses {
+            self.observed_address(*peer_id, observed_addr);
         }
     }
 
@@ -645,6 +663,44 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
     /* Internal functions */
 
+    /// Records the external address reported by a peer, updating the IP address in our ENR if a
+    /// majority of peers agree on a new address.
+    fn observed_address(&mut self, peer_id: PeerId, observed_addr: &Multiaddr) {
+        let ip = match observed_addr.iter().next() {
+            Some(MProtocol::Ip4(ip)) => IpAddr::V4(ip),
+            Some(MProtocol::Ip6(ip)) => IpAddr::V6(ip),
+            _ => return,
+        };
+
+        let new_ip = match self.observed_addresses.observe(peer_id, ip) {
+            Some(new_ip) => new_ip,
+            None => return,
+        };
+
+        let local_enr = self.discovery.local_enr();
+        let current_ip = match new_ip {
+            IpAddr::V4(_) => local_enr.ip().map(IpAddr::V4),
+            IpAddr::V6(_) => local_enr.ip6().map(IpAddr::V6),
+        };
+        if current_ip == Some(new_ip) {
+            return;
+        }
+
+        match self.discovery.update_enr_ip(new_ip) {
+            Ok(()) => {
+                info!(self.log, "Updated ENR address from peer observations"; "ip" => %new_ip, "previous_ip" => ?current_ip);
+                metrics::inc_counter(&metrics::ADDRESS_UPDATE_COUNT);
+
+                let mut multiaddr = Multiaddr::from(new_ip);
+                multiaddr.push(MProtocol::Tcp(self.network_globals.listen_port_tcp()));
+                self.events.push(PeerManagerEvent::SocketUpdated(multiaddr));
+            }
+            Err(e) => {
+                warn!(self.log, "Failed to update ENR address"; "ip" => %new_ip, "error" => e)
+            }
+        }
+    }
+
     // The underlying discovery server has updated our external IP address. We send this up to
     // notify libp2p.
     fn socket_updated(&mut self, socket: SocketAddr) {
@@ -0,0 +1,181 @@
+//! Tallies the external IP addresses that our peers report observing for us via the identify
+//! protocol.
+//!
+//! When a node is behind a NAT (or only has a routable IPv6 address) it may not know the address
+//! other nodes should use to reach it. Each peer's view is a single vote. Once enough peers agree
+//! on an address, the peer manager advertises it in our ENR.
+
+use crate::PeerId;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// The minimum number of distinct peers which must report an address before it is adopted.
+pub const MIN_OBSERVED_ADDRESS_VOTES: usize = 5;
+
+/// How long a peer's vote remains valid.
+pub const OBSERVED_ADDRESS_EXPIRY: Duration = Duration::from_secs(30 * 60);
+
+/// Returns `true` if `ip` could plausibly be reached from the public internet.
+fn is_global(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => {
+            let first_segment = ip.segments()[0];
+            // Exclude unique local (fc00::/7) and link-local (fe80::/10) addresses.
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || first_segment & 0xfe00 == 0xfc00
+                || first_segment & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// See the module-level documentation.
+#[derive(Default)]
+pub struct ObservedAddresses {
+    votes: HashMap<PeerId, (IpAddr, Instant)>,
+}
+
+impl ObservedAddresses {
+    /// Records that `peer_id` observed us at `ip`.
+    ///
+    /// Returns the address which has the support of a majority of the peers which reported an
+    /// address of the same family (IPv4 or IPv6) as `ip`, provided it has at least
+    /// `MIN_OBSERVED_ADDRESS_VOTES` votes.
+    pub fn observe(&mut self, peer_id: PeerId, ip: IpAddr) -> Option<IpAddr> {
+        self.observe_at(peer_id, ip, Instant::now())
+    }
+
+    fn observe_at(&mut self, peer_id: PeerId, ip: IpAddr, now: Instant) -> Option<IpAddr> {
+        if !is_global(&ip) {
+            return None;
+        }
+
+        self.votes.insert(peer_id, (ip, now));
+        self.votes.retain(|_, (_, observed_at)| {
+            now.checked_duration_since(*observed_at)
+                .map_or(true, |age| age <= OBSERVED_ADDRESS_EXPIRY)
+        });
+
+        let mut tally: HashMap<IpAddr, usize> = HashMap::new();
+        let mut total = 0;
+        for (vote, _) in self.votes.values() {
+            if vote.is_ipv4() == ip.is_ipv4() {
+                *tally.entry(*vote).or_default() += 1;
+                total += 1;
+            }
+        }
+
+        tally
+            .into_iter()
+            .find(|(_, votes)| *votes >= MIN_OBSERVED_ADDRESS_VOTES && votes * 2 > total)
+            .map(|(ip, _)| ip)
+    }
+
+    /// Removes the vote of `peer_id`, e.g., when it disconnects.
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.votes.remove(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    const IP_A: IpAddr = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+    const IP_B: IpAddr = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+
+    #[test]
+    fn requires_minimum_votes() {
+        let mut observed = ObservedAddresses::default();
+
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES - 1 {
+            assert_eq!(observed.observe(PeerId::random(), IP_A), None);
+        }
+        assert_eq!(observed.observe(PeerId::random(), IP_A), Some(IP_A));
+    }
+
+    #[test]
+    fn requires_majority() {
+        let mut observed = ObservedAddresses::default();
+
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES {
+            observed.observe(PeerId::random(), IP_B);
+        }
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES - 1 {
+            observed.observe(PeerId::random(), IP_A);
+        }
+        assert_eq!(
+            observed.observe(PeerId::random(), IP_A),
+            None,
+            "a tie is not a majority"
+        );
+        assert_eq!(observed.observe(PeerId::random(), IP_A), Some(IP_A));
+    }
+
+    #[test]
+    fn families_are_tallied_separately() {
+        let mut observed = ObservedAddresses::default();
+        let ip6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb9, 0, 0, 0, 0, 0, 1));
+
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES {
+            observed.observe(PeerId::random(), IP_A);
+        }
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES - 1 {
+            observed.observe(PeerId::random(), ip6);
+        }
+        assert_eq!(observed.observe(PeerId::random(), ip6), Some(ip6));
+        assert_eq!(observed.observe(PeerId::random(), IP_A), Some(IP_A));
+    }
+
+    #[test]
+    fn repeat_and_expired_votes() {
+        let mut observed = ObservedAddresses::default();
+        let peer_id = PeerId::random();
+        let then = Instant::now();
+        let now = then + OBSERVED_ADDRESS_EXPIRY + Duration::from_secs(1);
+
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES {
+            assert_eq!(
+                observed.observe_at(peer_id, IP_A, then),
+                None,
+                "a peer only has one vote"
+            );
+        }
+
+        for _ in 0..MIN_OBSERVED_ADDRESS_VOTES - 1 {
+            observed.observe_at(PeerId::random(), IP_A, then);
+        }
+        assert_eq!(
+            observed.observe_at(PeerId::random(), IP_A, now),
+            None,
+            "expired votes are not counted"
+        );
+    }
+
+    #[test]
+    fn ignores_local_addresses() {
+        let mut observed = ObservedAddresses::default();
+
+        for ip in &[
+            IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+            IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+            IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1)),
+        ] {
+            for _ in 0..MIN_OBSERVED_ADDRESS_VOTES {
+                assert_eq!(observed.observe(PeerId::random(), *ip), None);
+            }
+        }
+    }
+}
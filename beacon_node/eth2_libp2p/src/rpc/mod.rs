@@ -34,7 +34,7 @@ pub(crate) mod codec;
 mod handler;
 pub mod methods;
 mod protocol;
-mod rate_limiter;
+pub(crate) mod rate_limiter;
 
 /// RPC events sent from Lighthouse.
 #[derive(Debug, Clone)]
@@ -55,6 +55,16 @@ pub struct Quota {
     max_tokens: u64,
 }
 
+impl Quota {
+    /// Allow `n` tokens to be used every `time_period`.
+    pub fn n_every(n: u64, time_period: Duration) -> Self {
+        Quota {
+            max_tokens: n,
+            replenish_all_every: time_period,
+        }
+    }
+}
+
 /// Manages rate limiting of requests per peer, with differentiated rates per protocol.
 pub struct RPCRateLimiter {
     /// Interval to prune peers for which their timer ran out.
@@ -20,8 +20,9 @@ use libp2p::{
 };
 use slog::{crit, debug, info, o, trace, warn, Logger};
 use ssz::Decode;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
@@ -380,9 +381,8 @@ fn build_transport(
     ))
 }
 
-// Useful helper functions for debugging. Currently not used in the client.
-#[allow(dead_code)]
-fn keypair_from_hex(hex_bytes: &str) -> error::Result<Keypair> {
+/// Parses a hex-encoded secp256k1 secret key, with or without a `0x` prefix.
+pub fn keypair_from_hex(hex_bytes: &str) -> error::Result<Keypair> {
     let hex_bytes = if let Some(stripped) = hex_bytes.strip_prefix("0x") {
         stripped.to_string()
     } else {
@@ -394,7 +394,6 @@ fn keypair_from_hex(hex_bytes: &str) -> error::Result<Keypair> {
         .and_then(keypair_from_bytes)
 }
 
-#[allow(dead_code)]
 fn keypair_from_bytes(mut bytes: Vec<u8>) -> error::Result<Keypair> {
     libp2p::core::identity::secp256k1::SecretKey::from_bytes(&mut bytes)
         .map(|secret| {
@@ -410,47 +409,55 @@ fn keypair_from_bytes(mut bytes: Vec<u8>) -> error::Result<Keypair> {
 /// Currently only secp256k1 keys are allowed, as these are the only keys supported by discv5.
 pub fn load_private_key(config: &NetworkConfig, log: &slog::Logger) -> Keypair {
     // check for key from disk
-    let network_key_f = config.network_dir.join(NETWORK_KEY_FILENAME);
-    if let Ok(mut network_key_file) = File::open(network_key_f.clone()) {
-        let mut key_bytes: Vec<u8> = Vec::with_capacity(36);
-        match network_key_file.read_to_end(&mut key_bytes) {
-            Err(_) => debug!(log, "Could not read network key file"),
-            Ok(_) => {
-                // only accept secp256k1 keys for now
-                if let Ok(secret_key) =
-                    libp2p::core::identity::secp256k1::SecretKey::from_bytes(&mut key_bytes)
-                {
-                    let kp: libp2p::core::identity::secp256k1::Keypair = secret_key.into();
-                    debug!(log, "Loaded network key from disk.");
-                    return Keypair::Secp256k1(kp);
-                } else {
-                    debug!(log, "Network key file is not a valid secp256k1 key");
-                }
-            }
+    match load_private_key_from_disk(&config.network_dir) {
+        Ok(keypair) => {
+            debug!(log, "Loaded network key from disk.");
+            return keypair;
         }
+        Err(e) => debug!(log, "Could not load network key from disk"; "error" => e),
     }
 
     // if a key could not be loaded from disk, generate a new one and save it
     let local_private_key = Keypair::generate_secp256k1();
-    if let Keypair::Secp256k1(key) = local_private_key.clone() {
-        let _ = std::fs::create_dir_all(&config.network_dir);
-        match File::create(network_key_f.clone())
-            .and_then(|mut f| f.write_all(&key.secret().to_bytes()))
-        {
-            Ok(_) => {
-                debug!(log, "New network key generated and written to disk");
-            }
-            Err(e) => {
-                warn!(
-                    log,
-                    "Could not write node key to file: {:?}. error: {}", network_key_f, e
-                );
-            }
+    match save_private_key_to_disk(&config.network_dir, &local_private_key) {
+        Ok(_) => {
+            debug!(log, "New network key generated and written to disk");
+        }
+        Err(e) => {
+            warn!(log, "Could not write node key to file"; "error" => e);
         }
     }
     local_private_key
 }
 
+/// Loads the secp256k1 network key stored in `dir`.
+pub fn load_private_key_from_disk(dir: &Path) -> Result<Keypair, String> {
+    let network_key_f = dir.join(NETWORK_KEY_FILENAME);
+    let key_bytes = fs::read(&network_key_f)
+        .map_err(|e| format!("Could not read network key file {:?}: {}", network_key_f, e))?;
+    // only accept secp256k1 keys for now
+    keypair_from_bytes(key_bytes)
+        .map_err(|_| "Network key file is not a valid secp256k1 key".to_string())
+}
+
+/// Saves a secp256k1 network key to `dir`, replacing any existing key.
+pub fn save_private_key_to_disk(dir: &Path, keypair: &Keypair) -> Result<(), String> {
+    let secret_bytes = match keypair {
+        Keypair::Secp256k1(key) => key.secret().to_bytes(),
+        _ => return Err("Key is not a secp256k1 key".into()),
+    };
+    let network_key_f = dir.join(NETWORK_KEY_FILENAME);
+    fs::create_dir_all(dir)
+        .and_then(|_| File::create(&network_key_f))
+        .and_then(|mut f| f.write_all(&secret_bytes))
+        .map_err(|e| {
+            format!(
+                "Could not write node key to file {:?}: {}",
+                network_key_f, e
+            )
+        })
+}
+
 /// Generate authenticated XX Noise config from identity keys
 fn generate_noise_config(
     identity_keypair: &Keypair,
@@ -511,3 +518,19 @@ fn load_or_build_metadata<E: EthSpec>(
     save_metadata_to_disk(network_dir, meta_data.clone(), &log);
     meta_data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_key_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_private_key_from_disk(dir.path()).is_err());
+
+        let keypair = Keypair::generate_secp256k1();
+        save_private_key_to_disk(dir.path(), &keypair).unwrap();
+        let loaded = load_private_key_from_disk(dir.path()).unwrap();
+        assert_eq!(loaded.public(), keypair.public());
+    }
+}
@@ -0,0 +1,157 @@
+//! Tracks whether the finalized checkpoints advertised by peers agree with our own.
+//!
+//! Peers with a conflicting finalized checkpoint are disconnected during the `Status` handshake,
+//! so they will not remain in the peer DB. Instead, we remember the verdict for each peer for
+//! `OBSERVATION_WINDOW` so that we can detect when a supermajority of the network disagrees with
+//! us, which indicates that the local node may be on a minority fork.
+
+use crate::PeerId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long an observation of a peer's finalized checkpoint is considered relevant.
+pub const OBSERVATION_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// The minimum number of peers that must have been observed before we are willing to declare a
+/// chain split. This avoids raising an alarm when we have only seen a handful of peers.
+pub const MIN_OBSERVED_PEERS: usize = 8;
+
+/// The fraction of observed peers (expressed as `numerator / denominator`) which must disagree
+/// with our finalized checkpoint before a chain split is declared.
+pub const DISAGREEMENT_NUMERATOR: usize = 2;
+pub const DISAGREEMENT_DENOMINATOR: usize = 3;
+
+/// A summary of how our finalized checkpoint compares with those of recently observed peers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainSplitStatus {
+    /// The number of peers which advertised a finalized checkpoint on our chain.
+    pub agreeing_peers: usize,
+    /// The number of peers which advertised a finalized checkpoint that conflicts with our chain.
+    pub disagreeing_peers: usize,
+    /// Set to `true` if a supermajority of observed peers disagree with our finalized checkpoint.
+    pub minority_fork: bool,
+}
+
+/// See the module-level documentation.
+#[derive(Default)]
+pub struct FinalizedCheckpointObservations {
+    observations: HashMap<PeerId, (bool, Instant)>,
+}
+
+impl FinalizedCheckpointObservations {
+    /// Record whether or not `peer_id` advertised a finalized checkpoint which agrees with ours.
+    ///
+    /// Returns the updated status.
+    pub fn observe(&mut self, peer_id: PeerId, agrees: bool) -> ChainSplitStatus {
+        self.observe_at(peer_id, agrees, Instant::now())
+    }
+
+    fn observe_at(&mut self, peer_id: PeerId, agrees: bool, now: Instant) -> ChainSplitStatus {
+        self.observations.insert(peer_id, (agrees, now));
+        self.prune(now);
+        self.status()
+    }
+
+    /// Remove all observations which are older than `OBSERVATION_WINDOW`.
+    fn prune(&mut self, now: Instant) {
+        self.observations.retain(|_, (_, observed_at)| {
+            now.checked_duration_since(*observed_at)
+                .map_or(true, |age| age <= OBSERVATION_WINDOW)
+        });
+    }
+
+    /// Returns a summary of the current observations.
+    pub fn status(&self) -> ChainSplitStatus {
+        let agreeing_peers = self
+            .observations
+            .values()
+            .filter(|(agrees, _)| *agrees)
+            .count();
+        let disagreeing_peers = self.observations.len() - agreeing_peers;
+        let observed = self.observations.len();
+
+        ChainSplitStatus {
+            agreeing_peers,
+            disagreeing_peers,
+            minority_fork: observed >= MIN_OBSERVED_PEERS
+                && disagreeing_peers * DISAGREEMENT_DENOMINATOR
+                    >= observed * DISAGREEMENT_NUMERATOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observe_many(
+        observations: &mut FinalizedCheckpointObservations,
+        n: usize,
+        agrees: bool,
+        now: Instant,
+    ) -> ChainSplitStatus {
+        let mut status = observations.status();
+        for _ in 0..n {
+            status = observations.observe_at(PeerId::random(), agrees, now);
+        }
+        status
+    }
+
+    #[test]
+    fn no_split_with_too_few_peers() {
+        let mut observations = FinalizedCheckpointObservations::default();
+        let status = observe_many(
+            &mut observations,
+            MIN_OBSERVED_PEERS - 1,
+            false,
+            Instant::now(),
+        );
+
+        assert_eq!(status.disagreeing_peers, MIN_OBSERVED_PEERS - 1);
+        assert!(!status.minority_fork);
+    }
+
+    #[test]
+    fn split_with_supermajority_disagreement() {
+        let mut observations = FinalizedCheckpointObservations::default();
+        let now = Instant::now();
+
+        observe_many(&mut observations, 3, true, now);
+        let status = observe_many(&mut observations, 5, false, now);
+        assert!(!status.minority_fork, "5/8 is below the threshold");
+
+        let status = observe_many(&mut observations, 1, false, now);
+        assert_eq!(status.agreeing_peers, 3);
+        assert_eq!(status.disagreeing_peers, 6);
+        assert!(status.minority_fork, "6/9 meets the threshold");
+    }
+
+    #[test]
+    fn repeated_observations_replace_prior() {
+        let mut observations = FinalizedCheckpointObservations::default();
+        let now = Instant::now();
+        let peer_id = PeerId::random();
+
+        observations.observe_at(peer_id, false, now);
+        let status = observations.observe_at(peer_id, true, now);
+
+        assert_eq!(status.agreeing_peers, 1);
+        assert_eq!(status.disagreeing_peers, 0);
+    }
+
+    #[test]
+    fn old_observations_are_pruned() {
+        let mut observations = FinalizedCheckpointObservations::default();
+        let then = Instant::now();
+        let now = then + OBSERVATION_WINDOW + Duration::from_secs(1);
+
+        observe_many(&mut observations, MIN_OBSERVED_PEERS, false, then);
+        assert!(observations.status().minority_fork);
+
+        let status = observations.observe_at(PeerId::random(), true, now);
+        assert_eq!(status.disagreeing_peers, 0);
+        assert_eq!(status.agreeing_peers, 1);
+        assert!(!status.minority_fork);
+    }
+}
@@ -1,7 +1,7 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::PeerDB;
 use crate::rpc::MetaData;
-use crate::types::SyncState;
+use crate::types::{ChainSplitStatus, FinalizedCheckpointObservations, SyncChainStatus, SyncState};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
@@ -29,6 +29,10 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// A snapshot of the chains being range synced.
+    pub sync_chains: RwLock<Vec<SyncChainStatus>>,
+    /// Recent observations of whether peers agree with our finalized checkpoint.
+    pub finalized_checkpoint_observations: RwLock<FinalizedCheckpointObservations>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -50,6 +54,8 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             peers: RwLock::new(PeerDB::new(trusted_peers, log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            sync_chains: RwLock::new(Vec::new()),
+            finalized_checkpoint_observations: RwLock::new(<_>::default()),
         }
     }
 
@@ -99,6 +105,17 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.sync_state.read().clone()
     }
 
+    /// Returns a snapshot of the chains being range synced.
+    pub fn sync_chains(&self) -> Vec<SyncChainStatus> {
+        self.sync_chains.read().clone()
+    }
+
+    /// Returns a summary of how our finalized checkpoint compares with those of recently observed
+    /// peers.
+    pub fn chain_split_status(&self) -> ChainSplitStatus {
+        self.finalized_checkpoint_observations.read().status()
+    }
+
     /// Returns a `Client` type if one is known for the `PeerId`.
     pub fn client(&self, peer_id: &PeerId) -> Client {
         self.peers
@@ -0,0 +1,248 @@
+//! Limits which are applied to gossip messages before they are decoded and passed on for
+//! validation.
+
+use crate::types::{GossipKind, CORE_TOPICS};
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, BYTES_PER_LENGTH_OFFSET};
+use std::str::FromStr;
+use std::time::Duration;
+use types::{
+    AggregateSignature, AttestationData, EthSpec, ProposerSlashing, Signature, SignedVoluntaryExit,
+    Unsigned,
+};
+
+/// The topic name used to refer to all attestation subnets.
+const ATTESTATION_KIND: &str = "beacon_attestation";
+
+/// The maximum length of the SSZ encoding of each kind of gossip message.
+///
+/// Messages which decompress to more than this are rejected before they are decompressed. The
+/// limits are derived from the `EthSpec` (and therefore the fork) in use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GossipSizeLimits {
+    beacon_block: usize,
+    aggregate_and_proof: usize,
+    attestation: usize,
+    voluntary_exit: usize,
+    proposer_slashing: usize,
+    attester_slashing: usize,
+}
+
+impl GossipSizeLimits {
+    /// Computes the limits for `T`, none of which will exceed `max_size`.
+    pub fn new<T: EthSpec>(max_size: usize) -> Self {
+        let max_committee_size = T::MaxValidatorsPerCommittee::to_usize();
+        let signature_len = <Signature as Decode>::ssz_fixed_len();
+        let attestation_data_len = <AttestationData as Decode>::ssz_fixed_len();
+
+        // aggregation_bits (offset and bitlist), data, signature.
+        let attestation = BYTES_PER_LENGTH_OFFSET
+            + max_committee_size / 8
+            + 1
+            + attestation_data_len
+            + <AggregateSignature as Decode>::ssz_fixed_len();
+
+        // message (offset), signature, aggregator_index, aggregate (offset), selection_proof.
+        let aggregate_and_proof = BYTES_PER_LENGTH_OFFSET
+            + signature_len
+            + <u64 as Decode>::ssz_fixed_len()
+            + BYTES_PER_LENGTH_OFFSET
+            + attestation
+            + signature_len;
+
+        // attesting_indices (offset and list), data, signature.
+        let indexed_attestation = BYTES_PER_LENGTH_OFFSET
+            + max_committee_size * <u64 as Decode>::ssz_fixed_len()
+            + attestation_data_len
+            + <AggregateSignature as Decode>::ssz_fixed_len();
+        let attester_slashing = 2 * (BYTES_PER_LENGTH_OFFSET + indexed_attestation);
+
+        Self {
+            // Blocks are only bound by the maximum gossip size.
+            beacon_block: max_size,
+            aggregate_and_proof: std::cmp::min(aggregate_and_proof, max_size),
+            attestation: std::cmp::min(attestation, max_size),
+            voluntary_exit: std::cmp::min(
+                <SignedVoluntaryExit as Decode>::ssz_fixed_len(),
+                max_size,
+            ),
+            proposer_slashing: std::cmp::min(
+                <ProposerSlashing as Decode>::ssz_fixed_len(),
+                max_size,
+            ),
+            attester_slashing: std::cmp::min(attester_slashing, max_size),
+        }
+    }
+
+    /// Returns the maximum length of a message on a topic of the given `kind`.
+    pub fn max_size(&self, kind: &GossipKind) -> usize {
+        match kind {
+            GossipKind::BeaconBlock => self.beacon_block,
+            GossipKind::BeaconAggregateAndProof => self.aggregate_and_proof,
+            GossipKind::Attestation(_) => self.attestation,
+            GossipKind::VoluntaryExit => self.voluntary_exit,
+            GossipKind::ProposerSlashing => self.proposer_slashing,
+            GossipKind::AttesterSlashing => self.attester_slashing,
+        }
+    }
+}
+
+/// Limits the number of messages that each peer may send on each topic of a given kind.
+///
+/// A limit for `beacon_attestation` applies to each attestation subnet individually.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GossipRateLimit {
+    /// The topic kind (e.g., `beacon_block`), as per `GossipKind::as_ref`.
+    pub kind: String,
+    /// The number of messages allowed in each `period`.
+    pub messages: u64,
+    /// The period over which `messages` are replenished.
+    pub period: Duration,
+}
+
+impl FromStr for GossipRateLimit {
+    type Err = String;
+
+    /// Parses a limit of the form `<kind>:<messages>/<seconds>`, e.g. `beacon_block:4/12`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "Invalid gossip rate limit \"{}\", expected <topic>:<messages>/<seconds>",
+                s
+            )
+        };
+
+        let (kind, quota) = split_once(s, ':').ok_or_else(err)?;
+        let (messages, seconds) = split_once(quota, '/').ok_or_else(err)?;
+
+        let is_known_kind = kind == ATTESTATION_KIND
+            || CORE_TOPICS
+                .iter()
+                .any(|core_kind| core_kind.as_ref() == kind);
+        if !is_known_kind {
+            return Err(format!("Unknown gossip topic in rate limit: {}", kind));
+        }
+
+        let messages = messages.parse::<u64>().map_err(|_| err())?;
+        let seconds = seconds.parse::<u64>().map_err(|_| err())?;
+        if messages == 0 || seconds == 0 {
+            return Err(format!(
+                "Gossip rate limit for {} must have non-zero messages and seconds",
+                kind
+            ));
+        }
+
+        Ok(Self {
+            kind: kind.to_string(),
+            messages,
+            period: Duration::from_secs(seconds),
+        })
+    }
+}
+
+fn split_once(s: &str, delimiter: char) -> Option<(&str, &str)> {
+    let mut parts = s.splitn(2, delimiter);
+    Some((parts.next()?, parts.next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::Encode;
+    use types::test_utils::test_random_instance;
+    use types::{
+        AggregateAndProof, Attestation, AttesterSlashing, BitList, IndexedAttestation,
+        MainnetEthSpec, SignedAggregateAndProof, SubnetId, VariableList,
+    };
+
+    type E = MainnetEthSpec;
+
+    const MAX_SIZE: usize = 1_048_576;
+
+    fn max_attestation() -> Attestation<E> {
+        let committee_size = <E as EthSpec>::MaxValidatorsPerCommittee::to_usize();
+        Attestation {
+            aggregation_bits: BitList::with_capacity(committee_size).unwrap(),
+            data: test_random_instance(),
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    fn max_indexed_attestation() -> IndexedAttestation<E> {
+        let committee_size = <E as EthSpec>::MaxValidatorsPerCommittee::to_usize();
+        IndexedAttestation {
+            attesting_indices: VariableList::new(vec![0; committee_size]).unwrap(),
+            data: test_random_instance(),
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    #[test]
+    fn size_limits_match_max_encodings() {
+        let limits = GossipSizeLimits::new::<E>(MAX_SIZE);
+
+        assert_eq!(
+            limits.max_size(&GossipKind::Attestation(SubnetId::new(0))),
+            max_attestation().as_ssz_bytes().len()
+        );
+
+        let aggregate = SignedAggregateAndProof {
+            message: AggregateAndProof {
+                aggregator_index: 0,
+                aggregate: max_attestation(),
+                selection_proof: Signature::empty(),
+            },
+            signature: Signature::empty(),
+        };
+        assert_eq!(
+            limits.max_size(&GossipKind::BeaconAggregateAndProof),
+            aggregate.as_ssz_bytes().len()
+        );
+
+        let attester_slashing = AttesterSlashing {
+            attestation_1: max_indexed_attestation(),
+            attestation_2: max_indexed_attestation(),
+        };
+        assert_eq!(
+            limits.max_size(&GossipKind::AttesterSlashing),
+            attester_slashing.as_ssz_bytes().len()
+        );
+
+        assert_eq!(
+            limits.max_size(&GossipKind::ProposerSlashing),
+            test_random_instance::<ProposerSlashing>()
+                .as_ssz_bytes()
+                .len()
+        );
+        assert_eq!(limits.max_size(&GossipKind::BeaconBlock), MAX_SIZE);
+    }
+
+    #[test]
+    fn size_limits_are_capped() {
+        let limits = GossipSizeLimits::new::<E>(128);
+
+        for kind in CORE_TOPICS.iter() {
+            assert!(limits.max_size(kind) <= 128);
+        }
+    }
+
+    #[test]
+    fn parse_rate_limit() {
+        assert_eq!(
+            "beacon_attestation:64/12".parse::<GossipRateLimit>(),
+            Ok(GossipRateLimit {
+                kind: ATTESTATION_KIND.to_string(),
+                messages: 64,
+                period: Duration::from_secs(12),
+            })
+        );
+        assert!("beacon_block:4/12".parse::<GossipRateLimit>().is_ok());
+
+        assert!("beacon_attestation_1:64/12"
+            .parse::<GossipRateLimit>()
+            .is_err());
+        assert!("beacon_block:0/12".parse::<GossipRateLimit>().is_err());
+        assert!("beacon_block:4".parse::<GossipRateLimit>().is_err());
+        assert!("beacon_block".parse::<GossipRateLimit>().is_err());
+    }
+}
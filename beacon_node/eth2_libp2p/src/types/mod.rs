@@ -1,5 +1,7 @@
+mod chain_split;
 pub mod error;
 mod globals;
+mod gossip_limits;
 mod pubsub;
 mod subnet;
 mod sync_state;
@@ -12,8 +14,10 @@ pub type EnrBitfield<T: EthSpec> = BitVector<T::SubnetBitfieldLength>;
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
+pub use chain_split::{ChainSplitStatus, FinalizedCheckpointObservations};
 pub use globals::NetworkGlobals;
+pub use gossip_limits::{GossipRateLimit, GossipSizeLimits};
 pub use pubsub::{PubsubMessage, SnappyTransform};
 pub use subnet::SubnetDiscovery;
-pub use sync_state::SyncState;
+pub use sync_state::{SyncBatchStatus, SyncChainStatus, SyncState};
 pub use topics::{subnet_id_from_topic_hash, GossipEncoding, GossipKind, GossipTopic, CORE_TOPICS};
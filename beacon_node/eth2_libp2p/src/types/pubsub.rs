@@ -1,9 +1,10 @@
 //! Handles the encoding and decoding of pubsub messages.
 
-use crate::types::{GossipEncoding, GossipKind, GossipTopic};
+use crate::metrics;
+use crate::types::{GossipEncoding, GossipKind, GossipSizeLimits, GossipTopic};
 use crate::TopicHash;
 use libp2p::gossipsub::{DataTransform, GossipsubMessage, RawGossipsubMessage};
-use snap::raw::{decompress_len, Decoder, Encoder};
+use snap::raw::{decompress_len, max_compress_len, Decoder, Encoder};
 use ssz::{Decode, Encode};
 use std::boxed::Box;
 use std::io::{Error, ErrorKind};
@@ -33,12 +34,27 @@ pub enum PubsubMessage<T: EthSpec> {
 pub struct SnappyTransform {
     /// Sets the maximum size we allow gossipsub messages to decompress to.
     max_size_per_message: usize,
+    /// The maximum size each kind of message may decompress to.
+    size_limits: GossipSizeLimits,
 }
 
 impl SnappyTransform {
-    pub fn new(max_size_per_message: usize) -> Self {
+    pub fn new(max_size_per_message: usize, size_limits: GossipSizeLimits) -> Self {
         SnappyTransform {
             max_size_per_message,
+            size_limits,
+        }
+    }
+
+    /// Returns the maximum size that a message on `topic` may decompress to.
+    fn max_size_for_topic(&self, topic: &TopicHash) -> (usize, &'static str) {
+        match GossipTopic::decode(topic.as_str()) {
+            Ok(gossip_topic) => (
+                self.size_limits.max_size(gossip_topic.kind()),
+                gossip_topic.kind().into(),
+            ),
+            // Unknown topics will fail to decode later, just apply the global limit.
+            Err(_) => (self.max_size_per_message, "unknown"),
         }
     }
 }
@@ -49,12 +65,24 @@ impl DataTransform for SnappyTransform {
         &self,
         raw_message: RawGossipsubMessage,
     ) -> Result<GossipsubMessage, std::io::Error> {
-        // check the length of the raw bytes
+        let (max_size, kind) = self.max_size_for_topic(&raw_message.topic);
+
+        // check the length of the raw bytes. A message which is larger than the compressed form
+        // of the largest valid message is rejected without reading it.
+        if raw_message.data.len() > max_compress_len(max_size) {
+            metrics::inc_counter_vec(&metrics::GOSSIP_OVERSIZED_MESSAGES_PER_KIND, &[kind]);
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "ssz_snappy encoded data > max compressed size for topic",
+            ));
+        }
+
         let len = decompress_len(&raw_message.data)?;
-        if len > self.max_size_per_message {
+        if len > max_size {
+            metrics::inc_counter_vec(&metrics::GOSSIP_OVERSIZED_MESSAGES_PER_KIND, &[kind]);
             return Err(Error::new(
                 ErrorKind::InvalidData,
-                "ssz_snappy decoded data > GOSSIP_MAX_SIZE",
+                "ssz_snappy decoded data > max size for topic",
             ));
         }
 
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use types::Slot;
+use types::{Epoch, Hash256, Slot};
 
 /// The current state of the node.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -65,3 +65,39 @@ impl std::fmt::Display for SyncState {
         }
     }
 }
+
+/// A snapshot of a chain which is being range synced, for diagnostic purposes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncChainStatus {
+    /// The identifier of the chain, as it appears in the logs.
+    pub id: u64,
+    /// Either `"Finalized"` or `"Head"`.
+    pub sync_type: String,
+    /// `true` if batches are being downloaded and processed for this chain.
+    pub is_syncing: bool,
+    /// Any epoch prior to this one has been validated.
+    pub start_epoch: Epoch,
+    pub target_head_slot: Slot,
+    pub target_head_root: Hash256,
+    /// The start epoch of the next batch to be processed.
+    pub processing_target: Epoch,
+    pub validated_epochs: u64,
+    /// The peers which agree with the chain's target.
+    pub peers: Vec<String>,
+    pub batches: Vec<SyncBatchStatus>,
+}
+
+/// A snapshot of a batch within a `SyncChainStatus`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncBatchStatus {
+    pub start_epoch: Epoch,
+    pub start_slot: Slot,
+    /// The last slot of the batch (inclusive).
+    pub end_slot: Slot,
+    /// E.g., `"Downloading"` or `"AwaitingValidation"`.
+    pub state: String,
+    /// The peer which is serving (or served) the batch, if any.
+    pub peer: Option<String>,
+    pub failed_download_attempts: usize,
+    pub failed_processing_attempts: usize,
+}
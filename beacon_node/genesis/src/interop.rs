@@ -117,4 +117,54 @@ mod test {
             "validator count should be correct"
         );
     }
+
+    #[test]
+    fn invalid_deposit_signatures() {
+        let spec = &TestEthSpec::default_spec();
+        let keypairs = generate_deterministic_keypairs(2);
+        let amount = spec.max_effective_balance;
+
+        let deposit_data = |keypair: &Keypair, signing_keypair: &Keypair, amount: u64| {
+            let mut data = DepositData {
+                withdrawal_credentials: Hash256::repeat_byte(1),
+                pubkey: keypair.pk.clone().into(),
+                amount,
+                signature: Signature::empty().into(),
+            };
+            data.signature = data.create_signature(&signing_keypair.sk, spec);
+            data
+        };
+
+        let datas = vec![
+            // A valid deposit.
+            deposit_data(&keypairs[0], &keypairs[0], amount),
+            // A new validator whose proof-of-possession is signed by the wrong key.
+            deposit_data(&keypairs[1], &keypairs[0], amount),
+            // A top-up for an existing validator, which is not signature checked.
+            deposit_data(&keypairs[0], &keypairs[1], spec.min_deposit_amount),
+        ];
+
+        let state = initialize_beacon_state_from_eth1::<TestEthSpec>(
+            Hash256::repeat_byte(0x42),
+            2_u64.pow(40),
+            genesis_deposits(datas, spec).expect("should build deposits"),
+            spec,
+        )
+        .expect("should not fail on an invalid deposit signature");
+
+        assert_eq!(
+            state.eth1_deposit_index, 3,
+            "all deposits should be consumed"
+        );
+        assert_eq!(
+            state.validators.len(),
+            1,
+            "deposit with invalid signature should be skipped"
+        );
+        assert_eq!(
+            state.balances[0],
+            amount + spec.min_deposit_amount,
+            "top-up should be applied regardless of its signature"
+        );
+    }
 }
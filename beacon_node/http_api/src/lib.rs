@@ -15,31 +15,42 @@ mod validator_inclusion;
 use beacon_chain::{
     attestation_verification::SignatureVerifiedAttestation,
     observed_operations::ObservationOutcome,
+    proposal_timings::ProposalStage,
     validator_monitor::{get_block_delay_ms, timestamp_now},
-    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
+    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes, DebugArchive,
 };
 use block_id::BlockId;
 use eth2::types::{self as api_types, ValidatorId};
-use eth2_libp2p::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use eth2_libp2p::{types::SyncState, EnrExt, Eth2Enr, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
+use logging::LogFilterHandle;
 use network::NetworkMessage;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 use state_id::StateId;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::future::Future;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::mpsc::UnboundedSender;
-use tokio_stream::{wrappers::BroadcastStream, StreamExt};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_stream::{
+    wrappers::{BroadcastStream, ReceiverStream},
+    StreamExt,
+};
 use types::{
-    Attestation, AttesterSlashing, CommitteeCache, Epoch, EthSpec, ProposerSlashing, RelativeEpoch,
-    SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit, Slot, YamlConfig,
+    Attestation, AttesterSlashing, CommitteeCache, Epoch, EthSpec, ProposerSlashing,
+    PublicKeyBytes, RelativeEpoch, SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit,
+    Slot, Validator, YamlConfig,
 };
 use warp::http::StatusCode;
+use warp::hyper::Body;
 use warp::sse::Event;
 use warp::Reply;
 use warp::{http::Response, Filter};
@@ -55,6 +66,13 @@ const API_VERSION: &str = "v1";
 /// finalized head.
 const SYNC_TOLERANCE_EPOCHS: u64 = 8;
 
+/// The approximate size of each chunk of a streamed validators response.
+const VALIDATORS_STREAM_CHUNK_BYTES: usize = 64 * 1_024;
+
+/// The number of chunks of a streamed validators response which may be serialized ahead of the
+/// client reading them.
+const VALIDATORS_STREAM_BUFFER: usize = 16;
+
 /// A wrapper around all the items required to spawn the HTTP server.
 ///
 /// The server will gracefully handle the case where any fields are `None`.
@@ -64,6 +82,7 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_tx: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub eth1_service: Option<eth1::Service>,
+    pub log_filter: Option<LogFilterHandle>,
     pub log: Logger,
 }
 
@@ -74,6 +93,10 @@ pub struct Config {
     pub listen_addr: Ipv4Addr,
     pub listen_port: u16,
     pub allow_origin: Option<String>,
+    /// The directory in which database snapshots are created. Snapshots are disabled if `None`.
+    pub snapshot_dir: Option<PathBuf>,
+    /// The data directory of the beacon node, used to report disk usage.
+    pub data_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -83,6 +106,8 @@ impl Default for Config {
             listen_addr: Ipv4Addr::new(127, 0, 0, 1),
             listen_port: 5052,
             allow_origin: None,
+            snapshot_dir: None,
+            data_dir: None,
         }
     }
 }
@@ -290,6 +315,37 @@ pub fn serve<T: BeaconChainTypes>(
             }
         });
 
+    // Create a `warp` filter that provides access to the log filter.
+    let inner_ctx = ctx.clone();
+    let log_directives_filter = warp::any()
+        .map(move || inner_ctx.log_filter.clone())
+        .and_then(|log_filter| async move {
+            match log_filter {
+                Some(log_filter) => Ok(log_filter),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "The logger does not support log directives.".to_string(),
+                )),
+            }
+        });
+
+    // Create a `warp` filter that provides access to the database snapshot directory.
+    let inner_ctx = ctx.clone();
+    let snapshot_dir_filter = warp::any()
+        .map(move || inner_ctx.config.snapshot_dir.clone())
+        .and_then(|snapshot_dir| async move {
+            match snapshot_dir {
+                Some(snapshot_dir) => Ok(snapshot_dir),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "Database snapshots are not enabled.".to_string(),
+                )),
+            }
+        });
+
+    // Only one database snapshot may be in progress at a time, since each one copies the entire
+    // database and occupies a blocking thread until it completes.
+    let snapshot_lock = Arc::new(Mutex::new(()));
+    let snapshot_lock_filter = warp::any().map(move || snapshot_lock.clone());
+
     // Create a `warp` filter that rejects request whilst the node is syncing.
     let not_while_syncing_filter = warp::any()
         .and(network_globals.clone())
@@ -428,28 +484,28 @@ pub fn serve<T: BeaconChainTypes>(
                 blocking_json_task(move || {
                     state_id
                         .map_state(&chain, |state| {
-                            Ok(state
-                                .validators
-                                .iter()
-                                .zip(state.balances.iter())
-                                .enumerate()
-                                // filter by validator id(s) if provided
-                                .filter(|(index, (validator, _))| {
-                                    query.id.as_ref().map_or(true, |ids| {
-                                        ids.0.iter().any(|id| match id {
-                                            ValidatorId::PublicKey(pubkey) => {
-                                                &validator.pubkey == pubkey
-                                            }
-                                            ValidatorId::Index(param_index) => {
-                                                *param_index == *index as u64
-                                            }
-                                        })
-                                    })
-                                })
-                                .map(|(index, (_, balance))| {
+                            // filter by validator id(s) if provided
+                            let indices = match &query.id {
+                                Some(ids) => validator_indices(
+                                    &chain,
+                                    state.validators.len(),
+                                    |index| {
+                                        state
+                                            .validators
+                                            .get(index)
+                                            .map(|validator| validator.pubkey)
+                                    },
+                                    &ids.0,
+                                )?,
+                                None => (0..state.validators.len()).collect(),
+                            };
+
+                            Ok(indices
+                                .into_iter()
+                                .filter_map(|index| {
                                     Some(api_types::ValidatorBalanceData {
                                         index: index as u64,
-                                        balance: *balance,
+                                        balance: *state.balances.get(index)?,
                                     })
                                 })
                                 .collect::<Vec<_>>())
@@ -467,58 +523,117 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and_then(
             |state_id: StateId, chain: Arc<BeaconChain<T>>, query: api_types::ValidatorsQuery| {
-                blocking_json_task(move || {
+                blocking_task(move || {
+                    let far_future_epoch = chain.spec.far_future_epoch;
+                    let status_filter = query.status.clone();
+
+                    // filter by status(es) if provided and map the result
+                    let to_validator_data =
+                        move |index: usize, validator: &Validator, balance: u64, epoch: Epoch| {
+                            let status = api_types::ValidatorStatus::from_validator(
+                                validator,
+                                epoch,
+                                far_future_epoch,
+                            );
+
+                            let status_matches = status_filter.as_ref().map_or(true, |statuses| {
+                                statuses.0.contains(&status)
+                                    || statuses.0.contains(&status.superstatus())
+                            });
+
+                            if status_matches {
+                                Some(api_types::ValidatorData {
+                                    index: index as u64,
+                                    balance,
+                                    status,
+                                    validator: validator.clone(),
+                                })
+                            } else {
+                                None
+                            }
+                        };
+
+                    // Where possible, read the registry directly from the database rather than
+                    // loading the full state. Only the requested validators are decoded.
+                    if let Some(registry) = state_id.stored_validator_registry(&chain)? {
+                        let epoch = registry.slot().epoch(T::EthSpec::slots_per_epoch());
+
+                        // filter by validator id(s) if provided
+                        let indices = match &query.id {
+                            Some(ids) => validator_indices(
+                                &chain,
+                                registry.len(),
+                                |index| registry.pubkey(index),
+                                &ids.0,
+                            )?,
+                            None => (0..registry.len()).collect(),
+                        };
+
+                        // The registry of a large network is hundreds of megabytes of JSON, so
+                        // rather than building the whole response in memory it is serialized in
+                        // chunks on a blocking thread and streamed to the client. An error part
+                        // way through aborts the response, since the status has already been sent.
+                        let (tx, rx) = mpsc::channel(VALIDATORS_STREAM_BUFFER);
+                        tokio::task::spawn_blocking(move || {
+                            let result = stream_validator_data(&tx, indices, |index| {
+                                let (validator, balance) = registry
+                                    .get(index)
+                                    .map_err(|e| {
+                                        format!("unable to decode validator {}: {:?}", index, e)
+                                    })?
+                                    .ok_or_else(|| format!("missing validator {}", index))?;
+                                Ok(to_validator_data(index, &validator, balance, epoch))
+                            });
+                            if let Err(e) = result {
+                                let _ = tx.blocking_send(Err(e));
+                            }
+                        });
+
+                        return Response::builder()
+                            .status(200)
+                            .header("Content-Type", "application/json")
+                            .body(Body::wrap_stream(ReceiverStream::new(rx)))
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "failed to create response: {}",
+                                    e
+                                ))
+                            });
+                    }
+
                     state_id
                         .map_state(&chain, |state| {
                             let epoch = state.current_epoch();
-                            let far_future_epoch = chain.spec.far_future_epoch;
-
-                            Ok(state
-                                .validators
-                                .iter()
-                                .zip(state.balances.iter())
-                                .enumerate()
-                                // filter by validator id(s) if provided
-                                .filter(|(index, (validator, _))| {
-                                    query.id.as_ref().map_or(true, |ids| {
-                                        ids.0.iter().any(|id| match id {
-                                            ValidatorId::PublicKey(pubkey) => {
-                                                &validator.pubkey == pubkey
-                                            }
-                                            ValidatorId::Index(param_index) => {
-                                                *param_index == *index as u64
-                                            }
-                                        })
-                                    })
-                                })
-                                // filter by status(es) if provided and map the result
-                                .filter_map(|(index, (validator, balance))| {
-                                    let status = api_types::ValidatorStatus::from_validator(
-                                        validator,
-                                        epoch,
-                                        far_future_epoch,
-                                    );
 
-                                    let status_matches =
-                                        query.status.as_ref().map_or(true, |statuses| {
-                                            statuses.0.contains(&status)
-                                                || statuses.0.contains(&status.superstatus())
-                                        });
-
-                                    if status_matches {
-                                        Some(api_types::ValidatorData {
-                                            index: index as u64,
-                                            balance: *balance,
-                                            status,
-                                            validator: validator.clone(),
-                                        })
-                                    } else {
-                                        None
-                                    }
+                            // filter by validator id(s) if provided
+                            let indices = match &query.id {
+                                Some(ids) => validator_indices(
+                                    &chain,
+                                    state.validators.len(),
+                                    |index| {
+                                        state
+                                            .validators
+                                            .get(index)
+                                            .map(|validator| validator.pubkey)
+                                    },
+                                    &ids.0,
+                                )?,
+                                None => (0..state.validators.len()).collect(),
+                            };
+
+                            Ok(indices
+                                .into_iter()
+                                .filter_map(|index| {
+                                    let validator = state.validators.get(index)?;
+                                    let balance = *state.balances.get(index)?;
+                                    to_validator_data(index, validator, balance, epoch)
                                 })
                                 .collect::<Vec<_>>())
                         })
-                        .map(api_types::GenericResponse::from)
+                        .map(|data| {
+                            warp::reply::json(&api_types::GenericResponse::from(data))
+                                .into_response()
+                        })
                 })
             },
         );
@@ -539,9 +654,9 @@ pub fn serve<T: BeaconChainTypes>(
                     state_id
                         .map_state(&chain, |state| {
                             let index_opt = match &validator_id {
-                                ValidatorId::PublicKey(pubkey) => {
-                                    state.validators.iter().position(|v| v.pubkey == *pubkey)
-                                }
+                                ValidatorId::PublicKey(pubkey) => chain
+                                    .validator_index_in_state(pubkey, state)
+                                    .map_err(warp_utils::reject::beacon_chain_error)?,
                                 ValidatorId::Index(index) => Some(*index as usize),
                             };
 
@@ -788,13 +903,54 @@ pub fn serve<T: BeaconChainTypes>(
              log: Logger| {
                 blocking_json_task(move || {
                     let seen_timestamp = timestamp_now();
+                    let block_root = block.canonical_root();
+                    chain.proposal_timings.record(
+                        block.slot(),
+                        ProposalStage::BlockReceived,
+                        seen_timestamp,
+                        Some(block_root),
+                        &chain.slot_clock,
+                    );
+
+                    // Refuse to broadcast a block which conflicts with a block we have already
+                    // observed from the same proposer at the same slot, since publishing it would
+                    // be a slashable equivocation. Otherwise record the block before it is
+                    // published if its signature is valid, so that a conflicting block submitted
+                    // concurrently is refused. Blocks which are too old to be checked are left for
+                    // `process_block` to reject.
+                    if let Ok(Some(observed_root)) =
+                        chain.check_and_observe_proposal(block_root, &block)
+                    {
+                        warn!(
+                            log,
+                            "Refusing to publish equivocating block";
+                            "slot" => block.slot(),
+                            "proposer_index" => block.message.proposer_index,
+                            "block_root" => ?block_root,
+                            "observed_root" => ?observed_root,
+                        );
+                        return Err(warp_utils::reject::proposal_equivocation(format!(
+                            "block {:?} conflicts with block {:?} from proposer {} at slot {}",
+                            block_root,
+                            observed_root,
+                            block.message.proposer_index,
+                            block.slot()
+                        )));
+                    }
 
-                    // Send the block, regardless of whether or not it is valid. The API
+                    // Otherwise, send the block regardless of whether or not it is valid. The API
                     // specification is very clear that this is the desired behaviour.
                     publish_pubsub_message(
                         &network_tx,
                         PubsubMessage::BeaconBlock(Box::new(block.clone())),
                     )?;
+                    chain.proposal_timings.record(
+                        block.slot(),
+                        ProposalStage::BlockPublished,
+                        timestamp_now(),
+                        Some(block_root),
+                        &chain.slot_clock,
+                    );
 
                     // Determine the delay after the start of the slot, register it with metrics.
                     let delay =
@@ -804,7 +960,6 @@ pub fn serve<T: BeaconChainTypes>(
                         delay,
                     );
 
-
                     match chain.process_block(block.clone()) {
                         Ok(root) => {
                             info!(
@@ -813,6 +968,14 @@ pub fn serve<T: BeaconChainTypes>(
                                 "root" => format!("{}", root)
                             );
 
+                            chain.proposal_timings.record(
+                                block.slot(),
+                                ProposalStage::BlockImported,
+                                timestamp_now(),
+                                Some(root),
+                                &chain.slot_clock,
+                            );
+
                             // Notify the validator monitor.
                             chain.validator_monitor.read().register_api_block(
                                 seen_timestamp,
@@ -1668,6 +1831,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and_then(
             |slot: Slot, query: api_types::ValidatorBlocksQuery, chain: Arc<BeaconChain<T>>| {
                 blocking_json_task(move || {
+                    chain.proposal_timings.record(
+                        slot,
+                        ProposalStage::BlockRequested,
+                        timestamp_now(),
+                        None,
+                        &chain.slot_clock,
+                    );
+
                     let randao_reveal = (&query.randao_reveal).try_into().map_err(|e| {
                         warp_utils::reject::custom_bad_request(format!(
                             "randao reveal is not valid BLS signature: {:?}",
@@ -1675,11 +1846,19 @@ pub fn serve<T: BeaconChainTypes>(
                         ))
                     })?;
 
-                    chain
+                    let (block, _) = chain
                         .produce_block(randao_reveal, slot, query.graffiti.map(Into::into))
-                        .map(|block_and_state| block_and_state.0)
-                        .map(api_types::GenericResponse::from)
-                        .map_err(warp_utils::reject::block_production_error)
+                        .map_err(warp_utils::reject::block_production_error)?;
+
+                    chain.proposal_timings.record(
+                        slot,
+                        ProposalStage::BlockProduced,
+                        timestamp_now(),
+                        Some(block.canonical_root()),
+                        &chain.slot_clock,
+                    );
+
+                    Ok(api_types::GenericResponse::from(block))
                 })
             },
         );
@@ -1906,13 +2085,22 @@ pub fn serve<T: BeaconChainTypes>(
         );
 
     // GET lighthouse/health
+    let data_dir = ctx.config.data_dir.clone();
     let get_lighthouse_health = warp::path("lighthouse")
         .and(warp::path("health"))
         .and(warp::path::end())
-        .and_then(|| {
+        .and(chain_filter.clone())
+        .and(warp::any().map(move || data_dir.clone()))
+        .and_then(|chain: Arc<BeaconChain<T>>, data_dir: Option<PathBuf>| {
             blocking_json_task(move || {
                 eth2::lighthouse::Health::observe()
-                    .map(api_types::GenericResponse::from)
+                    .map(|mut health| {
+                        health.cache_memory = Some(chain.cache_memory_estimates());
+                        if let (Some(system), Some(data_dir)) = (&mut health.system, data_dir) {
+                            system.disk = eth2::lighthouse::DiskHealth::observe(&data_dir).ok();
+                        }
+                        api_types::GenericResponse::from(health)
+                    })
                     .map_err(warp_utils::reject::custom_bad_request)
             })
         });
@@ -1930,6 +2118,33 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/syncing/chains
+    let get_lighthouse_syncing_chains = warp::path("lighthouse")
+        .and(warp::path("syncing"))
+        .and(warp::path("chains"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.sync_chains(),
+                ))
+            })
+        });
+
+    // GET lighthouse/chain_split
+    let get_lighthouse_chain_split = warp::path("lighthouse")
+        .and(warp::path("chain_split"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.chain_split_status(),
+                ))
+            })
+        });
+
     // GET lighthouse/peers
     let get_lighthouse_peers = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -1949,6 +2164,42 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/peers/clients
+    let get_lighthouse_peers_clients = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("clients"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                let mut clients: BTreeMap<String, eth2::lighthouse::ClientPeers> = BTreeMap::new();
+
+                for (peer_id, peer_info) in network_globals.peers.read().connected_peers() {
+                    let client = peer_info.client.kind.to_string();
+                    let version = peer_info.client.version.clone();
+                    let client_peers = clients.entry(client.clone()).or_insert_with(|| {
+                        eth2::lighthouse::ClientPeers {
+                            client,
+                            versions: BTreeMap::new(),
+                            peers: vec![],
+                        }
+                    });
+
+                    *client_peers.versions.entry(version.clone()).or_default() += 1;
+                    client_peers.peers.push(eth2::lighthouse::ClientPeer {
+                        peer_id: peer_id.to_string(),
+                        version,
+                        agent_string: peer_info.client.agent_string.clone(),
+                        enr_fork_id: peer_info.enr.as_ref().and_then(|enr| enr.eth2().ok()),
+                    });
+                }
+
+                Ok(api_types::GenericResponse::from(
+                    clients.into_iter().map(|(_, v)| v).collect::<Vec<_>>(),
+                ))
+            })
+        });
+
     // GET lighthouse/peers/connected
     let get_lighthouse_peers_connected = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -1982,6 +2233,94 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/beacon/headers/{slot}/observed
+    let get_lighthouse_observed_block_headers = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("headers"))
+        .and(warp::path::param::<Slot>())
+        .and(warp::path("observed"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|slot: Slot, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    chain.observed_block_headers_at_slot(slot),
+                ))
+            })
+        });
+
+    // GET lighthouse/beacon/blocks/{block_id}/attestations
+    let get_lighthouse_beacon_blocks_attestations = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("blocks"))
+        .and(warp::path::param::<BlockId>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block ID".to_string(),
+            ))
+        }))
+        .and(warp::path("attestations"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                let block_root = block_id.root(&chain)?;
+
+                // The index entries of blocks which are not the finalized block or one of its
+                // descendants are pruned, so report them as missing rather than as having no
+                // attestations.
+                if chain.config.index_attestations_by_block_root
+                    && !chain.fork_choice.read().contains_block(&block_root)
+                {
+                    return Err(warp_utils::reject::custom_not_found(format!(
+                        "block {:?} is unknown or is not a descendant of the finalized block, \
+                         so its attestations are not in the index",
+                        block_root
+                    )));
+                }
+
+                chain
+                    .attestations_for_block_root(&block_root)
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                    .map(api_types::GenericResponse::from)
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "attestation indexing is not enabled, \
+                            see the --index-attestations CLI flag"
+                                .to_string(),
+                        )
+                    })
+            })
+        });
+
+    // GET lighthouse/quarantine
+    let get_lighthouse_quarantine = warp::path("lighthouse")
+        .and(warp::path("quarantine"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                chain
+                    .quarantine
+                    .objects()
+                    .map(api_types::GenericResponse::from)
+                    .map_err(BeaconChainError::DBError)
+                    .map_err(warp_utils::reject::beacon_chain_error)
+            })
+        });
+
+    // GET lighthouse/proposal_timings
+    let get_lighthouse_proposal_timings = warp::path("lighthouse")
+        .and(warp::path("proposal_timings"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    chain.proposal_timings.timings(),
+                ))
+            })
+        });
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -2108,6 +2447,149 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/debug/archive
+    let get_lighthouse_debug_archive = warp::path("lighthouse")
+        .and(warp::path("debug"))
+        .and(warp::path("archive"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_task(move || {
+                let archive = DebugArchive::from_chain(&chain)
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+                Response::builder()
+                    .status(200)
+                    .header("Content-Type", "application/ssz")
+                    .body(archive.as_bytes())
+                    .map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "failed to create response: {}",
+                            e
+                        ))
+                    })
+            })
+        });
+
+    // GET lighthouse/logging
+    let get_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(log_directives_filter.clone())
+        .and_then(|log_filter_handle: LogFilterHandle| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    eth2::lighthouse::LogDirectivesData {
+                        directives: log_filter_handle.directives().to_string(),
+                    },
+                ))
+            })
+        });
+
+    // POST lighthouse/logging
+    let post_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(log_directives_filter)
+        .and(log_filter.clone())
+        .and_then(
+            |data: eth2::lighthouse::LogDirectivesData,
+             log_filter_handle: LogFilterHandle,
+             log: Logger| {
+                blocking_json_task(move || {
+                    // Apply the posted directives on top of the current ones, so that posting
+                    // only module directives does not reset the default level.
+                    let directives = log_filter_handle
+                        .merge_directives(&data.directives)
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+
+                    info!(
+                        log,
+                        "Updated log directives";
+                        "directives" => %directives,
+                    );
+
+                    Ok(())
+                })
+            },
+        );
+
+    // POST lighthouse/database/snapshot
+    let post_lighthouse_database_snapshot = warp::path("lighthouse")
+        .and(warp::path("database"))
+        .and(warp::path("snapshot"))
+        .and(warp::path::end())
+        .and(snapshot_dir_filter)
+        .and(snapshot_lock_filter)
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |snapshot_dir: PathBuf,
+             snapshot_lock: Arc<Mutex<()>>,
+             chain: Arc<BeaconChain<T>>,
+             log: Logger| {
+                blocking_json_task(move || {
+                    let _snapshot_guard = snapshot_lock.try_lock().ok_or_else(|| {
+                        warp_utils::reject::custom_conflict(
+                            "a database snapshot is already in progress".to_string(),
+                        )
+                    })?;
+
+                    let timestamp = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "system time is before the unix epoch: {:?}",
+                                e
+                            ))
+                        })?
+                        .as_secs();
+                    let path = snapshot_dir.join(timestamp.to_string());
+
+                    // The databases are created by the store, which refuses to overwrite an
+                    // existing database.
+                    std::fs::create_dir_all(&path).map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "unable to create snapshot directory: {:?}",
+                            e
+                        ))
+                    })?;
+
+                    info!(
+                        log,
+                        "Creating database snapshot";
+                        "path" => ?path,
+                    );
+
+                    let (hot_keys, cold_keys) = chain
+                        .store
+                        .snapshot_to(&path.join("chain_db"), &path.join("freezer_db"))
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to snapshot database: {:?}",
+                                e
+                            ))
+                        })?;
+
+                    info!(
+                        log,
+                        "Created database snapshot";
+                        "path" => ?path,
+                        "hot_keys" => hot_keys,
+                        "cold_keys" => cold_keys,
+                    );
+
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::DatabaseSnapshot {
+                            path,
+                            hot_keys,
+                            cold_keys,
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -2223,16 +2705,25 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_validator_aggregate_attestation.boxed())
                 .or(get_lighthouse_health.boxed())
                 .or(get_lighthouse_syncing.boxed())
+                .or(get_lighthouse_syncing_chains.boxed())
+                .or(get_lighthouse_chain_split.boxed())
                 .or(get_lighthouse_peers.boxed())
                 .or(get_lighthouse_peers_connected.boxed())
+                .or(get_lighthouse_peers_clients.boxed())
                 .or(get_lighthouse_proto_array.boxed())
+                .or(get_lighthouse_observed_block_headers.boxed())
+                .or(get_lighthouse_beacon_blocks_attestations.boxed())
+                .or(get_lighthouse_quarantine.boxed())
+                .or(get_lighthouse_proposal_timings.boxed())
                 .or(get_lighthouse_validator_inclusion_global.boxed())
                 .or(get_lighthouse_validator_inclusion.boxed())
                 .or(get_lighthouse_eth1_syncing.boxed())
                 .or(get_lighthouse_eth1_block_cache.boxed())
                 .or(get_lighthouse_eth1_deposit_cache.boxed())
                 .or(get_lighthouse_beacon_states_ssz.boxed())
+                .or(get_lighthouse_debug_archive.boxed())
                 .or(get_lighthouse_staking.boxed())
+                .or(get_lighthouse_logging.boxed())
                 .or(get_events.boxed()),
         )
         .or(warp::post().and(
@@ -2244,7 +2735,9 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(post_beacon_pool_voluntary_exits.boxed())
                 .or(post_validator_duties_attester.boxed())
                 .or(post_validator_aggregate_and_proofs.boxed())
-                .or(post_validator_beacon_committee_subscriptions.boxed()),
+                .or(post_validator_beacon_committee_subscriptions.boxed())
+                .or(post_lighthouse_logging.boxed())
+                .or(post_lighthouse_database_snapshot.boxed()),
         ))
         .recover(warp_utils::reject::handle_rejection)
         .with(slog_logging(log.clone()))
@@ -2271,6 +2764,79 @@ pub fn serve<T: BeaconChainTypes>(
     Ok((listening_socket, server))
 }
 
+/// Returns the indices of the validators identified by `ids` in a registry of `num_validators`
+/// validators, in ascending order and without duplicates. Validators which are not present in the
+/// registry are omitted.
+///
+/// Public keys are resolved using the validator pubkey cache, rather than scanning the registry.
+/// The public key of each validator in the registry is provided by `pubkey_at`.
+fn validator_indices<T: BeaconChainTypes, F>(
+    chain: &BeaconChain<T>,
+    num_validators: usize,
+    pubkey_at: F,
+    ids: &[ValidatorId],
+) -> Result<Vec<usize>, warp::Rejection>
+where
+    F: Fn(usize) -> Option<PublicKeyBytes>,
+{
+    let mut indices = ids
+        .iter()
+        .filter_map(|id| match id {
+            ValidatorId::PublicKey(pubkey) => chain
+                .validator_index_in_registry(pubkey, num_validators, &pubkey_at)
+                .map_err(warp_utils::reject::beacon_chain_error)
+                .transpose(),
+            ValidatorId::Index(index) => Some(*index as usize)
+                .filter(|index| *index < num_validators)
+                .map(Ok),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    indices.sort_unstable();
+    indices.dedup();
+
+    Ok(indices)
+}
+
+/// Serializes `{"data": [...]}` for the validators at `indices` and sends it to `tx` in chunks of
+/// roughly `VALIDATORS_STREAM_CHUNK_BYTES`.
+///
+/// `validator_data` returns `None` for validators which should be omitted from the response.
+/// Returns an error if a validator cannot be read or if `tx` is closed, which happens when the
+/// client disconnects.
+fn stream_validator_data<F>(
+    tx: &mpsc::Sender<Result<Vec<u8>, String>>,
+    indices: Vec<usize>,
+    mut validator_data: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize) -> Result<Option<api_types::ValidatorData>, String>,
+{
+    let send = |chunk| {
+        tx.blocking_send(Ok(chunk))
+            .map_err(|_| "response stream closed".to_string())
+    };
+
+    let mut chunk = br#"{"data":["#.to_vec();
+    let mut empty = true;
+    for index in indices {
+        if let Some(data) = validator_data(index)? {
+            if !empty {
+                chunk.push(b',');
+            }
+            empty = false;
+            serde_json::to_writer(&mut chunk, &data)
+                .map_err(|e| format!("unable to serialize validator {}: {:?}", index, e))?;
+        }
+
+        if chunk.len() >= VALIDATORS_STREAM_CHUNK_BYTES {
+            send(std::mem::take(&mut chunk))?;
+        }
+    }
+    chunk.extend_from_slice(b"]}");
+    send(chunk)
+}
+
 /// Publish a message to the libp2p pubsub network.
 fn publish_pubsub_message<T: EthSpec>(
     network_tx: &UnboundedSender<NetworkMessage<T>>,
@@ -1,4 +1,4 @@
-use beacon_chain::{BeaconChain, BeaconChainTypes};
+use beacon_chain::{store::ValidatorRegistry, BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2::types::StateId as CoreStateId;
 use std::str::FromStr;
 use types::{BeaconState, EthSpec, Fork, Hash256, Slot};
@@ -52,6 +52,26 @@ impl StateId {
             })
     }
 
+    /// Return the validator registry of the state identified by `self`, read directly from the
+    /// database without decoding the rest of the state.
+    ///
+    /// Returns `None` if `self` refers to the head (which is already in memory) or to a state
+    /// which is not stored in full. In these cases `Self::map_state` should be used instead.
+    pub fn stored_validator_registry<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<Option<ValidatorRegistry>, warp::Rejection> {
+        if let CoreStateId::Head = self.0 {
+            return Ok(None);
+        }
+
+        let state_root = self.root(chain)?;
+        chain
+            .store
+            .load_hot_validator_registry(&state_root)
+            .map_err(|e| warp_utils::reject::beacon_chain_error(BeaconChainError::DBError(e)))
+    }
+
     /// Return the `fork` field of the state identified by `self`.
     pub fn fork<T: BeaconChainTypes>(
         &self,
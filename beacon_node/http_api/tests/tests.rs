@@ -2,13 +2,13 @@
 
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
-    BeaconChain, StateSkipConfig, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
+    BeaconChain, DebugArchive, StateSkipConfig, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
 use discv5::enr::{CombinedKey, EnrBuilder};
 use environment::null_logger;
 use eth2::Error;
 use eth2::StatusCode;
-use eth2::{types::*, BeaconNodeHttpClient, Url};
+use eth2::{types::*, BeaconNodeHttpClient, Timeouts, Url};
 use eth2_libp2p::{
     rpc::methods::MetaData,
     types::{EnrBitfield, SyncState},
@@ -17,13 +17,17 @@ use eth2_libp2p::{
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
 use http_api::{Config, Context};
+use logging::{LogDirectives, LogFilter};
 use network::NetworkMessage;
+use slog::Level;
 use slot_clock::SlotClock;
 use state_processing::per_slot_processing;
 use std::convert::TryInto;
 use std::iter::Iterator;
 use std::net::Ipv4Addr;
 use std::sync::Arc;
+use store::{DBColumn, KeyValueStore, LevelDB};
+use tempfile::{tempdir, TempDir};
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::time::Duration;
@@ -35,6 +39,7 @@ use types::{
 
 type E = MainnetEthSpec;
 
+const SECONDS_PER_SLOT: u64 = 12;
 const SLOTS_PER_EPOCH: u64 = 32;
 const VALIDATOR_COUNT: usize = SLOTS_PER_EPOCH as usize;
 const CHAIN_LENGTH: u64 = SLOTS_PER_EPOCH * 5 - 1; // Make `next_block` an epoch transition
@@ -68,6 +73,7 @@ struct ApiTester {
     network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
     local_enr: Enr,
     external_peer_id: PeerId,
+    snapshot_dir: Option<TempDir>,
 }
 
 impl ApiTester {
@@ -176,17 +182,24 @@ impl ApiTester {
         let eth1_service =
             eth1::Service::new(eth1::Config::default(), log.clone(), chain.spec.clone());
 
+        let snapshot_dir = tempdir().unwrap();
+
         let context = Arc::new(Context {
             config: Config {
                 enabled: true,
                 listen_addr: Ipv4Addr::new(127, 0, 0, 1),
                 listen_port: 0,
                 allow_origin: None,
+                snapshot_dir: Some(snapshot_dir.path().to_path_buf()),
+                data_dir: None,
             },
             chain: Some(chain.clone()),
             network_tx: Some(network_tx),
             network_globals: Some(Arc::new(network_globals)),
             eth1_service: Some(eth1_service),
+            log_filter: Some(
+                LogFilter::new(slog::Discard, LogDirectives::new(Level::Info)).handle(),
+            ),
             log,
         });
         let ctx = context.clone();
@@ -206,6 +219,7 @@ impl ApiTester {
                 listening_socket.port()
             ))
             .unwrap(),
+            Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
         );
 
         Self {
@@ -221,6 +235,7 @@ impl ApiTester {
             network_rx,
             local_enr: enr_clone,
             external_peer_id: peer_id,
+            snapshot_dir: Some(snapshot_dir),
         }
     }
 
@@ -289,11 +304,14 @@ impl ApiTester {
                 listen_addr: Ipv4Addr::new(127, 0, 0, 1),
                 listen_port: 0,
                 allow_origin: None,
+                snapshot_dir: None,
+                data_dir: None,
             },
             chain: Some(chain.clone()),
             network_tx: Some(network_tx),
             network_globals: Some(Arc::new(network_globals)),
             eth1_service: Some(eth1_service),
+            log_filter: None,
             log,
         });
         let ctx = context.clone();
@@ -313,6 +331,7 @@ impl ApiTester {
                 listening_socket.port()
             ))
             .unwrap(),
+            Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
         );
 
         Self {
@@ -328,6 +347,7 @@ impl ApiTester {
             network_rx,
             local_enr: enr_clone,
             external_peer_id: peer_id,
+            snapshot_dir: None,
         }
     }
 
@@ -958,6 +978,102 @@ impl ApiTester {
         self
     }
 
+    /// Returns a block from the same proposer at the same slot as `self.next_block`, validly
+    /// signed but with a different root.
+    fn conflicting_next_block(&self) -> SignedBeaconBlock<E> {
+        let mut message = self.next_block.message.clone();
+        message.body.graffiti = Graffiti::from([42; GRAFFITI_BYTES_LEN]);
+        message.sign(
+            &self.validator_keypairs[message.proposer_index as usize].sk,
+            &self.chain.head_info().unwrap().fork,
+            self.chain.genesis_validators_root,
+            &self.chain.spec,
+        )
+    }
+
+    pub async fn test_post_beacon_blocks_equivocation(mut self) -> Self {
+        let next_block = &self.next_block;
+
+        self.client.post_beacon_blocks(next_block).await.unwrap();
+
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "valid blocks should be sent to network"
+        );
+
+        let conflicting_block = self.conflicting_next_block();
+
+        let error = self
+            .client
+            .post_beacon_blocks(&conflicting_block)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        // Re-publishing the original block is not an equivocation, it is simply already known.
+        let error = self
+            .client
+            .post_beacon_blocks(next_block)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::ACCEPTED));
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_concurrent_equivocation(mut self) -> Self {
+        let next_block = &self.next_block;
+
+        let conflicting_block = self.conflicting_next_block();
+
+        // Submit both blocks back to back, without waiting for either to be imported.
+        let (result_a, result_b) = tokio::join!(
+            self.client.post_beacon_blocks(next_block),
+            self.client.post_beacon_blocks(&conflicting_block)
+        );
+
+        let rejected = match (result_a, result_b) {
+            (Ok(()), Err(e)) | (Err(e), Ok(())) => e,
+            (a, b) => panic!("exactly one block should be accepted: {:?}, {:?}", a, b),
+        };
+        assert_eq!(rejected.status(), Some(StatusCode::BAD_REQUEST));
+
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "the accepted block should be sent to network"
+        );
+        assert!(
+            self.network_rx.recv().now_or_never().is_none(),
+            "the conflicting block should not be sent to network"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_forged_equivocation(mut self) -> Self {
+        // A conflicting block with an invalid signature is published (and rejected), but must
+        // not prevent the genuine block from being published.
+        let mut forged_block = self.next_block.clone();
+        forged_block.message.body.graffiti = Graffiti::from([42; GRAFFITI_BYTES_LEN]);
+
+        assert!(self.client.post_beacon_blocks(&forged_block).await.is_err());
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "invalid blocks should be sent to network"
+        );
+
+        self.client
+            .post_beacon_blocks(&self.next_block)
+            .await
+            .unwrap();
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "the genuine block should be sent to network"
+        );
+
+        self
+    }
+
     pub async fn test_beacon_blocks(self) -> Self {
         for block_id in self.interesting_block_ids() {
             let expected = self.get_block(block_id);
@@ -1791,6 +1907,32 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_proposal_timings(self) -> Self {
+        let timings = self
+            .client
+            .get_lighthouse_proposal_timings()
+            .await
+            .unwrap()
+            .data;
+
+        assert!(!timings.is_empty());
+        for timing in timings {
+            let block = self
+                .chain
+                .block_at_slot(timing.slot)
+                .unwrap()
+                .expect("block was produced at slot");
+            assert_eq!(timing.block_root, Some(block.canonical_root()));
+            assert!(timing.block_requested_ms.is_some());
+            assert!(timing.block_produced_ms.is_some());
+            assert!(timing.block_received_ms.is_some());
+            assert!(timing.block_published_ms.is_some());
+            assert!(timing.block_imported_ms.is_some());
+        }
+
+        self
+    }
+
     pub async fn test_get_validator_attestation_data(self) -> Self {
         let mut state = self.chain.head_beacon_state().unwrap();
         let slot = state.slot;
@@ -1988,7 +2130,13 @@ impl ApiTester {
 
     #[cfg(target_os = "linux")]
     pub async fn test_get_lighthouse_health(self) -> Self {
-        self.client.get_lighthouse_health().await.unwrap();
+        let health = self.client.get_lighthouse_health().await.unwrap().data;
+
+        let cache_memory = health
+            .cache_memory
+            .expect("beacon node reports cache memory");
+        assert!(cache_memory.snapshot_cache_bytes.unwrap() > 0);
+        assert!(cache_memory.validator_pubkey_cache_bytes.unwrap() > 0);
 
         self
     }
@@ -2006,6 +2154,77 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_syncing_chains(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_syncing_chains()
+            .await
+            .unwrap()
+            .data;
+
+        // There are no peers to sync from.
+        assert!(result.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_peers_clients(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_peers_clients()
+            .await
+            .unwrap()
+            .data;
+
+        // The external peer has not identified itself.
+        let expected = vec![eth2::lighthouse::ClientPeers {
+            client: "Unknown".to_string(),
+            versions: vec![("unknown".to_string(), 1)].into_iter().collect(),
+            peers: vec![eth2::lighthouse::ClientPeer {
+                peer_id: self.external_peer_id.to_string(),
+                version: "unknown".to_string(),
+                agent_string: None,
+                enr_fork_id: None,
+            }],
+        }];
+
+        assert_eq!(result, expected);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_chain_split(self) -> Self {
+        let result = self.client.get_lighthouse_chain_split().await.unwrap().data;
+
+        // No peers have sent us a `Status` message.
+        assert_eq!(result, eth2::lighthouse::ChainSplitStatus::default());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_observed_block_headers(self) -> Self {
+        let head_block = self.chain.head_beacon_block().unwrap();
+
+        let result = self
+            .client
+            .get_lighthouse_observed_block_headers(head_block.slot())
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(result, vec![head_block.signed_block_header()]);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_quarantine(self) -> Self {
+        let result = self.client.get_lighthouse_quarantine().await.unwrap().data;
+
+        assert_eq!(result, self.chain.quarantine.objects().unwrap());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
@@ -2075,6 +2294,102 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_debug_archive(self) -> Self {
+        let bytes = self
+            .client
+            .get_lighthouse_debug_archive()
+            .await
+            .unwrap()
+            .unwrap();
+        let archive = DebugArchive::<E>::from_bytes(&bytes).unwrap();
+
+        let head_block_root = self.chain.head_info().unwrap().block_root;
+        assert_eq!(archive.head_block_root, head_block_root);
+        assert_eq!(archive.find_head().unwrap(), head_block_root);
+
+        self
+    }
+
+    pub async fn test_lighthouse_logging(self) -> Self {
+        let initial = self.client.get_lighthouse_logging().await.unwrap().data;
+        assert_eq!(initial.directives, "info");
+
+        self.client
+            .post_lighthouse_logging("warn,network=debug")
+            .await
+            .unwrap();
+        assert_eq!(
+            self.client
+                .get_lighthouse_logging()
+                .await
+                .unwrap()
+                .data
+                .directives,
+            "warn,network=debug"
+        );
+
+        // Invalid directives are rejected and leave the directives unchanged.
+        let error = self
+            .client
+            .post_lighthouse_logging("network=loud")
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            self.client
+                .get_lighthouse_logging()
+                .await
+                .unwrap()
+                .data
+                .directives,
+            "warn,network=debug"
+        );
+
+        // Module directives are merged into the current directives, keeping the default level.
+        self.client
+            .post_lighthouse_logging("network=trace,http_api=error")
+            .await
+            .unwrap();
+        assert_eq!(
+            self.client
+                .get_lighthouse_logging()
+                .await
+                .unwrap()
+                .data
+                .directives,
+            "warn,network=trace,http_api=error"
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_database_snapshot(self) -> Self {
+        let snapshot = self
+            .client
+            .post_lighthouse_database_snapshot(Duration::from_secs(SECONDS_PER_SLOT))
+            .await
+            .unwrap()
+            .data;
+
+        assert!(snapshot
+            .path
+            .starts_with(self.snapshot_dir.as_ref().unwrap().path()));
+        assert!(snapshot.hot_keys > 0);
+
+        // The copy of the hot database contains the head block.
+        let hot_db = LevelDB::<E>::open(&snapshot.path.join("chain_db")).unwrap();
+        assert_eq!(hot_db.keys_iter().count() as u64, snapshot.hot_keys);
+        let head_root = self.chain.head_info().unwrap().block_root;
+        assert!(hot_db
+            .key_exists(DBColumn::BeaconBlock.into(), head_root.as_bytes())
+            .unwrap());
+
+        let freezer_db = LevelDB::<E>::open(&snapshot.path.join("freezer_db")).unwrap();
+        assert_eq!(freezer_db.keys_iter().count() as u64, snapshot.cold_keys);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_staking(self) -> Self {
         let result = self.client.get_lighthouse_staking().await.unwrap();
 
@@ -2308,6 +2623,27 @@ async fn post_beacon_blocks_valid() {
     ApiTester::new().test_post_beacon_blocks_valid().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_equivocation() {
+    ApiTester::new()
+        .test_post_beacon_blocks_equivocation()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_forged_equivocation() {
+    ApiTester::new()
+        .test_post_beacon_blocks_forged_equivocation()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_concurrent_equivocation() {
+    ApiTester::new()
+        .test_post_beacon_blocks_concurrent_equivocation()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_beacon_blocks_invalid() {
     ApiTester::new().test_post_beacon_blocks_invalid().await;
@@ -2444,6 +2780,15 @@ async fn block_production() {
     ApiTester::new().test_block_production().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn proposal_timings() {
+    ApiTester::new()
+        .test_block_production()
+        .await
+        .test_get_lighthouse_proposal_timings()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn block_production_with_skip_slots() {
     ApiTester::new()
@@ -2524,8 +2869,18 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_syncing_chains()
+        .await
+        .test_get_lighthouse_peers_clients()
+        .await
+        .test_get_lighthouse_chain_split()
+        .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_observed_block_headers()
+        .await
+        .test_get_lighthouse_quarantine()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -2538,6 +2893,12 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
+        .test_get_lighthouse_debug_archive()
+        .await
+        .test_lighthouse_logging()
+        .await
+        .test_post_lighthouse_database_snapshot()
+        .await
         .test_get_lighthouse_staking()
         .await;
 }
@@ -49,6 +49,12 @@ pub struct Config {
     pub listen_addr: Ipv4Addr,
     pub listen_port: u16,
     pub allow_origin: Option<String>,
+    /// If `Some`, only metric families with names starting with one of these prefixes are
+    /// exported.
+    pub allowlist: Option<Vec<String>>,
+    /// If `Some`, metric families with more label sets than this (e.g., per-client or
+    /// per-validator metrics) have the additional label sets omitted.
+    pub max_series_per_metric: Option<usize>,
 }
 
 impl Default for Config {
@@ -58,6 +64,8 @@ impl Default for Config {
             listen_addr: Ipv4Addr::new(127, 0, 0, 1),
             listen_port: 5054,
             allow_origin: None,
+            allowlist: None,
+            max_series_per_metric: None,
         }
     }
 }
@@ -1,6 +1,7 @@
-use crate::Context;
+use crate::{Config, Context};
 use beacon_chain::BeaconChainTypes;
 use lighthouse_metrics::{Encoder, TextEncoder};
+use prometheus::proto::MetricFamily;
 
 pub use lighthouse_metrics::*;
 
@@ -35,15 +36,89 @@ pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
         (ctx.db_path.as_ref(), ctx.freezer_db_path.as_ref())
     {
         store::scrape_for_metrics(db_path, freezer_db_path);
+        warp_utils::metrics::scrape_disk_metrics(db_path);
     }
 
     eth2_libp2p::scrape_discovery_metrics();
 
     warp_utils::metrics::scrape_health_metrics();
 
-    encoder
-        .encode(&lighthouse_metrics::gather(), &mut buffer)
-        .unwrap();
+    let mut families = lighthouse_metrics::gather();
+    apply_limits(&mut families, &ctx.config);
+
+    encoder.encode(&families, &mut buffer).unwrap();
 
     String::from_utf8(buffer).map_err(|e| format!("Failed to encode prometheus info: {:?}", e))
 }
+
+/// Removes the metric families which are not in the `config.allowlist` and truncates each family
+/// to at most `config.max_series_per_metric` label sets.
+fn apply_limits(families: &mut Vec<MetricFamily>, config: &Config) {
+    if let Some(allowlist) = &config.allowlist {
+        families.retain(|family| {
+            allowlist
+                .iter()
+                .any(|prefix| family.get_name().starts_with(prefix.as_str()))
+        });
+    }
+
+    if let Some(max_series) = config.max_series_per_metric {
+        for family in families.iter_mut() {
+            family.mut_metric().truncate(max_series);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::proto::{LabelPair, Metric};
+
+    fn family(name: &str, series: usize) -> MetricFamily {
+        let mut family = MetricFamily::default();
+        family.set_name(name.to_string());
+        for i in 0..series {
+            let mut label = LabelPair::default();
+            label.set_name("client".to_string());
+            label.set_value(i.to_string());
+            let mut metric = Metric::default();
+            metric.mut_label().push(label);
+            family.mut_metric().push(metric);
+        }
+        family
+    }
+
+    #[test]
+    fn allowlist_and_max_series() {
+        let mut families = vec![
+            family("beacon_head_slot", 1),
+            family("libp2p_peers_per_client", 10),
+            family("process_cpu_seconds", 1),
+        ];
+        let config = Config {
+            allowlist: Some(vec!["beacon_".to_string(), "libp2p_".to_string()]),
+            max_series_per_metric: Some(4),
+            ..Config::default()
+        };
+
+        apply_limits(&mut families, &config);
+
+        assert_eq!(
+            families
+                .iter()
+                .map(|family| (family.get_name(), family.get_metric().len()))
+                .collect::<Vec<_>>(),
+            vec![("beacon_head_slot", 1), ("libp2p_peers_per_client", 4)]
+        );
+    }
+
+    #[test]
+    fn no_limits() {
+        let mut families = vec![family("beacon_head_slot", 1), family("libp2p_peers", 10)];
+
+        apply_limits(&mut families, &Config::default());
+
+        assert_eq!(families.len(), 2);
+        assert_eq!(families[1].get_metric().len(), 10);
+    }
+}
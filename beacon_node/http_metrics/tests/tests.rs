@@ -20,6 +20,8 @@ async fn returns_200_ok() {
                 listen_addr: Ipv4Addr::new(127, 0, 0, 1),
                 listen_port: 0,
                 allow_origin: None,
+                allowlist: None,
+                max_series_per_metric: None,
             },
             chain: None,
             db_path: None,
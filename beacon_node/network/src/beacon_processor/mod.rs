@@ -1000,6 +1000,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
             chain,
             network_tx: self.network_tx.clone(),
             sync_tx: self.sync_tx.clone(),
+            network_globals: self.network_globals.clone(),
             log: self.log.clone(),
         };
 
@@ -50,6 +50,56 @@ impl<T: BeaconChainTypes> Worker<T> {
         })
     }
 
+    /// Rejects the message with `message_id` and, if `quarantine_item` is present, adds it to the
+    /// quarantine so that it may be inspected later.
+    fn reject_and_quarantine<U: Encode>(
+        &self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        kind: &str,
+        reason: String,
+        quarantine_item: Option<U>,
+    ) {
+        self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+        if let Some(item) = quarantine_item {
+            self.quarantine(peer_id, kind, reason, &item);
+        }
+    }
+
+    /// Adds the SSZ bytes of `item`, which was rejected for `reason`, to the quarantine.
+    fn quarantine<U: Encode>(&self, peer_id: PeerId, kind: &str, reason: String, item: &U) {
+        let slot = self
+            .chain
+            .slot_clock
+            .now()
+            .unwrap_or_else(|| self.chain.slot_clock.genesis_slot());
+
+        if let Err(e) = self.chain.quarantine.insert(
+            slot,
+            kind,
+            peer_id.to_string(),
+            reason,
+            item.as_ssz_bytes(),
+        ) {
+            warn!(
+                self.log,
+                "Unable to quarantine rejected object";
+                "kind" => kind,
+                "error" => ?e,
+            );
+        }
+    }
+
+    /// Returns a copy of `item` if the quarantine is enabled, so that it may be quarantined if it
+    /// is rejected. It is only encoded once it has been rejected.
+    fn quarantine_copy<U: Clone>(&self, item: &U) -> Option<U> {
+        if self.chain.quarantine.is_enabled() {
+            Some(item.clone())
+        } else {
+            None
+        }
+    }
+
     /* Processing functions */
 
     /// Process the unaggregated attestation received from the gossip network and:
@@ -69,6 +119,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         seen_timestamp: Duration,
     ) {
         let beacon_block_root = attestation.data.beacon_block_root;
+        let quarantine_item = self.quarantine_copy(&attestation);
 
         let attestation = match self
             .chain
@@ -82,6 +133,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     beacon_block_root,
                     "unaggregated",
                     e,
+                    quarantine_item,
                 );
                 return;
             }
@@ -156,6 +208,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         seen_timestamp: Duration,
     ) {
         let beacon_block_root = aggregate.message.aggregate.data.beacon_block_root;
+        let quarantine_item = self.quarantine_copy(&aggregate);
 
         let aggregate = match self
             .chain
@@ -170,6 +223,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     beacon_block_root,
                     "aggregated",
                     e,
+                    quarantine_item,
                 );
                 return;
             }
@@ -247,6 +301,8 @@ impl<T: BeaconChainTypes> Worker<T> {
             get_block_delay_ms(seen_duration, &block.message, &self.chain.slot_clock),
         );
 
+        let quarantine_item = self.quarantine_copy(&block);
+
         let verified_block = match self.chain.verify_block_for_gossip(block) {
             Ok(verified_block) => {
                 info!(
@@ -308,7 +364,13 @@ impl<T: BeaconChainTypes> Worker<T> {
             | Err(e @ BlockError::GenesisBlock) => {
                 warn!(self.log, "Could not verify block for gossip, rejecting the block";
                             "error" => %e);
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    "block",
+                    e.to_string(),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
                 return;
             }
@@ -449,6 +511,9 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "block slot" => block.slot()
                 );
                 self.gossip_penalize_peer(peer_id, PeerAction::MidToleranceError);
+                if self.chain.quarantine.is_enabled() {
+                    self.quarantine(peer_id, "block", format!("{:?}", other), &*block);
+                }
                 trace!(
                     self.log,
                     "Invalid gossip beacon block ssz";
@@ -626,15 +691,20 @@ impl<T: BeaconChainTypes> Worker<T> {
 
     /// Handle an error whilst verifying an `Attestation` or `SignedAggregateAndProof` from the
     /// network.
-    pub fn handle_attestation_verification_failure(
+    pub fn handle_attestation_verification_failure<U: Encode>(
         &self,
         peer_id: PeerId,
         message_id: MessageId,
         beacon_block_root: Hash256,
         attestation_type: &str,
         error: AttnError,
+        quarantine_item: Option<U>,
     ) {
         metrics::register_attestation_error(&error);
+        let quarantine_kind = match attestation_type {
+            "aggregated" => "aggregate",
+            _ => "attestation",
+        };
         match &error {
             AttnError::FutureEpoch { .. }
             | AttnError::PastEpoch { .. }
@@ -667,7 +737,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::EmptyAggregationBitfield => {
@@ -678,7 +754,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  * violation of the spec nor indication of fault.
                  *
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::AggregatorPubkeyUnknown(_) => {
@@ -695,7 +777,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::AggregatorNotInCommittee { .. } => {
@@ -712,7 +800,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::AttestationAlreadyKnown { .. } => {
@@ -786,7 +880,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "block" => %beacon_block_root,
                     "type" => ?attestation_type,
                 );
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::UnknownHeadBlock { beacon_block_root } => {
@@ -833,7 +933,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::BadTargetEpoch => {
@@ -843,16 +949,28 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
-            AttnError::NoCommitteeForSlotAndIndex { .. } => {
+            AttnError::NoCommitteeForSlotAndIndex { .. } | AttnError::BadCommitteeIndex { .. } => {
                 /*
                  * It is not possible to attest this the given committee in the given slot.
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::NotExactlyOneAggregationBitSet(_) => {
@@ -861,7 +979,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::AttestsToFutureBlock { .. } => {
@@ -870,7 +994,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
 
@@ -884,7 +1014,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "expected" => ?expected,
                     "received" => ?received,
                 );
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::Invalid(_) => {
@@ -893,7 +1029,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::InvalidTargetEpoch { .. } => {
@@ -902,7 +1044,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::InvalidTargetRoot { .. } => {
@@ -911,7 +1059,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::LowToleranceError);
             }
             AttnError::TooManySkippedSlots {
@@ -931,7 +1085,13 @@ impl<T: BeaconChainTypes> Worker<T> {
                 );
                 // In this case we wish to penalize gossipsub peers that do this to avoid future
                 // attestations that have too many skip slots.
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_and_quarantine(
+                    message_id,
+                    peer_id,
+                    quarantine_kind,
+                    format!("{:?}", error),
+                    quarantine_item,
+                );
                 self.gossip_penalize_peer(peer_id, PeerAction::MidToleranceError);
             }
             AttnError::BeaconChainError(e) => {
@@ -1,4 +1,5 @@
 use crate::beacon_processor::worker::FUTURE_SLOT_TOLERANCE;
+use crate::metrics;
 use crate::service::NetworkMessage;
 use crate::status::ToStatusMessage;
 use crate::sync::SyncMessage;
@@ -7,9 +8,9 @@ use eth2_libp2p::rpc::StatusMessage;
 use eth2_libp2p::rpc::*;
 use eth2_libp2p::{PeerId, PeerRequestId, ReportSource, Response, SyncInfo};
 use itertools::process_results;
-use slog::{debug, error, warn};
+use slog::{crit, debug, error, info, warn};
 use slot_clock::SlotClock;
-use types::{Epoch, EthSpec, Hash256, Slot};
+use types::{EthSpec, Hash256, Slot};
 
 use super::Worker;
 
@@ -47,7 +48,6 @@ impl<T: BeaconChainTypes> Worker<T> {
         remote: &StatusMessage,
     ) -> Result<Option<String>, BeaconChainError> {
         let local = self.chain.status_message()?;
-        let start_slot = |epoch: Epoch| epoch.start_slot(T::EthSpec::slots_per_epoch());
 
         let irrelevant_reason = if local.fork_digest != remote.fork_digest {
             // The node is on a different network/fork
@@ -67,14 +67,7 @@ impl<T: BeaconChainTypes> Worker<T> {
             // current slot. This could be because they are using a different genesis time, or that
             // their or our system's clock is incorrect.
             Some("Different system clocks or genesis time".to_string())
-        } else if remote.finalized_epoch <= local.finalized_epoch
-            && remote.finalized_root != Hash256::zero()
-            && local.finalized_root != Hash256::zero()
-            && self
-                .chain
-                .root_at_slot(start_slot(remote.finalized_epoch))
-                .map(|root_opt| root_opt != Some(remote.finalized_root))?
-        {
+        } else if self.finalized_checkpoint_agreement(&local, remote)? == Some(false) {
             // The remote's finalized epoch is less than or equal to ours, but the block root is
             // different to the one in our chain. Therefore, the node is on a different chain and we
             // should not communicate with them.
@@ -86,7 +79,122 @@ impl<T: BeaconChainTypes> Worker<T> {
         Ok(irrelevant_reason)
     }
 
+    /// Determines if the finalized checkpoint of the `remote` is consistent with our chain.
+    ///
+    /// Returns `None` if this cannot be determined (e.g., the remote has finalized beyond us).
+    fn finalized_checkpoint_agreement(
+        &self,
+        local: &StatusMessage,
+        remote: &StatusMessage,
+    ) -> Result<Option<bool>, BeaconChainError> {
+        if remote.finalized_epoch <= local.finalized_epoch
+            && remote.finalized_root != Hash256::zero()
+            && local.finalized_root != Hash256::zero()
+        {
+            let start_slot = remote
+                .finalized_epoch
+                .start_slot(T::EthSpec::slots_per_epoch());
+            self.chain
+                .root_at_slot(start_slot)
+                .map(|root_opt| Some(root_opt == Some(remote.finalized_root)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Determines if the finalized checkpoint of the `remote` is on our canonical chain.
+    ///
+    /// Unlike `Self::finalized_checkpoint_agreement`, a remote finalized checkpoint which is ahead
+    /// of ours is also compared, against the unfinalized part of our chain. A node on a minority
+    /// fork is usually behind the majority in finality, so otherwise it would never compare
+    /// against them. `Status` messages do not carry the justified checkpoint, so this comparison
+    /// (which covers our justified checkpoint) stands in for comparing justified checkpoints.
+    ///
+    /// Returns `None` if this cannot be determined (e.g., the remote has finalized beyond our head).
+    fn canonical_chain_agreement(
+        &self,
+        local: &StatusMessage,
+        remote: &StatusMessage,
+    ) -> Result<Option<bool>, BeaconChainError> {
+        let start_slot = remote
+            .finalized_epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+        if remote.finalized_root == Hash256::zero() || start_slot > local.head_slot {
+            return Ok(None);
+        }
+
+        self.chain
+            .root_at_slot(start_slot)
+            .map(|root_opt| Some(root_opt == Some(remote.finalized_root)))
+    }
+
+    /// Records whether or not the finalized checkpoint of the `remote` agrees with ours, raising
+    /// an alert if a supermajority of recently observed peers disagree.
+    fn observe_finalized_checkpoint(
+        &self,
+        peer_id: PeerId,
+        remote: &StatusMessage,
+    ) -> Result<(), BeaconChainError> {
+        // Only compare against peers on the same network.
+        let local = self.chain.status_message()?;
+        if local.fork_digest != remote.fork_digest {
+            return Ok(());
+        }
+
+        let agrees = match self.canonical_chain_agreement(&local, remote)? {
+            Some(agrees) => agrees,
+            None => return Ok(()),
+        };
+
+        let (prior, status) = {
+            let mut observations = self
+                .network_globals
+                .finalized_checkpoint_observations
+                .write();
+            let prior = observations.status();
+            (prior, observations.observe(peer_id, agrees))
+        };
+
+        metrics::set_gauge(
+            &metrics::PEERS_AGREEING_FINALIZED_CHECKPOINT,
+            status.agreeing_peers as i64,
+        );
+        metrics::set_gauge(
+            &metrics::PEERS_DISAGREEING_FINALIZED_CHECKPOINT,
+            status.disagreeing_peers as i64,
+        );
+        metrics::set_gauge(
+            &metrics::MINORITY_FORK_DETECTED,
+            status.minority_fork as i64,
+        );
+
+        if status.minority_fork && !prior.minority_fork {
+            crit!(
+                self.log,
+                "Local node may be on a minority fork";
+                "msg" => "a supermajority of peers disagree with our finalized checkpoint",
+                "agreeing_peers" => status.agreeing_peers,
+                "disagreeing_peers" => status.disagreeing_peers,
+                "finalized_epoch" => local.finalized_epoch,
+                "finalized_root" => ?local.finalized_root,
+            );
+        } else if !status.minority_fork && prior.minority_fork {
+            info!(
+                self.log,
+                "Peers agree with our finalized checkpoint";
+                "agreeing_peers" => status.agreeing_peers,
+                "disagreeing_peers" => status.disagreeing_peers,
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn process_status(&self, peer_id: PeerId, status: StatusMessage) {
+        if let Err(e) = self.observe_finalized_checkpoint(peer_id, &status) {
+            error!(self.log, "Could not compare finalized checkpoints"; "error" => ?e);
+        }
+
         match self.check_peer_relevance(&status) {
             Ok(Some(irrelevant_reason)) => {
                 debug!(self.log, "Handshake Failure"; "peer" => %peer_id, "reason" => irrelevant_reason);
@@ -1,7 +1,7 @@
 use beacon_chain::attestation_verification::Error as AttnError;
 use eth2_libp2p::PubsubMessage;
 use eth2_libp2p::{
-    types::GossipKind, BandwidthSinks, GossipTopic, Gossipsub, NetworkGlobals, TopicHash,
+    types::GossipKind, BandwidthSinks, Eth2Enr, GossipTopic, Gossipsub, NetworkGlobals, TopicHash,
 };
 use fnv::FnvHashMap;
 pub use lighthouse_metrics::*;
@@ -115,6 +115,16 @@ lazy_static! {
             "Number of mesh peers for BeaconAggregateAndProof topic per client",
             &["Client"]
         );
+    pub static ref PEERS_PER_CLIENT_VERSION: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "libp2p_peers_per_client_version",
+        "The connected peers per client implementation and release",
+        &["Client", "Version"]
+    );
+    pub static ref PEERS_PER_NEXT_FORK_VERSION: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "libp2p_peers_per_next_fork_version",
+        "The connected peers per next fork version advertised in their ENR",
+        &["Version"]
+    );
 }
 
 lazy_static! {
@@ -359,6 +369,22 @@ lazy_static! {
         &["range_type"]
     );
 
+    /*
+     * Chain split detection
+     */
+    pub static ref PEERS_AGREEING_FINALIZED_CHECKPOINT: Result<IntGauge> = try_create_int_gauge(
+        "network_peers_agreeing_finalized_checkpoint",
+        "Number of recently observed peers with a finalized checkpoint on our chain",
+    );
+    pub static ref PEERS_DISAGREEING_FINALIZED_CHECKPOINT: Result<IntGauge> = try_create_int_gauge(
+        "network_peers_disagreeing_finalized_checkpoint",
+        "Number of recently observed peers with a finalized checkpoint conflicting with our chain",
+    );
+    pub static ref MINORITY_FORK_DETECTED: Result<IntGauge> = try_create_int_gauge(
+        "network_minority_fork_detected",
+        "Set to 1 if a supermajority of recently observed peers disagree with our finalized checkpoint",
+    );
+
     /*
      * Block Delay Metrics
      */
@@ -706,6 +732,56 @@ pub fn update_gossip_metrics<T: EthSpec>(
     }
 }
 
+/// Returns the release of a client version, dropping any build metadata such as a commit hash
+/// (e.g., `v1.3.0-3a24ca5` becomes `v1.3.0`) to limit the number of metric labels.
+fn client_release(version: &str) -> &str {
+    version
+        .split(|c| c == '-' || c == '+')
+        .next()
+        .unwrap_or(version)
+}
+
+pub fn update_client_metrics<T: EthSpec>(network_globals: &Arc<NetworkGlobals<T>>) {
+    // reset the counts
+    if PEERS_PER_CLIENT_VERSION
+        .as_ref()
+        .map(|metric| metric.reset())
+        .is_err()
+        || PEERS_PER_NEXT_FORK_VERSION
+            .as_ref()
+            .map(|metric| metric.reset())
+            .is_err()
+    {
+        return;
+    };
+
+    let mut peers_per_client_version: HashMap<(&'static str, String), i64> = HashMap::new();
+    let mut peers_per_next_fork_version: HashMap<String, i64> = HashMap::new();
+    for (_, info) in network_globals.peers.read().connected_peers() {
+        let release = client_release(&info.client.version).to_string();
+        *peers_per_client_version
+            .entry((info.client.kind.as_static(), release))
+            .or_default() += 1;
+
+        let next_fork_version = info
+            .enr
+            .as_ref()
+            .and_then(|enr| enr.eth2().ok())
+            .map(|enr_fork_id| hex::encode(enr_fork_id.next_fork_version))
+            .unwrap_or_else(|| "unknown".to_string());
+        *peers_per_next_fork_version
+            .entry(next_fork_version)
+            .or_default() += 1;
+    }
+
+    for ((client, version), peer_count) in peers_per_client_version {
+        set_gauge_entry(&PEERS_PER_CLIENT_VERSION, &[client, &version], peer_count);
+    }
+    for (version, peer_count) in peers_per_next_fork_version {
+        set_gauge_entry(&PEERS_PER_NEXT_FORK_VERSION, &[&version], peer_count);
+    }
+}
+
 pub fn update_sync_metrics<T: EthSpec>(network_globals: &Arc<NetworkGlobals<T>>) {
     // reset the counts
     if PEERS_PER_SYNC_TYPE
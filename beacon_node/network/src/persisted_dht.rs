@@ -5,6 +5,9 @@ use types::{EthSpec, Hash256};
 
 /// 32-byte key for accessing the `DhtEnrs`. All zero because `DhtEnrs` has its own column.
 pub const DHT_DB_KEY: Hash256 = Hash256::zero();
+/// 32-byte key for accessing the ENRs of peers which were connected and in good standing at
+/// shutdown. We use `repeat_byte` because it's a const fn.
+pub const GOOD_PEERS_DB_KEY: Hash256 = Hash256::repeat_byte(1);
 
 pub fn load_dht<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     store: Arc<HotColdDB<E, Hot, Cold>>,
@@ -27,6 +30,28 @@ pub fn persist_dht<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     store.put_item(&DHT_DB_KEY, &PersistedDht { enrs })
 }
 
+/// Load the ENRs of the peers which were connected and in good standing at shutdown, best first.
+pub fn load_good_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Vec<Enr> {
+    match store.get_item(&GOOD_PEERS_DB_KEY) {
+        Ok(Some(p)) => {
+            let p: PersistedDht = p;
+            p.enrs
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Attempt to persist the ENRs of known-good peers to `self.store`, so that they may be dialed
+/// immediately on restart rather than waiting for discovery.
+pub fn persist_good_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    enrs: Vec<Enr>,
+) -> Result<(), store::Error> {
+    store.put_item(&GOOD_PEERS_DB_KEY, &PersistedDht { enrs })
+}
+
 /// Wrapper around DHT for persistence to disk.
 pub struct PersistedDht {
     pub enrs: Vec<Enr>,
@@ -74,4 +99,22 @@ mod tests {
         let dht: PersistedDht = store.get_item(&DHT_DB_KEY).unwrap().unwrap();
         assert_eq!(dht.enrs, enrs);
     }
+
+    #[test]
+    fn test_persisted_good_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+        let enrs = vec![Enr::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").unwrap()];
+        assert!(load_good_peers(store.clone()).is_empty());
+
+        persist_good_peers(store.clone(), enrs.clone()).unwrap();
+        assert_eq!(load_good_peers(store.clone()), enrs);
+        // The good peers are stored separately from the DHT.
+        assert!(load_dht(store).is_empty());
+    }
 }
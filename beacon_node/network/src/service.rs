@@ -1,4 +1,4 @@
-use crate::persisted_dht::{load_dht, persist_dht};
+use crate::persisted_dht::{load_dht, load_good_peers, persist_dht, persist_good_peers};
 use crate::router::{Router, RouterMessage};
 use crate::{
     attestation_service::{AttServiceMessage, AttestationService},
@@ -10,11 +10,11 @@ use eth2_libp2p::{
     rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId},
     Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, ReportSource, Request, Response,
 };
-use eth2_libp2p::{types::GossipKind, BehaviourEvent, MessageId, NetworkGlobals, PeerId};
+use eth2_libp2p::{types::GossipKind, BehaviourEvent, EnrExt, MessageId, NetworkGlobals, PeerId};
 use eth2_libp2p::{MessageAcceptance, Service as LibP2PService};
 use futures::prelude::*;
 use slog::{debug, error, info, o, trace, warn};
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{cmp::Ordering, net::SocketAddr, sync::Arc, time::Duration};
 use store::HotColdDB;
 use tokio::sync::mpsc;
 use tokio::time::Sleep;
@@ -179,6 +179,20 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             for enr in enrs_to_load {
                 libp2p.swarm.add_enr(enr.clone());
             }
+
+            // Dial the peers which were known to be good before the last shutdown, rather than
+            // waiting for discovery to find peers.
+            let good_peers =
+                load_good_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
+            debug!(
+                network_log,
+                "Dialing previously known good peers"; "peers" => good_peers.len().min(config.target_peers)
+            );
+            for enr in good_peers.into_iter().take(config.target_peers) {
+                let peer_id = enr.peer_id();
+                libp2p.swarm.add_enr(enr);
+                libp2p.swarm.dial(&peer_id);
+            }
         }
 
         // launch derived network services
@@ -255,6 +269,8 @@ fn spawn_service<T: BeaconChainTypes>(
                     );
                     // update sync metrics
                     metrics::update_sync_metrics(&service.network_globals);
+                    // update client diversity metrics
+                    metrics::update_client_metrics(&service.network_globals);
 
                 }
                 _ = service.gossipsub_parameter_update.tick() => {
@@ -579,6 +595,36 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
             ),
         }
 
+        // Record the connected peers in good standing, best first, so they may be dialed on restart.
+        let good_peers = {
+            let peers = self.network_globals.peers.read();
+            let mut good_peers = peers
+                .connected_peers()
+                .filter(|(_, info)| info.score().score() >= 0.0)
+                .filter_map(|(_, info)| Some((info.score().score(), info.enr.clone()?)))
+                .collect::<Vec<_>>();
+            good_peers.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+            good_peers
+                .into_iter()
+                .map(|(_, enr)| enr)
+                .collect::<Vec<_>>()
+        };
+        debug!(
+            self.log,
+            "Persisting good peers to store";
+            "Number of peers" => good_peers.len(),
+        );
+        if let Err(e) = persist_good_peers::<T::EthSpec, T::HotStore, T::ColdStore>(
+            self.store.clone(),
+            good_peers,
+        ) {
+            error!(
+                self.log,
+                "Failed to persist good peers on drop";
+                "error" => ?e
+            )
+        }
+
         // attempt to remove port mappings
         crate::nat::remove_mappings(self.upnp_mappings.0, self.upnp_mappings.1, &self.log);
 
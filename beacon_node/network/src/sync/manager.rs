@@ -628,6 +628,8 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
     /// Updates the global sync state and logs any changes.
     fn update_sync_state(&mut self) {
+        *self.network_globals.sync_chains.write() = self.range_sync.chains_status();
+
         let new_state: SyncState = match self.range_sync.state() {
             Err(e) => {
                 crit!(self.log, "Error getting range sync state"; "error" => %e);
@@ -1,5 +1,6 @@
 use crate::sync::RequestId;
 use eth2_libp2p::rpc::methods::BlocksByRangeRequest;
+use eth2_libp2p::types::SyncBatchStatus;
 use eth2_libp2p::PeerId;
 use ssz::Encode;
 use std::collections::HashSet;
@@ -147,6 +148,33 @@ impl<T: EthSpec> BatchInfo<T> {
         &self.failed_processing_attempts
     }
 
+    /// Returns a snapshot of the batch, which starts at `start_epoch`.
+    pub fn status(&self, start_epoch: Epoch) -> SyncBatchStatus {
+        let state = match self.state {
+            BatchState::AwaitingDownload => "AwaitingDownload",
+            BatchState::Downloading(..) => "Downloading",
+            BatchState::AwaitingProcessing(..) => "AwaitingProcessing",
+            BatchState::Processing(..) => "Processing",
+            BatchState::AwaitingValidation(..) => "AwaitingValidation",
+            BatchState::Poisoned => "Poisoned",
+            BatchState::Failed => "Failed",
+        };
+        let peer = match self.state {
+            BatchState::Poisoned => None,
+            _ => self.current_peer().map(ToString::to_string),
+        };
+
+        SyncBatchStatus {
+            start_epoch,
+            start_slot: self.start_slot,
+            end_slot: self.end_slot - 1,
+            state: state.to_string(),
+            peer,
+            failed_download_attempts: self.failed_download_attempts.len(),
+            failed_processing_attempts: self.failed_processing_attempts.len(),
+        }
+    }
+
     /// Adds a block to a downloading batch.
     pub fn add_block(&mut self, block: SignedBeaconBlock<T>) -> Result<(), WrongState> {
         match self.state.poison() {
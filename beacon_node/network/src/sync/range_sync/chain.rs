@@ -1,8 +1,10 @@
 use super::batch::{BatchInfo, BatchState};
+use super::sync_type::RangeSyncType;
 use crate::beacon_processor::ProcessId;
 use crate::beacon_processor::WorkEvent as BeaconWorkEvent;
 use crate::sync::{network_context::SyncNetworkContext, BatchProcessResult, RequestId};
 use beacon_chain::BeaconChainTypes;
+use eth2_libp2p::types::SyncChainStatus;
 use eth2_libp2p::{PeerAction, PeerId};
 use fnv::FnvHashMap;
 use rand::seq::SliceRandom;
@@ -903,6 +905,26 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 
+    /// Returns a snapshot of the chain and its batches.
+    pub fn status(&self, sync_type: RangeSyncType) -> SyncChainStatus {
+        SyncChainStatus {
+            id: self.id,
+            sync_type: sync_type.as_str().to_string(),
+            is_syncing: self.is_syncing(),
+            start_epoch: self.start_epoch,
+            target_head_slot: self.target_head_slot,
+            target_head_root: self.target_head_root,
+            processing_target: self.processing_target,
+            validated_epochs: self.validated_epochs(),
+            peers: self.peers().map(|peer_id| peer_id.to_string()).collect(),
+            batches: self
+                .batches
+                .iter()
+                .map(|(id, batch)| batch.status(*id))
+                .collect(),
+        }
+    }
+
     /// Attempts to request the next required batches from the peer pool if the chain is syncing. It will exhaust the peer
     /// pool and left over batches until the batch buffer is reached or all peers are exhausted.
     fn request_batches(
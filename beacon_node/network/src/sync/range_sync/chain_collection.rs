@@ -9,6 +9,7 @@ use crate::beacon_processor::WorkEvent as BeaconWorkEvent;
 use crate::metrics;
 use crate::sync::network_context::SyncNetworkContext;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2_libp2p::types::SyncChainStatus;
 use eth2_libp2p::PeerId;
 use eth2_libp2p::SyncInfo;
 use fnv::FnvHashMap;
@@ -251,6 +252,19 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         }
     }
 
+    /// Returns a snapshot of every chain in the collection.
+    pub fn status(&self) -> Vec<SyncChainStatus> {
+        self.finalized_chains
+            .values()
+            .map(|chain| chain.status(RangeSyncType::Finalized))
+            .chain(
+                self.head_chains
+                    .values()
+                    .map(|chain| chain.status(RangeSyncType::Head)),
+            )
+            .collect()
+    }
+
     /// This looks at all current finalized chains and decides if a new chain should be prioritised
     /// or not.
     fn update_finalized_chains(
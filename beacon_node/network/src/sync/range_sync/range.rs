@@ -47,6 +47,7 @@ use crate::status::ToStatusMessage;
 use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::{BatchProcessResult, RequestId};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2_libp2p::types::SyncChainStatus;
 use eth2_libp2p::PeerId;
 use eth2_libp2p::SyncInfo;
 use slog::{crit, debug, error, trace};
@@ -94,6 +95,11 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         self.chains.state()
     }
 
+    /// Returns a snapshot of the finalized and head chains being synced.
+    pub fn chains_status(&self) -> Vec<SyncChainStatus> {
+        self.chains.status()
+    }
+
     /// A useful peer has been added. The SyncManager has identified this peer as needing either
     /// a finalized or head chain sync. This processes the peer and starts/resumes any chain that
     /// may need to be synced as a result. A new peer, may increase the peer pool of a finalized
@@ -12,6 +12,7 @@ use attestation_id::AttestationId;
 use attester_slashing::AttesterSlashingMaxCover;
 use max_cover::{maximum_cover, MaxCover};
 use parking_lot::RwLock;
+use ssz::Encode;
 use state_processing::per_block_processing::errors::AttestationValidationError;
 use state_processing::per_block_processing::{
     get_slashable_indices_modular, verify_attestation_for_block_inclusion, verify_exit,
@@ -206,14 +207,23 @@ impl<T: EthSpec> OperationPool<T> {
 
     /// Remove attestations which are too old to be included in a block.
     pub fn prune_attestations(&self, current_epoch: Epoch) {
+        let mut num_pruned = 0;
+
         // Prune attestations that are from before the previous epoch.
         self.attestations.write().retain(|_, attestations| {
             // All the attestations in this bucket have the same data, so we only need to
             // check the first one.
-            attestations
+            let keep = attestations
                 .first()
-                .map_or(false, |att| current_epoch <= att.data.target.epoch + 1)
+                .map_or(false, |att| current_epoch <= att.data.target.epoch + 1);
+
+            if !keep {
+                num_pruned += attestations.len();
+            }
+            keep
         });
+
+        observe_pruned("attestation", num_pruned);
     }
 
     /// Insert a proposer slashing into the pool.
@@ -289,40 +299,48 @@ impl<T: EthSpec> OperationPool<T> {
 
     /// Prune proposer slashings for validators which are exited in the finalized epoch.
     pub fn prune_proposer_slashings(&self, head_state: &BeaconState<T>) {
-        prune_validator_hash_map(
+        let num_pruned = prune_validator_hash_map(
             &mut self.proposer_slashings.write(),
             |validator| validator.exit_epoch <= head_state.finalized_checkpoint.epoch,
             head_state,
         );
+
+        observe_pruned("proposer_slashing", num_pruned);
     }
 
     /// Prune attester slashings for all slashed or withdrawn validators, or attestations on another
     /// fork.
     pub fn prune_attester_slashings(&self, head_state: &BeaconState<T>) {
-        self.attester_slashings
-            .write()
-            .retain(|(slashing, fork_version)| {
-                let previous_fork_is_finalized =
-                    head_state.finalized_checkpoint.epoch >= head_state.fork.epoch;
-                // Prune any slashings which don't match the current fork version, or the previous
-                // fork version if it is not finalized yet.
-                let fork_ok = (fork_version == &head_state.fork.current_version)
-                    || (fork_version == &head_state.fork.previous_version
-                        && !previous_fork_is_finalized);
-                // Slashings that don't slash any validators can also be dropped.
-                let slashing_ok =
-                    get_slashable_indices_modular(head_state, slashing, |_, validator| {
-                        // Declare that a validator is still slashable if they have not exited prior
-                        // to the finalized epoch.
-                        //
-                        // We cannot check the `slashed` field since the `head` is not finalized and
-                        // a fork could un-slash someone.
-                        validator.exit_epoch > head_state.finalized_checkpoint.epoch
-                    })
-                    .map_or(false, |indices| !indices.is_empty());
-
-                fork_ok && slashing_ok
-            });
+        let mut attester_slashings = self.attester_slashings.write();
+        let initial_len = attester_slashings.len();
+
+        attester_slashings.retain(|(slashing, fork_version)| {
+            let previous_fork_is_finalized =
+                head_state.finalized_checkpoint.epoch >= head_state.fork.epoch;
+            // Prune any slashings which don't match the current fork version, or the previous
+            // fork version if it is not finalized yet.
+            let fork_ok = (fork_version == &head_state.fork.current_version)
+                || (fork_version == &head_state.fork.previous_version
+                    && !previous_fork_is_finalized);
+            // Slashings that don't slash any validators can also be dropped.
+            let slashing_ok =
+                get_slashable_indices_modular(head_state, slashing, |_, validator| {
+                    // Declare that a validator is still slashable if they have not exited prior
+                    // to the finalized epoch.
+                    //
+                    // We cannot check the `slashed` field since the `head` is not finalized and
+                    // a fork could un-slash someone.
+                    validator.exit_epoch > head_state.finalized_checkpoint.epoch
+                })
+                .map_or(false, |indices| !indices.is_empty());
+
+            fork_ok && slashing_ok
+        });
+
+        observe_pruned(
+            "attester_slashing",
+            initial_len.saturating_sub(attester_slashings.len()),
+        );
     }
 
     /// Total number of attester slashings in the pool.
@@ -358,7 +376,7 @@ impl<T: EthSpec> OperationPool<T> {
 
     /// Prune if validator has already exited at or before the finalized checkpoint of the head.
     pub fn prune_voluntary_exits(&self, head_state: &BeaconState<T>) {
-        prune_validator_hash_map(
+        let num_pruned = prune_validator_hash_map(
             &mut self.voluntary_exits.write(),
             // This condition is slightly too loose, since there will be some finalized exits that
             // are missed here.
@@ -368,6 +386,8 @@ impl<T: EthSpec> OperationPool<T> {
             |validator| validator.exit_epoch <= head_state.finalized_checkpoint.epoch,
             head_state,
         );
+
+        observe_pruned("voluntary_exit", num_pruned);
     }
 
     /// Prune all types of transactions given the latest head state and head fork.
@@ -383,6 +403,30 @@ impl<T: EthSpec> OperationPool<T> {
         self.voluntary_exits.read().len()
     }
 
+    /// Returns an estimate of the number of bytes used by the operations in the pool, based upon
+    /// their SSZ size.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let attestations = self
+            .attestations
+            .read()
+            .values()
+            .flatten()
+            .map(Encode::ssz_bytes_len)
+            .sum::<usize>();
+        let attester_slashings = self
+            .attester_slashings
+            .read()
+            .iter()
+            .map(|(slashing, _)| slashing.ssz_bytes_len())
+            .sum::<usize>();
+        let proposer_slashings =
+            self.num_proposer_slashings() * <ProposerSlashing as Encode>::ssz_fixed_len();
+        let voluntary_exits =
+            self.num_voluntary_exits() * <SignedVoluntaryExit as Encode>::ssz_fixed_len();
+
+        attestations + attester_slashings + proposer_slashings + voluntary_exits
+    }
+
     /// Returns all known `Attestation` objects.
     ///
     /// This method may return objects that are invalid for block inclusion.
@@ -460,7 +504,8 @@ where
         .collect()
 }
 
-/// Remove all entries from the given hash map for which `prune_if` returns true.
+/// Remove all entries from the given hash map for which `prune_if` returns true, returning the
+/// number of entries removed.
 ///
 /// The keys in the map should be validator indices, which will be looked up
 /// in the state's validator registry and then passed to `prune_if`.
@@ -469,15 +514,29 @@ fn prune_validator_hash_map<T, F, E: EthSpec>(
     map: &mut HashMap<u64, T>,
     prune_if: F,
     head_state: &BeaconState<E>,
-) where
+) -> usize
+where
     F: Fn(&Validator) -> bool,
 {
+    let initial_len = map.len();
+
     map.retain(|&validator_index, _| {
         head_state
             .validators
             .get(validator_index as usize)
             .map_or(true, |validator| !prune_if(validator))
     });
+
+    initial_len.saturating_sub(map.len())
+}
+
+/// Records that `num_pruned` operations of type `kind` were pruned from the pool.
+fn observe_pruned(kind: &str, num_pruned: usize) {
+    metrics::inc_counter_vec_by(
+        &metrics::OPERATIONS_PRUNED_TOTAL,
+        &[kind],
+        num_pruned as u64,
+    );
 }
 
 /// Compare two operation pools.
@@ -1080,6 +1139,27 @@ mod release_tests {
         assert_eq!(op_pool.get_slashings(state, spec).0, vec![slashing]);
     }
 
+    // Check that pruning a proposer slashing for a validator which has exited is counted
+    #[test]
+    fn prune_proposer_slashing_exited() {
+        let mut ctxt = TestContext::new();
+        let slashing = ctxt.proposer_slashing(0);
+        ctxt.op_pool
+            .insert_proposer_slashing(slashing.validate(&ctxt.state, &ctxt.spec).unwrap());
+
+        let num_pruned = || {
+            metrics::get_int_counter(&metrics::OPERATIONS_PRUNED_TOTAL, &["proposer_slashing"])
+                .map_or(0, |counter| counter.get())
+        };
+        let num_pruned_before = num_pruned();
+
+        ctxt.state.validators[0].exit_epoch = ctxt.state.finalized_checkpoint.epoch;
+        ctxt.op_pool.prune_proposer_slashings(&ctxt.state);
+
+        assert_eq!(ctxt.op_pool.num_proposer_slashings(), 0);
+        assert_eq!(num_pruned() - num_pruned_before, 1);
+    }
+
     // Sanity check on the pruning of attester slashings
     #[test]
     fn prune_attester_slashing_noop() {
@@ -11,4 +11,9 @@ lazy_static! {
         "op_pool_attestation_curr_epoch_packing_time",
         "Time to pack current epoch attestations"
     );
+    pub static ref OPERATIONS_PRUNED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "op_pool_operations_pruned_total",
+        "Count of operations removed from the op pool by pruning",
+        &["type"]
+    );
 }
@@ -165,6 +165,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("One or more comma-delimited trusted peer ids which always have the highest score according to the peer scoring system.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("gossip-rate-limits")
+                .long("gossip-rate-limits")
+                .value_name("TOPIC:MESSAGES/SECONDS")
+                .help("One or more comma-delimited limits on the number of messages each peer may \
+                       send on a gossip topic, e.g. \"beacon_block:4/12,beacon_attestation:64/12\". \
+                       A limit for beacon_attestation applies to each subnet. Messages over the \
+                       limit are ignored and the peer is penalized. By default there are no limits.")
+                .takes_value(true),
+        )
         /* REST API related arguments */
         .arg(
             Arg::with_name("http")
@@ -198,6 +208,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     address of this server (e.g., http://localhost:5052).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-snapshot-dir")
+                .long("http-snapshot-dir")
+                .value_name("DIR")
+                .help("Enables database snapshots via the HTTP API, creating them in the given \
+                    directory. Snapshots are disabled if this flag is not provided.")
+                .takes_value(true),
+        )
         /* Prometheus metrics HTTP server related arguments */
         .arg(
             Arg::with_name("metrics")
@@ -231,6 +249,55 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     address of this server (e.g., http://localhost:5054).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("metrics-allowlist")
+                .long("metrics-allowlist")
+                .value_name("PREFIXES")
+                .help("A comma-separated list of metric name prefixes (e.g., \"beacon_,libp2p_\"). \
+                    If supplied, only metrics with names starting with one of these prefixes are \
+                    exported. All metrics are exported by default.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-max-series")
+                .long("metrics-max-series")
+                .value_name("COUNT")
+                .help("The maximum number of label sets exported for each metric. Limits the \
+                    size of high-cardinality metrics such as those labelled by client or \
+                    validator. Unlimited by default.")
+                .takes_value(true),
+        )
+
+        /*
+         * Remote monitoring
+         */
+        .arg(
+            Arg::with_name("monitoring-endpoint")
+                .long("monitoring-endpoint")
+                .value_name("URL")
+                .help("Enables the monitoring service, which periodically sends a JSON summary \
+                    of the node's health (sync status, peers, monitored validators and system \
+                    statistics) to this URL.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-api-key")
+                .long("monitoring-api-key")
+                .value_name("KEY")
+                .help("An API key for the monitoring service, sent as the \"apikey\" query \
+                    parameter.")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-period")
+                .long("monitoring-period")
+                .value_name("SECONDS")
+                .help("The number of seconds between each summary sent to the monitoring \
+                    endpoint. [default: 60]")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
 
         /*
          * Standard staking flags
@@ -309,7 +376,34 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("block-cache-size")
                 .long("block-cache-size")
                 .value_name("SIZE")
-                .help("Specifies how many blocks the database should cache in memory [default: 5]")
+                .help("Specifies how many blocks the database should cache in memory. \
+                       [default: scaled with system memory, 5 on hosts with 8GB]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("snapshot-cache-size")
+                .long("snapshot-cache-size")
+                .value_name("SIZE")
+                .help("Specifies how many block and state snapshots should be cached in memory \
+                       for block processing. [default: scaled with system memory, 4 on hosts \
+                       with 8GB]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("shuffling-cache-size")
+                .long("shuffling-cache-size")
+                .value_name("SIZE")
+                .help("Specifies how many committee shufflings should be cached in memory. \
+                       [default: scaled with system memory, 16 on hosts with 8GB]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("state-provider-cache-size")
+                .long("state-provider-cache-size")
+                .value_name("SIZE")
+                .help("Specifies how many states recently loaded from the database should be \
+                       cached in memory for the HTTP API and block processing. [default: scaled \
+                       with system memory, 4 on hosts with 8GB]")
                 .takes_value(true)
         )
 
@@ -327,6 +421,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("If present, apply compaction to the database on start-up. Use with caution. \
                        It is generally not recommended unless auto-compaction is disabled.")
         )
+        .arg(
+            Arg::with_name("disable-startup-checks")
+                .long("disable-startup-checks")
+                .help("If present, do not check port availability, eth1 connectivity, the \
+                       database and the system clock whilst starting. Failing checks are \
+                       otherwise fatal, except for eth1 connectivity which only logs a warning.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("auto-compact-db")
                 .long("auto-compact-db")
@@ -334,6 +436,27 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("true")
         )
+        .arg(
+            Arg::with_name("db-backend")
+                .long("db-backend")
+                .value_name("BACKEND")
+                .help("Specifies the key-value database used for the hot and cold databases. \
+                       An existing database must be opened with the backend it was created \
+                       with; use `lcli convert-db` to convert between backends.")
+                .possible_values(&["leveldb", "rocksdb"])
+                .takes_value(true)
+                .default_value("leveldb")
+        )
+        .arg(
+            Arg::with_name("db-compression")
+                .long("db-compression")
+                .value_name("ALGORITHM")
+                .help("Specifies the compression applied to newly written data when using the \
+                       rocksdb backend. Ignored by leveldb.")
+                .possible_values(&["none", "snappy", "lz4", "zstd"])
+                .takes_value(true)
+                .default_value("snappy")
+        )
 
         /*
          * Misc.
@@ -359,6 +482,32 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("NUM_SLOTS")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("quarantine-size")
+                .long("quarantine-size")
+                .help(
+                    "Retain the SSZ bytes of up to this many gossip blocks and attestations which \
+                    failed verification, so that they can be retrieved from the \
+                    /lighthouse/quarantine HTTP API endpoint. The objects are stored in the database \
+                    and those rejected at the oldest slots are pruned once the limit is reached. \
+                    Disabled by default."
+                )
+                .value_name("SIZE")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("index-attestations")
+                .long("index-attestations")
+                .help(
+                    "Maintain a database index from each block root to the attestations included \
+                    in blocks which voted for it, served by the \
+                    /lighthouse/beacon/blocks/{block_root}/attestations HTTP API endpoint. Only \
+                    blocks imported while this flag is set are indexed. Attestations are removed \
+                    from the index once the block they voted for is pruned or becomes an ancestor \
+                    of the finalized block, after which the endpoint returns a 404 for it."
+                )
+                .takes_value(false)
+        )
         /*
          * Slasher.
          */
@@ -450,6 +599,34 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("WSS_CHECKPOINT")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("checkpoint-state")
+                .long("checkpoint-state")
+                .help("Path to an SSZ-encoded finalized BeaconState to start syncing from, rather \
+                       than genesis. Must be the post-state of --checkpoint-block. Only used when \
+                       the database is empty.")
+                .value_name("STATE_SSZ")
+                .takes_value(true)
+                .requires("checkpoint-block")
+        )
+        .arg(
+            Arg::with_name("checkpoint-block")
+                .long("checkpoint-block")
+                .help("Path to the SSZ-encoded SignedBeaconBlock of --checkpoint-state.")
+                .value_name("BLOCK_SSZ")
+                .takes_value(true)
+                .requires("checkpoint-state")
+        )
+        .arg(
+            Arg::with_name("checkpoint-sync-url")
+                .long("checkpoint-sync-url")
+                .help("Set the remote beacon node HTTP endpoint to use for checkpoint sync. The \
+                       finalized block and state are downloaded from this node, which must be \
+                       trusted. Only used when the database is empty.")
+                .value_name("BEACON_NODE")
+                .takes_value(true)
+                .conflicts_with("checkpoint-state")
+        )
         .arg(
             Arg::with_name("validator-monitor-auto")
                 .long("validator-monitor-auto")
@@ -2,7 +2,9 @@ use clap::ArgMatches;
 use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{ClientConfig, ClientGenesis};
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
-use eth2_libp2p::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use eth2_libp2p::{
+    multiaddr::Protocol, types::GossipRateLimit, Enr, Multiaddr, NetworkConfig, PeerIdSerialized,
+};
 use eth2_network_config::{Eth2NetworkConfig, DEFAULT_HARDCODED_NETWORK};
 use slog::{info, warn, Logger};
 use std::cmp;
@@ -106,6 +108,11 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.allow_origin = Some(allow_origin.to_string());
     }
 
+    if let Some(snapshot_dir) = cli_args.value_of("http-snapshot-dir") {
+        client_config.http_api.snapshot_dir = Some(PathBuf::from(snapshot_dir));
+    }
+    client_config.http_api.data_dir = Some(client_config.data_dir.clone());
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -135,6 +142,20 @@ pub fn get_config<E: EthSpec>(
         client_config.http_metrics.allow_origin = Some(allow_origin.to_string());
     }
 
+    if let Some(allowlist) = cli_args.value_of("metrics-allowlist") {
+        client_config.http_metrics.allowlist = Some(
+            allowlist
+                .split(',')
+                .map(str::trim)
+                .filter(|prefix| !prefix.is_empty())
+                .map(String::from)
+                .collect(),
+        );
+    }
+
+    client_config.http_metrics.max_series_per_metric =
+        clap_utils::parse_optional(cli_args, "metrics-max-series")?;
+
     // Log a warning indicating an open HTTP server if it wasn't specified explicitly
     // (e.g. using the --staking flag).
     if cli_args.is_present("staking") {
@@ -144,6 +165,18 @@ pub fn get_config<E: EthSpec>(
         );
     }
 
+    /*
+     * Remote monitoring
+     */
+    if let Some(endpoint) = cli_args.value_of("monitoring-endpoint") {
+        client_config.monitoring = Some(client::monitoring::Config {
+            endpoint: endpoint.to_string(),
+            api_key: cli_args.value_of("monitoring-api-key").map(String::from),
+            update_period_secs: clap_utils::parse_optional(cli_args, "monitoring-period")?
+                .unwrap_or(client::monitoring::DEFAULT_UPDATE_PERIOD_SECS),
+        });
+    }
+
     /*
      * Eth1
      */
@@ -186,6 +219,13 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.purge_cache = true;
     }
 
+    if let Some(timeout_multiplier) = clap_utils::parse_optional(cli_args, "timeout-multiplier")? {
+        if timeout_multiplier == 0 {
+            return Err("--timeout-multiplier must be at least 1".to_string());
+        }
+        client_config.eth1.timeout_multiplier = timeout_multiplier;
+    }
+
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
         client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
     }
@@ -201,11 +241,7 @@ pub fn get_config<E: EthSpec>(
         );
     }
 
-    if let Some(block_cache_size) = cli_args.value_of("block-cache-size") {
-        client_config.store.block_cache_size = block_cache_size
-            .parse()
-            .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
-    }
+    set_cache_sizes(cli_args, &mut client_config, &log)?;
 
     client_config.store.compact_on_init = cli_args.is_present("compact-db");
     if let Some(compact_on_prune) = cli_args.value_of("auto-compact-db") {
@@ -213,6 +249,14 @@ pub fn get_config<E: EthSpec>(
             .parse()
             .map_err(|_| "auto-compact-db takes a boolean".to_string())?;
     }
+    if let Some(backend) = clap_utils::parse_optional(cli_args, "db-backend")? {
+        client_config.store.backend = backend;
+    }
+    if let Some(compression) = clap_utils::parse_optional(cli_args, "db-compression")? {
+        client_config.store.compression = compression;
+    }
+
+    client_config.startup_checks = !cli_args.is_present("disable-startup-checks");
 
     /*
      * Zero-ports
@@ -270,10 +314,36 @@ pub fn get_config<E: EthSpec>(
         // Note: re-serializing the genesis state is not so efficient, however it avoids adding
         // trait bounds to the `ClientGenesis` enum. This would have significant flow-on
         // effects.
-        client_config.genesis = ClientGenesis::SszBytes {
-            genesis_state_bytes,
+        client_config.genesis = if let (Some(anchor_state_path), Some(anchor_block_path)) = (
+            cli_args.value_of("checkpoint-state"),
+            cli_args.value_of("checkpoint-block"),
+        ) {
+            ClientGenesis::WeakSubjSszBytes {
+                genesis_state_bytes,
+                anchor_state_path: PathBuf::from(anchor_state_path),
+                anchor_block_bytes: fs::read(anchor_block_path).map_err(|e| {
+                    format!(
+                        "Unable to read checkpoint file {}: {:?}",
+                        anchor_block_path, e
+                    )
+                })?,
+            }
+        } else if let Some(url) = cli_args.value_of("checkpoint-sync-url") {
+            ClientGenesis::CheckpointSyncUrl {
+                genesis_state_bytes,
+                url: url.to_string(),
+            }
+        } else {
+            ClientGenesis::SszBytes {
+                genesis_state_bytes,
+            }
         };
     } else {
+        if cli_args.is_present("checkpoint-state") || cli_args.is_present("checkpoint-sync-url") {
+            return Err(
+                "Checkpoint sync requires a network with a known genesis state".to_string(),
+            );
+        }
         client_config.genesis = ClientGenesis::DepositContract;
     }
 
@@ -341,6 +411,14 @@ pub fn get_config<E: EthSpec>(
         };
     }
 
+    if let Some(quarantine_size) = clap_utils::parse_optional(cli_args, "quarantine-size")? {
+        client_config.chain.quarantine_size = quarantine_size;
+    }
+
+    if cli_args.is_present("index-attestations") {
+        client_config.chain.index_attestations_by_block_root = true;
+    }
+
     if cli_args.is_present("slasher") {
         let slasher_dir = if let Some(slasher_dir) = cli_args.value_of("slasher-dir") {
             PathBuf::from(slasher_dir)
@@ -418,6 +496,99 @@ pub fn get_config<E: EthSpec>(
     Ok(client_config)
 }
 
+/// Cache sizes suitable for a host with a given amount of memory.
+#[derive(Debug, PartialEq)]
+struct CacheSizes {
+    snapshot_cache: usize,
+    shuffling_cache: usize,
+    state_provider_cache: usize,
+    block_cache: usize,
+}
+
+impl CacheSizes {
+    /// Returns cache sizes for a host with `total_memory` bytes of memory.
+    ///
+    /// The defaults are tuned for hosts with 8-16GB of memory. Smaller hosts shrink the caches to
+    /// avoid running out of memory whilst larger hosts grow them to reduce state reconstruction.
+    fn for_total_memory(total_memory: u64) -> Self {
+        const GIB: u64 = 1 << 30;
+
+        let (snapshot_cache, shuffling_cache, state_provider_cache, block_cache) =
+            if total_memory < 6 * GIB {
+                (2, 8, 2, 2)
+            } else if total_memory < 12 * GIB {
+                (4, 16, 4, 5)
+            } else if total_memory < 24 * GIB {
+                (6, 32, 6, 10)
+            } else {
+                (8, 64, 8, 20)
+            };
+
+        Self {
+            snapshot_cache,
+            shuffling_cache,
+            state_provider_cache,
+            block_cache,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn total_system_memory() -> Option<u64> {
+    psutil::memory::virtual_memory()
+        .ok()
+        .map(|memory| memory.total())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn total_system_memory() -> Option<u64> {
+    None
+}
+
+/// Sets the sizes of the larger in-memory caches.
+///
+/// Caches without an explicit size on the command line are scaled according to the total system
+/// memory. If the system memory can't be determined the compiled-in defaults are kept.
+fn set_cache_sizes(
+    cli_args: &ArgMatches,
+    client_config: &mut ClientConfig,
+    log: &Logger,
+) -> Result<(), String> {
+    if let Some(total_memory) = total_system_memory() {
+        let sizes = CacheSizes::for_total_memory(total_memory);
+
+        info!(
+            log,
+            "Scaled cache sizes to system memory";
+            "total_memory_mb" => total_memory / (1024 * 1024),
+            "snapshot_cache" => sizes.snapshot_cache,
+            "shuffling_cache" => sizes.shuffling_cache,
+            "state_provider_cache" => sizes.state_provider_cache,
+            "block_cache" => sizes.block_cache,
+        );
+
+        client_config.chain.snapshot_cache_size = sizes.snapshot_cache;
+        client_config.chain.shuffling_cache_size = sizes.shuffling_cache;
+        client_config.chain.state_provider_cache_size = sizes.state_provider_cache;
+        client_config.store.block_cache_size = sizes.block_cache;
+    }
+
+    if let Some(size) = clap_utils::parse_optional(cli_args, "snapshot-cache-size")? {
+        client_config.chain.snapshot_cache_size = size;
+    }
+    if let Some(size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
+        client_config.chain.shuffling_cache_size = size;
+    }
+    if let Some(size) = clap_utils::parse_optional(cli_args, "state-provider-cache-size")? {
+        client_config.chain.state_provider_cache_size = size;
+    }
+    if let Some(size) = clap_utils::parse_optional(cli_args, "block-cache-size")? {
+        client_config.store.block_cache_size = size;
+    }
+
+    Ok(())
+}
+
 /// Sets the network config from the command line arguments
 pub fn set_network_config(
     config: &mut NetworkConfig,
@@ -516,6 +687,13 @@ pub fn set_network_config(
             .collect::<Result<Vec<PeerIdSerialized>, _>>()?;
     }
 
+    if let Some(gossip_rate_limits_str) = cli_args.value_of("gossip-rate-limits") {
+        config.gossip_rate_limits = gossip_rate_limits_str
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<GossipRateLimit>, _>>()?;
+    }
+
     if let Some(enr_udp_port_str) = cli_args.value_of("enr-udp-port") {
         config.enr_udp_port = Some(
             enr_udp_port_str
@@ -676,3 +854,32 @@ pub fn unused_port(transport: &str) -> Result<u16, String> {
     };
     Ok(local_addr.port())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_sizes_scale_with_memory() {
+        const GIB: u64 = 1 << 30;
+
+        let small = CacheSizes::for_total_memory(4 * GIB);
+        let default = CacheSizes::for_total_memory(8 * GIB);
+        let large = CacheSizes::for_total_memory(64 * GIB);
+
+        assert!(small.snapshot_cache < default.snapshot_cache);
+        assert!(default.snapshot_cache < large.snapshot_cache);
+        assert!(small.shuffling_cache < large.shuffling_cache);
+        assert!(small.state_provider_cache < large.state_provider_cache);
+        assert!(small.block_cache < large.block_cache);
+        assert_eq!(
+            default,
+            CacheSizes {
+                snapshot_cache: 4,
+                shuffling_cache: 16,
+                state_provider_cache: 4,
+                block_cache: 5,
+            }
+        );
+    }
+}
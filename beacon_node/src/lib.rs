@@ -5,13 +5,13 @@ mod cli;
 mod config;
 
 pub use beacon_chain;
-use beacon_chain::store::LevelDB;
+use beacon_chain::store::BeaconNodeBackend;
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, slot_clock::SystemTimeSlotClock,
 };
 use clap::ArgMatches;
 pub use cli::cli_app;
-pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
+pub use client::{self_check, Client, ClientBuilder, ClientConfig, ClientGenesis};
 pub use config::{get_config, get_data_dir, get_eth2_network_config, set_network_config};
 use environment::RuntimeContext;
 pub use eth2_config::Eth2Config;
@@ -22,8 +22,15 @@ use std::sync::Arc;
 use types::EthSpec;
 
 /// A type-alias to the tighten the definition of a production-intended `Client`.
-pub type ProductionClient<E> =
-    Client<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, E, LevelDB<E>, LevelDB<E>>>;
+pub type ProductionClient<E> = Client<
+    Witness<
+        SystemTimeSlotClock,
+        CachingEth1Backend<E>,
+        E,
+        BeaconNodeBackend<E>,
+        BeaconNodeBackend<E>,
+    >,
+>;
 
 /// The beacon node `Client` that will be used in production.
 ///
@@ -66,6 +73,18 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let freezer_db_path_res = client_config.create_freezer_db_path();
         let executor = context.executor.clone();
 
+        if client_config.startup_checks {
+            self_check::check_ports(&client_config)?;
+            if let Err(e) = self_check::check_eth1_endpoints(&client_config).await {
+                warn!(
+                    log,
+                    "Eth1 endpoints unreachable";
+                    "error" => e,
+                    "advice" => "ensure the eth1 node is running and that --eth1-endpoints is correct"
+                );
+            }
+        }
+
         let builder = ClientBuilder::new(context.eth_spec_instance.clone())
             .runtime_context(context)
             .chain_spec(spec)
@@ -117,11 +136,21 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let discv5_executor = Discv5Executor(executor);
         client_config.network.discv5_config.executor = Some(Box::new(discv5_executor));
 
+        let builder = builder.build_beacon_chain()?;
+        let builder = if client_config.startup_checks {
+            let builder = builder.beacon_chain_self_check(&client_config.genesis)?;
+            info!(log, "Startup checks passed");
+            builder
+        } else {
+            warn!(log, "Startup checks disabled");
+            builder
+        };
+
         builder
-            .build_beacon_chain()?
             .network(&client_config.network)
             .await?
             .notifier()?
+            .monitoring_service(client_config.monitoring.clone())?
             .http_metrics_config(client_config.http_metrics.clone())
             .build()
             .map(Self)
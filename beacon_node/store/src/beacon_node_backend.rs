@@ -0,0 +1,269 @@
+//! The on-disk key-value store used by the beacon node, which is either a LevelDB or a RocksDB
+//! database as selected by `StoreConfig::backend`.
+use crate::config::{DatabaseBackend, StoreConfigError};
+use crate::{
+    get_key_for_col, ColumnIter, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp,
+    LevelDB, RocksDB, StoreConfig,
+};
+use parking_lot::MutexGuard;
+use std::path::Path;
+use strum::IntoEnumIterator;
+use types::EthSpec;
+
+/// The number of key-value pairs written in each batch by `copy_database`.
+const COPY_BATCH_SIZE: usize = 1_024;
+
+pub enum BeaconNodeBackend<E: EthSpec> {
+    LevelDb(LevelDB<E>),
+    RocksDb(RocksDB<E>),
+}
+
+impl<E: EthSpec> BeaconNodeBackend<E> {
+    /// Open a database at `path` using the backend and compression in `config`, creating a new
+    /// database if one does not already exist.
+    ///
+    /// Returns an error if an existing database at `path` uses a different backend.
+    pub fn open(config: &StoreConfig, path: &Path) -> Result<Self, Error> {
+        if let Some(on_disk) = DatabaseBackend::detect(path)? {
+            if on_disk != config.backend {
+                return Err(StoreConfigError::MismatchedBackend {
+                    config: config.backend,
+                    on_disk,
+                    path: path.display().to_string(),
+                }
+                .into());
+            }
+        }
+
+        match config.backend {
+            DatabaseBackend::LevelDb => LevelDB::open(path).map(BeaconNodeBackend::LevelDb),
+            DatabaseBackend::RocksDb => {
+                RocksDB::open(path, config.compression).map(BeaconNodeBackend::RocksDb)
+            }
+        }
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for BeaconNodeBackend<E> {
+    fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.get_bytes(column, key),
+            BeaconNodeBackend::RocksDb(db) => db.get_bytes(column, key),
+        }
+    }
+
+    fn put_bytes(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.put_bytes(column, key, value),
+            BeaconNodeBackend::RocksDb(db) => db.put_bytes(column, key, value),
+        }
+    }
+
+    fn put_bytes_sync(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.put_bytes_sync(column, key, value),
+            BeaconNodeBackend::RocksDb(db) => db.put_bytes_sync(column, key, value),
+        }
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.sync(),
+            BeaconNodeBackend::RocksDb(db) => db.sync(),
+        }
+    }
+
+    fn key_exists(&self, column: &str, key: &[u8]) -> Result<bool, Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.key_exists(column, key),
+            BeaconNodeBackend::RocksDb(db) => db.key_exists(column, key),
+        }
+    }
+
+    fn key_delete(&self, column: &str, key: &[u8]) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.key_delete(column, key),
+            BeaconNodeBackend::RocksDb(db) => db.key_delete(column, key),
+        }
+    }
+
+    fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.do_atomically(batch),
+            BeaconNodeBackend::RocksDb(db) => db.do_atomically(batch),
+        }
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.begin_rw_transaction(),
+            BeaconNodeBackend::RocksDb(db) => db.begin_rw_transaction(),
+        }
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.compact(),
+            BeaconNodeBackend::RocksDb(db) => db.compact(),
+        }
+    }
+
+    fn snapshot_to(&self, path: &Path) -> Result<u64, Error> {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.snapshot_to(path),
+            BeaconNodeBackend::RocksDb(db) => db.snapshot_to(path),
+        }
+    }
+
+    fn iter_column_from(&self, column: &str, from: &[u8]) -> ColumnIter {
+        match self {
+            BeaconNodeBackend::LevelDb(db) => db.iter_column_from(column, from),
+            BeaconNodeBackend::RocksDb(db) => db.iter_column_from(column, from),
+        }
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for BeaconNodeBackend<E> {}
+
+/// Copy every column of `from` into `to`, returning the number of key-value pairs copied.
+///
+/// Used to convert a database between backends. `from` should not be written to whilst the copy
+/// is in progress.
+pub fn copy_database<E: EthSpec>(
+    from: &impl KeyValueStore<E>,
+    to: &impl KeyValueStore<E>,
+) -> Result<u64, Error> {
+    let mut count = 0;
+
+    for column in DBColumn::iter() {
+        let mut batch = Vec::with_capacity(COPY_BATCH_SIZE);
+        for result in from.iter_column(column.as_str()) {
+            let (key, value) = result?;
+            batch.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(column.as_str(), &key),
+                value,
+            ));
+            count += 1;
+
+            if batch.len() == COPY_BATCH_SIZE {
+                to.do_atomically(std::mem::take(&mut batch))?;
+            }
+        }
+        to.do_atomically(batch)?;
+    }
+    to.sync()?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseCompression;
+    use crate::DBKey;
+    use tempfile::tempdir;
+    use types::{Hash256, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn config(backend: DatabaseBackend) -> StoreConfig {
+        StoreConfig {
+            backend,
+            compression: DatabaseCompression::Lz4,
+            ..StoreConfig::default()
+        }
+    }
+
+    #[test]
+    fn backend_mismatch() {
+        let dir = tempdir().unwrap();
+        drop(BeaconNodeBackend::<E>::open(&config(DatabaseBackend::RocksDb), dir.path()).unwrap());
+
+        assert_eq!(
+            DatabaseBackend::detect(dir.path()).unwrap(),
+            Some(DatabaseBackend::RocksDb)
+        );
+        assert!(matches!(
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::LevelDb), dir.path()),
+            Err(Error::ConfigError(
+                StoreConfigError::MismatchedBackend { .. }
+            ))
+        ));
+        assert!(
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::RocksDb), dir.path()).is_ok()
+        );
+    }
+
+    #[test]
+    fn copy_between_backends() {
+        let leveldb_dir = tempdir().unwrap();
+        let rocksdb_dir = tempdir().unwrap();
+        let leveldb =
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::LevelDb), leveldb_dir.path())
+                .unwrap();
+        let rocksdb =
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::RocksDb), rocksdb_dir.path())
+                .unwrap();
+
+        let keys = (0..2 * COPY_BATCH_SIZE as u64 + 1)
+            .map(Hash256::from_low_u64_be)
+            .collect::<Vec<_>>();
+        for key in &keys {
+            leveldb
+                .put_bytes(DBColumn::BeaconBlock.as_str(), &key.as_key_bytes(), &[1])
+                .unwrap();
+        }
+        leveldb
+            .put_bytes(DBColumn::BeaconMeta.as_str(), &keys[0].as_key_bytes(), &[2])
+            .unwrap();
+
+        assert_eq!(
+            copy_database(&leveldb, &rocksdb).unwrap(),
+            keys.len() as u64 + 1
+        );
+        for column in DBColumn::iter() {
+            assert_eq!(
+                rocksdb
+                    .iter_column(column.as_str())
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap(),
+                leveldb
+                    .iter_column(column.as_str())
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap(),
+                "column {:?} should be copied",
+                column
+            );
+        }
+    }
+
+    #[test]
+    fn rocksdb_snapshot() {
+        let dir = tempdir().unwrap();
+        let snapshot_path = dir.path().join("snapshot");
+        let db =
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::RocksDb), &dir.path().join("db"))
+                .unwrap();
+        for i in 0..3 {
+            db.put_bytes(
+                DBColumn::BeaconBlock.as_str(),
+                &Hash256::from_low_u64_be(i).as_key_bytes(),
+                &[1],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(db.snapshot_to(&snapshot_path).unwrap(), 3);
+        assert_eq!(
+            DatabaseBackend::detect(&snapshot_path).unwrap(),
+            Some(DatabaseBackend::RocksDb)
+        );
+        let snapshot =
+            BeaconNodeBackend::<E>::open(&config(DatabaseBackend::RocksDb), &snapshot_path)
+                .unwrap();
+        assert_eq!(
+            snapshot.iter_column(DBColumn::BeaconBlock.as_str()).count(),
+            3
+        );
+    }
+}
@@ -31,16 +31,16 @@ pub enum UpdatePattern {
     OncePerEpoch { lag: u64 },
 }
 
-/// Map a chunk index to bytes that can be used to key the NoSQL database.
+/// Map a chunk index to the key used for it in the NoSQL database.
 ///
 /// We shift chunks up by 1 to make room for a genesis chunk that is handled separately.
-pub fn chunk_key(cindex: u64) -> [u8; 8] {
-    (cindex + 1).to_be_bytes()
+pub fn chunk_key(cindex: u64) -> u64 {
+    cindex + 1
 }
 
 /// Return the database key for the genesis value.
-fn genesis_value_key() -> [u8; 8] {
-    0u64.to_be_bytes()
+fn genesis_value_key() -> u64 {
+    0
 }
 
 /// Trait for types representing fields of the `BeaconState`.
@@ -178,7 +178,7 @@ pub trait Field<E: EthSpec>: Copy {
     ///
     /// This genesis value should be used to fill the initial state of the vector.
     fn load_genesis_value<S: KeyValueStore<E>>(store: &S) -> Result<Self::Value, Error> {
-        let key = &genesis_value_key()[..];
+        let key = genesis_value_key();
         let chunk =
             Chunk::load(store, Self::column(), key)?.ok_or(ChunkError::MissingGenesisValue)?;
         chunk
@@ -197,7 +197,7 @@ pub trait Field<E: EthSpec>: Copy {
         value: Self::Value,
         ops: &mut Vec<KeyValueStoreOp>,
     ) -> Result<(), Error> {
-        let key = &genesis_value_key()[..];
+        let key = genesis_value_key();
 
         if let Some(existing_chunk) = Chunk::<Self::Value>::load(store, Self::column(), key)? {
             if existing_chunk.values.len() != 1 {
@@ -219,7 +219,7 @@ pub trait Field<E: EthSpec>: Copy {
             }
         } else {
             let chunk = Chunk::new(vec![value]);
-            chunk.store(Self::column(), &genesis_value_key()[..], ops)?;
+            chunk.store(Self::column(), key, ops)?;
             Ok(())
         }
     }
@@ -399,7 +399,7 @@ where
     I: Iterator<Item = usize>,
 {
     for chunk_index in range {
-        let chunk_key = &chunk_key(chunk_index as u64)[..];
+        let chunk_key = chunk_key(chunk_index as u64);
 
         let existing_chunk =
             Chunk::<F::Value>::load(store, F::column(), chunk_key)?.unwrap_or_else(Chunk::default);
@@ -434,7 +434,7 @@ fn range_query<S: KeyValueStore<E>, E: EthSpec, T: Decode + Encode>(
     let mut result = vec![];
 
     for chunk_index in start_index..=end_index {
-        let key = &chunk_key(chunk_index as u64)[..];
+        let key = chunk_key(chunk_index as u64);
         let chunk = Chunk::load(store, column, key)?.ok_or(ChunkError::Missing { chunk_index })?;
         result.push(chunk);
     }
@@ -585,10 +585,11 @@ where
     pub fn load<S: KeyValueStore<E>, E: EthSpec>(
         store: &S,
         column: DBColumn,
-        key: &[u8],
+        key: u64,
     ) -> Result<Option<Self>, Error> {
+        column.check_key_type::<u64>()?;
         store
-            .get_bytes(column.into(), key)?
+            .get_bytes(column.into(), &key.as_key_bytes())?
             .map(|bytes| Self::decode(&bytes))
             .transpose()
     }
@@ -596,11 +597,11 @@ where
     pub fn store(
         &self,
         column: DBColumn,
-        key: &[u8],
+        key: u64,
         ops: &mut Vec<KeyValueStoreOp>,
     ) -> Result<(), Error> {
-        let db_key = get_key_for_col(column.into(), key);
-        ops.push(KeyValueStoreOp::PutKeyValue(db_key, self.encode()?));
+        column.check_key_type::<u64>()?;
+        ops.push(KeyValueStoreOp::put(column, &key, self.encode()?));
         Ok(())
     }
 
@@ -2,6 +2,9 @@ use crate::{DBColumn, Error, StoreItem};
 use serde_derive::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use types::{EthSpec, MinimalEthSpec};
 
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
@@ -18,6 +21,28 @@ pub struct StoreConfig {
     pub compact_on_init: bool,
     /// Whether to compact the database during database pruning.
     pub compact_on_prune: bool,
+    /// The key-value database used for the hot and cold databases.
+    pub backend: DatabaseBackend,
+    /// The compression applied to new data by the backend, where supported.
+    pub compression: DatabaseCompression,
+}
+
+/// A key-value database implementation which may back the on-disk store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    LevelDb,
+    RocksDb,
+}
+
+/// The compression algorithm applied to data written to a RocksDB database. Ignored by LevelDB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseCompression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -30,7 +55,16 @@ pub struct OnDiskStoreConfig {
 
 #[derive(Debug, Clone)]
 pub enum StoreConfigError {
-    MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
+    MismatchedSlotsPerRestorePoint {
+        config: u64,
+        on_disk: u64,
+    },
+    /// The database at `path` uses a different backend to the one configured.
+    MismatchedBackend {
+        config: DatabaseBackend,
+        on_disk: DatabaseBackend,
+        path: String,
+    },
 }
 
 impl Default for StoreConfig {
@@ -41,6 +75,68 @@ impl Default for StoreConfig {
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
             compact_on_init: false,
             compact_on_prune: true,
+            backend: DatabaseBackend::LevelDb,
+            compression: DatabaseCompression::Snappy,
+        }
+    }
+}
+
+impl DatabaseBackend {
+    /// Detect the backend of an existing database at `path`, returning `None` if there is no
+    /// database at `path`.
+    ///
+    /// RocksDB databases are distinguished from LevelDB databases by their `OPTIONS-*` files.
+    pub fn detect(path: &Path) -> Result<Option<Self>, Error> {
+        if !path.join("CURRENT").exists() {
+            return Ok(None);
+        }
+
+        let is_rocksdb = std::fs::read_dir(path)
+            .map_err(|e| Error::DBError {
+                message: format!("unable to read {}: {:?}", path.display(), e),
+            })?
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_name().to_string_lossy().starts_with("OPTIONS-"));
+
+        if is_rocksdb {
+            Ok(Some(DatabaseBackend::RocksDb))
+        } else {
+            Ok(Some(DatabaseBackend::LevelDb))
+        }
+    }
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "leveldb" => Ok(DatabaseBackend::LevelDb),
+            "rocksdb" => Ok(DatabaseBackend::RocksDb),
+            other => Err(format!("unknown database backend: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatabaseBackend::LevelDb => write!(f, "leveldb"),
+            DatabaseBackend::RocksDb => write!(f, "rocksdb"),
+        }
+    }
+}
+
+impl FromStr for DatabaseCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(DatabaseCompression::None),
+            "snappy" => Ok(DatabaseCompression::Snappy),
+            "lz4" => Ok(DatabaseCompression::Lz4),
+            "zstd" => Ok(DatabaseCompression::Zstd),
+            other => Err(format!("unknown database compression: {}", other)),
         }
     }
 }
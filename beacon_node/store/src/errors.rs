@@ -13,13 +13,20 @@ pub enum Error {
     BeaconStateError(BeaconStateError),
     PartialBeaconStateError,
     HotColdDBError(HotColdDBError),
-    DBError { message: String },
+    DBError {
+        message: String,
+    },
     RlpError(String),
     BlockNotFound(Hash256),
     NoContinuationData,
     SplitPointModified(Slot, Slot),
     ConfigError(StoreConfigError),
     SchemaMigrationError(String),
+    /// A key was not the length expected for its type or column.
+    InvalidKey {
+        expected_len: usize,
+        len: usize,
+    },
 }
 
 impl From<DecodeError> for Error {
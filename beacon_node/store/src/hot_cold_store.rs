@@ -5,19 +5,17 @@ use crate::config::{OnDiskStoreConfig, StoreConfig};
 use crate::forwards_iter::HybridForwardsBlockRootsIterator;
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{ParentRootBlockIterator, StateRootsIterator};
-use crate::leveldb_store::BytesKey;
-use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
-    CompactionTimestamp, PruningCheckpoint, SchemaVersion, COMPACTION_TIMESTAMP_KEY, CONFIG_KEY,
-    CURRENT_SCHEMA_VERSION, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY,
+    AnchorInfo, CompactionTimestamp, PruningCheckpoint, SchemaVersion, ANCHOR_INFO_KEY,
+    COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, PRUNING_CHECKPOINT_KEY,
+    SCHEMA_VERSION_KEY, SPLIT_KEY,
 };
 use crate::metrics;
 use crate::{
-    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
-    StoreOp,
+    BeaconNodeBackend, DBColumn, DBKey, Error, ItemStore, KeyValueStoreOp, PartialBeaconState,
+    StoreItem, StoreOp, ValidatorRegistry,
 };
-use leveldb::iterator::LevelDBIterator;
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use slog::{debug, error, info, trace, warn, Logger};
@@ -55,6 +53,9 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     /// States with slots less than `split.slot` are in the cold DB, while states with slots
     /// greater than or equal are in the hot DB.
     split: RwLock<Split>,
+    /// The start of the stored history, if the database was initialized from a checkpoint rather
+    /// than from genesis.
+    anchor_info: RwLock<Option<AnchorInfo>>,
     config: StoreConfig,
     /// Cold database containing compact historical data.
     pub cold_db: Cold,
@@ -103,9 +104,14 @@ pub enum HotColdDBError {
         slots_per_epoch: u64,
     },
     RestorePointBlockHashError(BeaconStateError),
-    IterationError {
-        unexpected_key: BytesKey,
+    /// The state at `slot` precedes the checkpoint the database was initialized from, so it is
+    /// not stored.
+    HistoricalStateUnavailable {
+        slot: Slot,
+        anchor_slot: Slot,
     },
+    /// The database cannot be initialized from a checkpoint since it already contains a chain.
+    AnchorOnNonEmptyDatabase,
 }
 
 impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
@@ -118,6 +124,7 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
+            anchor_info: RwLock::new(None),
             cold_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
@@ -131,11 +138,14 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
     }
 }
 
-impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
+impl<E: EthSpec> HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>> {
     /// Open a new or existing database, with the given paths to the hot and cold DBs.
     ///
     /// The `slots_per_restore_point` parameter must be a divisor of `SLOTS_PER_HISTORICAL_ROOT`.
     ///
+    /// Both databases are opened with the backend in `config`, which must match the backend of
+    /// any existing database.
+    ///
     /// The `migrate_schema` function is passed in so that the parent `BeaconChain` can provide
     /// context and access `BeaconChain`-level code without creating a circular dependency.
     pub fn open(
@@ -150,8 +160,9 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
         let db = Arc::new(HotColdDB {
             split: RwLock::new(Split::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            anchor_info: RwLock::new(None),
+            cold_db: BeaconNodeBackend::open(&config, cold_path)?,
+            hot_db: BeaconNodeBackend::open(&config, hot_path)?,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
             config,
             spec,
@@ -191,6 +202,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             );
             *db.split.write() = split;
         }
+        *db.anchor_info.write() = db.load_anchor_info()?;
 
         // Run a garbage collection pass.
         db.remove_garbage()?;
@@ -204,30 +216,19 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
         Ok(db)
     }
+}
 
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
     /// Return an iterator over the state roots of all temporary states.
-    pub fn iter_temporary_state_roots(&self) -> impl Iterator<Item = Result<Hash256, Error>> + '_ {
-        let column = DBColumn::BeaconStateTemporary;
-        let start_key =
-            BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_bytes()));
-
-        let keys_iter = self.hot_db.keys_iter();
-        keys_iter.seek(&start_key);
-
-        keys_iter
-            .take_while(move |key| key.matches_column(column))
-            .map(move |bytes_key| {
-                bytes_key.remove_column(column).ok_or_else(|| {
-                    HotColdDBError::IterationError {
-                        unexpected_key: bytes_key,
-                    }
-                    .into()
-                })
-            })
+    pub fn iter_temporary_state_roots(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Hash256, Error>> + '_, Error> {
+        Ok(self
+            .hot_db
+            .iter_column_keys::<Hash256>(DBColumn::BeaconStateTemporary)?
+            .map(|result| result.map(|(state_root, _)| state_root)))
     }
-}
 
-impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
     /// Store a block and update the LRU cache.
     pub fn put_block(
         &self,
@@ -267,7 +268,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// Delete a block from the store and the block cache.
     pub fn delete_block(&self, block_root: &Hash256) -> Result<(), Error> {
         self.block_cache.lock().pop(block_root);
-        self.hot_db.delete::<SignedBeaconBlock<E>>(block_root)
+        self.hot_db.delete::<SignedBeaconBlock<E>, _>(block_root)
     }
 
     pub fn put_state_summary(
@@ -360,16 +361,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// (which will be deleted by this function but shouldn't be).
     pub fn delete_state(&self, state_root: &Hash256, slot: Slot) -> Result<(), Error> {
         // Delete the state summary.
-        self.hot_db
-            .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())?;
+        let mut ops = vec![KeyValueStoreOp::delete(
+            DBColumn::BeaconStateSummary,
+            state_root,
+        )];
 
         // Delete the full state if it lies on an epoch boundary.
         if slot % E::slots_per_epoch() == 0 {
-            self.hot_db
-                .key_delete(DBColumn::BeaconState.into(), state_root.as_bytes())?;
+            ops.push(KeyValueStoreOp::delete(DBColumn::BeaconState, state_root));
         }
 
-        Ok(())
+        self.hot_db.do_atomically(ops)
     }
 
     pub fn forwards_block_roots_iterator(
@@ -451,27 +453,32 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 }
 
                 StoreOp::DeleteStateTemporaryFlag(state_root) => {
-                    let db_key =
-                        get_key_for_col(TemporaryFlag::db_column().into(), state_root.as_bytes());
-                    key_value_batch.push(KeyValueStoreOp::DeleteKey(db_key));
+                    key_value_batch.push(KeyValueStoreOp::delete(
+                        TemporaryFlag::db_column(),
+                        state_root,
+                    ));
                 }
 
                 StoreOp::DeleteBlock(block_root) => {
-                    let key = get_key_for_col(DBColumn::BeaconBlock.into(), block_root.as_bytes());
-                    key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
+                    key_value_batch
+                        .push(KeyValueStoreOp::delete(DBColumn::BeaconBlock, block_root));
                 }
 
                 StoreOp::DeleteState(state_root, slot) => {
-                    let state_summary_key =
-                        get_key_for_col(DBColumn::BeaconStateSummary.into(), state_root.as_bytes());
-                    key_value_batch.push(KeyValueStoreOp::DeleteKey(state_summary_key));
+                    key_value_batch.push(KeyValueStoreOp::delete(
+                        DBColumn::BeaconStateSummary,
+                        state_root,
+                    ));
 
                     if slot.map_or(true, |slot| slot % E::slots_per_epoch() == 0) {
-                        let state_key =
-                            get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes());
-                        key_value_batch.push(KeyValueStoreOp::DeleteKey(state_key));
+                        key_value_batch
+                            .push(KeyValueStoreOp::delete(DBColumn::BeaconState, state_root));
                     }
                 }
+
+                StoreOp::KeyValueOp(kv_op) => {
+                    key_value_batch.push(kv_op.clone());
+                }
             }
         }
         Ok(key_value_batch)
@@ -502,6 +509,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 }
 
                 StoreOp::DeleteState(_, _) => (),
+
+                StoreOp::KeyValueOp(_) => (),
             }
         }
         Ok(())
@@ -537,6 +546,30 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Load the validator registry of the hot state with `state_root`, without decoding the rest
+    /// of the state.
+    ///
+    /// Only states which are stored in full (i.e., those at epoch boundaries) are supported.
+    /// `Ok(None)` is returned for all other states, which should be loaded with `Self::get_state`.
+    pub fn load_hot_validator_registry(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<ValidatorRegistry>, Error> {
+        if self.load_state_temporary_flag(state_root)?.is_some() {
+            return Ok(None);
+        }
+
+        match self
+            .hot_db
+            .get_bytes(DBColumn::BeaconState.into(), &state_root.as_key_bytes())?
+        {
+            Some(bytes) => Ok(Some(ValidatorRegistry::from_storage_container_bytes::<E>(
+                bytes,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
     /// Load a post-finalization state from the hot database.
     ///
     /// Will replay blocks from the nearest epoch boundary.
@@ -564,14 +597,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             )?;
 
             // Optimization to avoid even *thinking* about replaying blocks if we're already
-            // on an epoch boundary.
-            let state = if slot % E::slots_per_epoch() == 0 {
-                boundary_state
-            } else {
-                let blocks =
-                    self.load_blocks_to_replay(boundary_state.slot, slot, latest_block_root)?;
-                self.replay_blocks(boundary_state, blocks, slot, block_replay)?
-            };
+            // on an epoch boundary, or if the state is stored in full (i.e., it is the anchor).
+            let state =
+                if slot % E::slots_per_epoch() == 0 || epoch_boundary_state_root == *state_root {
+                    boundary_state
+                } else {
+                    let blocks =
+                        self.load_blocks_to_replay(boundary_state.slot, slot, latest_block_root)?;
+                    self.replay_blocks(boundary_state, blocks, slot, block_replay)?
+                };
 
             Ok(Some(state))
         } else {
@@ -639,6 +673,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// Will reconstruct the state if it lies between restore points.
     pub fn load_cold_state_by_slot(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
+        if let Some(anchor_info) = *self.anchor_info.read() {
+            if slot != self.spec.genesis_slot && slot < anchor_info.state_upper_limit {
+                return Err(HotColdDBError::HistoricalStateUnavailable {
+                    slot,
+                    anchor_slot: anchor_info.anchor_slot,
+                }
+                .into());
+            }
+        }
+
         if slot % self.config.slots_per_restore_point == 0 {
             let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
             self.load_restore_point_by_index(restore_point_idx)
@@ -833,7 +877,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the database schema version from disk.
-    fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
+    pub fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
         self.hot_db.get(&SCHEMA_VERSION_KEY)
     }
 
@@ -857,6 +901,73 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.get(&SPLIT_KEY)
     }
 
+    /// Load the anchor info from disk.
+    fn load_anchor_info(&self) -> Result<Option<AnchorInfo>, Error> {
+        self.hot_db.get(&ANCHOR_INFO_KEY)
+    }
+
+    /// Returns the anchor info, if the database was initialized from a checkpoint rather than
+    /// from genesis.
+    pub fn get_anchor_info(&self) -> Option<AnchorInfo> {
+        *self.anchor_info.read()
+    }
+
+    /// Initialize an empty database from a trusted, finalized `anchor_state`, rather than from
+    /// genesis.
+    ///
+    /// The genesis state is stored as the first restore point in the freezer, and the anchor state
+    /// is stored in full in the hot database and becomes the split point. The states in between
+    /// are never available, nor are any states prior to the first restore point after the anchor.
+    pub fn init_from_anchor(
+        &self,
+        genesis_state_root: Hash256,
+        genesis_state: &BeaconState<E>,
+        anchor_state_root: Hash256,
+        anchor_state: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        if self.load_split()?.is_some() || self.get_anchor_info().is_some() {
+            return Err(HotColdDBError::AnchorOnNonEmptyDatabase.into());
+        }
+
+        let mut cold_db_ops = vec![];
+        self.store_cold_state(&genesis_state_root, genesis_state, &mut cold_db_ops)?;
+        self.cold_db.do_atomically(cold_db_ops)?;
+
+        // The anchor state is stored in full and is its own epoch boundary state, since none of
+        // the states required to replay blocks up to it are available.
+        let mut hot_db_ops = vec![];
+        store_full_state(&anchor_state_root, anchor_state, &mut hot_db_ops)?;
+        let anchor_summary = HotStateSummary {
+            slot: anchor_state.slot,
+            latest_block_root: anchor_state.get_latest_block_root(anchor_state_root),
+            epoch_boundary_state_root: anchor_state_root,
+        };
+        hot_db_ops.push(anchor_summary.as_kv_store_op(anchor_state_root));
+
+        let split = Split {
+            slot: anchor_state.slot,
+            state_root: anchor_state_root,
+        };
+        hot_db_ops.push(split.as_kv_store_op(SPLIT_KEY));
+
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let anchor_info = AnchorInfo {
+            anchor_slot: anchor_state.slot,
+            state_upper_limit: Slot::new(
+                (anchor_state.slot.as_u64() + slots_per_restore_point - 1)
+                    / slots_per_restore_point
+                    * slots_per_restore_point,
+            ),
+        };
+        hot_db_ops.push(anchor_info.as_kv_store_op(ANCHOR_INFO_KEY));
+
+        self.hot_db.do_atomically(hot_db_ops)?;
+        *self.split.write() = split;
+        *self.anchor_info.write() = Some(anchor_info);
+
+        Ok(())
+    }
+
     /// Load the state root of a restore point.
     fn load_restore_point_hash(&self, restore_point_index: u64) -> Result<Hash256, Error> {
         let key = Self::restore_point_key(restore_point_index);
@@ -946,6 +1057,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Write copies of the hot and freezer databases to new databases at `hot_path` and
+    /// `cold_path`, whilst the node continues to run.
+    ///
+    /// Returns the number of keys written to each database.
+    ///
+    /// Each copy is taken from a snapshot of its own database, but the two snapshots are taken one
+    /// after the other rather than at a single point in time. The hot database is copied first:
+    /// migration writes states to the freezer before deleting them from the hot database, so the
+    /// copies never have a gap between them (although they may both contain some states, and the
+    /// freezer copy may be ahead of the split point recorded in the hot copy).
+    pub fn snapshot_to(&self, hot_path: &Path, cold_path: &Path) -> Result<(u64, u64), Error> {
+        let hot_keys = self.hot_db.snapshot_to(hot_path)?;
+        let cold_keys = self.cold_db.snapshot_to(cold_path)?;
+        Ok((hot_keys, cold_keys))
+    }
+
     /// Return `true` if compaction on finalization/pruning is enabled.
     pub fn compact_on_prune(&self) -> bool {
         self.config.compact_on_prune
@@ -0,0 +1,266 @@
+//! Typed keys for database columns.
+//!
+//! Each key type serializes to a fixed-length byte string whose lexicographic ordering matches
+//! the ordering of the key itself, so that iteration over a column visits keys in order and prefix
+//! scans (e.g., all epochs for a single validator) are meaningful.
+use crate::{get_key_for_col, DBColumn, Error};
+use types::{Epoch, Hash256, Slot};
+
+/// A key which may be used to index values in a database column.
+pub trait DBKey: Sized {
+    /// The length of the serialized key, in bytes.
+    const KEY_SIZE: usize;
+
+    /// Serialize `self` to an ordered byte key of exactly `Self::KEY_SIZE` bytes.
+    fn as_key_bytes(&self) -> Vec<u8>;
+
+    /// Deserialize a key previously produced by `Self::as_key_bytes`.
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+fn check_key_size<K: DBKey>(bytes: &[u8]) -> Result<(), Error> {
+    if bytes.len() == K::KEY_SIZE {
+        Ok(())
+    } else {
+        Err(Error::InvalidKey {
+            expected_len: K::KEY_SIZE,
+            len: bytes.len(),
+        })
+    }
+}
+
+impl DBKey for Hash256 {
+    const KEY_SIZE: usize = 32;
+
+    fn as_key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_key_size::<Self>(bytes)?;
+        Ok(Hash256::from_slice(bytes))
+    }
+}
+
+impl DBKey for u64 {
+    const KEY_SIZE: usize = 8;
+
+    /// Big-endian, so that byte ordering matches numeric ordering.
+    fn as_key_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_key_size::<Self>(bytes)?;
+        let mut array = [0; 8];
+        array.copy_from_slice(bytes);
+        Ok(u64::from_be_bytes(array))
+    }
+}
+
+impl DBKey for Slot {
+    const KEY_SIZE: usize = u64::KEY_SIZE;
+
+    fn as_key_bytes(&self) -> Vec<u8> {
+        self.as_u64().as_key_bytes()
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        u64::from_key_bytes(bytes).map(Slot::new)
+    }
+}
+
+impl DBKey for Epoch {
+    const KEY_SIZE: usize = u64::KEY_SIZE;
+
+    fn as_key_bytes(&self) -> Vec<u8> {
+        self.as_u64().as_key_bytes()
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        u64::from_key_bytes(bytes).map(Epoch::new)
+    }
+}
+
+/// A key for values which are stored per validator, per epoch.
+///
+/// Keys are ordered by validator index and then by epoch, so all the values for a single validator
+/// may be found with a prefix scan using `Self::validator_prefix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ValidatorEpochKey {
+    pub validator_index: u64,
+    pub epoch: Epoch,
+}
+
+impl ValidatorEpochKey {
+    pub fn new(validator_index: u64, epoch: Epoch) -> Self {
+        Self {
+            validator_index,
+            epoch,
+        }
+    }
+
+    /// The prefix shared by the keys of all epochs for `validator_index`.
+    pub fn validator_prefix(validator_index: u64) -> Vec<u8> {
+        validator_index.as_key_bytes()
+    }
+}
+
+impl DBKey for ValidatorEpochKey {
+    const KEY_SIZE: usize = u64::KEY_SIZE + Epoch::KEY_SIZE;
+
+    fn as_key_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.validator_index.as_key_bytes();
+        bytes.extend_from_slice(&self.epoch.as_key_bytes());
+        bytes
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_key_size::<Self>(bytes)?;
+        let (validator_index, epoch) = bytes.split_at(u64::KEY_SIZE);
+        Ok(Self {
+            validator_index: u64::from_key_bytes(validator_index)?,
+            epoch: Epoch::from_key_bytes(epoch)?,
+        })
+    }
+}
+
+/// A key for values which are stored per slot, with a sequence number distinguishing the values
+/// stored at the same slot.
+///
+/// Keys are ordered by slot and then by sequence number, so iterating a column visits the oldest
+/// slots first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SlotSequenceKey {
+    pub slot: Slot,
+    pub sequence: u64,
+}
+
+impl SlotSequenceKey {
+    pub fn new(slot: Slot, sequence: u64) -> Self {
+        Self { slot, sequence }
+    }
+}
+
+impl DBKey for SlotSequenceKey {
+    const KEY_SIZE: usize = Slot::KEY_SIZE + u64::KEY_SIZE;
+
+    fn as_key_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.slot.as_key_bytes();
+        bytes.extend_from_slice(&self.sequence.as_key_bytes());
+        bytes
+    }
+
+    fn from_key_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_key_size::<Self>(bytes)?;
+        let (slot, sequence) = bytes.split_at(Slot::KEY_SIZE);
+        Ok(Self {
+            slot: Slot::from_key_bytes(slot)?,
+            sequence: u64::from_key_bytes(sequence)?,
+        })
+    }
+}
+
+impl DBColumn {
+    /// The length of the keys stored in this column, in bytes.
+    pub fn key_size(self) -> usize {
+        match self {
+            DBColumn::BeaconMeta
+            | DBColumn::BeaconBlock
+            | DBColumn::BeaconState
+            | DBColumn::BeaconChain
+            | DBColumn::OpPool
+            | DBColumn::Eth1Cache
+            | DBColumn::ForkChoice
+            | DBColumn::PubkeyCache
+            | DBColumn::BeaconRestorePoint
+            | DBColumn::BeaconStateSummary
+            | DBColumn::BeaconStateTemporary
+            | DBColumn::DhtEnrs
+            | DBColumn::BeaconAttestationsByBlockRoot => Hash256::KEY_SIZE,
+            DBColumn::BeaconBlockRoots
+            | DBColumn::BeaconStateRoots
+            | DBColumn::BeaconHistoricalRoots
+            | DBColumn::BeaconRandaoMixes => u64::KEY_SIZE,
+            DBColumn::BeaconQuarantine => SlotSequenceKey::KEY_SIZE,
+        }
+    }
+
+    /// The full database key for `key` in this column, i.e. the column prefix followed by the
+    /// serialized key.
+    pub fn get_key<K: DBKey>(self, key: &K) -> Vec<u8> {
+        debug_assert_eq!(self.key_size(), K::KEY_SIZE);
+        get_key_for_col(self.as_str(), &key.as_key_bytes())
+    }
+
+    /// Returns an error if keys of type `K` are not the size expected for this column.
+    pub fn check_key_type<K: DBKey>(self) -> Result<(), Error> {
+        if self.key_size() == K::KEY_SIZE {
+            Ok(())
+        } else {
+            Err(Error::InvalidKey {
+                expected_len: self.key_size(),
+                len: K::KEY_SIZE,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<K: DBKey + PartialEq + std::fmt::Debug>(key: K) {
+        let bytes = key.as_key_bytes();
+        assert_eq!(bytes.len(), K::KEY_SIZE);
+        assert_eq!(K::from_key_bytes(&bytes).unwrap(), key);
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(Hash256::repeat_byte(7));
+        round_trip(u64::max_value());
+        round_trip(Slot::new(42));
+        round_trip(Epoch::new(3));
+        round_trip(ValidatorEpochKey::new(9, Epoch::new(11)));
+        round_trip(SlotSequenceKey::new(Slot::new(5), 8));
+    }
+
+    #[test]
+    fn wrong_size() {
+        assert!(Hash256::from_key_bytes(&[0; 31]).is_err());
+        assert!(u64::from_key_bytes(&[0; 9]).is_err());
+        assert!(ValidatorEpochKey::from_key_bytes(&[0; 8]).is_err());
+    }
+
+    #[test]
+    fn byte_order_matches_key_order() {
+        let keys = vec![
+            ValidatorEpochKey::new(0, Epoch::new(256)),
+            ValidatorEpochKey::new(1, Epoch::new(0)),
+            ValidatorEpochKey::new(1, Epoch::new(1)),
+            ValidatorEpochKey::new(256, Epoch::new(0)),
+        ];
+        let mut sorted_bytes = keys.iter().map(DBKey::as_key_bytes).collect::<Vec<_>>();
+        sorted_bytes.sort();
+
+        assert_eq!(
+            sorted_bytes,
+            keys.iter().map(DBKey::as_key_bytes).collect::<Vec<_>>()
+        );
+        assert!(keys[1]
+            .as_key_bytes()
+            .starts_with(&ValidatorEpochKey::validator_prefix(1)));
+    }
+
+    #[test]
+    fn column_key_types() {
+        assert!(DBColumn::BeaconBlock.check_key_type::<Hash256>().is_ok());
+        assert!(DBColumn::BeaconBlock.check_key_type::<Slot>().is_err());
+        assert!(DBColumn::BeaconBlockRoots.check_key_type::<u64>().is_ok());
+        assert!(DBColumn::BeaconMeta.check_key_type::<Hash256>().is_ok());
+        assert!(DBColumn::BeaconRandaoMixes
+            .check_key_type::<ValidatorEpochKey>()
+            .is_err());
+    }
+}
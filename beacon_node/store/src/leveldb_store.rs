@@ -6,12 +6,16 @@ use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
-use leveldb::iterator::{Iterable, KeyIterator};
+use leveldb::iterator::{Iterable, KeyIterator, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
+use leveldb::snapshots::Snapshots;
 use parking_lot::{Mutex, MutexGuard};
 use std::marker::PhantomData;
 use std::path::Path;
 
+/// The number of key-value pairs written in each batch when creating a snapshot.
+const SNAPSHOT_BATCH_SIZE: usize = 1_024;
+
 /// A wrapped leveldb database.
 pub struct LevelDB<E: EthSpec> {
     db: Database<BytesKey>,
@@ -158,10 +162,13 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
     fn compact(&self) -> Result<(), Error> {
         let endpoints = |column: DBColumn| {
             (
-                BytesKey::from_vec(get_key_for_col(column.as_str(), Hash256::zero().as_bytes())),
                 BytesKey::from_vec(get_key_for_col(
                     column.as_str(),
-                    Hash256::repeat_byte(0xff).as_bytes(),
+                    &Hash256::zero().as_key_bytes(),
+                )),
+                BytesKey::from_vec(get_key_for_col(
+                    column.as_str(),
+                    &Hash256::repeat_byte(0xff).as_key_bytes(),
                 )),
             )
         };
@@ -174,6 +181,61 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         }
         Ok(())
     }
+
+    fn snapshot_to(&self, path: &Path) -> Result<u64, Error> {
+        let snapshot = self.db.snapshot();
+        write_snapshot(
+            path,
+            snapshot
+                .iter(self.read_options())
+                .map(|(key, value)| (key.key, value)),
+        )
+    }
+
+    fn iter_column_from(&self, column: &str, from: &[u8]) -> ColumnIter {
+        let start_key = BytesKey::from_vec(get_key_for_col(column, from));
+        let column = column.as_bytes().to_vec();
+        let column_len = column.len();
+
+        let iter = self.db.iter(self.read_options());
+        iter.seek(&start_key);
+
+        Box::new(
+            iter.take_while(move |(key, _)| key.key.starts_with(&column))
+                .map(move |(key, value)| Ok((key.key[column_len..].to_vec(), value))),
+        )
+    }
+}
+
+/// Write `entries` to a new database at `path`, returning the number of entries written.
+///
+/// Returns an error if a database already exists at `path`.
+pub(crate) fn write_snapshot(
+    path: &Path,
+    entries: impl Iterator<Item = (Vec<u8>, Vec<u8>)>,
+) -> Result<u64, Error> {
+    let mut options = Options::new();
+    options.create_if_missing = true;
+    options.error_if_exists = true;
+    let db = Database::<BytesKey>::open(path, options)?;
+
+    let mut count = 0;
+    let mut batch = Writebatch::new();
+    for (key, value) in entries {
+        batch.put(BytesKey::from_vec(key), &value);
+        count += 1;
+
+        if count % SNAPSHOT_BATCH_SIZE as u64 == 0 {
+            db.write(WriteOptions::new(), &batch)?;
+            batch = Writebatch::new();
+        }
+    }
+
+    let mut write_options = WriteOptions::new();
+    write_options.sync = true;
+    db.write(write_options, &batch)?;
+
+    Ok(count)
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
@@ -195,22 +257,6 @@ impl Key for BytesKey {
 }
 
 impl BytesKey {
-    /// Return `true` iff this `BytesKey` was created with the given `column`.
-    pub fn matches_column(&self, column: DBColumn) -> bool {
-        self.key.starts_with(column.as_bytes())
-    }
-
-    /// Remove the column from a key, returning its `Hash256` portion.
-    pub fn remove_column(&self, column: DBColumn) -> Option<Hash256> {
-        if self.matches_column(column) {
-            let subkey = &self.key[column.as_bytes().len()..];
-            if subkey.len() == 32 {
-                return Some(Hash256::from_slice(subkey));
-            }
-        }
-        None
-    }
-
     pub fn from_vec(key: Vec<u8>) -> Self {
         Self { key }
     }
@@ -2,7 +2,7 @@
 //!
 //! Provides the following stores:
 //!
-//! - `HotColdDB`: an on-disk store backed by leveldb. Used in production.
+//! - `HotColdDB`: an on-disk store backed by leveldb or rocksdb. Used in production.
 //! - `MemoryStore`: an in-memory store backed by a hash-map. Used for testing.
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
@@ -10,6 +10,7 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod beacon_node_backend;
 pub mod chunked_iter;
 pub mod chunked_vector;
 pub mod config;
@@ -18,25 +19,41 @@ mod forwards_iter;
 mod garbage_collection;
 pub mod hot_cold_store;
 mod impls;
+pub mod keys;
 mod leveldb_store;
 mod memory_store;
 pub mod metadata;
 mod metrics;
 mod partial_beacon_state;
+mod rocksdb_store;
+pub mod validator_registry;
 
 pub mod iter;
 
-pub use self::config::StoreConfig;
+pub use self::beacon_node_backend::{copy_database, BeaconNodeBackend};
+pub use self::config::{DatabaseBackend, DatabaseCompression, StoreConfig};
 pub use self::hot_cold_store::{BlockReplay, HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
-pub use self::memory_store::MemoryStore;
+pub use self::memory_store::{MemoryStore, MemoryStoreSnapshot};
 pub use self::partial_beacon_state::PartialBeaconState;
+pub use self::rocksdb_store::RocksDB;
+pub use self::validator_registry::ValidatorRegistry;
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
+pub use keys::{DBKey, SlotSequenceKey, ValidatorEpochKey};
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
+use std::path::Path;
+use strum::EnumIter;
 pub use types::*;
 
+/// An iterator over the key-value pairs in a column, in ascending key order. Keys are returned
+/// without the column prefix.
+pub type ColumnIter<'a> = Box<dyn Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>> + 'a>;
+
+/// As per `ColumnIter`, but with keys decoded as `K`.
+pub type TypedColumnIter<'a, K> = Box<dyn Iterator<Item = Result<(K, Vec<u8>), Error>> + 'a>;
+
 pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Retrieve some bytes in `column` with `key`.
     fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
@@ -69,6 +86,66 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Compact the database, freeing space used by deleted items.
     fn compact(&self) -> Result<(), Error>;
+
+    /// Write a copy of the database to a new database at `path`, using the same on-disk format as
+    /// `self`, and return the number of keys written.
+    ///
+    /// Implementations should read from a snapshot of the database where the backend supports it,
+    /// so that writes which occur whilst the copy is in progress are not included.
+    fn snapshot_to(&self, path: &Path) -> Result<u64, Error>;
+
+    /// Iterate through the key-value pairs in `column` in ascending key order, starting at the
+    /// first key which is equal to or greater than `from`.
+    fn iter_column_from(&self, column: &str, from: &[u8]) -> ColumnIter;
+
+    /// Iterate through all key-value pairs in `column` in ascending key order.
+    fn iter_column(&self, column: &str) -> ColumnIter {
+        self.iter_column_from(column, &[])
+    }
+
+    /// Iterate through the key-value pairs in `column` whose keys begin with `prefix`, in
+    /// ascending key order.
+    fn iter_column_prefix(&self, column: &str, prefix: &[u8]) -> ColumnIter {
+        let prefix = prefix.to_vec();
+        Box::new(
+            self.iter_column_from(column, &prefix)
+                .take_while(move |result| {
+                    result
+                        .as_ref()
+                        .map_or(true, |(key, _)| key.starts_with(&prefix))
+                }),
+        )
+    }
+
+    /// Iterate through the key-value pairs in `column` in ascending key order, decoding each key
+    /// as a `K`.
+    ///
+    /// Returns an error if `K` is not the key type of `column`.
+    fn iter_column_keys<'a, K: DBKey + 'a>(
+        &'a self,
+        column: DBColumn,
+    ) -> Result<TypedColumnIter<'a, K>, Error> {
+        self.iter_column_keys_with_prefix(column, &[])
+    }
+
+    /// Iterate through the key-value pairs in `column` whose keys begin with `prefix`, decoding
+    /// each key as a `K`. See `ValidatorEpochKey::validator_prefix` for an example prefix.
+    ///
+    /// Returns an error if `K` is not the key type of `column`.
+    fn iter_column_keys_with_prefix<'a, K: DBKey + 'a>(
+        &'a self,
+        column: DBColumn,
+        prefix: &[u8],
+    ) -> Result<TypedColumnIter<'a, K>, Error> {
+        column.check_key_type::<K>()?;
+
+        Ok(Box::new(
+            self.iter_column_prefix(column.as_str(), prefix)
+                .map(|result| {
+                    result.and_then(|(key, value)| Ok((K::from_key_bytes(&key)?, value)))
+                }),
+        ))
+    }
 }
 
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
@@ -77,24 +154,39 @@ pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
     result
 }
 
+#[derive(Clone)]
 pub enum KeyValueStoreOp {
     PutKeyValue(Vec<u8>, Vec<u8>),
     DeleteKey(Vec<u8>),
 }
 
+impl KeyValueStoreOp {
+    /// Create an operation which stores `value` under `key` in `column`.
+    pub fn put<K: DBKey>(column: DBColumn, key: &K, value: Vec<u8>) -> Self {
+        KeyValueStoreOp::PutKeyValue(column.get_key(key), value)
+    }
+
+    /// Create an operation which deletes `key` from `column`.
+    pub fn delete<K: DBKey>(column: DBColumn, key: &K) -> Self {
+        KeyValueStoreOp::DeleteKey(column.get_key(key))
+    }
+}
+
 pub trait ItemStore<E: EthSpec>: KeyValueStore<E> + Sync + Send + Sized + 'static {
     /// Store an item in `Self`.
-    fn put<I: StoreItem>(&self, key: &Hash256, item: &I) -> Result<(), Error> {
+    fn put<I: StoreItem, K: DBKey>(&self, key: &K, item: &I) -> Result<(), Error> {
+        I::db_column().check_key_type::<K>()?;
         let column = I::db_column().into();
-        let key = key.as_bytes();
+        let key = &key.as_key_bytes();
 
         self.put_bytes(column, key, &item.as_store_bytes())
             .map_err(Into::into)
     }
 
-    fn put_sync<I: StoreItem>(&self, key: &Hash256, item: &I) -> Result<(), Error> {
+    fn put_sync<I: StoreItem, K: DBKey>(&self, key: &K, item: &I) -> Result<(), Error> {
+        I::db_column().check_key_type::<K>()?;
         let column = I::db_column().into();
-        let key = key.as_bytes();
+        let key = &key.as_key_bytes();
 
         self.put_bytes_sync(column, key, &item.as_store_bytes())
             .map_err(Into::into)
@@ -103,7 +195,7 @@ pub trait ItemStore<E: EthSpec>: KeyValueStore<E> + Sync + Send + Sized + 'stati
     /// Retrieve an item from `Self`.
     fn get<I: StoreItem>(&self, key: &Hash256) -> Result<Option<I>, Error> {
         let column = I::db_column().into();
-        let key = key.as_bytes();
+        let key = &key.as_key_bytes();
 
         match self.get_bytes(column, key)? {
             Some(bytes) => Ok(Some(I::from_store_bytes(&bytes[..])?)),
@@ -114,15 +206,16 @@ pub trait ItemStore<E: EthSpec>: KeyValueStore<E> + Sync + Send + Sized + 'stati
     /// Returns `true` if the given key represents an item in `Self`.
     fn exists<I: StoreItem>(&self, key: &Hash256) -> Result<bool, Error> {
         let column = I::db_column().into();
-        let key = key.as_bytes();
+        let key = &key.as_key_bytes();
 
         self.key_exists(column, key)
     }
 
     /// Remove an item from `Self`.
-    fn delete<I: StoreItem>(&self, key: &Hash256) -> Result<(), Error> {
+    fn delete<I: StoreItem, K: DBKey>(&self, key: &K) -> Result<(), Error> {
+        I::db_column().check_key_type::<K>()?;
         let column = I::db_column().into();
-        let key = key.as_bytes();
+        let key = &key.as_key_bytes();
 
         self.key_delete(column, key)
     }
@@ -138,10 +231,11 @@ pub enum StoreOp<'a, E: EthSpec> {
     DeleteStateTemporaryFlag(Hash256),
     DeleteBlock(Hash256),
     DeleteState(Hash256, Option<Slot>),
+    KeyValueOp(KeyValueStoreOp),
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     BeaconMeta,
@@ -165,6 +259,10 @@ pub enum DBColumn {
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
     DhtEnrs,
+    /// For the attestations included in blocks, keyed by the block root they vote for.
+    BeaconAttestationsByBlockRoot,
+    /// For rejected gossip objects, keyed by the slot at which they were rejected.
+    BeaconQuarantine,
 }
 
 impl Into<&'static str> for DBColumn {
@@ -187,6 +285,8 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
             DBColumn::DhtEnrs => "dht",
+            DBColumn::BeaconAttestationsByBlockRoot => "abr",
+            DBColumn::BeaconQuarantine => "bqr",
         }
     }
 }
@@ -215,8 +315,7 @@ pub trait StoreItem: Sized {
     fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error>;
 
     fn as_kv_store_op(&self, key: Hash256) -> KeyValueStoreOp {
-        let db_key = get_key_for_col(Self::db_column().into(), key.as_bytes());
-        KeyValueStoreOp::PutKeyValue(db_key, self.as_store_bytes())
+        KeyValueStoreOp::put(Self::db_column(), &key, self.as_store_bytes())
     }
 }
 
@@ -260,13 +359,84 @@ mod tests {
         let retrieved = store.get(&key).unwrap().unwrap();
         assert_eq!(item, retrieved);
 
-        store.delete::<StorableThing>(&key).unwrap();
+        store.delete::<StorableThing, _>(&key).unwrap();
 
         assert_eq!(store.exists::<StorableThing>(&key).unwrap(), false);
 
         assert_eq!(store.get::<StorableThing>(&key).unwrap(), None);
     }
 
+    fn test_iter_impl(store: impl ItemStore<MinimalEthSpec>) {
+        let column = DBColumn::BeaconBlock.as_str();
+        let other_column = DBColumn::BeaconState.as_str();
+
+        for key in &[&[2, 1][..], &[1, 2], &[1, 1], &[3], &[1]] {
+            store.put_bytes(column, key, key).unwrap();
+        }
+        store.put_bytes(other_column, &[1, 1], &[0]).unwrap();
+
+        let collect = |iter: ColumnIter| {
+            iter.map(|result| result.map(|(key, _)| key))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            collect(store.iter_column(column)),
+            vec![vec![1], vec![1, 1], vec![1, 2], vec![2, 1], vec![3]],
+            "all keys in the column are returned in order"
+        );
+        assert_eq!(
+            collect(store.iter_column_from(column, &[1, 2])),
+            vec![vec![1, 2], vec![2, 1], vec![3]],
+            "iteration starts from the given key"
+        );
+        assert_eq!(
+            collect(store.iter_column_from(column, &[1, 5])),
+            vec![vec![2, 1], vec![3]],
+            "iteration starts from the next key if the given key is absent"
+        );
+        assert_eq!(
+            collect(store.iter_column_prefix(column, &[1])),
+            vec![vec![1], vec![1, 1], vec![1, 2]],
+            "only keys with the prefix are returned"
+        );
+        assert!(collect(store.iter_column_prefix(column, &[4])).is_empty());
+        assert_eq!(
+            store
+                .iter_column(other_column)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![(vec![1, 1], vec![0])],
+            "values are returned and other columns are excluded"
+        );
+    }
+
+    #[test]
+    fn typed_iter() {
+        let store = MemoryStore::<MinimalEthSpec>::open();
+        let column = DBColumn::BeaconBlockRoots;
+
+        for key in &[300u64, 2, 1] {
+            store
+                .put_bytes(column.as_str(), &key.as_key_bytes(), &[*key as u8])
+                .unwrap();
+        }
+
+        let keys = store
+            .iter_column_keys::<u64>(column)
+            .unwrap()
+            .map(|result| result.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(keys, vec![1, 2, 300]);
+
+        assert!(
+            store.iter_column_keys::<Hash256>(column).is_err(),
+            "keys of the wrong type are rejected"
+        );
+    }
+
     #[test]
     fn simplediskdb() {
         let dir = tempdir().unwrap();
@@ -276,6 +446,14 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn rocksdb() {
+        let dir = tempdir().unwrap();
+        let store = RocksDB::open(dir.path(), DatabaseCompression::Lz4).unwrap();
+
+        test_impl(store);
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
@@ -283,6 +461,55 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn diskdb_iter() {
+        let dir = tempdir().unwrap();
+        let store = LevelDB::open(dir.path()).unwrap();
+
+        test_iter_impl(store);
+    }
+
+    #[test]
+    fn rocksdb_iter() {
+        let dir = tempdir().unwrap();
+        let store = RocksDB::open(dir.path(), DatabaseCompression::None).unwrap();
+
+        test_iter_impl(store);
+    }
+
+    #[test]
+    fn memorydb_iter() {
+        test_iter_impl(MemoryStore::open());
+    }
+
+    #[test]
+    fn memorydb_snapshot_restore() {
+        let store = MemoryStore::<MinimalEthSpec>::open();
+        let key = Hash256::random();
+        let other_key = Hash256::random();
+        store.put(&key, &StorableThing { a: 1, b: 42 }).unwrap();
+
+        let snapshot = store.snapshot();
+        let branch = MemoryStore::<MinimalEthSpec>::from_snapshot(&snapshot);
+
+        store.put(&key, &StorableThing { a: 2, b: 43 }).unwrap();
+        store
+            .put(&other_key, &StorableThing { a: 3, b: 44 })
+            .unwrap();
+        assert_eq!(
+            branch.get::<StorableThing>(&key).unwrap(),
+            Some(StorableThing { a: 1, b: 42 }),
+            "branches are unaffected by writes to the original"
+        );
+
+        store.restore(&snapshot);
+        assert_eq!(
+            store.get::<StorableThing>(&key).unwrap(),
+            Some(StorableThing { a: 1, b: 42 })
+        );
+        assert!(!store.exists::<StorableThing>(&other_key).unwrap());
+    }
+
     #[test]
     fn exists() {
         let store = MemoryStore::<MinimalEthSpec>::open();
@@ -295,7 +522,7 @@ mod tests {
 
         assert_eq!(store.exists::<StorableThing>(&key).unwrap(), true);
 
-        store.delete::<StorableThing>(&key).unwrap();
+        store.delete::<StorableThing, _>(&key).unwrap();
 
         assert_eq!(store.exists::<StorableThing>(&key).unwrap(), false);
     }
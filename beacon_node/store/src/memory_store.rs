@@ -1,14 +1,27 @@
-use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use super::{ColumnIter, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use crate::leveldb_store::write_snapshot;
 use parking_lot::{Mutex, MutexGuard, RwLock};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
 use types::*;
 
-type DBHashMap = HashMap<Vec<u8>, Vec<u8>>;
+/// Values are reference-counted so that snapshots share them rather than copying them.
+type DBMap = BTreeMap<Vec<u8>, Arc<[u8]>>;
 
-/// A thread-safe `HashMap` wrapper.
+/// A point-in-time copy of the contents of a `MemoryStore`.
+///
+/// Taking a snapshot only copies the keys; the values are shared with the store until they are
+/// overwritten, so tests may cheaply branch a database and later return to it.
+#[derive(Clone)]
+pub struct MemoryStoreSnapshot {
+    db: DBMap,
+}
+
+/// A thread-safe `BTreeMap` wrapper. An ordered map is used to support iteration.
 pub struct MemoryStore<E: EthSpec> {
-    db: RwLock<DBHashMap>,
+    db: RwLock<DBMap>,
     transaction_mutex: Mutex<()>,
     _phantom: PhantomData<E>,
 }
@@ -17,12 +30,31 @@ impl<E: EthSpec> MemoryStore<E> {
     /// Create a new, empty database.
     pub fn open() -> Self {
         Self {
-            db: RwLock::new(HashMap::new()),
+            db: RwLock::new(BTreeMap::new()),
             transaction_mutex: Mutex::new(()),
             _phantom: PhantomData,
         }
     }
 
+    /// Create a new database containing the contents of `snapshot`.
+    pub fn from_snapshot(snapshot: &MemoryStoreSnapshot) -> Self {
+        let store = Self::open();
+        store.restore(snapshot);
+        store
+    }
+
+    /// Take a snapshot of the current contents of the database.
+    pub fn snapshot(&self) -> MemoryStoreSnapshot {
+        MemoryStoreSnapshot {
+            db: self.db.read().clone(),
+        }
+    }
+
+    /// Replace the contents of the database with those of `snapshot`.
+    pub fn restore(&self, snapshot: &MemoryStoreSnapshot) {
+        *self.db.write() = snapshot.db.clone();
+    }
+
     fn get_key_for_col(col: &str, key: &[u8]) -> Vec<u8> {
         let mut col = col.as_bytes().to_vec();
         col.append(&mut key.to_vec());
@@ -34,13 +66,13 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
     /// Get the value of some key from the database. Returns `None` if the key does not exist.
     fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
         let column_key = Self::get_key_for_col(col, key);
-        Ok(self.db.read().get(&column_key).cloned())
+        Ok(self.db.read().get(&column_key).map(|value| value.to_vec()))
     }
 
     /// Puts a key in the database.
     fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
         let column_key = Self::get_key_for_col(col, key);
-        self.db.write().insert(column_key, val.to_vec());
+        self.db.write().insert(column_key, val.into());
         Ok(())
     }
 
@@ -70,7 +102,7 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
         for op in batch {
             match op {
                 KeyValueStoreOp::PutKeyValue(key, value) => {
-                    self.db.write().insert(key, value);
+                    self.db.write().insert(key, value.into());
                 }
 
                 KeyValueStoreOp::DeleteKey(hash) => {
@@ -88,6 +120,32 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
     fn compact(&self) -> Result<(), Error> {
         Ok(())
     }
+
+    fn snapshot_to(&self, path: &Path) -> Result<u64, Error> {
+        let entries = self.db.read().clone();
+        write_snapshot(
+            path,
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, value.to_vec())),
+        )
+    }
+
+    fn iter_column_from(&self, column: &str, from: &[u8]) -> ColumnIter {
+        let start_key = Self::get_key_for_col(column, from);
+        let column = column.as_bytes();
+
+        // Copy the entries so that the lock is not held whilst iterating.
+        let entries = self
+            .db
+            .read()
+            .range(start_key..)
+            .take_while(|(key, _)| key.starts_with(column))
+            .map(|(key, value)| Ok((key[column.len()..].to_vec(), value.to_vec())))
+            .collect::<Vec<_>>();
+
+        Box::new(entries.into_iter())
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
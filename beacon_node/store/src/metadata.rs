@@ -1,6 +1,7 @@
 use crate::{DBColumn, Error, StoreItem};
 use ssz::{Decode, Encode};
-use types::{Checkpoint, Hash256};
+use ssz_derive::{Decode, Encode};
+use types::{Checkpoint, Hash256, Slot};
 
 pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(3);
 
@@ -12,6 +13,7 @@ pub const CONFIG_KEY: Hash256 = Hash256::repeat_byte(1);
 pub const SPLIT_KEY: Hash256 = Hash256::repeat_byte(2);
 pub const PRUNING_CHECKPOINT_KEY: Hash256 = Hash256::repeat_byte(3);
 pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
+pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SchemaVersion(pub u64);
@@ -76,3 +78,30 @@ impl StoreItem for CompactionTimestamp {
         Ok(CompactionTimestamp(u64::from_ssz_bytes(bytes)?))
     }
 }
+
+/// The start of the history held by a database which was initialized from a finalized checkpoint
+/// state, rather than from genesis.
+///
+/// Only present for such databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct AnchorInfo {
+    /// The slot of the state the database was initialized from.
+    pub anchor_slot: Slot,
+    /// The first restore point at or after the anchor. Historical states prior to this slot are
+    /// unavailable, with the exception of the genesis state.
+    pub state_upper_limit: Slot,
+}
+
+impl StoreItem for AnchorInfo {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
@@ -222,6 +222,7 @@ impl<E: EthSpec> TryInto<BeaconState<E>> for PartialBeaconState<E> {
             committee_caches: <_>::default(),
             pubkey_cache: <_>::default(),
             exit_cache: <_>::default(),
+            total_active_balance: None,
             tree_hash_cache: <_>::default(),
         })
     }
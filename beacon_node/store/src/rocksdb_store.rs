@@ -0,0 +1,238 @@
+use super::*;
+use crate::config::DatabaseCompression;
+use crate::metrics;
+use parking_lot::{Mutex, MutexGuard};
+use rocksdb::{
+    checkpoint::Checkpoint, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType, Direction,
+    IteratorMode, Options, WriteBatch, WriteOptions, DB,
+};
+use std::marker::PhantomData;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+/// A wrapped rocksdb database, with one column family per `DBColumn`.
+pub struct RocksDB<E: EthSpec> {
+    db: DB,
+    /// A mutex to synchronise sensitive read-write transactions.
+    transaction_mutex: Mutex<()>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> RocksDB<E> {
+    /// Open a database at `path`, creating a new database if one does not already exist.
+    ///
+    /// A column family is created for each `DBColumn` that does not already have one.
+    pub fn open(path: &Path, compression: DatabaseCompression) -> Result<Self, Error> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let column_families = DBColumn::iter()
+            .map(|column| {
+                let mut cf_options = Options::default();
+                cf_options.set_compression_type(compression.into());
+                ColumnFamilyDescriptor::new(column.as_str(), cf_options)
+            })
+            .collect::<Vec<_>>();
+
+        let db = DB::open_cf_descriptors(&options, path, column_families)?;
+        let transaction_mutex = Mutex::new(());
+
+        Ok(Self {
+            db,
+            transaction_mutex,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the column family for `col`, or an error if `col` is not a `DBColumn`.
+    fn cf_handle(&self, col: &str) -> Result<&ColumnFamily, Error> {
+        self.db.cf_handle(col).ok_or_else(|| Error::DBError {
+            message: format!("unknown column: {}", col),
+        })
+    }
+
+    /// Split a key produced by `get_key_for_col` into its column family and key.
+    fn split_column_key<'a>(&self, key: &'a [u8]) -> Result<(&ColumnFamily, &'a [u8]), Error> {
+        let column = DBColumn::iter()
+            .find(|column| key.starts_with(column.as_bytes()))
+            .ok_or_else(|| Error::DBError {
+                message: format!("key without a known column: {:?}", key),
+            })?;
+        Ok((
+            self.cf_handle(column.as_str())?,
+            &key[column.as_bytes().len()..],
+        ))
+    }
+
+    fn write_options(&self) -> WriteOptions {
+        WriteOptions::default()
+    }
+
+    fn write_options_sync(&self) -> WriteOptions {
+        let mut opts = WriteOptions::default();
+        opts.set_sync(true);
+        opts
+    }
+
+    fn put_bytes_with_options(
+        &self,
+        col: &str,
+        key: &[u8],
+        val: &[u8],
+        opts: WriteOptions,
+    ) -> Result<(), Error> {
+        let cf = self.cf_handle(col)?;
+
+        metrics::inc_counter(&metrics::DISK_DB_WRITE_COUNT);
+        metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as u64);
+        let timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+
+        self.db
+            .put_cf_opt(cf, key, val, &opts)
+            .map_err(Into::into)
+            .map(|()| {
+                metrics::stop_timer(timer);
+            })
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for RocksDB<E> {
+    /// Store some `value` in `column`, indexed with `key`.
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes_with_options(col, key, val, self.write_options())
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes_with_options(col, key, val, self.write_options_sync())
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        for column in DBColumn::iter() {
+            self.db.flush_cf(self.cf_handle(column.as_str())?)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieve some bytes in `column` with `key`.
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let cf = self.cf_handle(col)?;
+
+        metrics::inc_counter(&metrics::DISK_DB_READ_COUNT);
+        let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+
+        self.db.get_cf(cf, key).map_err(Into::into).map(|opt| {
+            opt.map(|bytes| {
+                metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as u64);
+                metrics::stop_timer(timer);
+                bytes
+            })
+        })
+    }
+
+    /// Return `true` if `key` exists in `column`.
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        let cf = self.cf_handle(col)?;
+
+        metrics::inc_counter(&metrics::DISK_DB_EXISTS_COUNT);
+
+        self.db
+            .get_pinned_cf(cf, key)
+            .map_err(Into::into)
+            .map(|val| val.is_some())
+    }
+
+    /// Removes `key` from `column`.
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        let cf = self.cf_handle(col)?;
+
+        metrics::inc_counter(&metrics::DISK_DB_DELETE_COUNT);
+
+        self.db.delete_cf(cf, key).map_err(Into::into)
+    }
+
+    fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        let mut rocksdb_batch = WriteBatch::default();
+        for op in ops_batch {
+            match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => {
+                    let (cf, key) = self.split_column_key(&key)?;
+                    rocksdb_batch.put_cf(cf, key, &value);
+                }
+
+                KeyValueStoreOp::DeleteKey(key) => {
+                    let (cf, key) = self.split_column_key(&key)?;
+                    rocksdb_batch.delete_cf(cf, key);
+                }
+            }
+        }
+        self.db.write_opt(rocksdb_batch, &self.write_options())?;
+        Ok(())
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        self.transaction_mutex.lock()
+    }
+
+    /// Compact all values in the states and states flag columns.
+    fn compact(&self) -> Result<(), Error> {
+        for column in vec![DBColumn::BeaconStateTemporary, DBColumn::BeaconState] {
+            self.db
+                .compact_range_cf::<&[u8], &[u8]>(self.cf_handle(column.as_str())?, None, None);
+        }
+        Ok(())
+    }
+
+    /// Writes a RocksDB checkpoint, which hard-links the immutable table files where possible,
+    /// so the snapshot may be opened with the RocksDB backend.
+    fn snapshot_to(&self, path: &Path) -> Result<u64, Error> {
+        Checkpoint::new(&self.db)?.create_checkpoint(path)?;
+
+        // Count the keys in the checkpoint itself, rather than in `self`, which may have been
+        // written to since. Compression only applies to new writes, so it is irrelevant here.
+        let checkpoint = Self::open(path, DatabaseCompression::None)?;
+        let mut count = 0;
+        for column in DBColumn::iter() {
+            for result in checkpoint.iter_column(column.as_str()) {
+                result?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn iter_column_from(&self, column: &str, from: &[u8]) -> ColumnIter {
+        let cf = match self.cf_handle(column) {
+            Ok(cf) => cf,
+            Err(e) => return Box::new(std::iter::once(Err(e))),
+        };
+
+        Box::new(
+            self.db
+                .iterator_cf(cf, IteratorMode::From(from, Direction::Forward))
+                .map(|(key, value)| Ok((key.into_vec(), value.into_vec()))),
+        )
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for RocksDB<E> {}
+
+impl From<DatabaseCompression> for DBCompressionType {
+    fn from(compression: DatabaseCompression) -> Self {
+        match compression {
+            DatabaseCompression::None => DBCompressionType::None,
+            DatabaseCompression::Snappy => DBCompressionType::Snappy,
+            DatabaseCompression::Lz4 => DBCompressionType::Lz4,
+            DatabaseCompression::Zstd => DBCompressionType::Zstd,
+        }
+    }
+}
+
+impl From<rocksdb::Error> for Error {
+    fn from(e: rocksdb::Error) -> Error {
+        Error::DBError {
+            message: format!("{:?}", e),
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Provides `ValidatorRegistry`, which reads the validators and balances of a `BeaconState`
+//! directly from its SSZ bytes.
+//!
+//! Decoding a full `BeaconState` (and serializing it again) is expensive when there are hundreds
+//! of thousands of validators. This allows callers to locate the registry within the stored bytes
+//! and decode only the validators they require.
+
+use ssz::{Decode, DecodeError, BYTES_PER_LENGTH_OFFSET};
+use std::ops::Range;
+use types::{
+    BeaconBlockHeader, Eth1Data, EthSpec, FixedVector, Fork, Hash256, PublicKeyBytes, Slot,
+    Validator, VariableList,
+};
+
+/// The length of the validator public key, which is the first field of each `Validator`.
+const PUBKEY_LEN: usize = 48;
+
+/// The validators and balances of a `BeaconState`, backed by the state's SSZ bytes.
+pub struct ValidatorRegistry {
+    bytes: Vec<u8>,
+    slot: Slot,
+    validators: Range<usize>,
+    balances: Range<usize>,
+}
+
+/// Returns the number of bytes used by `T` in the fixed-length portion of an SSZ container.
+fn fixed_portion_len<T: Decode>() -> usize {
+    if T::is_ssz_fixed_len() {
+        T::ssz_fixed_len()
+    } else {
+        BYTES_PER_LENGTH_OFFSET
+    }
+}
+
+fn read_offset(bytes: &[u8], position: usize) -> Result<usize, DecodeError> {
+    let offset_bytes = bytes
+        .get(position..position + BYTES_PER_LENGTH_OFFSET)
+        .ok_or(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: position + BYTES_PER_LENGTH_OFFSET,
+        })?;
+    let mut array = [0; BYTES_PER_LENGTH_OFFSET];
+    array.copy_from_slice(offset_bytes);
+    Ok(u32::from_le_bytes(array) as usize)
+}
+
+impl ValidatorRegistry {
+    /// Locate the registry within the bytes of a stored `StorageContainer`.
+    pub fn from_storage_container_bytes<E: EthSpec>(bytes: Vec<u8>) -> Result<Self, DecodeError> {
+        // The container holds two variable-length fields: the state and the committee caches.
+        let state_start = read_offset(&bytes, 0)?;
+        let state_end = read_offset(&bytes, BYTES_PER_LENGTH_OFFSET)?;
+        if state_start > state_end || state_end > bytes.len() {
+            return Err(DecodeError::OffsetOutOfBounds(state_end));
+        }
+        Self::from_bytes::<E>(bytes, state_start..state_end)
+    }
+
+    /// Locate the registry within the SSZ bytes of a `BeaconState`.
+    pub fn from_state_bytes<E: EthSpec>(bytes: Vec<u8>) -> Result<Self, DecodeError> {
+        let len = bytes.len();
+        Self::from_bytes::<E>(bytes, 0..len)
+    }
+
+    fn from_bytes<E: EthSpec>(bytes: Vec<u8>, state: Range<usize>) -> Result<Self, DecodeError> {
+        let state_bytes = &bytes[state.clone()];
+
+        // Compute the positions of the fields in the fixed-length portion of the state, following
+        // the field order of `BeaconState`.
+        let slot_position = fixed_portion_len::<u64>() + fixed_portion_len::<Hash256>();
+        let validators_position = slot_position
+            + fixed_portion_len::<Slot>()
+            + fixed_portion_len::<Fork>()
+            + fixed_portion_len::<BeaconBlockHeader>()
+            + 2 * fixed_portion_len::<FixedVector<Hash256, E::SlotsPerHistoricalRoot>>()
+            + fixed_portion_len::<VariableList<Hash256, E::HistoricalRootsLimit>>()
+            + fixed_portion_len::<Eth1Data>()
+            + fixed_portion_len::<VariableList<Eth1Data, E::SlotsPerEth1VotingPeriod>>()
+            + fixed_portion_len::<u64>();
+        let balances_position = validators_position + BYTES_PER_LENGTH_OFFSET;
+        let attestations_position = balances_position
+            + BYTES_PER_LENGTH_OFFSET
+            + fixed_portion_len::<FixedVector<Hash256, E::EpochsPerHistoricalVector>>()
+            + fixed_portion_len::<FixedVector<u64, E::EpochsPerSlashingsVector>>();
+
+        let slot = Slot::from_ssz_bytes(
+            state_bytes
+                .get(slot_position..slot_position + fixed_portion_len::<Slot>())
+                .ok_or(DecodeError::InvalidByteLength {
+                    len: state_bytes.len(),
+                    expected: slot_position + fixed_portion_len::<Slot>(),
+                })?,
+        )?;
+
+        let validators_offset = read_offset(state_bytes, validators_position)?;
+        let balances_offset = read_offset(state_bytes, balances_position)?;
+        let attestations_offset = read_offset(state_bytes, attestations_position)?;
+
+        if validators_offset > balances_offset {
+            return Err(DecodeError::OffsetsAreDecreasing(balances_offset));
+        }
+        if balances_offset > attestations_offset {
+            return Err(DecodeError::OffsetsAreDecreasing(attestations_offset));
+        }
+        if attestations_offset > state_bytes.len() {
+            return Err(DecodeError::OffsetOutOfBounds(attestations_offset));
+        }
+
+        let validators_len = balances_offset - validators_offset;
+        let balances_len = attestations_offset - balances_offset;
+        let validator_size = <Validator as Decode>::ssz_fixed_len();
+        let balance_size = <u64 as Decode>::ssz_fixed_len();
+
+        if validators_len % validator_size != 0 {
+            return Err(DecodeError::InvalidListFixedBytesLen(validators_len));
+        }
+        if balances_len % balance_size != 0 {
+            return Err(DecodeError::InvalidListFixedBytesLen(balances_len));
+        }
+        if validators_len / validator_size != balances_len / balance_size {
+            return Err(DecodeError::BytesInvalid(format!(
+                "{} validators and {} balances",
+                validators_len / validator_size,
+                balances_len / balance_size
+            )));
+        }
+
+        Ok(Self {
+            slot,
+            validators: state.start + validators_offset..state.start + balances_offset,
+            balances: state.start + balances_offset..state.start + attestations_offset,
+            bytes,
+        })
+    }
+
+    /// The slot of the state which contains this registry.
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// The number of validators in the registry.
+    pub fn len(&self) -> usize {
+        self.validators.len() / <Validator as Decode>::ssz_fixed_len()
+    }
+
+    /// Returns `true` if the registry contains no validators.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the public key of the validator at `index`, without decoding the rest of the
+    /// validator.
+    pub fn pubkey(&self, index: usize) -> Option<PublicKeyBytes> {
+        let start = self.validator_bytes(index)?.start;
+        PublicKeyBytes::from_ssz_bytes(&self.bytes[start..start + PUBKEY_LEN]).ok()
+    }
+
+    /// Decodes the validator and balance at `index`.
+    pub fn get(&self, index: usize) -> Result<Option<(Validator, u64)>, DecodeError> {
+        let validator_bytes = match self.validator_bytes(index) {
+            Some(range) => &self.bytes[range],
+            None => return Ok(None),
+        };
+        let balance_size = <u64 as Decode>::ssz_fixed_len();
+        let balance_start = self.balances.start + index * balance_size;
+        let balance_bytes = &self.bytes[balance_start..balance_start + balance_size];
+
+        Ok(Some((
+            Validator::from_ssz_bytes(validator_bytes)?,
+            u64::from_ssz_bytes(balance_bytes)?,
+        )))
+    }
+
+    /// Iterates over all validators and their balances, decoding each lazily.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(Validator, u64), DecodeError>> + '_ {
+        self.bytes[self.validators.clone()]
+            .chunks_exact(<Validator as Decode>::ssz_fixed_len())
+            .zip(self.bytes[self.balances.clone()].chunks_exact(<u64 as Decode>::ssz_fixed_len()))
+            .map(|(validator_bytes, balance_bytes)| {
+                Ok((
+                    Validator::from_ssz_bytes(validator_bytes)?,
+                    u64::from_ssz_bytes(balance_bytes)?,
+                ))
+            })
+    }
+
+    fn validator_bytes(&self, index: usize) -> Option<Range<usize>> {
+        if index >= self.len() {
+            return None;
+        }
+        let validator_size = <Validator as Decode>::ssz_fixed_len();
+        let start = self.validators.start + index * validator_size;
+        Some(start..start + validator_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BeaconStateStorageContainer;
+    use ssz::Encode;
+    use types::{test_utils::generate_deterministic_keypairs, BeaconState, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn get_state(validator_count: usize) -> BeaconState<E> {
+        let spec = E::default_spec();
+        let mut state = BeaconState::new(0, Eth1Data::default(), &spec);
+        state.slot = Slot::new(42);
+
+        for (i, keypair) in generate_deterministic_keypairs(validator_count)
+            .into_iter()
+            .enumerate()
+        {
+            let validator = Validator {
+                pubkey: keypair.pk.compress(),
+                withdrawal_credentials: Hash256::from_low_u64_be(i as u64),
+                effective_balance: spec.max_effective_balance,
+                slashed: i % 2 == 0,
+                activation_eligibility_epoch: spec.far_future_epoch,
+                activation_epoch: spec.far_future_epoch,
+                exit_epoch: spec.far_future_epoch,
+                withdrawable_epoch: spec.far_future_epoch,
+            };
+            state.validators.push(validator).unwrap();
+            state.balances.push(i as u64).unwrap();
+        }
+
+        state
+    }
+
+    fn check_registry(state: &BeaconState<E>, registry: &ValidatorRegistry) {
+        assert_eq!(registry.slot(), state.slot);
+        assert_eq!(registry.len(), state.validators.len());
+
+        for (i, item) in registry.iter().enumerate() {
+            let expected = (state.validators[i].clone(), state.balances[i]);
+            assert_eq!(item.unwrap(), expected);
+            assert_eq!(registry.get(i).unwrap(), Some(expected));
+            assert_eq!(registry.pubkey(i), Some(state.validators[i].pubkey));
+        }
+
+        assert_eq!(registry.get(state.validators.len()).unwrap(), None);
+        assert_eq!(registry.pubkey(state.validators.len()), None);
+    }
+
+    #[test]
+    fn read_from_state_bytes() {
+        let state = get_state(8);
+        let registry = ValidatorRegistry::from_state_bytes::<E>(state.as_ssz_bytes()).unwrap();
+        check_registry(&state, &registry);
+    }
+
+    #[test]
+    fn read_from_storage_container_bytes() {
+        let state = get_state(8);
+        let bytes = BeaconStateStorageContainer::new(&state).as_ssz_bytes();
+        let registry = ValidatorRegistry::from_storage_container_bytes::<E>(bytes).unwrap();
+        check_registry(&state, &registry);
+    }
+
+    #[test]
+    fn empty_registry() {
+        let state = get_state(0);
+        let registry = ValidatorRegistry::from_state_bytes::<E>(state.as_ssz_bytes()).unwrap();
+        assert!(registry.is_empty());
+        assert_eq!(registry.iter().count(), 0);
+    }
+}
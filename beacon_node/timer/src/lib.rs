@@ -3,35 +3,28 @@
 //! This service allows task execution on the beacon node for various functionality.
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use environment::slot_scheduler::{Schedule, SlotFraction, SlotScheduler};
 use slog::info;
-use slot_clock::SlotClock;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::{interval_at, Instant};
+use types::EthSpec;
 
-/// Spawns a timer service which periodically executes tasks for the beacon chain
+/// Spawns a timer service which executes tasks for the beacon chain at the start of each slot.
 pub fn spawn_timer<T: BeaconChainTypes>(
     executor: task_executor::TaskExecutor,
     beacon_chain: Arc<BeaconChain<T>>,
-    seconds_per_slot: u64,
 ) -> Result<(), &'static str> {
-    let log = executor.log();
-    let start_instant = Instant::now()
-        + beacon_chain
-            .slot_clock
-            .duration_to_next_slot()
-            .ok_or("slot_notifier unable to determine time to next slot")?;
+    let log = executor.log().clone();
+    let scheduler = SlotScheduler::new(
+        executor,
+        beacon_chain.slot_clock.clone(),
+        T::EthSpec::slots_per_epoch(),
+    );
 
-    // Warning: `interval_at` panics if `seconds_per_slot` = 0.
-    let mut interval = interval_at(start_instant, Duration::from_secs(seconds_per_slot));
-    let timer_future = async move {
-        loop {
-            interval.tick().await;
-            beacon_chain.per_slot_task();
-        }
-    };
-
-    executor.spawn(timer_future, "timer");
+    scheduler.register(
+        "timer",
+        Schedule::EverySlot(SlotFraction::START),
+        move |_| beacon_chain.per_slot_task(),
+    );
     info!(log, "Timer service started");
 
     Ok(())
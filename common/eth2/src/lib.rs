@@ -24,6 +24,7 @@ use ssz::Decode;
 use std::convert::TryFrom;
 use std::fmt;
 use std::iter::Iterator;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Error {
@@ -76,12 +77,44 @@ impl fmt::Display for Error {
     }
 }
 
+/// A struct to define a variety of different timeouts for different validator tasks to ensure
+/// proper fallback behaviour.
+#[derive(Clone)]
+pub struct Timeouts {
+    pub attestation: Duration,
+    pub attester_duties: Duration,
+    pub proposal: Duration,
+    pub proposer_duties: Duration,
+}
+
+impl Timeouts {
+    pub fn set_all(timeout: Duration) -> Self {
+        Timeouts {
+            attestation: timeout,
+            attester_duties: timeout,
+            proposal: timeout,
+            proposer_duties: timeout,
+        }
+    }
+
+    /// Multiplies each timeout by `multiplier`.
+    pub fn scale(self, multiplier: u32) -> Self {
+        Timeouts {
+            attestation: self.attestation * multiplier,
+            attester_duties: self.attester_duties * multiplier,
+            proposal: self.proposal * multiplier,
+            proposer_duties: self.proposer_duties * multiplier,
+        }
+    }
+}
+
 /// A wrapper around `reqwest::Client` which provides convenience methods for interfacing with a
 /// Lighthouse Beacon Node HTTP server (`http_api`).
 #[derive(Clone)]
 pub struct BeaconNodeHttpClient {
     client: reqwest::Client,
     server: Url,
+    timeouts: Timeouts,
 }
 
 impl fmt::Display for BeaconNodeHttpClient {
@@ -97,15 +130,20 @@ impl AsRef<str> for BeaconNodeHttpClient {
 }
 
 impl BeaconNodeHttpClient {
-    pub fn new(server: Url) -> Self {
+    pub fn new(server: Url, timeouts: Timeouts) -> Self {
         Self {
             client: reqwest::Client::new(),
             server,
+            timeouts,
         }
     }
 
-    pub fn from_components(server: Url, client: reqwest::Client) -> Self {
-        Self { client, server }
+    pub fn from_components(server: Url, client: reqwest::Client, timeouts: Timeouts) -> Self {
+        Self {
+            client,
+            server,
+            timeouts,
+        }
     }
 
     /// Return the path with the standard `/eth1/v1` prefix applied.
@@ -130,6 +168,26 @@ impl BeaconNodeHttpClient {
             .map_err(Error::Reqwest)
     }
 
+    /// Perform a HTTP GET request with a custom timeout.
+    async fn get_with_timeout<T: DeserializeOwned, U: IntoUrl>(
+        &self,
+        url: U,
+        timeout: Duration,
+    ) -> Result<T, Error> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response)
+            .await?
+            .json()
+            .await
+            .map_err(Error::Reqwest)
+    }
+
     /// Perform a HTTP GET request, returning `None` on a 404 error.
     async fn get_opt<T: DeserializeOwned, U: IntoUrl>(&self, url: U) -> Result<Option<T>, Error> {
         let response = self.client.get(url).send().await.map_err(Error::Reqwest)?;
@@ -145,6 +203,31 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Perform a HTTP GET request with a custom timeout, returning `None` on a 404 error.
+    async fn get_opt_with_timeout<T: DeserializeOwned, U: IntoUrl>(
+        &self,
+        url: U,
+        timeout: Duration,
+    ) -> Result<Option<T>, Error> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        match ok_or_error(response).await {
+            Ok(resp) => resp.json().await.map(Option::Some).map_err(Error::Reqwest),
+            Err(err) => {
+                if err.status() == Some(StatusCode::NOT_FOUND) {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
     /// Perform a HTTP GET request using an 'accept' header, returning `None` on a 404 error.
     pub async fn get_bytes_opt_accept_header<U: IntoUrl>(
         &self,
@@ -189,15 +272,36 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
-    /// Perform a HTTP POST request, returning a JSON response.
-    async fn post_with_response<T: DeserializeOwned, U: IntoUrl, V: Serialize>(
+    /// Perform a HTTP POST request with a custom timeout.
+    async fn post_with_timeout<T: Serialize, U: IntoUrl>(
+        &self,
+        url: U,
+        body: &T,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let response = self
+            .client
+            .post(url)
+            .timeout(timeout)
+            .json(body)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response).await?;
+        Ok(())
+    }
+
+    /// Perform a HTTP POST request with a custom timeout, returning a JSON response.
+    async fn post_with_timeout_and_response<T: DeserializeOwned, U: IntoUrl, V: Serialize>(
         &self,
         url: U,
         body: &V,
+        timeout: Duration,
     ) -> Result<T, Error> {
         let response = self
             .client
             .post(url)
+            .timeout(timeout)
             .json(body)
             .send()
             .await
@@ -468,7 +572,8 @@ impl BeaconNodeHttpClient {
             .push("beacon")
             .push("blocks");
 
-        self.post(path, block).await?;
+        self.post_with_timeout(path, block, self.timeouts.proposal)
+            .await?;
 
         Ok(())
     }
@@ -566,6 +671,7 @@ impl BeaconNodeHttpClient {
         let response = self
             .client
             .post(path)
+            .timeout(self.timeouts.attestation)
             .json(attestations)
             .send()
             .await
@@ -927,7 +1033,8 @@ impl BeaconNodeHttpClient {
             .push("proposer")
             .push(&epoch.to_string());
 
-        self.get(path).await
+        self.get_with_timeout(path, self.timeouts.proposer_duties)
+            .await
     }
 
     /// `GET validator/blocks/{slot}`
@@ -953,7 +1060,7 @@ impl BeaconNodeHttpClient {
                 .append_pair("graffiti", &graffiti.to_string());
         }
 
-        self.get(path).await
+        self.get_with_timeout(path, self.timeouts.proposal).await
     }
 
     /// `GET validator/attestation_data?slot,committee_index`
@@ -973,7 +1080,7 @@ impl BeaconNodeHttpClient {
             .append_pair("slot", &slot.to_string())
             .append_pair("committee_index", &committee_index.to_string());
 
-        self.get(path).await
+        self.get_with_timeout(path, self.timeouts.attestation).await
     }
 
     /// `GET validator/attestation_attestation?slot,attestation_data_root`
@@ -996,7 +1103,8 @@ impl BeaconNodeHttpClient {
                 &format!("{:?}", attestation_data_root),
             );
 
-        self.get_opt(path).await
+        self.get_opt_with_timeout(path, self.timeouts.attestation)
+            .await
     }
 
     /// `POST validator/duties/attester/{epoch}`
@@ -1014,7 +1122,8 @@ impl BeaconNodeHttpClient {
             .push("attester")
             .push(&epoch.to_string());
 
-        self.post_with_response(path, &indices).await
+        self.post_with_timeout_and_response(path, &indices, self.timeouts.attester_duties)
+            .await
     }
 
     /// `POST validator/aggregate_and_proofs`
@@ -1032,6 +1141,7 @@ impl BeaconNodeHttpClient {
         let response = self
             .client
             .post(path)
+            .timeout(self.timeouts.attestation)
             .json(aggregates)
             .send()
             .await
@@ -1,8 +1,13 @@
 //! This module contains endpoints that are non-standard and only available on Lighthouse servers.
 
+mod system_health;
+
 use crate::{
     ok_or_error,
-    types::{BeaconState, Epoch, EthSpec, GenericResponse, ValidatorId},
+    types::{
+        BeaconState, BlockId, EnrForkId, Epoch, EthSpec, GenericResponse, IndexedAttestation,
+        SignedBeaconBlockHeader, Slot, ValidatorId,
+    },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, StateId, StatusCode,
 };
 use proto_array::core::ProtoArray;
@@ -10,8 +15,15 @@ use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
-pub use eth2_libp2p::{types::SyncState, PeerInfo};
+pub use eth2_libp2p::{
+    types::{ChainSplitStatus, SyncBatchStatus, SyncChainStatus, SyncState},
+    PeerInfo,
+};
+pub use system_health::{DiskHealth, SystemHealth};
 
 /// Information returned by `peers` and `connected_peers`.
 // TODO: this should be deserializable..
@@ -24,6 +36,26 @@ pub struct Peer<T: EthSpec> {
     pub peer_info: PeerInfo<T>,
 }
 
+/// The connected peers which run a particular client, returned by `peers/clients`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientPeers {
+    /// The client's name, as determined from the peers' agent strings.
+    pub client: String,
+    /// The number of peers running each version of the client.
+    pub versions: BTreeMap<String, usize>,
+    pub peers: Vec<ClientPeer>,
+}
+
+/// A peer within `ClientPeers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientPeer {
+    pub peer_id: String,
+    pub version: String,
+    pub agent_string: Option<String>,
+    /// The fork information advertised in the peer's ENR, if known.
+    pub enr_fork_id: Option<EnrForkId>,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -105,6 +137,105 @@ pub struct Health {
     pub sys_loadavg_5: f64,
     /// System load average over 15 minutes.
     pub sys_loadavg_15: f64,
+    /// Heap statistics from the memory allocator, if supported on this platform.
+    #[serde(default)]
+    pub allocator: Option<AllocatorStats>,
+    /// Estimates of the memory used by the larger in-memory caches. Only provided by the beacon
+    /// node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_memory: Option<CacheMemoryEstimates>,
+    /// CPU, file descriptor, disk and network usage.
+    #[serde(default)]
+    pub system: Option<SystemHealth>,
+}
+
+/// Heap statistics reported by the system allocator (glibc `malloc`).
+///
+/// Only available with glibc 2.33 or later, which provides `mallinfo2`. The counters returned by
+/// the older `mallinfo` are 32-bit and wrap on large heaps, so they are not used.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AllocatorStats {
+    /// Bytes allocated via `sbrk` in the main arena and via `mmap` in other arenas.
+    pub arena_bytes: u64,
+    /// Bytes in chunks allocated directly with `mmap`.
+    pub mmap_bytes: u64,
+    /// Number of chunks allocated directly with `mmap`.
+    pub mmap_chunks: u64,
+    /// Bytes in use by allocations.
+    pub in_use_bytes: u64,
+    /// Bytes in free chunks which are retained by the allocator.
+    pub free_bytes: u64,
+    /// Number of free chunks retained by the allocator.
+    pub free_chunks: u64,
+    /// Bytes at the top of the heap which could be released to the OS by `malloc_trim`.
+    pub releasable_bytes: u64,
+}
+
+impl AllocatorStats {
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    pub fn observe() -> Option<Self> {
+        None
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    pub fn observe() -> Option<Self> {
+        /// The `struct mallinfo2` returned by glibc's `mallinfo2`, which is not exposed by `libc`.
+        #[repr(C)]
+        #[allow(dead_code)]
+        struct Mallinfo2 {
+            arena: libc::size_t,
+            ordblks: libc::size_t,
+            smblks: libc::size_t,
+            hblks: libc::size_t,
+            hblkhd: libc::size_t,
+            usmblks: libc::size_t,
+            fsmblks: libc::size_t,
+            uordblks: libc::size_t,
+            fordblks: libc::size_t,
+            keepcost: libc::size_t,
+        }
+
+        // Look `mallinfo2` up at runtime so that the binary still runs against glibc < 2.33.
+        //
+        // Safe since the symbol name is a valid C string and `RTLD_DEFAULT` searches the
+        // libraries which are already loaded.
+        let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, b"mallinfo2\0".as_ptr() as _) };
+        if symbol.is_null() {
+            return None;
+        }
+
+        // Safe since `symbol` is glibc's `mallinfo2`, which has this signature and no
+        // preconditions, and simply returns a copy of the allocator's counters.
+        let mallinfo2: extern "C" fn() -> Mallinfo2 = unsafe { std::mem::transmute(symbol) };
+        let info = mallinfo2();
+
+        Some(Self {
+            arena_bytes: info.arena as u64,
+            mmap_bytes: info.hblkhd as u64,
+            mmap_chunks: info.hblks as u64,
+            in_use_bytes: info.uordblks as u64,
+            free_bytes: info.fordblks as u64,
+            free_chunks: info.ordblks as u64,
+            releasable_bytes: info.keepcost as u64,
+        })
+    }
+}
+
+/// Approximate sizes of the beacon node's in-memory caches.
+///
+/// These are lower bounds computed from the SSZ size of the cached objects. They exclude
+/// secondary caches (e.g., tree hash caches) and collection overheads. A `None` value indicates
+/// that the cache was busy and could not be inspected.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheMemoryEstimates {
+    /// Size of the states and blocks in the snapshot (state) cache.
+    pub snapshot_cache_bytes: Option<u64>,
+    /// Size of the validator public key cache.
+    pub validator_pubkey_cache_bytes: Option<u64>,
+    /// Size of the objects held in the operation pool.
+    pub op_pool_bytes: u64,
+    /// Size of the states held in the state provider cache of recently loaded states.
+    pub state_provider_bytes: u64,
 }
 
 impl Health {
@@ -142,10 +273,85 @@ impl Health {
             sys_loadavg_1: loadavg.one,
             sys_loadavg_5: loadavg.five,
             sys_loadavg_15: loadavg.fifteen,
+            allocator: AllocatorStats::observe(),
+            cache_memory: None,
+            system: SystemHealth::observe().ok(),
         })
     }
 }
 
+/// A gossip object which was rejected, as returned by `lighthouse/quarantine`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedObject {
+    /// Increments for each object quarantined, and is not reused across restarts.
+    pub id: u64,
+    /// The slot at which the object was rejected.
+    pub slot: Slot,
+    /// The type of the object, e.g., `block`, `attestation` or `aggregate`.
+    pub kind: String,
+    /// The peer from which the object was received.
+    pub source: String,
+    pub reason: String,
+    /// Seconds since the UNIX epoch.
+    pub received_at: u64,
+    /// The SSZ bytes of the object.
+    #[serde(with = "serde_utils::hex_vec")]
+    pub ssz: Vec<u8>,
+}
+
+/// The timings of a block proposal made via this beacon node, as returned by
+/// `lighthouse/proposal_timings`.
+///
+/// Each timing is the number of milliseconds after the start of `slot` at which the event
+/// occurred, or `None` if it did not occur (e.g., the signed block was never published).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProposalTiming {
+    pub slot: Slot,
+    /// The root of the most recently produced or published block for `slot`.
+    pub block_root: Option<Hash256>,
+    /// The validator client requested an unsigned block.
+    pub block_requested_ms: Option<u64>,
+    /// The unsigned block was produced and returned to the validator client.
+    pub block_produced_ms: Option<u64>,
+    /// The signed block was received from the validator client. The time since `block_produced_ms`
+    /// includes signing and the round-trip to the validator client.
+    pub block_received_ms: Option<u64>,
+    /// The signed block was handed to the network for gossip publication.
+    pub block_published_ms: Option<u64>,
+    /// The signed block was imported into the beacon chain.
+    pub block_imported_ms: Option<u64>,
+}
+
+/// An attestation which was included in a block, as returned by
+/// `lighthouse/beacon/blocks/{block_id}/attestations`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+#[serde(bound = "T: EthSpec")]
+pub struct IncludedAttestation<T: EthSpec> {
+    /// The root of the block which included the attestation.
+    pub block_root: Hash256,
+    /// The slot of the block which included the attestation.
+    pub block_slot: Slot,
+    pub attestation: IndexedAttestation<T>,
+}
+
+/// The log directives of a Lighthouse process, as used by `lighthouse/logging`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogDirectivesData {
+    /// A default level and per-module levels, e.g. `info,network=debug`.
+    pub directives: String,
+}
+
+/// A copy of the database taken by `lighthouse/database/snapshot`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseSnapshot {
+    /// The directory containing the `chain_db` and `freezer_db` copies.
+    pub path: PathBuf,
+    /// The number of keys copied from the hot database.
+    pub hot_keys: u64,
+    /// The number of keys copied from the freezer database.
+    pub cold_keys: u64,
+}
+
 /// Indicates how up-to-date the Eth1 caches are.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eth1SyncStatusData {
@@ -236,6 +442,78 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/syncing/chains`
+    pub async fn get_lighthouse_syncing_chains(
+        &self,
+    ) -> Result<GenericResponse<Vec<SyncChainStatus>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("syncing")
+            .push("chains");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/clients`
+    pub async fn get_lighthouse_peers_clients(
+        &self,
+    ) -> Result<GenericResponse<Vec<ClientPeers>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("clients");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/quarantine`
+    pub async fn get_lighthouse_quarantine(
+        &self,
+    ) -> Result<GenericResponse<Vec<QuarantinedObject>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("quarantine");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/proposal_timings`
+    pub async fn get_lighthouse_proposal_timings(
+        &self,
+    ) -> Result<GenericResponse<Vec<ProposalTiming>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proposal_timings");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/chain_split`
+    pub async fn get_lighthouse_chain_split(
+        &self,
+    ) -> Result<GenericResponse<ChainSplitStatus>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("chain_split");
+
+        self.get(path).await
+    }
+
     /*
      * Note:
      *
@@ -256,6 +534,24 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/beacon/headers/{slot}/observed`
+    pub async fn get_lighthouse_observed_block_headers(
+        &self,
+        slot: Slot,
+    ) -> Result<GenericResponse<Vec<SignedBeaconBlockHeader>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("headers")
+            .push(&slot.to_string())
+            .push("observed");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -357,6 +653,97 @@ impl BeaconNodeHttpClient {
             .transpose()
     }
 
+    /// `GET lighthouse/debug/archive`
+    ///
+    /// Returns the SSZ-encoded debug archive, which can be decoded with
+    /// `beacon_chain::DebugArchive::from_bytes`.
+    pub async fn get_lighthouse_debug_archive(&self) -> Result<Option<Vec<u8>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("debug")
+            .push("archive");
+
+        self.get_bytes_opt(path).await
+    }
+
+    /// `GET lighthouse/beacon/blocks/{block_id}/attestations`
+    ///
+    /// Returns `Ok(None)` on a 404 error, which includes the case where attestation indexing is
+    /// disabled.
+    pub async fn get_lighthouse_beacon_blocks_attestations<E: EthSpec>(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<GenericResponse<Vec<IncludedAttestation<E>>>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("blocks")
+            .push(&block_id.to_string())
+            .push("attestations");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/logging`
+    pub async fn get_lighthouse_logging(
+        &self,
+    ) -> Result<GenericResponse<LogDirectivesData>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/logging`
+    ///
+    /// Replaces the log directives of the beacon node.
+    pub async fn post_lighthouse_logging(&self, directives: &str) -> Result<(), Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.post(
+            path,
+            &LogDirectivesData {
+                directives: directives.to_string(),
+            },
+        )
+        .await
+    }
+
+    /// `POST lighthouse/database/snapshot`
+    ///
+    /// Copies the database whilst the node is running. Copying a large database may take several
+    /// minutes, so a `timeout` must be provided.
+    pub async fn post_lighthouse_database_snapshot(
+        &self,
+        timeout: Duration,
+    ) -> Result<GenericResponse<DatabaseSnapshot>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("snapshot");
+
+        self.post_with_timeout_and_response(path, &(), timeout)
+            .await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.clone();
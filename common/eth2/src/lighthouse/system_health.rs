@@ -0,0 +1,248 @@
+//! Process and host resource usage (CPU, file descriptors, disk and network) which is not covered
+//! by `Health`, so that minimal installs can be monitored without a separate `node_exporter`.
+//!
+//! All counters are cumulative since process start (for process values) or boot (for host
+//! values); rates should be computed by the consumer (e.g., with Prometheus' `rate`).
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Resource usage of the Lighthouse process and the host it runs on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SystemHealth {
+    /// CPU time (user and system) consumed by this process, in seconds.
+    pub pid_cpu_seconds_total: f64,
+    /// The number of file descriptors held open by this process.
+    pub pid_open_fds: u64,
+    /// Bytes read from storage by this process.
+    pub pid_disk_read_bytes_total: u64,
+    /// Bytes written to storage by this process.
+    pub pid_disk_write_bytes_total: u64,
+    /// The number of logical CPUs on the host.
+    pub sys_cpu_count: u64,
+    /// CPU time spent in any state other than idle or waiting for I/O, summed across all CPUs.
+    pub sys_cpu_busy_seconds_total: f64,
+    /// CPU time spent in all states, summed across all CPUs.
+    pub sys_cpu_seconds_total: f64,
+    /// Bytes received on all interfaces other than loopback.
+    pub sys_network_received_bytes_total: u64,
+    /// Bytes sent on all interfaces other than loopback.
+    pub sys_network_sent_bytes_total: u64,
+    /// Usage of the filesystem containing the data directory, if it is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<DiskHealth>,
+}
+
+/// Usage of a single filesystem.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiskHealth {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub used_bytes: u64,
+}
+
+impl DiskHealth {
+    #[cfg(not(target_os = "linux"))]
+    pub fn observe(_path: &Path) -> Result<Self, String> {
+        Err("Disk health is only available on Linux".into())
+    }
+
+    /// Observe the usage of the filesystem which contains `path`.
+    #[cfg(target_os = "linux")]
+    pub fn observe(path: &Path) -> Result<Self, String> {
+        let usage = psutil::disk::disk_usage(path)
+            .map_err(|e| format!("Unable to get disk usage: {:?}", e))?;
+
+        Ok(Self {
+            total_bytes: usage.total(),
+            free_bytes: usage.free(),
+            used_bytes: usage.used(),
+        })
+    }
+}
+
+impl SystemHealth {
+    #[cfg(not(target_os = "linux"))]
+    pub fn observe() -> Result<Self, String> {
+        Err("System health is only available on Linux".into())
+    }
+
+    /// Observe the current resource usage. The `disk` field is left empty, since the data
+    /// directory is only known to the caller.
+    #[cfg(target_os = "linux")]
+    pub fn observe() -> Result<Self, String> {
+        use procinfo::pid;
+        use std::fs;
+
+        let read = |path: &str| {
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {:?}", path, e))
+        };
+
+        // Safe since `sysconf` has no preconditions.
+        let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks_per_second <= 0 {
+            return Err("Unable to determine clock ticks per second".into());
+        }
+        let ticks_per_second = ticks_per_second as f64;
+
+        let stat = pid::stat_self().map_err(|e| format!("Unable to get stat: {:?}", e))?;
+        let pid_open_fds = fs::read_dir("/proc/self/fd")
+            .map_err(|e| format!("Unable to read open fds: {:?}", e))?
+            .count() as u64;
+        let (pid_disk_read_bytes_total, pid_disk_write_bytes_total) =
+            parse_process_io(&read("/proc/self/io")?)?;
+        let cpu = parse_cpu_ticks(&read("/proc/stat")?)?;
+        let (sys_network_received_bytes_total, sys_network_sent_bytes_total) =
+            parse_network_bytes(&read("/proc/net/dev")?)?;
+
+        Ok(Self {
+            pid_cpu_seconds_total: (stat.utime + stat.stime) as f64 / ticks_per_second,
+            pid_open_fds,
+            pid_disk_read_bytes_total,
+            pid_disk_write_bytes_total,
+            sys_cpu_count: cpu.count,
+            sys_cpu_busy_seconds_total: cpu.busy as f64 / ticks_per_second,
+            sys_cpu_seconds_total: cpu.total as f64 / ticks_per_second,
+            sys_network_received_bytes_total,
+            sys_network_sent_bytes_total,
+            disk: None,
+        })
+    }
+}
+
+/// Aggregate CPU times from `/proc/stat`, in clock ticks.
+#[cfg(any(target_os = "linux", test))]
+#[derive(Debug, PartialEq)]
+struct CpuTicks {
+    count: u64,
+    busy: u64,
+    total: u64,
+}
+
+/// Parses the aggregate `cpu` line and counts the per-CPU lines of `/proc/stat`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_ticks(proc_stat: &str) -> Result<CpuTicks, String> {
+    let mut aggregate = None;
+    let mut count = 0;
+
+    for line in proc_stat.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("cpu") => {
+                let ticks = fields
+                    .map(|field| field.parse::<u64>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Invalid cpu line in /proc/stat: {:?}", e))?;
+                aggregate = Some(ticks);
+            }
+            Some(name) if name.starts_with("cpu") => count += 1,
+            _ => (),
+        }
+    }
+
+    let ticks = aggregate.ok_or("No cpu line in /proc/stat")?;
+    // The first eight fields are user, nice, system, idle, iowait, irq, softirq and steal. Any
+    // later fields (guest time) are already included in user and nice.
+    let total = ticks.iter().take(8).sum::<u64>();
+    let idle = ticks.get(3).copied().unwrap_or(0) + ticks.get(4).copied().unwrap_or(0);
+
+    Ok(CpuTicks {
+        count,
+        busy: total.saturating_sub(idle),
+        total,
+    })
+}
+
+/// Returns the `read_bytes` and `write_bytes` values from `/proc/self/io`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_process_io(proc_io: &str) -> Result<(u64, u64), String> {
+    let field = |name: &str| {
+        proc_io
+            .lines()
+            .filter_map(|line| line.strip_prefix(name))
+            .filter_map(|rest| rest.strip_prefix(':'))
+            .map(|value| value.trim().parse::<u64>().map_err(|e| format!("{:?}", e)))
+            .next()
+            .unwrap_or_else(|| Err(format!("No {} in /proc/self/io", name)))
+    };
+
+    Ok((field("read_bytes")?, field("write_bytes")?))
+}
+
+/// Returns the bytes received and sent on all non-loopback interfaces from `/proc/net/dev`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_network_bytes(proc_net_dev: &str) -> Result<(u64, u64), String> {
+    let mut received = 0;
+    let mut sent = 0;
+
+    // The first two lines are headers.
+    for line in proc_net_dev.lines().skip(2) {
+        let (interface, counters) = match line.find(':') {
+            Some(i) => (line[..i].trim(), &line[i + 1..]),
+            None => continue,
+        };
+        if interface == "lo" {
+            continue;
+        }
+
+        // Eight receive counters precede the transmit counters, each starting with bytes.
+        let counters = counters
+            .split_whitespace()
+            .map(|field| field.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid line in /proc/net/dev: {:?}", e))?;
+        received += counters.get(0).copied().unwrap_or(0);
+        sent += counters.get(8).copied().unwrap_or(0);
+    }
+
+    Ok((received, sent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_ticks() {
+        let proc_stat = "cpu  100 20 30 400 50 6 7 8 9 10\n\
+                         cpu0 50 10 15 200 25 3 3 4 4 5\n\
+                         cpu1 50 10 15 200 25 3 4 4 5 5\n\
+                         intr 12345 0 0\n\
+                         ctxt 6789\n";
+
+        assert_eq!(
+            parse_cpu_ticks(proc_stat).unwrap(),
+            CpuTicks {
+                count: 2,
+                busy: 171,
+                total: 621,
+            }
+        );
+        assert!(parse_cpu_ticks("intr 1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn process_io() {
+        let proc_io = "rchar: 1000\n\
+                       wchar: 2000\n\
+                       syscr: 10\n\
+                       syscw: 20\n\
+                       read_bytes: 4096\n\
+                       write_bytes: 8192\n\
+                       cancelled_write_bytes: 0\n";
+
+        assert_eq!(parse_process_io(proc_io).unwrap(), (4096, 8192));
+        assert!(parse_process_io("rchar: 1000\n").is_err());
+    }
+
+    #[test]
+    fn network_bytes() {
+        let proc_net_dev = "Inter-|   Receive                                                |  Transmit\n \
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+             lo: 5000      50    0    0    0     0          0         0     5000      50    0    0    0     0       0          0\n  \
+             eth0: 1000      10    0    0    0     0          0         0      300       3    0    0    0     0       0          0\n  \
+             eth1: 24      1    0    0    0     0          0         0      76       1    0    0    0     0       0          0\n";
+
+        assert_eq!(parse_network_bytes(proc_net_dev).unwrap(), (1024, 376));
+    }
+}
@@ -1,6 +1,10 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod log_filter;
+
+pub use log_filter::{parse_level, LogDirectives, LogFilter, LogFilterHandle};
+
 use lighthouse_metrics::{
     inc_counter, try_create_int_counter, IntCounter, Result as MetricsResult,
 };
@@ -0,0 +1,244 @@
+//! Provides `LogFilter`, a `slog` drain which filters records by level on a per-module basis,
+//! according to directives which may be changed at runtime.
+
+use parking_lot::RwLock;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Parses a level as accepted by the `--debug-level` flag.
+pub fn parse_level(level: &str) -> Result<Level, String> {
+    match level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown debug-level: {}", unknown)),
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+        Level::Warning => "warn",
+        Level::Error => "error",
+        Level::Critical => "crit",
+    }
+}
+
+/// A default level and a list of per-module levels, e.g. `info,network=debug`.
+///
+/// Modules are Rust module paths (e.g., `network` or `beacon_chain::attestation_verification`).
+/// A directive applies to its module and all sub-modules, and the most specific directive wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogDirectives {
+    pub default: Level,
+    pub modules: Vec<(String, Level)>,
+}
+
+impl LogDirectives {
+    pub fn new(default: Level) -> Self {
+        Self {
+            default,
+            modules: vec![],
+        }
+    }
+
+    /// Returns the level which applies to records from `module`.
+    pub fn level_for(&self, module: &str) -> Level {
+        self.modules
+            .iter()
+            .filter(|(prefix, _)| {
+                module.starts_with(prefix.as_str())
+                    && (module.len() == prefix.len() || module[prefix.len()..].starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// Applies the `other` directives on top of `self`, replacing the default level if `other`
+    /// specifies one.
+    pub fn merge(&mut self, other: &str) -> Result<(), String> {
+        for directive in other.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.find('=') {
+                Some(i) => {
+                    let module = directive[..i].trim();
+                    let level = parse_level(directive[i + 1..].trim())?;
+                    if module.is_empty() {
+                        return Err(format!("Log directive has no module: {}", directive));
+                    }
+                    self.modules.retain(|(existing, _)| existing != module);
+                    self.modules.push((module.to_string(), level));
+                }
+                None => self.default = parse_level(directive)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LogDirectives {
+    type Err = String;
+
+    /// Parses directives of the form `<level>,<module>=<level>,...`. The default level is `info`
+    /// if it is not specified.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut directives = Self::new(Level::Info);
+        directives.merge(s)?;
+        Ok(directives)
+    }
+}
+
+impl fmt::Display for LogDirectives {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", level_name(self.default))?;
+        for (module, level) in &self.modules {
+            write!(f, ",{}={}", module, level_name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+/// A shared handle to the directives of a `LogFilter`, used to change them at runtime.
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    directives: Arc<RwLock<LogDirectives>>,
+}
+
+impl LogFilterHandle {
+    pub fn directives(&self) -> LogDirectives {
+        self.directives.read().clone()
+    }
+
+    pub fn set_directives(&self, directives: LogDirectives) {
+        *self.directives.write() = directives;
+    }
+
+    /// Applies the `other` directives on top of the current directives (see
+    /// `LogDirectives::merge`), returning the result. The current directives are left unchanged if
+    /// `other` is invalid.
+    pub fn merge_directives(&self, other: &str) -> Result<LogDirectives, String> {
+        let mut directives = self.directives.write();
+        let mut merged = directives.clone();
+        merged.merge(other)?;
+        *directives = merged.clone();
+        Ok(merged)
+    }
+}
+
+/// See the module-level documentation.
+pub struct LogFilter<D> {
+    drain: D,
+    handle: LogFilterHandle,
+}
+
+impl<D: Drain> LogFilter<D> {
+    pub fn new(drain: D, directives: LogDirectives) -> Self {
+        Self {
+            drain,
+            handle: LogFilterHandle {
+                directives: Arc::new(RwLock::new(directives)),
+            },
+        }
+    }
+
+    /// Create a filter which shares its directives with another filter, so that changes via
+    /// `handle` apply to both.
+    pub fn with_handle(drain: D, handle: LogFilterHandle) -> Self {
+        Self { drain, handle }
+    }
+
+    pub fn handle(&self) -> LogFilterHandle {
+        self.handle.clone()
+    }
+}
+
+impl<D: Drain> Drain for LogFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        let level = self.handle.directives.read().level_for(record.module());
+        if record.level().is_at_least(level) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let directives = "debug, network=trace,beacon_chain::fork_choice=warn"
+            .parse::<LogDirectives>()
+            .unwrap();
+
+        assert_eq!(directives.default, Level::Debug);
+        assert_eq!(
+            directives.to_string(),
+            "debug,network=trace,beacon_chain::fork_choice=warn"
+        );
+
+        let directives = "network=debug".parse::<LogDirectives>().unwrap();
+        assert_eq!(directives.default, Level::Info);
+        assert_eq!(
+            directives.modules,
+            vec![("network".to_string(), Level::Debug)]
+        );
+
+        assert!("network=loud".parse::<LogDirectives>().is_err());
+        assert!("=debug".parse::<LogDirectives>().is_err());
+    }
+
+    #[test]
+    fn most_specific_module_wins() {
+        let directives = "warn,beacon_chain=debug,beacon_chain::fork_choice=error"
+            .parse::<LogDirectives>()
+            .unwrap();
+
+        assert_eq!(directives.level_for("network::sync"), Level::Warning);
+        assert_eq!(directives.level_for("beacon_chain"), Level::Debug);
+        assert_eq!(
+            directives.level_for("beacon_chain::attestation_verification"),
+            Level::Debug
+        );
+        assert_eq!(
+            directives.level_for("beacon_chain::fork_choice::store"),
+            Level::Error
+        );
+        // A directive does not apply to modules which merely share a prefix.
+        assert_eq!(directives.level_for("beacon_chain_extra"), Level::Warning);
+    }
+
+    #[test]
+    fn handle_merge_is_all_or_nothing() {
+        let filter = LogFilter::new(slog::Discard, "info,network=debug".parse().unwrap());
+        let handle = filter.handle();
+
+        assert!(handle
+            .merge_directives("network=trace,http_api=loud")
+            .is_err());
+        assert_eq!(handle.directives().to_string(), "info,network=debug");
+
+        let merged = handle.merge_directives("http_api=warn").unwrap();
+        assert_eq!(merged.to_string(), "info,network=debug,http_api=warn");
+        assert_eq!(handle.directives(), merged);
+    }
+
+    #[test]
+    fn merge_replaces_existing_modules() {
+        let mut directives = "info,network=debug".parse::<LogDirectives>().unwrap();
+        directives.merge("network=trace,http_api=warn").unwrap();
+
+        assert_eq!(directives.to_string(), "info,network=trace,http_api=warn");
+    }
+}
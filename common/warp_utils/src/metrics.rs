@@ -1,5 +1,6 @@
-use eth2::lighthouse::Health;
+use eth2::lighthouse::{DiskHealth, Health};
 use lighthouse_metrics::*;
+use std::path::Path;
 
 lazy_static::lazy_static! {
     pub static ref PROCESS_NUM_THREADS: Result<IntGauge> = try_create_int_gauge(
@@ -34,6 +35,80 @@ lazy_static::lazy_static! {
         try_create_float_gauge("system_loadavg_5", "Loadavg over 5 minutes");
     pub static ref SYSTEM_LOADAVG_15: Result<Gauge> =
         try_create_float_gauge("system_loadavg_15", "Loadavg over 15 minutes");
+    pub static ref ALLOCATOR_ARENA_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "allocator_arena_bytes",
+        "Bytes allocated by the heap allocator in its arenas"
+    );
+    pub static ref ALLOCATOR_MMAP_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "allocator_mmap_bytes",
+        "Bytes allocated by the heap allocator with mmap"
+    );
+    pub static ref ALLOCATOR_MMAP_CHUNKS: Result<IntGauge> = try_create_int_gauge(
+        "allocator_mmap_chunks",
+        "Number of chunks allocated by the heap allocator with mmap"
+    );
+    pub static ref ALLOCATOR_IN_USE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "allocator_in_use_bytes",
+        "Bytes in use by heap allocations"
+    );
+    pub static ref ALLOCATOR_FREE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "allocator_free_bytes",
+        "Bytes in free chunks retained by the heap allocator"
+    );
+    pub static ref ALLOCATOR_FREE_CHUNKS: Result<IntGauge> = try_create_int_gauge(
+        "allocator_free_chunks",
+        "Number of free chunks retained by the heap allocator"
+    );
+    pub static ref ALLOCATOR_RELEASABLE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "allocator_releasable_bytes",
+        "Bytes at the top of the heap which could be released to the OS"
+    );
+    pub static ref PROCESS_CPU_SECONDS: Result<Counter> = try_create_float_counter(
+        "process_cpu_seconds_total",
+        "Total user and system CPU time spent by the current process in seconds"
+    );
+    pub static ref PROCESS_OPEN_FDS: Result<IntGauge> = try_create_int_gauge(
+        "process_open_fds",
+        "Number of open file descriptors held by the current process"
+    );
+    pub static ref PROCESS_DISK_READ_BYTES: Result<IntCounter> = try_create_int_counter(
+        "process_disk_read_bytes_total",
+        "Bytes read from storage by the current process"
+    );
+    pub static ref PROCESS_DISK_WRITE_BYTES: Result<IntCounter> = try_create_int_counter(
+        "process_disk_write_bytes_total",
+        "Bytes written to storage by the current process"
+    );
+    pub static ref SYSTEM_CPU_COUNT: Result<IntGauge> =
+        try_create_int_gauge("system_cpu_count", "Number of logical CPUs");
+    pub static ref SYSTEM_CPU_BUSY_SECONDS: Result<Counter> = try_create_float_counter(
+        "system_cpu_busy_seconds_total",
+        "Total non-idle CPU time across all CPUs in seconds"
+    );
+    pub static ref SYSTEM_CPU_SECONDS: Result<Counter> = try_create_float_counter(
+        "system_cpu_seconds_total",
+        "Total CPU time across all CPUs in seconds"
+    );
+    pub static ref SYSTEM_NETWORK_RECEIVED_BYTES: Result<IntCounter> = try_create_int_counter(
+        "system_network_received_bytes_total",
+        "Bytes received on all non-loopback network interfaces"
+    );
+    pub static ref SYSTEM_NETWORK_SENT_BYTES: Result<IntCounter> = try_create_int_counter(
+        "system_network_sent_bytes_total",
+        "Bytes sent on all non-loopback network interfaces"
+    );
+    pub static ref SYSTEM_DISK_TOTAL_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "system_disk_total_bytes",
+        "Size of the filesystem containing the data directory"
+    );
+    pub static ref SYSTEM_DISK_FREE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "system_disk_free_bytes",
+        "Free space on the filesystem containing the data directory"
+    );
+    pub static ref SYSTEM_DISK_USED_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "system_disk_used_bytes",
+        "Used space on the filesystem containing the data directory"
+    );
 }
 
 pub fn scrape_health_metrics() {
@@ -57,5 +132,46 @@ pub fn scrape_health_metrics() {
         set_float_gauge(&SYSTEM_LOADAVG_1, health.sys_loadavg_1);
         set_float_gauge(&SYSTEM_LOADAVG_5, health.sys_loadavg_5);
         set_float_gauge(&SYSTEM_LOADAVG_15, health.sys_loadavg_15);
+
+        if let Some(allocator) = health.allocator {
+            set_gauge(&ALLOCATOR_ARENA_BYTES, allocator.arena_bytes as i64);
+            set_gauge(&ALLOCATOR_MMAP_BYTES, allocator.mmap_bytes as i64);
+            set_gauge(&ALLOCATOR_MMAP_CHUNKS, allocator.mmap_chunks as i64);
+            set_gauge(&ALLOCATOR_IN_USE_BYTES, allocator.in_use_bytes as i64);
+            set_gauge(&ALLOCATOR_FREE_BYTES, allocator.free_bytes as i64);
+            set_gauge(&ALLOCATOR_FREE_CHUNKS, allocator.free_chunks as i64);
+            set_gauge(
+                &ALLOCATOR_RELEASABLE_BYTES,
+                allocator.releasable_bytes as i64,
+            );
+        }
+
+        if let Some(system) = health.system {
+            inc_float_counter_to(&PROCESS_CPU_SECONDS, system.pid_cpu_seconds_total);
+            set_gauge(&PROCESS_OPEN_FDS, system.pid_open_fds as i64);
+            inc_counter_to(&PROCESS_DISK_READ_BYTES, system.pid_disk_read_bytes_total);
+            inc_counter_to(&PROCESS_DISK_WRITE_BYTES, system.pid_disk_write_bytes_total);
+            set_gauge(&SYSTEM_CPU_COUNT, system.sys_cpu_count as i64);
+            inc_float_counter_to(&SYSTEM_CPU_BUSY_SECONDS, system.sys_cpu_busy_seconds_total);
+            inc_float_counter_to(&SYSTEM_CPU_SECONDS, system.sys_cpu_seconds_total);
+            inc_counter_to(
+                &SYSTEM_NETWORK_RECEIVED_BYTES,
+                system.sys_network_received_bytes_total,
+            );
+            inc_counter_to(
+                &SYSTEM_NETWORK_SENT_BYTES,
+                system.sys_network_sent_bytes_total,
+            );
+        }
+    }
+}
+
+/// Updates the disk usage metrics for the filesystem containing `data_dir`.
+pub fn scrape_disk_metrics(data_dir: &Path) {
+    // As above, this will silently fail on unsupported platforms.
+    if let Ok(disk) = DiskHealth::observe(data_dir) {
+        set_gauge(&SYSTEM_DISK_TOTAL_BYTES, disk.total_bytes as i64);
+        set_gauge(&SYSTEM_DISK_FREE_BYTES, disk.free_bytes as i64);
+        set_gauge(&SYSTEM_DISK_USED_BYTES, disk.used_bytes as i64);
     }
 }
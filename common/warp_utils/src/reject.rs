@@ -91,6 +91,15 @@ pub fn custom_server_error(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(CustomServerError(msg))
 }
 
+#[derive(Debug)]
+pub struct CustomConflict(pub String);
+
+impl Reject for CustomConflict {}
+
+pub fn custom_conflict(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CustomConflict(msg))
+}
+
 #[derive(Debug)]
 pub struct BroadcastWithoutImport(pub String);
 
@@ -100,6 +109,15 @@ pub fn broadcast_without_import(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(BroadcastWithoutImport(msg))
 }
 
+#[derive(Debug)]
+pub struct ProposalEquivocation(pub String);
+
+impl Reject for ProposalEquivocation {}
+
+pub fn proposal_equivocation(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(ProposalEquivocation(msg))
+}
+
 #[derive(Debug)]
 pub struct ObjectInvalid(pub String);
 
@@ -188,6 +206,9 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::CustomServerError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("INTERNAL_SERVER_ERROR: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::CustomConflict>() {
+        code = StatusCode::CONFLICT;
+        message = format!("CONFLICT: {}", e.0);
     } else if let Some(e) = err.find::<crate::reject::BroadcastWithoutImport>() {
         code = StatusCode::ACCEPTED;
         message = format!(
@@ -195,6 +216,13 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
             fully imported to the local database: {}",
             e.0
         );
+    } else if let Some(e) = err.find::<crate::reject::ProposalEquivocation>() {
+        code = StatusCode::BAD_REQUEST;
+        message = format!(
+            "BAD_REQUEST: refusing to publish a block which equivocates with an observed \
+            block: {}",
+            e.0
+        );
     } else if let Some(e) = err.find::<crate::reject::ObjectInvalid>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: Invalid object: {}", e.0);
@@ -857,6 +857,13 @@ pub struct PersistedForkChoice {
     queued_attestations: Vec<QueuedAttestation>,
 }
 
+impl PersistedForkChoice {
+    /// Decodes the persisted `ProtoArrayForkChoice`.
+    pub fn proto_array(&self) -> Result<ProtoArrayForkChoice, String> {
+        ProtoArrayForkChoice::from_bytes(&self.proto_array_bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use types::{EthSpec, MainnetEthSpec};
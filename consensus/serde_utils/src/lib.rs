@@ -2,6 +2,7 @@ mod quoted_int;
 
 pub mod bytes_4_hex;
 pub mod hex;
+pub mod hex_vec;
 pub mod quoted_u64_vec;
 pub mod u32_hex;
 pub mod u8_hex;
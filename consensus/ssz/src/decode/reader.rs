@@ -0,0 +1,248 @@
+//! Decoding of SSZ containers from an `io::Read`.
+//!
+//! Unlike `SszDecoderBuilder`, the full encoding is never held in memory. The fixed-length
+//! portion of the container is read first and its offsets are validated, then each
+//! variable-length field is read and decoded in turn, so at most one field's bytes are buffered at
+//! a time. This keeps the memory overhead of decoding large containers (e.g., `BeaconState`) close
+//! to the size of their largest field.
+use super::*;
+use smallvec::smallvec;
+use std::io::{self, Read};
+
+/// Returned when reading SSZ from an `io::Read` fails.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The underlying reader returned an error, or ended before the container was complete.
+    Io(io::Error),
+    /// The bytes read could not be decoded.
+    Decode(DecodeError),
+    /// The encoding is longer than the `max_len` given to the `SszReaderBuilder`.
+    MaxLengthExceeded { max_len: usize },
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<DecodeError> for ReadError {
+    fn from(e: DecodeError) -> Self {
+        ReadError::Decode(e)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Item {
+    /// A fixed-length item, stored in the fixed-length portion at `start..end`.
+    Fixed { start: usize, end: usize },
+    /// A variable-length item, with an offset stored in the fixed-length portion at `position`.
+    ///
+    /// `len` is known once the offsets are read, except for the final variable-length item which
+    /// extends to the end of the reader.
+    Variable { position: usize, len: Option<usize> },
+}
+
+/// Builds an `SszReader`.
+///
+/// Types are registered in the same way as with `SszDecoderBuilder`. Calling `build` reads the
+/// fixed-length portion of the container and validates its offsets.
+///
+/// ## Example
+///
+/// ```rust
+/// use ssz_derive::{Encode, Decode};
+/// use ssz::{Encode, SszReaderBuilder};
+///
+/// #[derive(PartialEq, Debug, Encode, Decode)]
+/// struct Foo {
+///     a: u64,
+///     b: Vec<u16>,
+/// }
+///
+/// fn ssz_reading_example() {
+///     let foo = Foo {
+///         a: 42,
+///         b: vec![1, 3, 3, 7]
+///     };
+///
+///     let bytes = foo.as_ssz_bytes();
+///
+///     let mut builder = SszReaderBuilder::new(bytes.as_slice(), 1_024);
+///
+///     builder.register_type::<u64>();
+///     builder.register_type::<Vec<u16>>();
+///
+///     let mut reader = builder.build().unwrap();
+///
+///     let read_foo = Foo {
+///         a: reader.decode_next().unwrap(),
+///         b: reader.decode_next().unwrap(),
+///     };
+///     reader.finish().unwrap();
+///
+///     assert_eq!(foo, read_foo);
+/// }
+///
+/// ```
+pub struct SszReaderBuilder<R> {
+    reader: R,
+    max_len: usize,
+    items: SmallVec8<Item>,
+    fixed_len: usize,
+}
+
+impl<R: Read> SszReaderBuilder<R> {
+    /// Instantiate a new builder which will read from `reader`, refusing any encoding which is
+    /// longer than `max_len` bytes.
+    pub fn new(reader: R, max_len: usize) -> Self {
+        Self {
+            reader,
+            max_len,
+            items: smallvec![],
+            fixed_len: 0,
+        }
+    }
+
+    /// Declares that some type `T` is the next item in the container.
+    pub fn register_type<T: Decode>(&mut self) {
+        if T::is_ssz_fixed_len() {
+            let start = self.fixed_len;
+            self.fixed_len += T::ssz_fixed_len();
+            self.items.push(Item::Fixed {
+                start,
+                end: self.fixed_len,
+            });
+        } else {
+            self.items.push(Item::Variable {
+                position: self.fixed_len,
+                len: None,
+            });
+            self.fixed_len += BYTES_PER_LENGTH_OFFSET;
+        }
+    }
+
+    /// Reads the fixed-length portion of the container and validates its offsets, returning a
+    /// `SszReader` that may be used to instantiate objects.
+    pub fn build(mut self) -> Result<SszReader<R>, ReadError> {
+        if self.fixed_len > self.max_len {
+            return Err(ReadError::MaxLengthExceeded {
+                max_len: self.max_len,
+            });
+        }
+
+        let mut fixed_bytes = vec![0; self.fixed_len];
+        self.reader.read_exact(&mut fixed_bytes)?;
+
+        let variable_positions = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| match item {
+                Item::Variable { position, .. } => Some((i, *position)),
+                Item::Fixed { .. } => None,
+            })
+            .collect::<SmallVec8<_>>();
+
+        let mut previous: Option<(usize, usize)> = None;
+        for (i, position) in variable_positions {
+            let offset = sanitize_offset(
+                read_offset(&fixed_bytes[position..])?,
+                previous.map(|(_, offset)| offset),
+                self.max_len,
+                Some(self.fixed_len),
+            )?;
+
+            if let Some((previous_i, previous_offset)) = previous {
+                if let Item::Variable { len, .. } = &mut self.items[previous_i] {
+                    *len = Some(offset - previous_offset);
+                }
+            }
+            previous = Some((i, offset));
+        }
+
+        Ok(SszReader {
+            reader: self.reader,
+            max_len: self.max_len,
+            fixed_bytes,
+            items: self.items,
+            next: 0,
+            bytes_read: self.fixed_len,
+        })
+    }
+}
+
+/// Reads and decodes the items of an SSZ container from an `io::Read`. Should be instantiated
+/// using [`SszReaderBuilder`](struct.SszReaderBuilder.html).
+///
+/// Items must be decoded in the order they were registered. Once all items are decoded, `finish`
+/// should be called to ensure there are no excess bytes.
+pub struct SszReader<R> {
+    reader: R,
+    max_len: usize,
+    fixed_bytes: Vec<u8>,
+    items: SmallVec8<Item>,
+    next: usize,
+    bytes_read: usize,
+}
+
+impl<R: Read> SszReader<R> {
+    /// Decodes the next item.
+    ///
+    /// # Panics
+    ///
+    /// Panics when attempting to decode more items than were registered.
+    pub fn decode_next<T: Decode>(&mut self) -> Result<T, ReadError> {
+        let item = self.items[self.next];
+        self.next += 1;
+
+        match item {
+            Item::Fixed { start, end } => {
+                T::from_ssz_bytes(&self.fixed_bytes[start..end]).map_err(Into::into)
+            }
+            Item::Variable { len: Some(len), .. } => {
+                let mut bytes = vec![0; len];
+                self.reader.read_exact(&mut bytes)?;
+                self.bytes_read += len;
+                T::from_ssz_bytes(&bytes).map_err(Into::into)
+            }
+            Item::Variable { len: None, .. } => {
+                let bytes = self.read_to_end()?;
+                T::from_ssz_bytes(&bytes).map_err(Into::into)
+            }
+        }
+    }
+
+    /// Ensures that the reader contains no bytes beyond the end of the container.
+    pub fn finish(mut self) -> Result<(), ReadError> {
+        let excess = self.read_to_end()?;
+
+        if excess.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::InvalidByteLength {
+                len: self.bytes_read,
+                expected: self.bytes_read - excess.len(),
+            }
+            .into())
+        }
+    }
+
+    /// Reads all remaining bytes, returning an error rather than reading beyond `self.max_len`.
+    fn read_to_end(&mut self) -> Result<Vec<u8>, ReadError> {
+        let limit = self.max_len - self.bytes_read;
+        let mut bytes = vec![];
+        (&mut self.reader)
+            .take(limit as u64 + 1)
+            .read_to_end(&mut bytes)?;
+
+        if bytes.len() > limit {
+            return Err(ReadError::MaxLengthExceeded {
+                max_len: self.max_len,
+            });
+        }
+
+        self.bytes_read += bytes.len();
+        Ok(bytes)
+    }
+}
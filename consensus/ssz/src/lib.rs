@@ -38,7 +38,9 @@ mod decode;
 mod encode;
 
 pub use decode::{
-    impls::decode_list_of_variable_length_items, Decode, DecodeError, SszDecoder, SszDecoderBuilder,
+    impls::decode_list_of_variable_length_items,
+    reader::{ReadError, SszReader, SszReaderBuilder},
+    Decode, DecodeError, SszDecoder, SszDecoderBuilder,
 };
 pub use encode::{Encode, SszEncoder};
 
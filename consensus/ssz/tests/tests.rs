@@ -384,3 +384,141 @@ mod round_trip {
         round_trip(vec);
     }
 }
+
+mod reader {
+    use super::*;
+    use ssz::{ReadError, SszReaderBuilder};
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Container {
+        a: u16,
+        b: Vec<u16>,
+        c: u64,
+        d: Vec<Vec<u8>>,
+    }
+
+    fn read_container(bytes: &[u8], max_len: usize) -> Result<Container, ReadError> {
+        let mut builder = SszReaderBuilder::new(bytes, max_len);
+        builder.register_type::<u16>();
+        builder.register_type::<Vec<u16>>();
+        builder.register_type::<u64>();
+        builder.register_type::<Vec<Vec<u8>>>();
+
+        let mut reader = builder.build()?;
+        let container = Container {
+            a: reader.decode_next()?,
+            b: reader.decode_next()?,
+            c: reader.decode_next()?,
+            d: reader.decode_next()?,
+        };
+        reader.finish()?;
+
+        Ok(container)
+    }
+
+    fn container() -> Container {
+        Container {
+            a: 42,
+            b: vec![1, 3, 3, 7],
+            c: u64::max_value(),
+            d: vec![vec![], vec![0, 1, 2]],
+        }
+    }
+
+    #[test]
+    fn matches_from_ssz_bytes() {
+        let items = vec![
+            container(),
+            Container {
+                a: 0,
+                b: vec![],
+                c: 0,
+                d: vec![],
+            },
+        ];
+
+        for item in items {
+            let bytes = item.as_ssz_bytes();
+            assert_eq!(read_container(&bytes, bytes.len()).unwrap(), item);
+        }
+    }
+
+    #[test]
+    fn fixed_len_excess_bytes() {
+        let mut bytes = (42u16, 7u64).as_ssz_bytes();
+        bytes.push(0);
+
+        let mut builder = SszReaderBuilder::new(bytes.as_slice(), 1_024);
+        builder.register_type::<u16>();
+        builder.register_type::<u64>();
+        let mut reader = builder.build().unwrap();
+        assert_eq!(reader.decode_next::<u16>().unwrap(), 42);
+        assert_eq!(reader.decode_next::<u64>().unwrap(), 7);
+
+        match reader.finish() {
+            Err(ReadError::Decode(DecodeError::InvalidByteLength { len, expected })) => {
+                assert_eq!((len, expected), (11, 10));
+            }
+            other => panic!("expected excess bytes error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn max_length_exceeded() {
+        let bytes = container().as_ssz_bytes();
+
+        // The offsets are within the limit but the final item is not.
+        assert!(matches!(
+            read_container(&bytes, bytes.len() - 1),
+            Err(ReadError::MaxLengthExceeded { .. })
+        ));
+        // The fixed-length portion alone exceeds the limit.
+        assert!(matches!(
+            read_container(&bytes, 4),
+            Err(ReadError::MaxLengthExceeded { max_len: 4 })
+        ));
+    }
+
+    #[test]
+    fn offset_out_of_bounds() {
+        let bytes = container().as_ssz_bytes();
+
+        // The offset of `d` points beyond `max_len`.
+        assert!(matches!(
+            read_container(&bytes, 20),
+            Err(ReadError::Decode(DecodeError::OffsetOutOfBounds(_)))
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::zero_prefixed_literal)]
+    fn offsets_decreasing() {
+        let bytes = vec![
+            //  1   2   3   4   5   6   7   8   9   10  11  12  13  14  15
+            //      | offset        | offset        | offset        | variable
+            01, 00, 14, 00, 00, 00, 15, 00, 00, 00, 14, 00, 00, 00, 00, 00,
+        ];
+
+        let mut builder = SszReaderBuilder::new(bytes.as_slice(), bytes.len());
+        builder.register_type::<u16>();
+        builder.register_type::<Vec<u16>>();
+        builder.register_type::<Vec<u16>>();
+        builder.register_type::<Vec<u16>>();
+
+        assert!(matches!(
+            builder.build(),
+            Err(ReadError::Decode(DecodeError::OffsetsAreDecreasing(14)))
+        ));
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = container().as_ssz_bytes();
+        let truncated = &bytes[..bytes.len() - 6];
+
+        assert!(matches!(
+            read_container(truncated, bytes.len()),
+            Err(ReadError::Io(_)) | Err(ReadError::Decode(_))
+        ));
+    }
+}
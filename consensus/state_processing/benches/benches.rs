@@ -5,6 +5,9 @@ extern crate env_logger;
 use criterion::Criterion;
 use criterion::{black_box, criterion_group, criterion_main, Benchmark};
 use ssz::Encode;
+use state_processing::per_block_processing::{
+    signature_sets::get_pubkey_from_state, BlockSignatureVerifier,
+};
 use state_processing::{test_utils::BlockBuilder, BlockSignatureStrategy, VerifySignatures};
 use types::{
     BeaconState, ChainSpec, EthSpec, MainnetEthSpec, MinimalEthSpec, SignedBeaconBlock, Slot,
@@ -175,6 +178,35 @@ fn bench_block<T: EthSpec>(
         .sample_size(10),
     );
 
+    // Epoch processing is benchmarked against the post-state of the block so that the pending
+    // attestations it includes are processed.
+    let mut local_state = state.clone();
+    state_processing::per_block_processing::<T>(
+        &mut local_state,
+        &block,
+        None,
+        BlockSignatureStrategy::NoVerification,
+        &spec,
+    )
+    .expect("block processing should succeed");
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("per_epoch_processing", move |b| {
+            b.iter_batched_ref(
+                || (local_spec.clone(), local_state.clone()),
+                |(spec, ref mut state)| {
+                    black_box(
+                        state_processing::per_epoch_processing::<T>(state, &spec)
+                            .expect("epoch processing should succeed"),
+                    )
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+
     let local_block = block.clone();
     let local_state = state.clone();
     let local_spec = spec.clone();
@@ -275,6 +307,52 @@ fn bench_block<T: EthSpec>(
         .sample_size(10),
     );
 
+    // Verifies the signatures of all the attestations in the block, one at a time and as a batch,
+    // as is done for gossip attestations and for blocks respectively.
+    let local_block = block.clone();
+    let local_state = state.clone();
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("verify_attestations/individual", move |b| {
+            b.iter(|| {
+                for attestation in &local_block.message.body.attestations {
+                    black_box(
+                        state_processing::per_block_processing::verify_attestation_for_block_inclusion(
+                            &local_state,
+                            attestation,
+                            VerifySignatures::True,
+                            &local_spec,
+                        )
+                        .expect("should verify attestation"),
+                    );
+                }
+            })
+        })
+        .sample_size(10),
+    );
+
+    let local_block = block.clone();
+    let local_state = state.clone();
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("verify_attestations/batched", move |b| {
+            b.iter(|| {
+                let mut verifier = BlockSignatureVerifier::new(
+                    &local_state,
+                    |i| get_pubkey_from_state(&local_state, i),
+                    &local_spec,
+                );
+                verifier
+                    .include_attestations(&local_block)
+                    .expect("should include attestations");
+                black_box(verifier.verify().expect("should verify attestations"))
+            })
+        })
+        .sample_size(10),
+    );
+
     let local_block = block.clone();
     let local_state = state.clone();
     c.bench(
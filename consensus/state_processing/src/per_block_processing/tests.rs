@@ -144,6 +144,7 @@ fn valid_4_deposits() {
     let test_task = DepositTestTask::Valid;
 
     let (block, mut state) = builder.build_with_n_deposits(4, test_task, None, None, &spec);
+    let validator_count = state.validators.len();
 
     let result = per_block_processing(
         &mut state,
@@ -155,6 +156,8 @@ fn valid_4_deposits() {
 
     // Expecting Ok because these are valid deposits.
     assert_eq!(result, Ok(()));
+    // Each deposit should add a new validator.
+    assert_eq!(state.validators.len(), validator_count + 4);
 }
 
 #[test]
@@ -278,6 +281,8 @@ fn invalid_deposit_wrong_sig() {
 
     let (block, mut state) =
         builder.build_with_n_deposits(NUM_DEPOSITS, test_task, None, None, &spec);
+    let validator_count = state.validators.len();
+    let deposit_index = state.eth1_deposit_index;
 
     let result = per_block_processing(
         &mut state,
@@ -289,6 +294,9 @@ fn invalid_deposit_wrong_sig() {
 
     // Expecting Ok(()) even though the block signature does not correspond to the correct public key
     assert_eq!(result, Ok(()));
+    // The deposits should be consumed, but skipped rather than adding validators.
+    assert_eq!(state.eth1_deposit_index, deposit_index + NUM_DEPOSITS);
+    assert_eq!(state.validators.len(), validator_count);
 }
 
 #[test]
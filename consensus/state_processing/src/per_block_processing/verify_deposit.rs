@@ -51,6 +51,25 @@ pub fn verify_deposit_merkle_proof<T: EthSpec>(
     deposit: &Deposit,
     deposit_index: u64,
     spec: &ChainSpec,
+) -> Result<()> {
+    verify_deposit_merkle_proof_against_root(
+        deposit,
+        deposit_index,
+        state.eth1_data.deposit_root,
+        spec,
+    )
+}
+
+/// Verify that a deposit is included in the deposit tree with the given `deposit_root` (which
+/// includes the deposit count) at `deposit_index`.
+///
+/// Unlike `verify_deposit_merkle_proof` this does not require a `BeaconState`, so it can be used
+/// to check deposits before they are packed into a block.
+pub fn verify_deposit_merkle_proof_against_root(
+    deposit: &Deposit,
+    deposit_index: u64,
+    deposit_root: Hash256,
+    spec: &ChainSpec,
 ) -> Result<()> {
     let leaf = deposit.data.tree_hash_root();
 
@@ -60,7 +79,7 @@ pub fn verify_deposit_merkle_proof<T: EthSpec>(
             &deposit.proof[..],
             spec.deposit_contract_tree_depth.safe_add(1)? as usize,
             deposit_index as usize,
-            state.eth1_data.deposit_root,
+            deposit_root,
         ),
         DepositInvalid::BadMerkleProof
     );
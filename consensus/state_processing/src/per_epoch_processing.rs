@@ -35,6 +35,7 @@ pub fn per_epoch_processing<T: EthSpec>(
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
     state.build_committee_cache(RelativeEpoch::Current, spec)?;
     state.build_committee_cache(RelativeEpoch::Next, spec)?;
+    state.build_total_active_balance_cache(spec)?;
 
     // Load the struct we use to assign validators into sets based on their participation.
     //
@@ -43,7 +44,7 @@ pub fn per_epoch_processing<T: EthSpec>(
     validator_statuses.process_attestations(&state, spec)?;
 
     // Justification and finalization.
-    process_justification_and_finalization(state, &validator_statuses.total_balances)?;
+    process_justification_and_finalization(state, &validator_statuses.total_balances, spec)?;
 
     // Rewards and Penalties.
     process_rewards_and_penalties(state, &mut validator_statuses, spec)?;
@@ -52,11 +53,8 @@ pub fn per_epoch_processing<T: EthSpec>(
     process_registry_updates(state, spec)?;
 
     // Slashings.
-    process_slashings(
-        state,
-        validator_statuses.total_balances.current_epoch(),
-        spec,
-    )?;
+    let total_active_balance = state.get_total_active_balance(spec)?;
+    process_slashings(state, total_active_balance, spec)?;
 
     // Final updates.
     process_final_updates(state, spec)?;
@@ -85,11 +83,14 @@ pub fn per_epoch_processing<T: EthSpec>(
 pub fn process_justification_and_finalization<T: EthSpec>(
     state: &mut BeaconState<T>,
     total_balances: &TotalBalances,
+    spec: &ChainSpec,
 ) -> Result<(), Error> {
     if state.current_epoch() <= T::genesis_epoch().safe_add(1)? {
         return Ok(());
     }
 
+    let total_active_balance = state.get_total_active_balance(spec)?;
+
     let previous_epoch = state.previous_epoch();
     let current_epoch = state.current_epoch();
 
@@ -103,7 +104,7 @@ pub fn process_justification_and_finalization<T: EthSpec>(
     if total_balances
         .previous_epoch_target_attesters()
         .safe_mul(3)?
-        >= total_balances.current_epoch().safe_mul(2)?
+        >= total_active_balance.safe_mul(2)?
     {
         state.current_justified_checkpoint = Checkpoint {
             epoch: previous_epoch,
@@ -115,7 +116,7 @@ pub fn process_justification_and_finalization<T: EthSpec>(
     if total_balances
         .current_epoch_target_attesters()
         .safe_mul(3)?
-        >= total_balances.current_epoch().safe_mul(2)?
+        >= total_active_balance.safe_mul(2)?
     {
         state.current_justified_checkpoint = Checkpoint {
             epoch: current_epoch,
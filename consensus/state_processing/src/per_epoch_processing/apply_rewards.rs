@@ -79,6 +79,7 @@ fn get_attestation_deltas<T: EthSpec>(
     let mut deltas = vec![Delta::default(); state.validators.len()];
 
     let total_balances = &validator_statuses.total_balances;
+    let total_active_balance = state.get_total_active_balance(spec)?;
 
     // Filter out ineligible validators. All sub-functions of the spec do this except for
     // `get_inclusion_delay_deltas`. It's safe to do so here because any validator that is in the
@@ -89,14 +90,32 @@ fn get_attestation_deltas<T: EthSpec>(
         .enumerate()
         .filter(|(_, validator)| is_eligible_validator(validator))
     {
-        let base_reward = get_base_reward(state, index, total_balances.current_epoch(), spec)?;
-
-        let source_delta =
-            get_source_delta(validator, base_reward, total_balances, finality_delay, spec)?;
-        let target_delta =
-            get_target_delta(validator, base_reward, total_balances, finality_delay, spec)?;
-        let head_delta =
-            get_head_delta(validator, base_reward, total_balances, finality_delay, spec)?;
+        let base_reward = get_base_reward(state, index, total_active_balance, spec)?;
+
+        let source_delta = get_source_delta(
+            validator,
+            base_reward,
+            total_balances,
+            total_active_balance,
+            finality_delay,
+            spec,
+        )?;
+        let target_delta = get_target_delta(
+            validator,
+            base_reward,
+            total_balances,
+            total_active_balance,
+            finality_delay,
+            spec,
+        )?;
+        let head_delta = get_head_delta(
+            validator,
+            base_reward,
+            total_balances,
+            total_active_balance,
+            finality_delay,
+            spec,
+        )?;
         let (inclusion_delay_delta, proposer_delta) =
             get_inclusion_delay_delta(validator, base_reward, spec)?;
         let inactivity_penalty_delta =
@@ -123,15 +142,13 @@ fn get_attestation_deltas<T: EthSpec>(
 fn get_attestation_component_delta(
     index_in_unslashed_attesting_indices: bool,
     attesting_balance: u64,
-    total_balances: &TotalBalances,
+    total_active_balance: u64,
     base_reward: u64,
     finality_delay: u64,
     spec: &ChainSpec,
 ) -> Result<Delta, Error> {
     let mut delta = Delta::default();
 
-    let total_balance = total_balances.current_epoch();
-
     if index_in_unslashed_attesting_indices {
         if finality_delay > spec.min_epochs_to_inactivity_penalty {
             // Since full base reward will be canceled out by inactivity penalty deltas,
@@ -140,10 +157,10 @@ fn get_attestation_component_delta(
         } else {
             let reward_numerator = base_reward
                 .safe_mul(attesting_balance.safe_div(spec.effective_balance_increment)?)?;
-            delta.reward(
-                reward_numerator
-                    .safe_div(total_balance.safe_div(spec.effective_balance_increment)?)?,
-            )?;
+            delta
+                .reward(reward_numerator.safe_div(
+                    total_active_balance.safe_div(spec.effective_balance_increment)?,
+                )?)?;
         }
     } else {
         delta.penalize(base_reward)?;
@@ -156,13 +173,14 @@ fn get_source_delta(
     validator: &ValidatorStatus,
     base_reward: u64,
     total_balances: &TotalBalances,
+    total_active_balance: u64,
     finality_delay: u64,
     spec: &ChainSpec,
 ) -> Result<Delta, Error> {
     get_attestation_component_delta(
         validator.is_previous_epoch_attester && !validator.is_slashed,
         total_balances.previous_epoch_attesters(),
-        total_balances,
+        total_active_balance,
         base_reward,
         finality_delay,
         spec,
@@ -173,13 +191,14 @@ fn get_target_delta(
     validator: &ValidatorStatus,
     base_reward: u64,
     total_balances: &TotalBalances,
+    total_active_balance: u64,
     finality_delay: u64,
     spec: &ChainSpec,
 ) -> Result<Delta, Error> {
     get_attestation_component_delta(
         validator.is_previous_epoch_target_attester && !validator.is_slashed,
         total_balances.previous_epoch_target_attesters(),
-        total_balances,
+        total_active_balance,
         base_reward,
         finality_delay,
         spec,
@@ -190,13 +209,14 @@ fn get_head_delta(
     validator: &ValidatorStatus,
     base_reward: u64,
     total_balances: &TotalBalances,
+    total_active_balance: u64,
     finality_delay: u64,
     spec: &ChainSpec,
 ) -> Result<Delta, Error> {
     get_attestation_component_delta(
         validator.is_previous_epoch_head_attester && !validator.is_slashed,
         total_balances.previous_epoch_head_attesters(),
-        total_balances,
+        total_active_balance,
         base_reward,
         finality_delay,
         spec,
@@ -15,6 +15,7 @@ use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum::Unsigned, BitVector, FixedVector};
 use std::convert::TryInto;
 use std::fmt;
+use std::io::Read;
 use swap_or_not_shuffle::compute_shuffled_index;
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
@@ -221,6 +222,13 @@ where
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
     pub exit_cache: ExitCache,
+    /// The total active balance for the epoch it was computed in.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[ssz(skip_serializing)]
+    #[ssz(skip_deserializing)]
+    #[tree_hash(skip_hashing)]
+    #[test_random(default)]
+    pub total_active_balance: Option<(Epoch, u64)>,
     #[serde(skip_serializing, skip_deserializing)]
     #[ssz(skip_serializing)]
     #[ssz(skip_deserializing)]
@@ -286,10 +294,97 @@ impl<T: EthSpec> BeaconState<T> {
             ],
             pubkey_cache: PubkeyCache::default(),
             exit_cache: ExitCache::default(),
+            total_active_balance: None,
             tree_hash_cache: None,
         }
     }
 
+    /// Decodes an SSZ-encoded state from `reader`, without buffering the entire encoding.
+    ///
+    /// At most one field of the state is held in memory as bytes at a time. The reader is
+    /// rejected if it contains more than `max_len` bytes.
+    pub fn from_ssz_reader<R: Read>(reader: R, max_len: usize) -> Result<Self, ssz::ReadError> {
+        let mut builder = ssz::SszReaderBuilder::new(reader, max_len);
+
+        builder.register_type::<u64>();
+        builder.register_type::<Hash256>();
+        builder.register_type::<Slot>();
+        builder.register_type::<Fork>();
+        builder.register_type::<BeaconBlockHeader>();
+        builder.register_type::<FixedVector<Hash256, T::SlotsPerHistoricalRoot>>();
+        builder.register_type::<FixedVector<Hash256, T::SlotsPerHistoricalRoot>>();
+        builder.register_type::<VariableList<Hash256, T::HistoricalRootsLimit>>();
+        builder.register_type::<Eth1Data>();
+        builder.register_type::<VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>>();
+        builder.register_type::<u64>();
+        builder.register_type::<VariableList<Validator, T::ValidatorRegistryLimit>>();
+        builder.register_type::<VariableList<u64, T::ValidatorRegistryLimit>>();
+        builder.register_type::<FixedVector<Hash256, T::EpochsPerHistoricalVector>>();
+        builder.register_type::<FixedVector<u64, T::EpochsPerSlashingsVector>>();
+        builder.register_type::<VariableList<PendingAttestation<T>, T::MaxPendingAttestations>>();
+        builder.register_type::<VariableList<PendingAttestation<T>, T::MaxPendingAttestations>>();
+        builder.register_type::<BitVector<T::JustificationBitsLength>>();
+        builder.register_type::<Checkpoint>();
+        builder.register_type::<Checkpoint>();
+        builder.register_type::<Checkpoint>();
+
+        let mut reader = builder.build()?;
+
+        let state = BeaconState {
+            // Versioning
+            genesis_time: reader.decode_next()?,
+            genesis_validators_root: reader.decode_next()?,
+            slot: reader.decode_next()?,
+            fork: reader.decode_next()?,
+
+            // History
+            latest_block_header: reader.decode_next()?,
+            block_roots: reader.decode_next()?,
+            state_roots: reader.decode_next()?,
+            historical_roots: reader.decode_next()?,
+
+            // Eth1
+            eth1_data: reader.decode_next()?,
+            eth1_data_votes: reader.decode_next()?,
+            eth1_deposit_index: reader.decode_next()?,
+
+            // Validator registry
+            validators: reader.decode_next()?,
+            balances: reader.decode_next()?,
+
+            // Randomness
+            randao_mixes: reader.decode_next()?,
+
+            // Slashings
+            slashings: reader.decode_next()?,
+
+            // Attestations
+            previous_epoch_attestations: reader.decode_next()?,
+            current_epoch_attestations: reader.decode_next()?,
+
+            // Finality
+            justification_bits: reader.decode_next()?,
+            previous_justified_checkpoint: reader.decode_next()?,
+            current_justified_checkpoint: reader.decode_next()?,
+            finalized_checkpoint: reader.decode_next()?,
+
+            // Caching (not in spec)
+            committee_caches: [
+                CommitteeCache::default(),
+                CommitteeCache::default(),
+                CommitteeCache::default(),
+            ],
+            pubkey_cache: PubkeyCache::default(),
+            exit_cache: ExitCache::default(),
+            total_active_balance: None,
+            tree_hash_cache: None,
+        };
+
+        reader.finish()?;
+
+        Ok(state)
+    }
+
     /// Returns the `tree_hash_root` of the state.
     ///
     /// Spec v0.12.1
@@ -951,6 +1046,46 @@ impl<T: EthSpec> BeaconState<T> {
         })
     }
 
+    /// Return the combined effective balance of the validators which are active in the current
+    /// epoch, with a minimum of `EFFECTIVE_BALANCE_INCREMENT` to avoid division by zero.
+    ///
+    /// Uses the total active balance cache if it has been built for the current epoch, otherwise
+    /// requires the current epoch committee cache to be built.
+    ///
+    /// Spec v0.12.1
+    pub fn get_total_active_balance(&self, spec: &ChainSpec) -> Result<u64, Error> {
+        match self.total_active_balance {
+            Some((epoch, balance)) if epoch == self.current_epoch() => Ok(balance),
+            _ => self.compute_total_active_balance(spec),
+        }
+    }
+
+    /// Compute the total active balance for the current epoch, and cache it so that future calls
+    /// to `Self::get_total_active_balance` in this epoch avoid iterating the validator registry.
+    ///
+    /// Effective balances only change during epoch processing and newly activated validators only
+    /// become active at an epoch boundary, so the cached value remains valid for the whole epoch.
+    pub fn build_total_active_balance_cache(&mut self, spec: &ChainSpec) -> Result<(), Error> {
+        let epoch = self.current_epoch();
+        if self
+            .total_active_balance
+            .map_or(true, |(cached, _)| cached != epoch)
+        {
+            self.total_active_balance = Some((epoch, self.compute_total_active_balance(spec)?));
+        }
+        Ok(())
+    }
+
+    fn compute_total_active_balance(&self, spec: &ChainSpec) -> Result<u64, Error> {
+        let active_indices = self.get_cached_active_validator_indices(RelativeEpoch::Current)?;
+        let total_balance = self.get_total_balance(active_indices, spec)?;
+
+        Ok(std::cmp::max(
+            total_balance,
+            spec.effective_balance_increment,
+        ))
+    }
+
     /// Get the number of outstanding deposits.
     ///
     /// Returns `Err` if the state is invalid.
@@ -969,6 +1104,7 @@ impl<T: EthSpec> BeaconState<T> {
         self.build_all_committee_caches(spec)?;
         self.update_pubkey_cache()?;
         self.exit_cache.build(&self.validators, spec)?;
+        self.build_total_active_balance_cache(spec)?;
 
         Ok(())
     }
@@ -989,6 +1125,7 @@ impl<T: EthSpec> BeaconState<T> {
         self.drop_pubkey_cache();
         self.drop_tree_hash_cache();
         self.exit_cache = ExitCache::default();
+        self.total_active_balance = None;
     }
 
     /// Returns `true` if the committee cache for `relative_epoch` is built and ready to use.
@@ -1194,6 +1331,7 @@ impl<T: EthSpec> BeaconState<T> {
             } else {
                 ExitCache::default()
             },
+            total_active_balance: self.total_active_balance,
             tree_hash_cache: if config.tree_hash_cache {
                 self.tree_hash_cache.clone()
             } else {
@@ -1293,6 +1431,7 @@ impl<T: EthSpec> arbitrary::Arbitrary for BeaconState<T> {
             ],
             pubkey_cache: PubkeyCache::arbitrary(u)?,
             exit_cache: ExitCache::arbitrary(u)?,
+            total_active_balance: None,
             tree_hash_cache: None,
         })
     }
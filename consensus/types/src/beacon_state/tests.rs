@@ -198,6 +198,38 @@ fn clone_config() {
     }
 }
 
+#[test]
+fn from_ssz_reader() {
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (state, _keypairs) = builder.build();
+    let bytes = state.as_ssz_bytes();
+
+    let decoded = BeaconState::<MinimalEthSpec>::from_ssz_reader(bytes.as_slice(), bytes.len())
+        .expect("should decode state");
+    assert_eq!(decoded, state);
+
+    // Encodings longer than the limit are rejected.
+    assert!(matches!(
+        BeaconState::<MinimalEthSpec>::from_ssz_reader(bytes.as_slice(), bytes.len() - 1),
+        Err(ssz::ReadError::MaxLengthExceeded { .. })
+    ));
+
+    // Excess bytes are rejected.
+    let mut excess = bytes.clone();
+    excess.push(0);
+    assert!(
+        BeaconState::<MinimalEthSpec>::from_ssz_reader(excess.as_slice(), excess.len()).is_err()
+    );
+
+    // Truncated encodings are rejected.
+    assert!(
+        BeaconState::<MinimalEthSpec>::from_ssz_reader(&bytes[..bytes.len() - 1], bytes.len())
+            .is_err()
+    );
+}
+
 #[test]
 fn tree_hash_cache() {
     use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
@@ -417,6 +449,88 @@ mod committees {
     }
 }
 
+#[test]
+fn get_total_active_balance() {
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (mut state, _keypairs) = builder.build();
+
+    assert!(
+        state.get_total_active_balance(&spec).is_err(),
+        "requires the committee cache"
+    );
+
+    state
+        .build_committee_cache(RelativeEpoch::Current, &spec)
+        .unwrap();
+    assert_eq!(
+        state.get_total_active_balance(&spec),
+        Ok(16 * spec.max_effective_balance)
+    );
+
+    // Effective balances are used, not actual balances.
+    state.balances[0] = 0;
+    state.validators[1].effective_balance -= spec.effective_balance_increment;
+    assert_eq!(
+        state.get_total_active_balance(&spec),
+        Ok(16 * spec.max_effective_balance - spec.effective_balance_increment)
+    );
+
+    // The total is never less than one increment.
+    for validator in state.validators.iter_mut() {
+        validator.effective_balance = 0;
+    }
+    assert_eq!(
+        state.get_total_active_balance(&spec),
+        Ok(spec.effective_balance_increment)
+    );
+}
+
+#[test]
+fn total_active_balance_cache() {
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (mut state, _keypairs) = builder.build();
+    let total = 16 * spec.max_effective_balance;
+
+    state.build_all_caches(&spec).unwrap();
+    assert_eq!(
+        state.total_active_balance,
+        Some((state.current_epoch(), total))
+    );
+
+    // The cached value is used for the rest of the epoch.
+    state.validators[0].effective_balance = 0;
+    assert_eq!(state.get_total_active_balance(&spec), Ok(total));
+    assert_eq!(
+        state
+            .clone_with(CloneConfig::none())
+            .get_total_active_balance(&spec),
+        Ok(total),
+        "the cache is cloned"
+    );
+
+    // The cache is not used in a later epoch.
+    state.slot += MinimalEthSpec::slots_per_epoch();
+    state
+        .build_committee_cache(RelativeEpoch::Current, &spec)
+        .unwrap();
+    assert_eq!(
+        state.get_total_active_balance(&spec),
+        Ok(total - spec.max_effective_balance)
+    );
+    state.build_total_active_balance_cache(&spec).unwrap();
+    assert_eq!(
+        state.total_active_balance,
+        Some((state.current_epoch(), total - spec.max_effective_balance))
+    );
+
+    state.drop_all_caches();
+    assert_eq!(state.total_active_balance, None);
+}
+
 mod get_outstanding_deposit_len {
     use super::*;
     use crate::test_utils::TestingBeaconStateBuilder;
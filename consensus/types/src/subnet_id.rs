@@ -110,3 +110,66 @@ impl AsRef<str> for SubnetId {
         subnet_id_to_string(self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    /// The subnet is determined by the number of committees since the start of the epoch, not by
+    /// the committee index alone.
+    #[test]
+    fn compute_subnet_follows_spec() {
+        let spec = E::default_spec();
+
+        let cases = [
+            // (slot, committee_index, committee_count_at_slot, expected_subnet)
+            (0, 0, 1, 0),
+            (0, 3, 4, 3),
+            (1, 0, 4, 4),
+            (1, 3, 4, 7),
+            (5, 2, 2, 12),
+            // Wraps around `ATTESTATION_SUBNET_COUNT`.
+            (2, 5, 32, 5),
+            // The slot within the epoch is used, not the absolute slot.
+            (33, 1, 4, 5),
+        ];
+
+        for (slot, committee_index, committee_count_at_slot, expected) in cases.iter().copied() {
+            assert_eq!(
+                SubnetId::compute_subnet::<E>(
+                    Slot::new(slot),
+                    committee_index,
+                    committee_count_at_slot,
+                    &spec
+                ),
+                Ok(SubnetId::new(expected)),
+                "slot {} index {} count {}",
+                slot,
+                committee_index,
+                committee_count_at_slot
+            );
+        }
+    }
+
+    #[test]
+    fn compute_subnet_for_attestation_data() {
+        let spec = E::default_spec();
+        let data = AttestationData {
+            slot: Slot::new(3),
+            index: 1,
+            ..AttestationData::default()
+        };
+
+        assert_eq!(
+            SubnetId::compute_subnet_for_attestation_data::<E>(&data, 8, &spec),
+            SubnetId::compute_subnet::<E>(Slot::new(3), 1, 8, &spec)
+        );
+        assert_eq!(
+            SubnetId::compute_subnet_for_attestation_data::<E>(&data, 8, &spec),
+            Ok(SubnetId::new(25))
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Checks the SSZ encoding and `tree_hash_root` of a fixed sample of each consensus type against
+//! the values in `ssz_golden.json`, so that accidental changes to an encoding are detected.
+//!
+//! The samples are built by hand (rather than with `TestRandom`) so that they can be reproduced by
+//! other SSZ implementations. If an encoding is changed intentionally (e.g., for a spec upgrade),
+//! regenerate the fixtures with:
+//!
+//! ```bash
+//! SSZ_GOLDEN_UPDATE=1 cargo test -p types --test ssz_golden
+//! ```
+
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs::File;
+use std::path::PathBuf;
+use tree_hash::TreeHash;
+use types::*;
+
+type E = MinimalEthSpec;
+
+const FAR_FUTURE: u64 = u64::max_value();
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Golden {
+    ssz: String,
+    tree_hash_root: String,
+}
+
+fn golden<T: Encode + Decode + TreeHash + PartialEq + Debug>(value: &T) -> Golden {
+    let bytes = value.as_ssz_bytes();
+    assert_eq!(&T::from_ssz_bytes(&bytes).expect("sample decodes"), value);
+
+    Golden {
+        ssz: format!("0x{}", hex::encode(&bytes)),
+        tree_hash_root: format!("0x{}", hex::encode(&value.tree_hash_root()[..])),
+    }
+}
+
+fn root(byte: u8) -> Hash256 {
+    Hash256::repeat_byte(byte)
+}
+
+fn pubkey() -> PublicKeyBytes {
+    PublicKeyBytes::deserialize(&[0xab; 48]).unwrap()
+}
+
+fn checkpoint(epoch: u64, root_byte: u8) -> Checkpoint {
+    Checkpoint {
+        epoch: Epoch::new(epoch),
+        root: root(root_byte),
+    }
+}
+
+fn fork() -> Fork {
+    Fork {
+        previous_version: [0, 0, 0, 1],
+        current_version: [1, 0, 0, 1],
+        epoch: Epoch::new(5),
+    }
+}
+
+fn eth1_data() -> Eth1Data {
+    Eth1Data {
+        deposit_root: root(0x33),
+        deposit_count: 7,
+        block_hash: root(0x44),
+    }
+}
+
+fn attestation_data(block_root_byte: u8) -> AttestationData {
+    AttestationData {
+        slot: Slot::new(9),
+        index: 1,
+        beacon_block_root: root(block_root_byte),
+        source: checkpoint(1, 0x66),
+        target: checkpoint(2, 0x77),
+    }
+}
+
+fn block_header(state_root_byte: u8) -> BeaconBlockHeader {
+    BeaconBlockHeader {
+        slot: Slot::new(9),
+        proposer_index: 4,
+        parent_root: root(0x88),
+        state_root: root(state_root_byte),
+        body_root: root(0xaa),
+    }
+}
+
+fn signed_block_header(state_root_byte: u8) -> SignedBeaconBlockHeader {
+    SignedBeaconBlockHeader {
+        message: block_header(state_root_byte),
+        signature: Signature::empty(),
+    }
+}
+
+fn validator() -> Validator {
+    Validator {
+        pubkey: pubkey(),
+        withdrawal_credentials: root(0x01),
+        effective_balance: 32_000_000_000,
+        slashed: true,
+        activation_eligibility_epoch: Epoch::new(1),
+        activation_epoch: Epoch::new(2),
+        exit_epoch: Epoch::new(FAR_FUTURE),
+        withdrawable_epoch: Epoch::new(FAR_FUTURE),
+    }
+}
+
+fn deposit_data() -> DepositData {
+    DepositData {
+        pubkey: pubkey(),
+        withdrawal_credentials: root(0x01),
+        amount: 32_000_000_000,
+        signature: SignatureBytes::empty(),
+    }
+}
+
+fn deposit() -> Deposit {
+    Deposit {
+        proof: FixedVector::new((0..33).map(root).collect()).unwrap(),
+        data: deposit_data(),
+    }
+}
+
+fn signed_voluntary_exit() -> SignedVoluntaryExit {
+    SignedVoluntaryExit {
+        message: VoluntaryExit {
+            epoch: Epoch::new(4),
+            validator_index: 6,
+        },
+        signature: Signature::empty(),
+    }
+}
+
+fn proposer_slashing() -> ProposerSlashing {
+    ProposerSlashing {
+        signed_header_1: signed_block_header(0x99),
+        signed_header_2: signed_block_header(0x9a),
+    }
+}
+
+fn indexed_attestation(block_root_byte: u8) -> IndexedAttestation<E> {
+    IndexedAttestation {
+        attesting_indices: VariableList::from(vec![1, 3, 5]),
+        data: attestation_data(block_root_byte),
+        signature: AggregateSignature::empty(),
+    }
+}
+
+fn attester_slashing() -> AttesterSlashing<E> {
+    AttesterSlashing {
+        attestation_1: indexed_attestation(0x55),
+        attestation_2: indexed_attestation(0x56),
+    }
+}
+
+fn aggregation_bits() -> BitList<<E as EthSpec>::MaxValidatorsPerCommittee> {
+    let mut bits = BitList::with_capacity(5).unwrap();
+    bits.set(0, true).unwrap();
+    bits.set(3, true).unwrap();
+    bits
+}
+
+fn attestation() -> Attestation<E> {
+    Attestation {
+        aggregation_bits: aggregation_bits(),
+        data: attestation_data(0x55),
+        signature: AggregateSignature::empty(),
+    }
+}
+
+fn pending_attestation() -> PendingAttestation<E> {
+    PendingAttestation {
+        aggregation_bits: aggregation_bits(),
+        data: attestation_data(0x55),
+        inclusion_delay: 1,
+        proposer_index: 2,
+    }
+}
+
+fn aggregate_and_proof() -> AggregateAndProof<E> {
+    AggregateAndProof {
+        aggregator_index: 3,
+        aggregate: attestation(),
+        selection_proof: Signature::empty(),
+    }
+}
+
+fn block() -> BeaconBlock<E> {
+    BeaconBlock {
+        slot: Slot::new(9),
+        proposer_index: 4,
+        parent_root: root(0x88),
+        state_root: root(0x99),
+        body: BeaconBlockBody {
+            randao_reveal: Signature::empty(),
+            eth1_data: eth1_data(),
+            graffiti: Graffiti::from([0x67; GRAFFITI_BYTES_LEN]),
+            proposer_slashings: VariableList::from(vec![proposer_slashing()]),
+            attester_slashings: VariableList::from(vec![attester_slashing()]),
+            attestations: VariableList::from(vec![attestation()]),
+            deposits: VariableList::from(vec![deposit()]),
+            voluntary_exits: VariableList::from(vec![signed_voluntary_exit()]),
+        },
+    }
+}
+
+fn state() -> BeaconState<E> {
+    let mut state = BeaconState::new(1_606_824_000, eth1_data(), &E::default_spec());
+
+    state.genesis_validators_root = root(0x22);
+    state.slot = Slot::new(10);
+    state.fork = fork();
+    state.latest_block_header = block_header(0x99);
+    state.block_roots[1] = root(0x31);
+    state.state_roots[2] = root(0x32);
+    state.historical_roots.push(root(0x34)).unwrap();
+    state.eth1_data_votes.push(eth1_data()).unwrap();
+    state.eth1_deposit_index = 7;
+    state.validators.push(validator()).unwrap();
+    state.balances.push(31_000_000_000).unwrap();
+    state.randao_mixes[3] = root(0x35);
+    state.slashings[4] = 1_000_000_000;
+    state
+        .previous_epoch_attestations
+        .push(pending_attestation())
+        .unwrap();
+    state.justification_bits.set(1, true).unwrap();
+    state.previous_justified_checkpoint = checkpoint(1, 0x66);
+    state.current_justified_checkpoint = checkpoint(3, 0x11);
+    state.finalized_checkpoint = checkpoint(1, 0x66);
+
+    state
+}
+
+fn samples() -> BTreeMap<String, Golden> {
+    let block = block();
+
+    vec![
+        ("AggregateAndProof", golden(&aggregate_and_proof())),
+        ("Attestation", golden(&attestation())),
+        ("AttestationData", golden(&attestation_data(0x55))),
+        ("AttesterSlashing", golden(&attester_slashing())),
+        ("BeaconBlock", golden(&block)),
+        ("BeaconBlockBody", golden(&block.body)),
+        ("BeaconBlockHeader", golden(&block_header(0x99))),
+        ("BeaconState", golden(&state())),
+        ("Checkpoint", golden(&checkpoint(3, 0x11))),
+        ("Deposit", golden(&deposit())),
+        ("DepositData", golden(&deposit_data())),
+        (
+            "DepositMessage",
+            golden(&DepositMessage {
+                pubkey: pubkey(),
+                withdrawal_credentials: root(0x01),
+                amount: 32_000_000_000,
+            }),
+        ),
+        (
+            "EnrForkId",
+            golden(&EnrForkId {
+                fork_digest: [0xaa, 0xbb, 0xcc, 0xdd],
+                next_fork_version: [1, 0, 0, 1],
+                next_fork_epoch: Epoch::new(FAR_FUTURE),
+            }),
+        ),
+        ("Eth1Data", golden(&eth1_data())),
+        ("Fork", golden(&fork())),
+        (
+            "ForkData",
+            golden(&ForkData {
+                current_version: [1, 0, 0, 1],
+                genesis_validators_root: root(0x22),
+            }),
+        ),
+        (
+            "HistoricalBatch",
+            golden(&HistoricalBatch::<E> {
+                block_roots: FixedVector::new((0..64).map(root).collect()).unwrap(),
+                state_roots: FixedVector::new((0x80..0xc0).map(root).collect()).unwrap(),
+            }),
+        ),
+        ("IndexedAttestation", golden(&indexed_attestation(0x55))),
+        ("PendingAttestation", golden(&pending_attestation())),
+        ("ProposerSlashing", golden(&proposer_slashing())),
+        (
+            "SignedAggregateAndProof",
+            golden(&SignedAggregateAndProof {
+                message: aggregate_and_proof(),
+                signature: Signature::empty(),
+            }),
+        ),
+        (
+            "SignedBeaconBlock",
+            golden(&SignedBeaconBlock {
+                message: block.clone(),
+                signature: Signature::empty(),
+            }),
+        ),
+        (
+            "SignedBeaconBlockHeader",
+            golden(&signed_block_header(0x99)),
+        ),
+        ("SignedVoluntaryExit", golden(&signed_voluntary_exit())),
+        (
+            "SigningData",
+            golden(&SigningData {
+                object_root: root(0x23),
+                domain: root(0x24),
+            }),
+        ),
+        ("Validator", golden(&validator())),
+        ("VoluntaryExit", golden(&signed_voluntary_exit().message)),
+    ]
+    .into_iter()
+    .map(|(name, golden)| (name.to_string(), golden))
+    .collect()
+}
+
+#[test]
+fn ssz_golden_vectors() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/ssz_golden.json");
+    let actual = samples();
+
+    if std::env::var("SSZ_GOLDEN_UPDATE").is_ok() {
+        let file = File::create(&path).expect("should create fixtures");
+        serde_json::to_writer_pretty(file, &actual).expect("should write fixtures");
+        return;
+    }
+
+    let file = File::open(&path).expect("should open fixtures");
+    let expected: BTreeMap<String, Golden> =
+        serde_json::from_reader(file).expect("should parse fixtures");
+
+    assert_eq!(
+        expected.keys().collect::<Vec<_>>(),
+        actual.keys().collect::<Vec<_>>(),
+        "the set of types should match the fixtures"
+    );
+    for (name, expected) in &expected {
+        assert_eq!(&actual[name], expected, "encoding of {} has changed", name);
+    }
+}
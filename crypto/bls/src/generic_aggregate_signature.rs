@@ -34,6 +34,9 @@ pub trait TAggregateSignature<Pub, AggPub, Sig>: Sized + Clone {
     /// Deserialize `self` from compressed bytes.
     fn deserialize(bytes: &[u8]) -> Result<Self, Error>;
 
+    /// Returns `true` if `self` is in the correct subgroup.
+    fn subgroup_check(&self) -> bool;
+
     /// Verify that `self` represents an aggregate signature where all `pubkeys` have signed `msg`.
     fn fast_aggregate_verify(&self, msg: Hash256, pubkeys: &[&GenericPublicKey<Pub>]) -> bool;
 
@@ -115,6 +118,15 @@ where
         self.point.as_ref()
     }
 
+    /// Returns an error if `self` is not in the correct subgroup. The "empty" value has no point
+    /// and always fails verification, so it is not checked here.
+    pub(crate) fn check_subgroup(&self) -> Result<(), Error> {
+        match &self.point {
+            Some(point) if !point.subgroup_check() => Err(Error::SignatureNotInSubgroup),
+            _ => Ok(()),
+        }
+    }
+
     /// Aggregates a signature onto `self`.
     pub fn add_assign(&mut self, other: &GenericSignature<Pub, Sig>) {
         if let Some(other_point) = other.point() {
@@ -177,9 +189,22 @@ where
     Sig: TSignature<Pub>,
     AggSig: TAggregateSignature<Pub, AggPub, Sig>,
 {
+    /// Checks the conditions which must hold before `self` may be verified against `pubkeys`,
+    /// returning a distinct error for each. Verification always fails if this returns an error.
+    ///
+    /// Public keys are not checked against the point at infinity here since a `GenericPublicKey`
+    /// cannot represent it (see `GenericPublicKey::deserialize`).
+    pub fn check_preconditions(&self, pubkeys: &[&GenericPublicKey<Pub>]) -> Result<(), Error> {
+        if pubkeys.is_empty() {
+            return Err(Error::NoSignatures);
+        }
+
+        self.check_subgroup()
+    }
+
     /// Verify that `self` represents an aggregate signature where all `pubkeys` have signed `msg`.
     pub fn fast_aggregate_verify(&self, msg: Hash256, pubkeys: &[&GenericPublicKey<Pub>]) -> bool {
-        if pubkeys.is_empty() {
+        if self.check_preconditions(pubkeys).is_err() {
             return false;
         }
 
@@ -200,6 +225,10 @@ where
             return false;
         }
 
+        if self.check_preconditions(pubkeys).is_err() {
+            return false;
+        }
+
         match self.point.as_ref() {
             Some(point) => point.aggregate_verify(msgs, pubkeys),
             None => false,
@@ -3,7 +3,7 @@ use crate::{
     generic_aggregate_signature::{GenericAggregateSignature, TAggregateSignature},
     generic_public_key::{GenericPublicKey, TPublicKey},
     generic_signature::{GenericSignature, TSignature},
-    Hash256,
+    Error, Hash256,
 };
 use std::borrow::Cow;
 use std::marker::PhantomData;
@@ -106,6 +106,18 @@ where
         }
     }
 
+    /// Checks the conditions which must hold before `self` may be verified, returning a distinct
+    /// error for each. Verification always fails if this returns an error.
+    ///
+    /// See `GenericAggregateSignature::check_preconditions`.
+    pub fn check_preconditions(&self) -> Result<(), Error> {
+        if self.signing_keys.is_empty() {
+            return Err(Error::NoSignatures);
+        }
+
+        self.signature.check_subgroup()
+    }
+
     /// Returns `true` if `self.signature` is a signature across `self.message` by
     /// `self.signing_keys`.
     pub fn verify(self) -> bool {
@@ -69,11 +69,12 @@ pub fn verify_signature_sets<'a>(
         // Grab a slice of the message, to satisfy the blst API.
         msgs_refs.push(set.message.as_bytes());
 
+        // Reject sets without signing keys and signatures outside the subgroup.
+        if set.check_preconditions().is_err() {
+            return false;
+        }
+
         if let Some(point) = set.signature.point() {
-            // Subgroup check the signature
-            if !point.0.subgroup_check() {
-                return false;
-            }
             // Convert the aggregate signature into a signature.
             sigs.push(point.0.to_signature())
         } else {
@@ -81,12 +82,6 @@ pub fn verify_signature_sets<'a>(
             return false;
         }
 
-        // Sanity check.
-        if set.signing_keys.is_empty() {
-            // A signature that has no signing keys is invalid.
-            return false;
-        }
-
         // Collect all the public keys into a point, to satisfy the blst API.
         //
         // Note: we could potentially have the `SignatureSet` take a pubkey point instead of a
@@ -218,6 +213,10 @@ impl TAggregateSignature<blst_core::PublicKey, BlstAggregatePublicKey, blst_core
             .map(Self)
     }
 
+    fn subgroup_check(&self) -> bool {
+        self.0.subgroup_check()
+    }
+
     fn fast_aggregate_verify(
         &self,
         msg: Hash256,
@@ -226,8 +225,8 @@ impl TAggregateSignature<blst_core::PublicKey, BlstAggregatePublicKey, blst_core
         let pubkeys = pubkeys.iter().map(|pk| pk.point()).collect::<Vec<_>>();
         let signature = self.0.clone().to_signature();
         // Public keys are already valid due to PoP
-        // Check Signature inside function for subgroup
-        signature.fast_aggregate_verify(true, msg.as_bytes(), DST, &pubkeys)
+        // Signature has already been subgroup checked in `check_preconditions`
+        signature.fast_aggregate_verify(false, msg.as_bytes(), DST, &pubkeys)
             == BLST_ERROR::BLST_SUCCESS
     }
 
@@ -240,8 +239,8 @@ impl TAggregateSignature<blst_core::PublicKey, BlstAggregatePublicKey, blst_core
         let msgs = msgs.iter().map(|hash| hash.as_bytes()).collect::<Vec<_>>();
         let signature = self.0.clone().to_signature();
         // Public keys have already been checked for subgroup and infinity
-        // Check Signature inside function for subgroup
-        signature.aggregate_verify(true, &msgs, DST, &pubkeys, false) == BLST_ERROR::BLST_SUCCESS
+        // Signature has already been subgroup checked in `check_preconditions`
+        signature.aggregate_verify(false, &msgs, DST, &pubkeys, false) == BLST_ERROR::BLST_SUCCESS
     }
 }
 
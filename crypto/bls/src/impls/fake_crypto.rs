@@ -142,6 +142,10 @@ impl TAggregateSignature<PublicKey, AggregatePublicKey, Signature> for Aggregate
         Ok(Self(key))
     }
 
+    fn subgroup_check(&self) -> bool {
+        true
+    }
+
     fn fast_aggregate_verify(
         &self,
         _msg: Hash256,
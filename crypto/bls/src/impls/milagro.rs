@@ -38,6 +38,8 @@ pub fn verify_signature_sets<'a>(
 
     signature_sets
         .map(|signature_set| {
+            signature_set.check_preconditions().map_err(|_| ())?;
+
             let mut aggregate = milagro::AggregatePublicKey::from_public_key(
                 signature_set.signing_keys.first().ok_or(())?.point(),
             );
@@ -133,6 +135,11 @@ impl TAggregateSignature<milagro::PublicKey, milagro::AggregatePublicKey, milagr
         milagro::AggregateSignature::from_bytes(&bytes).map_err(Error::MilagroError)
     }
 
+    fn subgroup_check(&self) -> bool {
+        // Milagro checks subgroup membership itself whilst verifying.
+        true
+    }
+
     fn fast_aggregate_verify(
         &self,
         msg: Hash256,
@@ -60,6 +60,11 @@ pub enum Error {
     InvalidSecretKeyLength { got: usize, expected: usize },
     /// The public key represents the point at infinity, which is invalid.
     InvalidInfinityPublicKey,
+    /// A signature was to be verified against an empty set of public keys (i.e., no validators
+    /// participated), which is invalid.
+    NoSignatures,
+    /// The signature is a valid curve point but is not in the correct subgroup, which is invalid.
+    SignatureNotInSubgroup,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
 }
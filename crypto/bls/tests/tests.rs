@@ -1,4 +1,4 @@
-use bls::{Hash256, INFINITY_SIGNATURE, SECRET_KEY_BYTES_LEN};
+use bls::{Error, Hash256, INFINITY_PUBLIC_KEY, INFINITY_SIGNATURE, SECRET_KEY_BYTES_LEN};
 use ssz::{Decode, Encode};
 use std::borrow::Cow;
 use std::fmt::Debug;
@@ -24,6 +24,20 @@ macro_rules! test_suite {
             assert!(SecretKey::deserialize(&[0; SECRET_KEY_BYTES_LEN]).is_err());
         }
 
+        #[test]
+        fn infinity_public_key_is_rejected() {
+            assert_eq!(
+                PublicKey::deserialize(&INFINITY_PUBLIC_KEY),
+                Err(Error::InvalidInfinityPublicKey)
+            );
+            assert_eq!(
+                PublicKeyBytes::deserialize(&INFINITY_PUBLIC_KEY)
+                    .unwrap()
+                    .decompress(),
+                Err(Error::InvalidInfinityPublicKey)
+            );
+        }
+
         #[test]
         fn infinity_agg_sig() {
             assert_eq!(
@@ -250,6 +264,29 @@ macro_rules! test_suite {
             AggregateSignatureTester::new_with_single_msg(0).assert_single_message_verify(false)
         }
 
+        /// Verifying an aggregate against no public keys should be rejected with a distinct error.
+        #[test]
+        fn aggregate_preconditions_no_signatures() {
+            let tester = AggregateSignatureTester::new_with_single_msg(0);
+            assert_eq!(
+                tester.sig.check_preconditions(&[]),
+                Err(Error::NoSignatures)
+            );
+
+            let set = SignatureSet::multiple_pubkeys(&tester.sig, vec![], tester.msgs[0]);
+            assert_eq!(set.check_preconditions(), Err(Error::NoSignatures));
+            let sets = vec![set];
+            assert!(!verify_signature_sets(sets.iter()));
+        }
+
+        /// A valid aggregate should satisfy the preconditions.
+        #[test]
+        fn aggregate_preconditions_valid() {
+            let tester = AggregateSignatureTester::new_with_single_msg(2);
+            let pubkeys = tester.pubkeys.iter().collect::<Vec<_>>();
+            assert_eq!(tester.sig.check_preconditions(&pubkeys), Ok(()));
+        }
+
         /// An aggregate of size 1 should verify.
         #[test]
         fn fast_aggregate_verify_1_pubkey() {
@@ -496,6 +533,30 @@ macro_rules! test_suite {
 
 mod blst {
     test_suite!(blst_implementations);
+
+    /// A point on the curve which is not in the G2 subgroup, taken from the
+    /// `deserialization_fails_not_in_G2` BLS test vector.
+    ///
+    /// Only blst is tested, since milagro defers the subgroup check until verification.
+    #[test]
+    fn aggregate_preconditions_signature_not_in_subgroup() {
+        let bytes = hex::decode(
+            "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef\
+             0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef\
+             0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+
+        let sig = AggregateSignature::deserialize(&bytes).unwrap();
+        let pubkey = secret_from_u64(0).public_key();
+        let msg = Hash256::from_low_u64_be(42);
+
+        assert_eq!(
+            sig.check_preconditions(&[&pubkey]),
+            Err(Error::SignatureNotInSubgroup)
+        );
+        assert!(!sig.fast_aggregate_verify(msg, &[&pubkey]));
+    }
 }
 
 #[cfg(all(feature = "milagro", not(debug_assertions)))]
@@ -0,0 +1,47 @@
+use clap::ArgMatches;
+use std::path::PathBuf;
+use store::{copy_database, BeaconNodeBackend, DatabaseBackend, StoreConfig};
+use types::EthSpec;
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let input_dir: PathBuf = clap_utils::parse_required(matches, "input-dir")?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, "output-dir")?;
+    let backend = clap_utils::parse_required(matches, "backend")?;
+    let compression = clap_utils::parse_required(matches, "compression")?;
+
+    let input_backend = DatabaseBackend::detect(&input_dir)
+        .map_err(|e| format!("Unable to read input database: {:?}", e))?
+        .ok_or_else(|| format!("No database found at {}", input_dir.display()))?;
+    if output_dir.exists() {
+        return Err(format!("{} already exists", output_dir.display()));
+    }
+
+    let input_config = StoreConfig {
+        backend: input_backend,
+        ..StoreConfig::default()
+    };
+    let output_config = StoreConfig {
+        backend,
+        compression,
+        ..StoreConfig::default()
+    };
+
+    let input = BeaconNodeBackend::<T>::open(&input_config, &input_dir)
+        .map_err(|e| format!("Unable to open input database: {:?}", e))?;
+    let output = BeaconNodeBackend::<T>::open(&output_config, &output_dir)
+        .map_err(|e| format!("Unable to create output database: {:?}", e))?;
+
+    let count =
+        copy_database(&input, &output).map_err(|e| format!("Unable to copy database: {:?}", e))?;
+
+    println!(
+        "Copied {} keys from {} database at {} to {} database at {}",
+        count,
+        input_backend,
+        input_dir.display(),
+        backend,
+        output_dir.display()
+    );
+
+    Ok(())
+}
@@ -2,11 +2,13 @@
 extern crate log;
 mod change_genesis_time;
 mod check_deposit_data;
+mod convert_db;
 mod deploy_deposit_contract;
 mod eth1_genesis;
 mod generate_bootnode_enr;
 mod insecure_validators;
 mod interop_genesis;
+mod network_key;
 mod new_testnet;
 mod parse_hex;
 mod replace_state_pubkeys;
@@ -507,6 +509,89 @@ fn main() {
                         .help("The number of nodes to divide the validator keys to"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("network-key")
+                .about("Manages the libp2p/discv5 key and ENR stored in a beacon node network dir.")
+                .arg(
+                    Arg::with_name("network-dir")
+                        .long("network-dir")
+                        .value_name("NETWORK_DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The beacon node network directory, e.g. ~/.lighthouse/mainnet/beacon/network"),
+                )
+                .subcommand(
+                    SubCommand::with_name("print")
+                        .about("Prints the peer id and ENR of the node."),
+                )
+                .subcommand(
+                    SubCommand::with_name("regenerate")
+                        .about("Replaces the key with a new random key. The ENR is rebuilt when the node next starts."),
+                )
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about("Writes the hex-encoded secret key to a file.")
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .value_name("FILE")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The file to create"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about("Replaces the key with a hex-encoded secret key read from a file. The ENR is rebuilt when the node next starts.")
+                        .arg(
+                            Arg::with_name("input")
+                                .long("input")
+                                .value_name("FILE")
+                                .takes_value(true)
+                                .required(true)
+                                .help("A file containing a hex-encoded secp256k1 secret key"),
+                        ),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("convert-db")
+                .about("Copies a beacon node database (e.g. chain_db or freezer_db) to a new \
+                        database with a different backend. The beacon node must not be running.")
+                .arg(
+                    Arg::with_name("input-dir")
+                        .long("input-dir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The database to convert, e.g. ~/.lighthouse/mainnet/beacon/chain_db"),
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The directory in which to create the new database. Must not exist."),
+                )
+                .arg(
+                    Arg::with_name("backend")
+                        .long("backend")
+                        .value_name("BACKEND")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(&["leveldb", "rocksdb"])
+                        .help("The backend of the new database"),
+                )
+                .arg(
+                    Arg::with_name("compression")
+                        .long("compression")
+                        .value_name("ALGORITHM")
+                        .takes_value(true)
+                        .possible_values(&["none", "snappy", "lz4", "zstd"])
+                        .default_value("snappy")
+                        .help("The compression used by the new database, if it is a rocksdb database"),
+                )
+        )
         .get_matches();
 
     let result = matches
@@ -607,6 +692,10 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run generate-bootnode-enr command: {}", e)),
         ("insecure-validators", Some(matches)) => insecure_validators::run(matches)
             .map_err(|e| format!("Failed to run insecure-validators command: {}", e)),
+        ("network-key", Some(matches)) => network_key::run(matches)
+            .map_err(|e| format!("Failed to run network-key command: {}", e)),
+        ("convert-db", Some(matches)) => convert_db::run::<T>(matches)
+            .map_err(|e| format!("Failed to run convert-db command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
@@ -0,0 +1,82 @@
+use account_utils::create_with_600_perms;
+use clap::ArgMatches;
+use eth2_libp2p::{
+    discovery::{load_enr_from_disk, Keypair, ENR_FILENAME},
+    keypair_from_hex, load_private_key_from_disk, save_private_key_to_disk, PeerId,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let network_dir: PathBuf = clap_utils::parse_required(matches, "network-dir")?;
+
+    match matches.subcommand() {
+        ("print", Some(_)) => print(&network_dir),
+        ("regenerate", Some(_)) => {
+            replace_key(&network_dir, &Keypair::generate_secp256k1())?;
+            print(&network_dir)
+        }
+        ("export", Some(matches)) => {
+            let output: PathBuf = clap_utils::parse_required(matches, "output")?;
+            export(&network_dir, &output)
+        }
+        ("import", Some(matches)) => {
+            let input: PathBuf = clap_utils::parse_required(matches, "input")?;
+            let hex = fs::read_to_string(&input)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", input, e))?;
+            let keypair = keypair_from_hex(hex.trim())
+                .map_err(|e| format!("Unable to parse network key: {:?}", e))?;
+            replace_key(&network_dir, &keypair)?;
+            print(&network_dir)
+        }
+        (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
+    }
+}
+
+/// Prints the peer id of the key in `network_dir` and, if one exists, its ENR.
+fn print(network_dir: &Path) -> Result<(), String> {
+    let keypair = load_private_key_from_disk(network_dir)?;
+    println!("Peer ID: {}", PeerId::from(keypair.public()));
+
+    match load_enr_from_disk(network_dir) {
+        Ok(enr) => println!("ENR: {}", enr.to_base64()),
+        Err(_) => println!("ENR: none, one will be created when the beacon node next starts"),
+    }
+
+    Ok(())
+}
+
+/// Writes the hex-encoded key from `network_dir` to `output`, refusing to overwrite a file.
+///
+/// The file is only readable and writable by its owner, since it contains a secret key.
+fn export(network_dir: &Path, output: &Path) -> Result<(), String> {
+    if output.exists() {
+        return Err(format!("{:?} already exists, will not override", output));
+    }
+
+    let secret_bytes = match load_private_key_from_disk(network_dir)? {
+        Keypair::Secp256k1(key) => key.secret().to_bytes(),
+        _ => return Err("Key is not a secp256k1 key".into()),
+    };
+
+    create_with_600_perms(
+        output,
+        format!("0x{}", hex::encode(secret_bytes)).as_bytes(),
+    )
+    .map_err(|e| format!("Unable to write {:?}: {:?}", output, e))
+}
+
+/// Replaces the key in `network_dir`, removing the ENR which was signed by the previous key.
+///
+/// The beacon node builds and signs a new ENR when it next starts.
+fn replace_key(network_dir: &Path, keypair: &Keypair) -> Result<(), String> {
+    save_private_key_to_disk(network_dir, keypair)?;
+
+    let enr_path = network_dir.join(ENR_FILENAME);
+    if enr_path.exists() {
+        fs::remove_file(&enr_path)
+            .map_err(|e| format!("Unable to remove {:?}: {:?}", enr_path, e))?;
+    }
+
+    Ok(())
+}
@@ -7,9 +7,7 @@ use std::io::Read;
 use types::{EthSpec, SignedBeaconBlock};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
-    let type_str = matches
-        .value_of("type")
-        .ok_or("No type supplied")?;
+    let type_str = matches.value_of("type").ok_or("No type supplied")?;
     let path = parse_path(matches, "path")?;
 
     info!("Type: {:?}", type_str);
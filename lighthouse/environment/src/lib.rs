@@ -7,6 +7,10 @@
 //! `Context` which can be handed to any service that wishes to start async tasks or perform
 //! logging.
 
+mod metrics;
+mod rotating_file;
+pub mod slot_scheduler;
+
 use eth2_config::Eth2Config;
 use eth2_network_config::Eth2NetworkConfig;
 use futures::channel::{
@@ -15,12 +19,17 @@ use futures::channel::{
 };
 use futures::{future, StreamExt};
 
-use slog::{error, info, o, warn, Drain, Level, Logger};
+use logging::{parse_level, LogDirectives, LogFilter, LogFilterHandle};
+use rotating_file::RotatingFile;
+use slog::{error, info, o, warn, Drain, FnValue, Logger, Record};
 use sloggers::{null::NullLoggerBuilder, Build};
+use slot_clock::SlotClock;
+use slot_scheduler::SlotScheduler;
 use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::fs::{rename as FsRename, OpenOptions};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use task_executor::TaskExecutor;
@@ -31,11 +40,30 @@ pub const ETH2_CONFIG_FILENAME: &str = "eth2-spec.toml";
 const LOG_CHANNEL_SIZE: usize = 2048;
 /// The maximum time in seconds the client will wait for all internal tasks to shutdown.
 const MAXIMUM_SHUTDOWN_TIME: u64 = 15;
+/// The default maximum number of threads for blocking operations, as per `tokio`.
+const DEFAULT_MAX_BLOCKING_THREADS: usize = 512;
+/// The default prefix for the names of runtime threads.
+const DEFAULT_THREAD_NAME_PREFIX: &str = "tokio-runtime-worker";
+
+/// The settings used to build the `tokio` runtime of an `Environment`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeConfig {
+    /// The number of threads which run async tasks.
+    pub worker_threads: usize,
+    /// The maximum number of threads which run blocking tasks.
+    pub max_blocking_threads: usize,
+    /// Threads are named `<prefix>-<n>`.
+    pub thread_name_prefix: String,
+}
 
 /// Builds an `Environment`.
 pub struct EnvironmentBuilder<E: EthSpec> {
-    runtime: Option<Arc<Runtime>>,
+    multi_threaded_runtime: bool,
+    worker_threads: Option<usize>,
+    max_blocking_threads: Option<usize>,
+    thread_name_prefix: Option<String>,
     log: Option<Logger>,
+    log_filter: Option<LogFilterHandle>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     testnet: Option<Eth2NetworkConfig>,
@@ -45,8 +73,12 @@ impl EnvironmentBuilder<MinimalEthSpec> {
     /// Creates a new builder using the `minimal` eth2 specification.
     pub fn minimal() -> Self {
         Self {
-            runtime: None,
+            multi_threaded_runtime: false,
+            worker_threads: None,
+            max_blocking_threads: None,
+            thread_name_prefix: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             testnet: None,
@@ -58,8 +90,12 @@ impl EnvironmentBuilder<MainnetEthSpec> {
     /// Creates a new builder using the `mainnet` eth2 specification.
     pub fn mainnet() -> Self {
         Self {
-            runtime: None,
+            multi_threaded_runtime: false,
+            worker_threads: None,
+            max_blocking_threads: None,
+            thread_name_prefix: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             testnet: None,
@@ -71,8 +107,12 @@ impl EnvironmentBuilder<V012LegacyEthSpec> {
     /// Creates a new builder using the v0.12.x eth2 specification.
     pub fn v012_legacy() -> Self {
         Self {
-            runtime: None,
+            multi_threaded_runtime: false,
+            worker_threads: None,
+            max_blocking_threads: None,
+            thread_name_prefix: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: V012LegacyEthSpec,
             eth2_config: Eth2Config::v012_legacy(),
             testnet: None,
@@ -83,20 +123,44 @@ impl EnvironmentBuilder<V012LegacyEthSpec> {
 impl<E: EthSpec> EnvironmentBuilder<E> {
     /// Specifies that a multi-threaded tokio runtime should be used. Ideal for production uses.
     ///
-    /// The `Runtime` used is just the standard tokio runtime.
+    /// The `Runtime` used is the standard tokio runtime, unless shaped with
+    /// `Self::tokio_worker_threads`, `Self::blocking_threads` or `Self::thread_name_prefix`. The
+    /// runtime is started by `Self::build`.
     pub fn multi_threaded_tokio_runtime(mut self) -> Result<Self, String> {
-        self.runtime = Some(Arc::new(
-            RuntimeBuilder::new_multi_thread()
-                .enable_all()
-                .build()
-                .map_err(|e| format!("Failed to start runtime: {:?}", e))?,
-        ));
+        self.multi_threaded_runtime = true;
+        Ok(self)
+    }
+
+    /// Sets the number of threads which run async tasks. Defaults to the number of CPUs.
+    pub fn tokio_worker_threads(mut self, n: usize) -> Result<Self, String> {
+        if n == 0 {
+            return Err("The number of tokio worker threads must be at least 1".into());
+        }
+        self.worker_threads = Some(n);
         Ok(self)
     }
 
+    /// Sets the maximum number of threads which run blocking tasks (e.g., database and state
+    /// processing work). Defaults to 512.
+    pub fn blocking_threads(mut self, n: usize) -> Result<Self, String> {
+        if n == 0 {
+            return Err("The number of blocking threads must be at least 1".into());
+        }
+        self.max_blocking_threads = Some(n);
+        Ok(self)
+    }
+
+    /// Sets the prefix of the names given to runtime threads, which are visible in tools like
+    /// `top -H`.
+    pub fn thread_name_prefix(mut self, prefix: &str) -> Self {
+        self.thread_name_prefix = Some(prefix.to_string());
+        self
+    }
+
     /// Specifies that all logs should be sent to `null` (i.e., ignored).
     pub fn null_logger(mut self) -> Result<Self, String> {
         self.log = Some(null_logger()?);
+        self.log_filter = None;
         Ok(self)
     }
 
@@ -131,15 +195,8 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let drain = LogFilter::new(drain, LogDirectives::new(parse_level(debug_level)?));
+        self.log_filter = Some(drain.handle());
 
         self.log = Some(Logger::root(drain.fuse(), o!()));
         Ok(self)
@@ -198,15 +255,8 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let drain = LogFilter::new(drain, LogDirectives::new(parse_level(debug_level)?));
+        self.log_filter = Some(drain.handle());
 
         let log = Logger::root(drain.fuse(), o!());
         info!(
@@ -220,6 +270,76 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         Ok(self)
     }
 
+    /// Additionally writes logs as JSON records to the file at `path`, one record per line.
+    ///
+    /// Each record includes the `module` which raised it along with all the key-value pairs of
+    /// the log (e.g., `slot` and `peer_id`), so it may be ingested by log aggregators without
+    /// parsing the terminal format. Once the file exceeds `max_size` bytes it is rotated, keeping
+    /// at most `rotation` old files.
+    ///
+    /// The JSON records share the log directives of the existing logger. If no logger has been
+    /// configured, the JSON file becomes the only logger, at `info` level.
+    pub fn json_logger(
+        mut self,
+        path: PathBuf,
+        max_size: u64,
+        rotation: usize,
+    ) -> Result<Self, String> {
+        if max_size == 0 {
+            return Err("JSON log max size must be greater than zero".to_string());
+        }
+
+        let file = RotatingFile::open(path.clone(), max_size, rotation)
+            .map_err(|e| format!("Unable to open JSON logfile: {:?}", e))?;
+        let drain = slog_json::Json::new(file)
+            .add_default_keys()
+            .add_key_value(o!("module" => FnValue(|record: &Record| record.module())))
+            .build()
+            .fuse();
+        let drain = slog_async::Async::new(drain)
+            .chan_size(LOG_CHANNEL_SIZE)
+            .build();
+
+        let drain = match &self.log_filter {
+            Some(handle) => LogFilter::with_handle(drain, handle.clone()),
+            None => {
+                let drain = LogFilter::new(drain, LogDirectives::new(slog::Level::Info));
+                self.log_filter = Some(drain.handle());
+                drain
+            }
+        };
+
+        let log = match self.log.take() {
+            Some(log) => Logger::root(slog::Duplicate::new(log, drain).fuse(), o!()),
+            None => Logger::root(drain.fuse(), o!()),
+        };
+        info!(
+            log,
+            "Logging JSON to file";
+            "path" => format!("{:?}", path)
+        );
+
+        self.log = Some(log);
+
+        Ok(self)
+    }
+
+    /// Applies per-module log directives (e.g., `network=debug,beacon_chain=trace`) on top of the
+    /// level given to `Self::async_logger` or `Self::log_to_file`.
+    ///
+    /// The directives may be changed after the environment is built via
+    /// `RuntimeContext::log_filter`.
+    pub fn log_directives(self, directives: &str) -> Result<Self, String> {
+        let log_filter = self
+            .log_filter
+            .as_ref()
+            .ok_or("A logger must be configured before log directives")?;
+        let mut current = log_filter.directives();
+        current.merge(directives)?;
+        log_filter.set_directives(current);
+        Ok(self)
+    }
+
     /// Adds a testnet configuration to the environment.
     pub fn eth2_network_config(
         mut self,
@@ -257,17 +377,44 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
 
     /// Consumes the builder, returning an `Environment`.
     pub fn build(self) -> Result<Environment<E>, String> {
+        if !self.multi_threaded_runtime {
+            return Err("Cannot build environment without runtime".into());
+        }
+
+        let runtime_config = RuntimeConfig {
+            worker_threads: self.worker_threads.unwrap_or_else(num_cpus::get),
+            max_blocking_threads: self
+                .max_blocking_threads
+                .unwrap_or(DEFAULT_MAX_BLOCKING_THREADS),
+            thread_name_prefix: self
+                .thread_name_prefix
+                .unwrap_or_else(|| DEFAULT_THREAD_NAME_PREFIX.to_string()),
+        };
+
+        let thread_name_prefix = runtime_config.thread_name_prefix.clone();
+        let thread_count = AtomicUsize::new(0);
+        let runtime = RuntimeBuilder::new_multi_thread()
+            .worker_threads(runtime_config.worker_threads)
+            .max_blocking_threads(runtime_config.max_blocking_threads)
+            .thread_name_fn(move || {
+                let n = thread_count.fetch_add(1, Ordering::Relaxed);
+                format!("{}-{}", thread_name_prefix, n)
+            })
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start runtime: {:?}", e))?;
+
         let (signal, exit) = exit_future::signal();
         let (signal_tx, signal_rx) = channel(1);
         Ok(Environment {
-            runtime: self
-                .runtime
-                .ok_or("Cannot build environment without runtime")?,
+            runtime: Arc::new(runtime),
+            runtime_config,
             signal_tx,
             signal_rx: Some(signal_rx),
             signal: Some(signal),
             exit,
             log: self.log.ok_or("Cannot build environment without log")?,
+            log_filter: self.log_filter,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             testnet: self.testnet,
@@ -284,6 +431,9 @@ pub struct RuntimeContext<E: EthSpec> {
     pub executor: TaskExecutor,
     pub eth_spec_instance: E,
     pub eth2_config: Eth2Config,
+    /// Allows the log directives to be changed at runtime. `None` if the logger does not support
+    /// filtering (e.g., the null logger).
+    pub log_filter: Option<LogFilterHandle>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -295,6 +445,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             executor: self.executor.clone_with_name(service_name),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
@@ -303,6 +454,11 @@ impl<E: EthSpec> RuntimeContext<E> {
         &self.eth2_config
     }
 
+    /// Returns a `SlotScheduler` which spawns tasks on this context's executor.
+    pub fn slot_scheduler<S: SlotClock + 'static>(&self, slot_clock: S) -> SlotScheduler<S> {
+        SlotScheduler::new(self.executor.clone(), slot_clock, E::slots_per_epoch())
+    }
+
     /// Returns a reference to the logger for this service.
     pub fn log(&self) -> &slog::Logger {
         self.executor.log()
@@ -313,6 +469,7 @@ impl<E: EthSpec> RuntimeContext<E> {
 /// validator client, or to run tests that involve logging and async task execution.
 pub struct Environment<E: EthSpec> {
     runtime: Arc<Runtime>,
+    runtime_config: RuntimeConfig,
     /// Receiver side of an internal shutdown signal.
     signal_rx: Option<Receiver<&'static str>>,
     /// Sender to request shutting down.
@@ -320,6 +477,7 @@ pub struct Environment<E: EthSpec> {
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
     log: Logger,
+    log_filter: Option<LogFilterHandle>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub testnet: Option<Eth2NetworkConfig>,
@@ -334,6 +492,11 @@ impl<E: EthSpec> Environment<E> {
         &self.runtime
     }
 
+    /// Returns the settings used to build the `tokio` runtime, with defaults resolved.
+    pub fn runtime_config(&self) -> &RuntimeConfig {
+        &self.runtime_config
+    }
+
     /// Returns a `Context` where no "service" has been added to the logger output.
     pub fn core_context(&mut self) -> RuntimeContext<E> {
         RuntimeContext {
@@ -345,6 +508,7 @@ impl<E: EthSpec> Environment<E> {
             ),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
@@ -359,6 +523,7 @@ impl<E: EthSpec> Environment<E> {
             ),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
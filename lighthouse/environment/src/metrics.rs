@@ -0,0 +1,23 @@
+use lazy_static::lazy_static;
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    /*
+     * Slot scheduler
+     */
+    pub static ref SCHEDULED_TASK_RUNS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "scheduled_task_runs_total",
+        "Count of runs of each task registered with the slot scheduler",
+        &["task"]
+    );
+    pub static ref SCHEDULED_TASK_SECONDS: Result<HistogramVec> = try_create_histogram_vec(
+        "scheduled_task_seconds",
+        "Time taken by each run of a task registered with the slot scheduler",
+        &["task"]
+    );
+    pub static ref SCHEDULED_TASK_DELAY_SECONDS: Result<HistogramVec> = try_create_histogram_vec(
+        "scheduled_task_delay_seconds",
+        "Time between the scheduled start of a task and the time it started running",
+        &["task"]
+    );
+}
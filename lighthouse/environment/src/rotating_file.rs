@@ -0,0 +1,131 @@
+//! A file writer which rotates the file once it reaches a maximum size.
+//!
+//! When the file at `path` grows beyond `max_size` bytes it is renamed to `path.1`, any existing
+//! `path.1` is renamed to `path.2` and so on, keeping at most `max_rotated` old files.
+//!
+//! Rotation only happens at the start of a line, so that a record written with several calls to
+//! `write` (e.g., by `slog_json`) is never split across two files.
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_rotated: usize,
+    file: File,
+    size: u64,
+    at_line_start: bool,
+}
+
+impl RotatingFile {
+    /// Open the file at `path` for appending, creating it if it does not exist.
+    pub fn open(path: PathBuf, max_size: u64, max_rotated: usize) -> io::Result<Self> {
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_size,
+            max_rotated,
+            file,
+            size,
+            at_line_start: true,
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        name.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_rotated == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let oldest = self.rotated_path(self.max_rotated);
+            if oldest.exists() {
+                fs::remove_file(&oldest)?;
+            }
+            for n in (1..self.max_rotated).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.at_line_start && self.size >= self.max_size && self.size > 0 {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        if written > 0 {
+            self.at_line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn rotates_at_line_boundaries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log.json");
+        let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+
+        // A single line which is split across writes and exceeds `max_size` is not split across
+        // files.
+        file.write_all(b"0123456").unwrap();
+        file.write_all(b"789abc\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+        file.write_all(b"third\n").unwrap();
+        file.write_all(b"fourth\n").unwrap();
+        file.flush().unwrap();
+
+        let read = |p: PathBuf| fs::read_to_string(p).unwrap();
+        assert_eq!(read(file.rotated_path(2)), "0123456789abc\n");
+        assert_eq!(read(file.rotated_path(1)), "second\nthird\n");
+        assert_eq!(read(path), "fourth\n");
+    }
+
+    #[test]
+    fn keeps_at_most_max_rotated() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log.json");
+        let mut file = RotatingFile::open(path, 1, 2).unwrap();
+
+        for line in &["a\n", "b\n", "c\n", "d\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+        assert!(!file.rotated_path(3).exists());
+        assert_eq!(fs::read_to_string(file.rotated_path(2)).unwrap(), "b\n");
+    }
+}
@@ -0,0 +1,281 @@
+//! Provides `SlotScheduler`, which runs periodic tasks at fixed points in chain time (e.g., halfway
+//! through each slot, or at the start of each epoch).
+//!
+//! Services register tasks rather than maintaining their own interval timers so that all periodic
+//! work stays aligned with the slot clock (e.g., when the system clock is adjusted) and so that
+//! each task is observable via the `scheduled_task_*` metrics.
+
+use crate::metrics;
+use futures::future::{self, Future};
+use slog::{crit, error};
+use slot_clock::SlotClock;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::Slot;
+
+/// A point in time within a slot, expressed as a fraction of the slot duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotFraction {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl SlotFraction {
+    /// The start of the slot.
+    pub const START: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    /// Returns the point `numerator / denominator` of the way through the slot.
+    ///
+    /// Returns `None` if the point is not within the slot.
+    pub fn new(numerator: u32, denominator: u32) -> Option<Self> {
+        if denominator > 0 && numerator < denominator {
+            Some(Self {
+                numerator,
+                denominator,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the offset from the start of a slot with the given duration.
+    pub fn offset(&self, slot_duration: Duration) -> Duration {
+        slot_duration * self.numerator / self.denominator
+    }
+}
+
+/// When a task registered with the `SlotScheduler` should run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// At the given point within every slot.
+    EverySlot(SlotFraction),
+    /// At the given point within the first slot of every epoch.
+    EveryEpoch(SlotFraction),
+}
+
+/// See the module-level documentation.
+#[derive(Clone)]
+pub struct SlotScheduler<S> {
+    executor: TaskExecutor,
+    slot_clock: S,
+    slots_per_epoch: u64,
+}
+
+impl<S: SlotClock + 'static> SlotScheduler<S> {
+    pub fn new(executor: TaskExecutor, slot_clock: S, slots_per_epoch: u64) -> Self {
+        Self {
+            executor,
+            slot_clock,
+            slots_per_epoch,
+        }
+    }
+
+    /// Spawns a task which calls `task` with the current slot according to `schedule`, until the
+    /// executor shuts down.
+    ///
+    /// `task` runs on the async executor, so it should return quickly.
+    pub fn register<F>(&self, name: &'static str, schedule: Schedule, mut task: F)
+    where
+        F: FnMut(Slot) + Send + 'static,
+    {
+        self.register_async(name, schedule, move |slot| {
+            task(slot);
+            future::ready(())
+        })
+    }
+
+    /// As per `Self::register`, but awaits the future returned by `task`. The next run is not
+    /// scheduled until the future has completed.
+    pub fn register_async<F, R>(&self, name: &'static str, schedule: Schedule, mut task: F)
+    where
+        F: FnMut(Slot) -> R + Send + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        let slot_clock = self.slot_clock.clone();
+        let slots_per_epoch = self.slots_per_epoch;
+        let log = self.executor.log().clone();
+
+        if slot_clock.slot_duration() == Duration::from_secs(0) {
+            crit!(log, "Unable to schedule task"; "task" => name, "error" => "zero slot duration");
+            return;
+        }
+
+        let future = async move {
+            let mut last_run = None;
+
+            loop {
+                let (slot, duration) =
+                    match next_run(&slot_clock, slots_per_epoch, schedule, last_run) {
+                        Some(next) => next,
+                        None => {
+                            error!(log, "Failed to read slot clock"; "task" => name);
+                            // If we can't read the slot clock, just wait another slot.
+                            sleep(slot_clock.slot_duration()).await;
+                            continue;
+                        }
+                    };
+
+                sleep(duration).await;
+
+                if let Some(delay) = slot_clock.now_duration().and_then(|now| {
+                    let scheduled = run_time(&slot_clock, schedule, slot)?;
+                    now.checked_sub(scheduled)
+                }) {
+                    metrics::observe_timer_vec(
+                        &metrics::SCHEDULED_TASK_DELAY_SECONDS,
+                        &[name],
+                        delay,
+                    );
+                }
+
+                let timer = metrics::start_timer_vec(&metrics::SCHEDULED_TASK_SECONDS, &[name]);
+                task(slot).await;
+                metrics::stop_timer(timer);
+                metrics::inc_counter_vec(&metrics::SCHEDULED_TASK_RUNS_TOTAL, &[name]);
+
+                last_run = Some(slot);
+            }
+        };
+
+        self.executor.spawn(future, name);
+    }
+}
+
+/// Returns the time at which `schedule` should run in `slot`, as a duration since the UNIX epoch.
+fn run_time<S: SlotClock>(slot_clock: &S, schedule: Schedule, slot: Slot) -> Option<Duration> {
+    let fraction = match schedule {
+        Schedule::EverySlot(fraction) | Schedule::EveryEpoch(fraction) => fraction,
+    };
+    Some(slot_clock.start_of(slot)? + fraction.offset(slot_clock.slot_duration()))
+}
+
+/// Returns the next slot in which `schedule` should run and the duration from now until it should
+/// run. The slot is always later than `last_run`, so a task which wakes early does not run twice
+/// in the same slot.
+///
+/// Prior to genesis, the first run is in the genesis slot (or epoch).
+pub fn next_run<S: SlotClock>(
+    slot_clock: &S,
+    slots_per_epoch: u64,
+    schedule: Schedule,
+    last_run: Option<Slot>,
+) -> Option<(Slot, Duration)> {
+    let now = slot_clock.now_duration()?;
+    let current_slot = slot_clock.now_or_genesis()?;
+
+    let (mut slot, step) = match schedule {
+        Schedule::EverySlot(_) => (current_slot, 1),
+        Schedule::EveryEpoch(_) => (
+            current_slot
+                .epoch(slots_per_epoch)
+                .start_slot(slots_per_epoch),
+            slots_per_epoch,
+        ),
+    };
+
+    loop {
+        let scheduled = run_time(slot_clock, schedule, slot)?;
+        if scheduled > now && last_run.map_or(true, |last_run| slot > last_run) {
+            return Some((slot, scheduled - now));
+        }
+        slot += step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+
+    const SLOTS_PER_EPOCH: u64 = 4;
+
+    /// Returns a clock with 12 second slots and genesis at 100 seconds, set to `now` seconds.
+    fn clock(now: f64) -> ManualSlotClock {
+        let clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(100),
+            Duration::from_secs(12),
+        );
+        clock.set_current_time(Duration::from_secs_f64(now));
+        clock
+    }
+
+    fn half_slot() -> SlotFraction {
+        SlotFraction::new(1, 2).unwrap()
+    }
+
+    #[test]
+    fn slot_fraction_bounds() {
+        assert!(SlotFraction::new(0, 0).is_none());
+        assert!(SlotFraction::new(3, 3).is_none());
+        assert_eq!(
+            SlotFraction::new(2, 3)
+                .unwrap()
+                .offset(Duration::from_secs(12)),
+            Duration::from_secs(8)
+        );
+    }
+
+    #[test]
+    fn every_slot() {
+        let schedule = Schedule::EverySlot(half_slot());
+
+        // Before the half-way point of slot 1.
+        assert_eq!(
+            next_run(&clock(113.0), SLOTS_PER_EPOCH, schedule, None),
+            Some((Slot::new(1), Duration::from_secs(5)))
+        );
+        // After the half-way point of slot 1.
+        assert_eq!(
+            next_run(&clock(119.0), SLOTS_PER_EPOCH, schedule, None),
+            Some((Slot::new(2), Duration::from_secs(11)))
+        );
+    }
+
+    #[test]
+    fn every_slot_does_not_repeat() {
+        let schedule = Schedule::EverySlot(SlotFraction::START);
+
+        assert_eq!(
+            next_run(&clock(111.5), SLOTS_PER_EPOCH, schedule, Some(Slot::new(1))),
+            Some((Slot::new(2), Duration::from_secs_f64(12.5)))
+        );
+    }
+
+    #[test]
+    fn every_epoch() {
+        let schedule = Schedule::EveryEpoch(SlotFraction::START);
+
+        // During slot 1 of epoch 0.
+        assert_eq!(
+            next_run(&clock(113.0), SLOTS_PER_EPOCH, schedule, None),
+            Some((Slot::new(4), Duration::from_secs(35)))
+        );
+    }
+
+    #[test]
+    fn prior_to_genesis() {
+        assert_eq!(
+            next_run(
+                &clock(90.0),
+                SLOTS_PER_EPOCH,
+                Schedule::EverySlot(SlotFraction::START),
+                None
+            ),
+            Some((Slot::new(0), Duration::from_secs(10)))
+        );
+        assert_eq!(
+            next_run(
+                &clock(90.0),
+                SLOTS_PER_EPOCH,
+                Schedule::EveryEpoch(half_slot()),
+                None
+            ),
+            Some((Slot::new(0), Duration::from_secs(16)))
+        );
+    }
+}
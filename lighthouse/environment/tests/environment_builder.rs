@@ -42,3 +42,92 @@ mod setup_eth2_config {
         }
     }
 }
+
+mod runtime_config {
+    use super::*;
+
+    #[test]
+    fn defaults_are_resolved() {
+        let environment = builder().build().expect("should build environment");
+        let config = environment.runtime_config();
+
+        assert!(config.worker_threads >= 1);
+        assert_eq!(config.max_blocking_threads, 512);
+        assert_eq!(config.thread_name_prefix, "tokio-runtime-worker");
+    }
+
+    #[test]
+    fn custom_settings() {
+        let environment = builder()
+            .tokio_worker_threads(2)
+            .expect("should set worker threads")
+            .blocking_threads(8)
+            .expect("should set blocking threads")
+            .thread_name_prefix("lighthouse")
+            .build()
+            .expect("should build environment");
+        let config = environment.runtime_config();
+
+        assert_eq!(config.worker_threads, 2);
+        assert_eq!(config.max_blocking_threads, 8);
+        assert_eq!(config.thread_name_prefix, "lighthouse");
+
+        let thread_name = environment
+            .runtime()
+            .block_on(async {
+                tokio::task::spawn_blocking(|| std::thread::current().name().map(String::from))
+                    .await
+            })
+            .expect("should run blocking task")
+            .expect("thread should be named");
+        assert!(thread_name.starts_with("lighthouse-"));
+    }
+
+    #[test]
+    fn zero_threads_is_an_error() {
+        assert!(builder().tokio_worker_threads(0).is_err());
+        assert!(builder().blocking_threads(0).is_err());
+    }
+}
+
+mod json_logger {
+    use super::*;
+    use slog::info;
+    use tempfile::tempdir;
+
+    #[test]
+    fn writes_json_records() {
+        let dir = tempdir().expect("should create temp dir");
+        let path = dir.path().join("lighthouse.json");
+
+        {
+            let mut environment = EnvironmentBuilder::v012_legacy()
+                .multi_threaded_tokio_runtime()
+                .expect("should set runtime")
+                .json_logger(path.clone(), 1_024 * 1_024, 1)
+                .expect("should set json logger")
+                .build()
+                .expect("should build environment");
+
+            info!(environment.core_context().log(), "Test record"; "slot" => 42);
+        }
+
+        let contents = std::fs::read_to_string(&path).expect("should read json log");
+        let record = contents
+            .lines()
+            .find(|line| line.contains("Test record"))
+            .expect("should contain the test record");
+
+        assert!(record.starts_with('{') && record.ends_with('}'));
+        assert!(record.contains("\"slot\":42"));
+        assert!(record.contains("\"module\":\"environment_builder::json_logger\""));
+    }
+
+    #[test]
+    fn zero_max_size_is_an_error() {
+        let dir = tempdir().expect("should create temp dir");
+        assert!(builder()
+            .json_logger(dir.path().join("lighthouse.json"), 0, 1)
+            .is_err());
+    }
+}
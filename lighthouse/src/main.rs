@@ -79,6 +79,32 @@ fn main() {
                 .possible_values(&["JSON"])
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("json-logfile")
+                .long("json-logfile")
+                .value_name("FILE")
+                .help(
+                    "File path where structured JSON logs will be written, in addition to the \
+                    terminal or --logfile output. Each line is a single JSON record.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("json-logfile-max-size")
+                .long("json-logfile-max-size")
+                .value_name("SIZE")
+                .help("The maximum size (in MB) of the JSON logfile before it is rotated.")
+                .default_value("200")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("json-logfile-keep")
+                .long("json-logfile-keep")
+                .value_name("COUNT")
+                .help("The number of rotated JSON logfiles to keep.")
+                .default_value("5")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("debug-level")
                 .long("debug-level")
@@ -89,6 +115,55 @@ fn main() {
                 .global(true)
                 .default_value("info"),
         )
+        .arg(
+            Arg::with_name("log-filter")
+                .long("log-filter")
+                .value_name("DIRECTIVES")
+                .help(
+                    "Comma-separated per-module log levels which override --debug-level, e.g. \
+                    \"network=debug,beacon_chain::attestation_verification=trace\". Modules are \
+                    Rust module paths and each directive applies to all sub-modules. The \
+                    directives can be changed at runtime via the beacon node HTTP API."
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("timeout-multiplier")
+                .long("timeout-multiplier")
+                .value_name("MULTIPLIER")
+                .help(
+                    "Multiplies the timeouts of HTTP requests to the eth1 node (beacon node) and \
+                    to the beacon node (validator client). Other deadlines, such as those of \
+                    libp2p requests or of the validator duties within a slot, are not affected. \
+                    Operators on slow hardware (e.g., a Raspberry Pi) may increase this to trade \
+                    latency for reliability. Must be at least 1. [default: 1]"
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("worker-threads")
+                .long("worker-threads")
+                .value_name("COUNT")
+                .help(
+                    "The number of threads used to run async tasks. Operators on constrained \
+                    machines may reduce this to limit resource usage. [default: number of CPUs]"
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("blocking-threads")
+                .long("blocking-threads")
+                .value_name("COUNT")
+                .help(
+                    "The maximum number of threads used to run blocking tasks, such as database \
+                    and state processing work. [default: 512]"
+                )
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("datadir")
                 .long("datadir")
@@ -214,8 +289,38 @@ fn run<E: EthSpec>(
         environment_builder.async_logger(debug_level, log_format)?
     };
 
-    let mut environment = builder
+    let builder =
+        if let Some(path) = clap_utils::parse_optional::<PathBuf>(matches, "json-logfile")? {
+            let max_size_mb: u64 = clap_utils::parse_required(matches, "json-logfile-max-size")?;
+            let keep = clap_utils::parse_required(matches, "json-logfile-keep")?;
+            builder.json_logger(path, max_size_mb.saturating_mul(1_024 * 1_024), keep)?
+        } else {
+            builder
+        };
+
+    let builder = if let Some(directives) = matches.value_of("log-filter") {
+        builder.log_directives(directives)?
+    } else {
+        builder
+    };
+
+    let builder = builder
         .multi_threaded_tokio_runtime()?
+        .thread_name_prefix("lighthouse");
+
+    let builder = if let Some(n) = clap_utils::parse_optional(matches, "worker-threads")? {
+        builder.tokio_worker_threads(n)?
+    } else {
+        builder
+    };
+
+    let builder = if let Some(n) = clap_utils::parse_optional(matches, "blocking-threads")? {
+        builder.blocking_threads(n)?
+    } else {
+        builder
+    };
+
+    let mut environment = builder
         .optional_eth2_network_config(Some(testnet_config))?
         .build()?;
 
@@ -4,6 +4,7 @@ use account_manager::{
     validator::{
         create::*,
         import::{self, CMD as IMPORT_CMD},
+        recover::{BEACON_STATE_FLAG, CMD as RECOVER_CMD, GAP_LIMIT_FLAG, MNEMONIC_FLAG},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -14,11 +15,16 @@ use account_manager::{
     CMD as ACCOUNT_CMD, WALLETS_DIR_FLAG, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
+    eth2_keystore::{keypair_from_secret, KeystoreBuilder},
+    eth2_wallet::{
+        bip39::{Language, Mnemonic, Seed},
+        recover_validator_secret_from_mnemonic, KeyType,
+    },
     validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
     ZeroizeString,
 };
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use ssz::Encode;
 use std::env;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
@@ -26,7 +32,9 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
 use tempfile::{tempdir, TempDir};
-use types::{Keypair, PublicKey};
+use types::{
+    BeaconState, Eth1Data, EthSpec, Keypair, MainnetEthSpec, PublicKey, PublicKeyBytes, Validator,
+};
 use validator_dir::ValidatorDir;
 
 // TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
@@ -594,6 +602,172 @@ fn validator_import_launchpad_password_file() {
     );
 }
 
+#[test]
+fn validator_import_password_dir() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "0x8d4b.json";
+    const NO_PASSWORD_KEYSTORE_NAME: &str = "0x9a3f.json";
+
+    let src_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keypair = Keypair::random();
+    let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // Create two keystores in the src dir, only one of which has a password file.
+    for name in &[KEYSTORE_NAME, NO_PASSWORD_KEYSTORE_NAME] {
+        File::create(src_dir.path().join(name))
+            .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+            .unwrap();
+    }
+    File::create(password_dir.path().join("0x8d4b.txt"))
+        .map(|mut file| file.write(format!("{}\n", PASSWORD).as_ref()))
+        .unwrap()
+        .unwrap();
+
+    output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(IMPORT_CMD)
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_DIR_FLAG))
+            .arg(password_dir.path().as_os_str()),
+    )
+    .unwrap();
+
+    let voting_keystore_path = dst_dir
+        .path()
+        .join(format!("0x{}", keystore.pubkey()))
+        .join(KEYSTORE_NAME);
+
+    assert!(
+        voting_keystore_path.exists(),
+        "keystore should be present in dst dir"
+    );
+
+    // Validator should be registered with slashing protection.
+    check_slashing_protection(&dst_dir, std::iter::once(keystore.public_key().unwrap()));
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+
+    let expected_def = ValidatorDefinition {
+        enabled: true,
+        description: "".into(),
+        voting_public_key: keystore.public_key().unwrap(),
+        graffiti: None,
+        signing_definition: SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore_password_path: None,
+            voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+        },
+    };
+
+    assert!(
+        defs.as_slice() == &[expected_def],
+        "only the keystore with a password file should be imported"
+    );
+}
+
+/// A valid BIP-39 mnemonic, used to recover validators.
+const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                             abandon abandon abandon about";
+
+/// Returns the voting public key at `index` of the `TEST_MNEMONIC` HD wallet.
+fn mnemonic_voting_pubkey(index: u32) -> PublicKeyBytes {
+    let mnemonic = Mnemonic::from_phrase(TEST_MNEMONIC, Language::English).unwrap();
+    let seed = Seed::new(&mnemonic, "");
+    let (secret, _) =
+        recover_validator_secret_from_mnemonic(seed.as_bytes(), index, KeyType::Voting).unwrap();
+    keypair_from_secret(secret.as_bytes())
+        .unwrap()
+        .pk
+        .compress()
+}
+
+/// Runs `validator recover --gap-limit` against a beacon state containing the validators at the
+/// `deposited` indices of the `TEST_MNEMONIC` wallet, returning the recovered voting public keys.
+fn recover_with_gap_limit(deposited: &[u32], gap_limit: u32) -> Vec<PublicKeyBytes> {
+    let dir = tempdir().unwrap();
+    let validator_dir = dir.path().join("validators");
+    let secrets_dir = dir.path().join("secrets");
+    let mnemonic_path = dir.path().join("mnemonic");
+    let state_path = dir.path().join("state.ssz");
+
+    fs::write(&mnemonic_path, TEST_MNEMONIC).unwrap();
+
+    let spec = MainnetEthSpec::default_spec();
+    let mut state = BeaconState::<MainnetEthSpec>::new(0, Eth1Data::default(), &spec);
+    for index in deposited {
+        state
+            .validators
+            .push(Validator {
+                pubkey: mnemonic_voting_pubkey(*index),
+                ..Validator::default()
+            })
+            .unwrap();
+    }
+    fs::write(&state_path, state.as_ssz_bytes()).unwrap();
+
+    let output = output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.as_os_str())
+            .arg(RECOVER_CMD)
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.as_os_str())
+            .arg(format!("--{}", MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", BEACON_STATE_FLAG))
+            .arg(state_path.as_os_str())
+            .arg(format!("--{}", GAP_LIMIT_FLAG))
+            .arg(gap_limit.to_string()),
+    )
+    .unwrap();
+
+    let recovered = from_utf8(&output.stdout)
+        .expect("stdout is not utf8")
+        .lines()
+        .map(|line| {
+            let tab = line.find('\t').expect("line must have tab");
+            line[tab + 1..]
+                .parse()
+                .expect("line must end with a pubkey")
+        })
+        .collect::<Vec<PublicKeyBytes>>();
+
+    assert_eq!(dir_validator_count(&validator_dir), recovered.len());
+
+    recovered
+}
+
+#[test]
+fn validator_recover_across_gap() {
+    // Index 2 was never deposited, but the gap is smaller than the gap limit.
+    assert_eq!(
+        recover_with_gap_limit(&[0, 1, 3], 2),
+        vec![
+            mnemonic_voting_pubkey(0),
+            mnemonic_voting_pubkey(1),
+            mnemonic_voting_pubkey(3)
+        ]
+    );
+}
+
+#[test]
+fn validator_recover_stops_at_gap_limit() {
+    // Indices 1 and 2 were never deposited, which reaches the gap limit before index 3.
+    assert_eq!(
+        recover_with_gap_limit(&[0, 3], 2),
+        vec![mnemonic_voting_pubkey(0)]
+    );
+}
+
 /// Check that all of the given pubkeys have been registered with slashing protection.
 fn check_slashing_protection(validator_dir: &TempDir, pubkeys: impl Iterator<Item = PublicKey>) {
     let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
@@ -59,7 +59,7 @@ impl<E: EthSpec> EpochTransition<E> for JustificationAndFinalization {
     fn run(state: &mut BeaconState<E>, spec: &ChainSpec) -> Result<(), EpochProcessingError> {
         let mut validator_statuses = ValidatorStatuses::new(state, spec)?;
         validator_statuses.process_attestations(state, spec)?;
-        process_justification_and_finalization(state, &validator_statuses.total_balances)
+        process_justification_and_finalization(state, &validator_statuses.total_balances, spec)
     }
 }
 
@@ -79,13 +79,8 @@ impl<E: EthSpec> EpochTransition<E> for RegistryUpdates {
 
 impl<E: EthSpec> EpochTransition<E> for Slashings {
     fn run(state: &mut BeaconState<E>, spec: &ChainSpec) -> Result<(), EpochProcessingError> {
-        let mut validator_statuses = ValidatorStatuses::new(&state, spec)?;
-        validator_statuses.process_attestations(&state, spec)?;
-        process_slashings(
-            state,
-            validator_statuses.total_balances.current_epoch(),
-            spec,
-        )?;
+        let total_active_balance = state.get_total_active_balance(spec)?;
+        process_slashings(state, total_active_balance, spec)?;
         Ok(())
     }
 }
@@ -6,7 +6,7 @@ use beacon_node::ProductionBeaconNode;
 use environment::RuntimeContext;
 use eth2::{
     reqwest::{ClientBuilder, Url},
-    BeaconNodeHttpClient,
+    BeaconNodeHttpClient, Timeouts,
 };
 use std::path::PathBuf;
 use std::time::Duration;
@@ -78,6 +78,7 @@ impl<E: EthSpec> LocalBeaconNode<E> {
         Ok(BeaconNodeHttpClient::from_components(
             beacon_node_url,
             beacon_node_http_client,
+            Timeouts::set_all(HTTP_TIMEOUT),
         ))
     }
 }
@@ -0,0 +1,340 @@
+//! An implementation of `SlashingProtectionStorage` which holds all signing history in memory.
+//!
+//! Nothing is persisted, so this backend is only suitable for testing or for validator clients
+//! which are guaranteed to never restart with the same keys (e.g. ephemeral testnets).
+use crate::storage::{SlashingProtectionStorage, StorageTransaction};
+use crate::{NotSafe, SignedAttestation, SignedBlock, SigningRoot};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use types::{Epoch, PublicKeyBytes, Slot};
+
+/// The signing history of a single validator.
+#[derive(Default)]
+struct ValidatorHistory {
+    blocks: BTreeMap<Slot, SigningRoot>,
+    /// Attestations keyed by target epoch, with values of `(source_epoch, signing_root)`.
+    attestations: BTreeMap<Epoch, (Epoch, SigningRoot)>,
+}
+
+impl ValidatorHistory {
+    fn attestation(&self, target_epoch: Epoch) -> Option<SignedAttestation> {
+        self.attestations
+            .get(&target_epoch)
+            .map(|(source_epoch, signing_root)| {
+                SignedAttestation::new(*source_epoch, target_epoch, *signing_root)
+            })
+    }
+}
+
+#[derive(Default)]
+struct InMemoryData {
+    validator_ids: HashMap<PublicKeyBytes, i64>,
+    /// The history for the validator with ID `i` is stored at index `i`.
+    histories: Vec<ValidatorHistory>,
+}
+
+impl InMemoryData {
+    fn history(&self, validator_id: i64) -> Result<&ValidatorHistory, NotSafe> {
+        self.histories
+            .get(validator_id as usize)
+            .ok_or_else(|| NotSafe::StorageError(format!("Unknown validator {}", validator_id)))
+    }
+
+    fn history_mut(&mut self, validator_id: i64) -> Result<&mut ValidatorHistory, NotSafe> {
+        self.histories
+            .get_mut(validator_id as usize)
+            .ok_or_else(|| NotSafe::StorageError(format!("Unknown validator {}", validator_id)))
+    }
+}
+
+/// See the module-level documentation.
+///
+/// Transactions are made exclusive by holding a lock for their duration. The protection rules only
+/// write after all checks have passed and none of the writes below can fail, so there is no need
+/// to roll back a transaction which returns an error.
+#[derive(Default)]
+pub struct InMemorySlashingProtection {
+    data: Mutex<InMemoryData>,
+}
+
+impl SlashingProtectionStorage for InMemorySlashingProtection {
+    fn exclusive_transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn StorageTransaction) -> Result<(), NotSafe>,
+    ) -> Result<(), NotSafe> {
+        f(&mut *self.data.lock())
+    }
+}
+
+impl StorageTransaction for InMemoryData {
+    fn get_validator_id(&self, public_key: &PublicKeyBytes) -> Result<Option<i64>, NotSafe> {
+        Ok(self.validator_ids.get(public_key).copied())
+    }
+
+    fn insert_validator(&mut self, public_key: &PublicKeyBytes) -> Result<(), NotSafe> {
+        let validator_id = self.histories.len() as i64;
+        self.validator_ids.insert(*public_key, validator_id);
+        self.histories.push(ValidatorHistory::default());
+        Ok(())
+    }
+
+    fn block_at_slot(&self, validator_id: i64, slot: Slot) -> Result<Option<SignedBlock>, NotSafe> {
+        Ok(self
+            .history(validator_id)?
+            .blocks
+            .get(&slot)
+            .map(|signing_root| SignedBlock::new(slot, *signing_root)))
+    }
+
+    fn min_block_slot(&self, validator_id: i64) -> Result<Option<Slot>, NotSafe> {
+        Ok(self.history(validator_id)?.blocks.keys().next().copied())
+    }
+
+    fn insert_block(
+        &mut self,
+        validator_id: i64,
+        slot: Slot,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe> {
+        self.history_mut(validator_id)?
+            .blocks
+            .insert(slot, signing_root);
+        Ok(())
+    }
+
+    fn attestation_with_target(
+        &self,
+        validator_id: i64,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        Ok(self.history(validator_id)?.attestation(target_epoch))
+    }
+
+    fn surrounding_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        let history = self.history(validator_id)?;
+        Ok(history
+            .attestations
+            .range(target_epoch + 1..)
+            .rev()
+            .find(|(_, (prev_source, _))| *prev_source < source_epoch)
+            .and_then(|(prev_target, _)| history.attestation(*prev_target)))
+    }
+
+    fn surrounded_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        let history = self.history(validator_id)?;
+        Ok(history
+            .attestations
+            .range(..target_epoch)
+            .rev()
+            .find(|(_, (prev_source, _))| *prev_source > source_epoch)
+            .and_then(|(prev_target, _)| history.attestation(*prev_target)))
+    }
+
+    fn min_attestation_source(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe> {
+        Ok(self
+            .history(validator_id)?
+            .attestations
+            .values()
+            .map(|(source_epoch, _)| *source_epoch)
+            .min())
+    }
+
+    fn min_attestation_target(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe> {
+        Ok(self
+            .history(validator_id)?
+            .attestations
+            .keys()
+            .next()
+            .copied())
+    }
+
+    fn insert_attestation(
+        &mut self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe> {
+        self.history_mut(validator_id)?
+            .attestations
+            .insert(target_epoch, (source_epoch, signing_root));
+        Ok(())
+    }
+
+    fn prune_blocks(&mut self, validator_id: i64, new_min_slot: Slot) -> Result<(), NotSafe> {
+        let blocks = &mut self.history_mut(validator_id)?.blocks;
+        if let Some(max_slot) = blocks.keys().next_back().copied() {
+            *blocks = blocks.split_off(&std::cmp::min(new_min_slot, max_slot));
+        }
+        Ok(())
+    }
+
+    fn prune_attestations(
+        &mut self,
+        validator_id: i64,
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe> {
+        let attestations = &mut self.history_mut(validator_id)?.attestations;
+        if let Some(max_target) = attestations.keys().next_back().copied() {
+            *attestations = attestations.split_off(&std::cmp::min(new_min_target, max_target));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signed_attestation::InvalidAttestation;
+    use crate::signed_block::InvalidBlock;
+    use crate::test_utils::pubkey;
+    use crate::{Safe, SlashingProtection};
+    use types::{AttestationData, BeaconBlockHeader, Checkpoint, Hash256};
+
+    fn protection() -> SlashingProtection {
+        let protection = SlashingProtection::from(InMemorySlashingProtection::default());
+        protection.register_validator(pubkey(0)).unwrap();
+        protection
+    }
+
+    fn block(slot: u64) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: Slot::new(slot),
+            proposer_index: 0,
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::zero(),
+        }
+    }
+
+    fn attestation(source: u64, target: u64) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(0),
+            index: 0,
+            beacon_block_root: Hash256::zero(),
+            source: Checkpoint {
+                epoch: Epoch::new(source),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target),
+                root: Hash256::zero(),
+            },
+        }
+    }
+
+    #[test]
+    fn unregistered_validator() {
+        let protection = protection();
+        assert_eq!(
+            protection.check_and_insert_block_proposal(&pubkey(1), &block(1), Hash256::zero()),
+            Err(NotSafe::UnregisteredValidator(pubkey(1)))
+        );
+        assert!(protection
+            .check_validator_registrations([pubkey(0), pubkey(1)].iter())
+            .is_err());
+    }
+
+    #[test]
+    fn double_block_proposal() {
+        let protection = protection();
+        let domain = Hash256::zero();
+
+        assert_eq!(
+            protection.check_and_insert_block_proposal(&pubkey(0), &block(2), domain),
+            Ok(Safe::Valid)
+        );
+        assert_eq!(
+            protection.check_and_insert_block_proposal(&pubkey(0), &block(2), domain),
+            Ok(Safe::SameData)
+        );
+
+        let mut conflicting = block(2);
+        conflicting.body_root = Hash256::repeat_byte(1);
+        assert!(matches!(
+            protection.check_and_insert_block_proposal(&pubkey(0), &conflicting, domain),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ));
+        assert!(matches!(
+            protection.check_and_insert_block_proposal(&pubkey(0), &block(1), domain),
+            Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn surrounding_and_double_votes() {
+        let protection = protection();
+        let domain = Hash256::zero();
+
+        assert_eq!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(2, 3), domain),
+            Ok(Safe::Valid)
+        );
+        assert_eq!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(2, 3), domain),
+            Ok(Safe::SameData)
+        );
+        assert!(matches!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(1, 3), domain),
+            Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                _
+            )))
+        ));
+        assert!(matches!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(1, 4), domain),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::NewSurroundsPrev { .. }
+            ))
+        ));
+
+        assert_eq!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(3, 6), domain),
+            Ok(Safe::Valid)
+        );
+        assert!(matches!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(4, 5), domain),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::PrevSurroundsNew { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn pruning_retains_latest() {
+        let protection = protection();
+        let domain = Hash256::zero();
+
+        for target in 1..4 {
+            protection
+                .check_and_insert_attestation(&pubkey(0), &attestation(0, target), domain)
+                .unwrap();
+        }
+
+        protection
+            .prune_all_signed_attestations([pubkey(0)].iter(), Epoch::new(10))
+            .unwrap();
+
+        assert!(matches!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(0, 3), domain),
+            Ok(Safe::SameData)
+        ));
+        assert!(matches!(
+            protection.check_and_insert_attestation(&pubkey(0), &attestation(0, 2), domain),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound { .. }
+            ))
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 mod attestation_tests;
 mod block_tests;
+mod in_memory;
 pub mod interchange;
 pub mod interchange_test;
 mod parallel_tests;
@@ -7,13 +8,16 @@ mod registration_tests;
 mod signed_attestation;
 mod signed_block;
 mod slashing_database;
+pub mod storage;
 pub mod test_utils;
 
+pub use crate::in_memory::InMemorySlashingProtection;
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::{
     InterchangeImportOutcome, SlashingDatabase, SUPPORTED_INTERCHANGE_FORMAT_VERSION,
 };
+pub use crate::storage::{SlashingProtection, SlashingProtectionStorage};
 use rusqlite::Error as SQLError;
 use std::io::{Error as IOError, ErrorKind};
 use std::string::ToString;
@@ -33,6 +37,8 @@ pub enum NotSafe {
     IOError(ErrorKind),
     SQLError(String),
     SQLPoolError(String),
+    /// An error from a storage backend other than SQLite.
+    StorageError(String),
 }
 
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
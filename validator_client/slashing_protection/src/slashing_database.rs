@@ -2,8 +2,7 @@ use crate::interchange::{
     Interchange, InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
     SignedBlock as InterchangeBlock,
 };
-use crate::signed_attestation::InvalidAttestation;
-use crate::signed_block::InvalidBlock;
+use crate::storage::{self, SlashingProtectionStorage, StorageTransaction};
 use crate::{hash256_from_row, NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
@@ -171,13 +170,7 @@ impl SlashingDatabase {
         public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
-        let mut stmt = txn.prepare("INSERT INTO validators (public_key) VALUES (?1)")?;
-        for pubkey in public_keys {
-            if self.get_validator_id_opt(&txn, pubkey)?.is_none() {
-                stmt.execute(&[pubkey.to_hex_string()])?;
-            }
-        }
-        Ok(())
+        storage::register_validators(&mut SqliteTransaction(txn), public_keys)
     }
 
     /// Check that all of the given validators are registered.
@@ -206,247 +199,7 @@ impl SlashingDatabase {
         txn: &Transaction,
         public_key: &PublicKeyBytes,
     ) -> Result<i64, NotSafe> {
-        self.get_validator_id_opt(txn, public_key)?
-            .ok_or_else(|| NotSafe::UnregisteredValidator(*public_key))
-    }
-
-    /// Optional version of `get_validator_id`.
-    fn get_validator_id_opt(
-        &self,
-        txn: &Transaction,
-        public_key: &PublicKeyBytes,
-    ) -> Result<Option<i64>, NotSafe> {
-        Ok(txn
-            .query_row(
-                "SELECT id FROM validators WHERE public_key = ?1",
-                params![&public_key.to_hex_string()],
-                |row| row.get(0),
-            )
-            .optional()?)
-    }
-
-    /// Check a block proposal from `validator_pubkey` for slash safety.
-    fn check_block_proposal(
-        &self,
-        txn: &Transaction,
-        validator_pubkey: &PublicKeyBytes,
-        slot: Slot,
-        signing_root: SigningRoot,
-    ) -> Result<Safe, NotSafe> {
-        let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
-
-        let existing_block = txn
-            .prepare(
-                "SELECT slot, signing_root
-                 FROM signed_blocks
-                 WHERE validator_id = ?1 AND slot = ?2",
-            )?
-            .query_row(params![validator_id, slot], SignedBlock::from_row)
-            .optional()?;
-
-        if let Some(existing_block) = existing_block {
-            if existing_block.signing_root == signing_root {
-                // Same slot and same hash -> we're re-broadcasting a previously signed block
-                return Ok(Safe::SameData);
-            } else {
-                // Same epoch but not the same hash -> it's a DoubleBlockProposal
-                return Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
-                    existing_block,
-                )));
-            }
-        }
-
-        let min_slot = txn
-            .prepare("SELECT MIN(slot) FROM signed_blocks WHERE validator_id = ?1")?
-            .query_row(params![validator_id], |row| row.get(0))?;
-
-        if let Some(min_slot) = min_slot {
-            if slot <= min_slot {
-                return Err(NotSafe::InvalidBlock(
-                    InvalidBlock::SlotViolatesLowerBound {
-                        block_slot: slot,
-                        bound_slot: min_slot,
-                    },
-                ));
-            }
-        }
-
-        Ok(Safe::Valid)
-    }
-
-    /// Check an attestation from `validator_pubkey` for slash safety.
-    fn check_attestation(
-        &self,
-        txn: &Transaction,
-        validator_pubkey: &PublicKeyBytes,
-        att_source_epoch: Epoch,
-        att_target_epoch: Epoch,
-        att_signing_root: SigningRoot,
-    ) -> Result<Safe, NotSafe> {
-        // Although it's not required to avoid slashing, we disallow attestations
-        // which are obviously invalid by virtue of their source epoch exceeding their target.
-        if att_source_epoch > att_target_epoch {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::SourceExceedsTarget,
-            ));
-        }
-
-        let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
-
-        // Check for a double vote. Namely, an existing attestation with the same target epoch,
-        // and a different signing root.
-        let same_target_att = txn
-            .prepare(
-                "SELECT source_epoch, target_epoch, signing_root
-                 FROM signed_attestations
-                 WHERE validator_id = ?1 AND target_epoch = ?2",
-            )?
-            .query_row(
-                params![validator_id, att_target_epoch],
-                SignedAttestation::from_row,
-            )
-            .optional()?;
-
-        if let Some(existing_attestation) = same_target_att {
-            // If the new attestation is identical to the existing attestation, then we already
-            // know that it is safe, and can return immediately.
-            if existing_attestation.signing_root == att_signing_root {
-                return Ok(Safe::SameData);
-            // Otherwise if the hashes are different, this is a double vote.
-            } else {
-                return Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
-                    existing_attestation,
-                )));
-            }
-        }
-
-        // Check that no previous vote is surrounding `attestation`.
-        // If there is a surrounding attestation, we only return the most recent one.
-        let surrounding_attestation = txn
-            .prepare(
-                "SELECT source_epoch, target_epoch, signing_root
-                 FROM signed_attestations
-                 WHERE validator_id = ?1 AND source_epoch < ?2 AND target_epoch > ?3
-                 ORDER BY target_epoch DESC
-                 LIMIT 1",
-            )?
-            .query_row(
-                params![validator_id, att_source_epoch, att_target_epoch],
-                SignedAttestation::from_row,
-            )
-            .optional()?;
-
-        if let Some(prev) = surrounding_attestation {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::PrevSurroundsNew { prev },
-            ));
-        }
-
-        // Check that no previous vote is surrounded by `attestation`.
-        // If there is a surrounded attestation, we only return the most recent one.
-        let surrounded_attestation = txn
-            .prepare(
-                "SELECT source_epoch, target_epoch, signing_root
-                 FROM signed_attestations
-                 WHERE validator_id = ?1 AND source_epoch > ?2 AND target_epoch < ?3
-                 ORDER BY target_epoch DESC
-                 LIMIT 1",
-            )?
-            .query_row(
-                params![validator_id, att_source_epoch, att_target_epoch],
-                SignedAttestation::from_row,
-            )
-            .optional()?;
-
-        if let Some(prev) = surrounded_attestation {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::NewSurroundsPrev { prev },
-            ));
-        }
-
-        // Check lower bounds: ensure that source is greater than or equal to min source,
-        // and target is greater than min target. This allows pruning, and compatibility
-        // with the interchange format.
-        let min_source = txn
-            .prepare("SELECT MIN(source_epoch) FROM signed_attestations WHERE validator_id = ?1")?
-            .query_row(params![validator_id], |row| row.get(0))?;
-
-        if let Some(min_source) = min_source {
-            if att_source_epoch < min_source {
-                return Err(NotSafe::InvalidAttestation(
-                    InvalidAttestation::SourceLessThanLowerBound {
-                        source_epoch: att_source_epoch,
-                        bound_epoch: min_source,
-                    },
-                ));
-            }
-        }
-
-        let min_target = txn
-            .prepare("SELECT MIN(target_epoch) FROM signed_attestations WHERE validator_id = ?1")?
-            .query_row(params![validator_id], |row| row.get(0))?;
-
-        if let Some(min_target) = min_target {
-            if att_target_epoch <= min_target {
-                return Err(NotSafe::InvalidAttestation(
-                    InvalidAttestation::TargetLessThanOrEqLowerBound {
-                        target_epoch: att_target_epoch,
-                        bound_epoch: min_target,
-                    },
-                ));
-            }
-        }
-
-        // Everything has been checked, return Valid
-        Ok(Safe::Valid)
-    }
-
-    /// Insert a block proposal into the slashing database.
-    ///
-    /// This should *only* be called in the same (exclusive) transaction as `check_block_proposal`
-    /// so that the check isn't invalidated by a concurrent mutation.
-    fn insert_block_proposal(
-        &self,
-        txn: &Transaction,
-        validator_pubkey: &PublicKeyBytes,
-        slot: Slot,
-        signing_root: SigningRoot,
-    ) -> Result<(), NotSafe> {
-        let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
-
-        txn.execute(
-            "INSERT INTO signed_blocks (validator_id, slot, signing_root)
-             VALUES (?1, ?2, ?3)",
-            params![validator_id, slot, signing_root.to_hash256().as_bytes()],
-        )?;
-        Ok(())
-    }
-
-    /// Insert an attestation into the slashing database.
-    ///
-    /// This should *only* be called in the same (exclusive) transaction as `check_attestation`
-    /// so that the check isn't invalidated by a concurrent mutation.
-    fn insert_attestation(
-        &self,
-        txn: &Transaction,
-        validator_pubkey: &PublicKeyBytes,
-        att_source_epoch: Epoch,
-        att_target_epoch: Epoch,
-        att_signing_root: SigningRoot,
-    ) -> Result<(), NotSafe> {
-        let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
-
-        txn.execute(
-            "INSERT INTO signed_attestations (validator_id, source_epoch, target_epoch, signing_root)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![
-                validator_id,
-                att_source_epoch,
-                att_target_epoch,
-                att_signing_root.to_hash256().as_bytes()
-            ],
-        )?;
-        Ok(())
+        storage::get_validator_id(&SqliteTransaction(txn), public_key)
     }
 
     /// Check a block proposal for slash safety, and if it is safe, record it in the database.
@@ -495,12 +248,12 @@ impl SlashingDatabase {
         signing_root: SigningRoot,
         txn: &Transaction,
     ) -> Result<Safe, NotSafe> {
-        let safe = self.check_block_proposal(&txn, validator_pubkey, slot, signing_root)?;
-
-        if safe != Safe::SameData {
-            self.insert_block_proposal(&txn, validator_pubkey, slot, signing_root)?;
-        }
-        Ok(safe)
+        storage::check_and_insert_block(
+            &mut SqliteTransaction(txn),
+            validator_pubkey,
+            slot,
+            signing_root,
+        )
     }
 
     /// Check an attestation for slash safety, and if it is safe, record it in the database.
@@ -554,24 +307,13 @@ impl SlashingDatabase {
         att_signing_root: SigningRoot,
         txn: &Transaction,
     ) -> Result<Safe, NotSafe> {
-        let safe = self.check_attestation(
-            &txn,
+        storage::check_and_insert_attestation(
+            &mut SqliteTransaction(txn),
             validator_pubkey,
             att_source_epoch,
             att_target_epoch,
             att_signing_root,
-        )?;
-
-        if safe != Safe::SameData {
-            self.insert_attestation(
-                &txn,
-                validator_pubkey,
-                att_source_epoch,
-                att_target_epoch,
-                att_signing_root,
-            )?;
-        }
-        Ok(safe)
+        )
     }
 
     /// Import slashing protection from another client in the interchange format.
@@ -761,20 +503,9 @@ impl SlashingDatabase {
         new_min_slot: Slot,
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
-        let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
-
-        txn.execute(
-            "DELETE FROM signed_blocks
-             WHERE
-                validator_id = ?1 AND
-                slot < ?2 AND
-                slot < (SELECT MAX(slot)
-                        FROM signed_blocks
-                        WHERE validator_id = ?1)",
-            params![validator_id, new_min_slot],
-        )?;
-
-        Ok(())
+        let mut txn = SqliteTransaction(txn);
+        let validator_id = storage::get_validator_id(&txn, public_key)?;
+        txn.prune_blocks(validator_id, new_min_slot)
     }
 
     /// Prune the signed blocks table for the given public keys.
@@ -807,24 +538,9 @@ impl SlashingDatabase {
         new_min_target: Epoch,
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
-        let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
-
-        // The following holds:
-        //   a.target < new_min_target --> a.source <= new_min_source
-        //
-        // The `MAX(target_epoch)` acts as a guard to prevent accidentally clearing the DB.
-        txn.execute(
-            "DELETE FROM signed_attestations
-             WHERE
-                validator_id = ?1 AND
-                target_epoch < ?2 AND
-                target_epoch < (SELECT MAX(target_epoch)
-                                FROM signed_attestations
-                                WHERE validator_id = ?1)",
-            params![validator_id, new_min_target],
-        )?;
-
-        Ok(())
+        let mut txn = SqliteTransaction(txn);
+        let validator_id = storage::get_validator_id(&txn, public_key)?;
+        txn.prune_attestations(validator_id, new_min_target)
     }
 
     /// Prune the signed attestations table for the given validator keys.
@@ -891,6 +607,209 @@ impl SlashingDatabase {
     }
 }
 
+impl SlashingProtectionStorage for SlashingDatabase {
+    fn exclusive_transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn StorageTransaction) -> Result<(), NotSafe>,
+    ) -> Result<(), NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        f(&mut SqliteTransaction(&txn))?;
+        txn.commit()?;
+        Ok(())
+    }
+}
+
+/// Implements the slashing protection storage primitives on top of an SQLite transaction.
+struct SqliteTransaction<'a>(&'a Transaction<'a>);
+
+impl<'a> StorageTransaction for SqliteTransaction<'a> {
+    fn get_validator_id(&self, public_key: &PublicKeyBytes) -> Result<Option<i64>, NotSafe> {
+        Ok(self
+            .0
+            .query_row(
+                "SELECT id FROM validators WHERE public_key = ?1",
+                params![&public_key.to_hex_string()],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn insert_validator(&mut self, public_key: &PublicKeyBytes) -> Result<(), NotSafe> {
+        self.0.execute(
+            "INSERT INTO validators (public_key) VALUES (?1)",
+            &[public_key.to_hex_string()],
+        )?;
+        Ok(())
+    }
+
+    fn block_at_slot(&self, validator_id: i64, slot: Slot) -> Result<Option<SignedBlock>, NotSafe> {
+        Ok(self
+            .0
+            .prepare(
+                "SELECT slot, signing_root
+                 FROM signed_blocks
+                 WHERE validator_id = ?1 AND slot = ?2",
+            )?
+            .query_row(params![validator_id, slot], SignedBlock::from_row)
+            .optional()?)
+    }
+
+    fn min_block_slot(&self, validator_id: i64) -> Result<Option<Slot>, NotSafe> {
+        Ok(self
+            .0
+            .prepare("SELECT MIN(slot) FROM signed_blocks WHERE validator_id = ?1")?
+            .query_row(params![validator_id], |row| row.get(0))?)
+    }
+
+    fn insert_block(
+        &mut self,
+        validator_id: i64,
+        slot: Slot,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe> {
+        self.0.execute(
+            "INSERT INTO signed_blocks (validator_id, slot, signing_root)
+             VALUES (?1, ?2, ?3)",
+            params![validator_id, slot, signing_root.to_hash256().as_bytes()],
+        )?;
+        Ok(())
+    }
+
+    fn attestation_with_target(
+        &self,
+        validator_id: i64,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        Ok(self
+            .0
+            .prepare(
+                "SELECT source_epoch, target_epoch, signing_root
+                 FROM signed_attestations
+                 WHERE validator_id = ?1 AND target_epoch = ?2",
+            )?
+            .query_row(
+                params![validator_id, target_epoch],
+                SignedAttestation::from_row,
+            )
+            .optional()?)
+    }
+
+    fn surrounding_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        Ok(self
+            .0
+            .prepare(
+                "SELECT source_epoch, target_epoch, signing_root
+                 FROM signed_attestations
+                 WHERE validator_id = ?1 AND source_epoch < ?2 AND target_epoch > ?3
+                 ORDER BY target_epoch DESC
+                 LIMIT 1",
+            )?
+            .query_row(
+                params![validator_id, source_epoch, target_epoch],
+                SignedAttestation::from_row,
+            )
+            .optional()?)
+    }
+
+    fn surrounded_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe> {
+        Ok(self
+            .0
+            .prepare(
+                "SELECT source_epoch, target_epoch, signing_root
+                 FROM signed_attestations
+                 WHERE validator_id = ?1 AND source_epoch > ?2 AND target_epoch < ?3
+                 ORDER BY target_epoch DESC
+                 LIMIT 1",
+            )?
+            .query_row(
+                params![validator_id, source_epoch, target_epoch],
+                SignedAttestation::from_row,
+            )
+            .optional()?)
+    }
+
+    fn min_attestation_source(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe> {
+        Ok(self
+            .0
+            .prepare("SELECT MIN(source_epoch) FROM signed_attestations WHERE validator_id = ?1")?
+            .query_row(params![validator_id], |row| row.get(0))?)
+    }
+
+    fn min_attestation_target(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe> {
+        Ok(self
+            .0
+            .prepare("SELECT MIN(target_epoch) FROM signed_attestations WHERE validator_id = ?1")?
+            .query_row(params![validator_id], |row| row.get(0))?)
+    }
+
+    fn insert_attestation(
+        &mut self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe> {
+        self.0.execute(
+            "INSERT INTO signed_attestations (validator_id, source_epoch, target_epoch, signing_root)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                validator_id,
+                source_epoch,
+                target_epoch,
+                signing_root.to_hash256().as_bytes()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn prune_blocks(&mut self, validator_id: i64, new_min_slot: Slot) -> Result<(), NotSafe> {
+        self.0.execute(
+            "DELETE FROM signed_blocks
+             WHERE
+                validator_id = ?1 AND
+                slot < ?2 AND
+                slot < (SELECT MAX(slot)
+                        FROM signed_blocks
+                        WHERE validator_id = ?1)",
+            params![validator_id, new_min_slot],
+        )?;
+        Ok(())
+    }
+
+    fn prune_attestations(
+        &mut self,
+        validator_id: i64,
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe> {
+        // The following holds:
+        //   a.target < new_min_target --> a.source <= new_min_source
+        //
+        // The `MAX(target_epoch)` acts as a guard to prevent accidentally clearing the DB.
+        self.0.execute(
+            "DELETE FROM signed_attestations
+             WHERE
+                validator_id = ?1 AND
+                target_epoch < ?2 AND
+                target_epoch < (SELECT MAX(target_epoch)
+                                FROM signed_attestations
+                                WHERE validator_id = ?1)",
+            params![validator_id, new_min_target],
+        )?;
+        Ok(())
+    }
+}
+
 /// Minimum and maximum slots and epochs signed by a validator.
 #[derive(Debug)]
 pub struct ValidatorSummary {
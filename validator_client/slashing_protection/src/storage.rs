@@ -0,0 +1,406 @@
+//! Decouples the slashing protection rules from the storage in which signing history is kept.
+//!
+//! A storage backend only needs to implement `SlashingProtectionStorage`, which provides a small
+//! set of primitive queries and writes executed inside an exclusive transaction. The rules which
+//! determine whether a message is safe to sign are implemented once, in this module, on top of
+//! those primitives.
+use crate::signed_attestation::InvalidAttestation;
+use crate::signed_block::InvalidBlock;
+use crate::{NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
+use std::sync::Arc;
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
+
+/// Primitive operations on the slashing protection data, performed within a single transaction.
+///
+/// `validator_id` is a storage-internal identifier obtained from `get_validator_id`. It is NOT a
+/// validator index.
+pub trait StorageTransaction {
+    /// Get the storage-internal ID for a validator, if it has been registered.
+    fn get_validator_id(&self, public_key: &PublicKeyBytes) -> Result<Option<i64>, NotSafe>;
+
+    /// Register a validator which is not already registered.
+    fn insert_validator(&mut self, public_key: &PublicKeyBytes) -> Result<(), NotSafe>;
+
+    /// Get the block signed by the validator at `slot`, if any.
+    fn block_at_slot(&self, validator_id: i64, slot: Slot) -> Result<Option<SignedBlock>, NotSafe>;
+
+    /// Get the lowest slot of any block signed by the validator.
+    fn min_block_slot(&self, validator_id: i64) -> Result<Option<Slot>, NotSafe>;
+
+    fn insert_block(
+        &mut self,
+        validator_id: i64,
+        slot: Slot,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe>;
+
+    /// Get the attestation signed by the validator with `target_epoch`, if any.
+    fn attestation_with_target(
+        &self,
+        validator_id: i64,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe>;
+
+    /// Get the attestation with the highest target epoch such that
+    /// `source_epoch < att.source_epoch && att.target_epoch > target_epoch`.
+    fn surrounding_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe>;
+
+    /// Get the attestation with the highest target epoch such that
+    /// `att.source_epoch > source_epoch && att.target_epoch < target_epoch`.
+    fn surrounded_attestation(
+        &self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    ) -> Result<Option<SignedAttestation>, NotSafe>;
+
+    /// Get the lowest source epoch of any attestation signed by the validator.
+    fn min_attestation_source(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe>;
+
+    /// Get the lowest target epoch of any attestation signed by the validator.
+    fn min_attestation_target(&self, validator_id: i64) -> Result<Option<Epoch>, NotSafe>;
+
+    fn insert_attestation(
+        &mut self,
+        validator_id: i64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: SigningRoot,
+    ) -> Result<(), NotSafe>;
+
+    /// Remove all blocks with `slot < new_min_slot`, except for the block with the highest slot.
+    fn prune_blocks(&mut self, validator_id: i64, new_min_slot: Slot) -> Result<(), NotSafe>;
+
+    /// Remove all attestations with `target_epoch < new_min_target`, except for the attestation
+    /// with the highest target epoch.
+    fn prune_attestations(
+        &mut self,
+        validator_id: i64,
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe>;
+}
+
+/// A store for the signing history of validators.
+pub trait SlashingProtectionStorage: Send + Sync {
+    /// Run `f` within a transaction, committing it if and only if `f` returns `Ok`.
+    ///
+    /// The transaction **MUST** be exclusive with respect to all other transactions on the same
+    /// data (including those from other processes or machines), otherwise slashable messages could
+    /// be checked and signed concurrently.
+    fn exclusive_transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn StorageTransaction) -> Result<(), NotSafe>,
+    ) -> Result<(), NotSafe>;
+}
+
+/// Run `f` within an exclusive transaction on `storage`, returning its result.
+fn with_transaction<T>(
+    storage: &dyn SlashingProtectionStorage,
+    mut f: impl FnMut(&mut dyn StorageTransaction) -> Result<T, NotSafe>,
+) -> Result<T, NotSafe> {
+    let mut output = None;
+    storage.exclusive_transaction(&mut |txn| {
+        output = Some(f(txn)?);
+        Ok(())
+    })?;
+    output.ok_or_else(|| NotSafe::StorageError("Transaction completed without running".into()))
+}
+
+/// Slashing protection backed by any `SlashingProtectionStorage`.
+///
+/// This is the interface used by the validator client.
+#[derive(Clone)]
+pub struct SlashingProtection {
+    storage: Arc<dyn SlashingProtectionStorage>,
+}
+
+impl<S: SlashingProtectionStorage + 'static> From<S> for SlashingProtection {
+    fn from(storage: S) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
+    }
+}
+
+impl SlashingProtection {
+    /// Register a validator with the slashing protection storage.
+    pub fn register_validator(&self, validator_pk: PublicKeyBytes) -> Result<(), NotSafe> {
+        self.register_validators(std::iter::once(&validator_pk))
+    }
+
+    /// Register multiple validators with the slashing protection storage.
+    pub fn register_validators<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+    ) -> Result<(), NotSafe> {
+        let public_keys = public_keys.collect::<Vec<_>>();
+        with_transaction(self.storage.as_ref(), |txn| {
+            register_validators(txn, public_keys.iter().copied())
+        })
+    }
+
+    /// Check that all of the given validators are registered.
+    pub fn check_validator_registrations<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+    ) -> Result<(), NotSafe> {
+        let public_keys = public_keys.collect::<Vec<_>>();
+        with_transaction(self.storage.as_ref(), |txn| {
+            public_keys
+                .iter()
+                .try_for_each(|public_key| get_validator_id(txn, public_key).map(|_| ()))
+        })
+    }
+
+    /// Check a block proposal for slash safety, and if it is safe, record it.
+    pub fn check_and_insert_block_proposal(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        let signing_root = block_header.signing_root(domain).into();
+        with_transaction(self.storage.as_ref(), |txn| {
+            check_and_insert_block(txn, validator_pubkey, block_header.slot, signing_root)
+        })
+    }
+
+    /// Check an attestation for slash safety, and if it is safe, record it.
+    pub fn check_and_insert_attestation(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        attestation: &AttestationData,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        let signing_root = attestation.signing_root(domain).into();
+        with_transaction(self.storage.as_ref(), |txn| {
+            check_and_insert_attestation(
+                txn,
+                validator_pubkey,
+                attestation.source.epoch,
+                attestation.target.epoch,
+                signing_root,
+            )
+        })
+    }
+
+    /// Prune the signed blocks of the given public keys.
+    pub fn prune_all_signed_blocks<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+        new_min_slot: Slot,
+    ) -> Result<(), NotSafe> {
+        let public_keys = public_keys.collect::<Vec<_>>();
+        with_transaction(self.storage.as_ref(), |txn| {
+            public_keys.iter().try_for_each(|public_key| {
+                let validator_id = get_validator_id(txn, public_key)?;
+                txn.prune_blocks(validator_id, new_min_slot)
+            })
+        })
+    }
+
+    /// Prune the signed attestations of the given public keys.
+    pub fn prune_all_signed_attestations<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe> {
+        let public_keys = public_keys.collect::<Vec<_>>();
+        with_transaction(self.storage.as_ref(), |txn| {
+            public_keys.iter().try_for_each(|public_key| {
+                let validator_id = get_validator_id(txn, public_key)?;
+                txn.prune_attestations(validator_id, new_min_target)
+            })
+        })
+    }
+}
+
+/// Register each validator in `public_keys` which is not already registered.
+pub fn register_validators<'a>(
+    txn: &mut dyn StorageTransaction,
+    public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+) -> Result<(), NotSafe> {
+    for public_key in public_keys {
+        if txn.get_validator_id(public_key)?.is_none() {
+            txn.insert_validator(public_key)?;
+        }
+    }
+    Ok(())
+}
+
+/// Get the storage-internal ID for a validator, erroring if it is not registered.
+pub fn get_validator_id(
+    txn: &dyn StorageTransaction,
+    public_key: &PublicKeyBytes,
+) -> Result<i64, NotSafe> {
+    txn.get_validator_id(public_key)?
+        .ok_or_else(|| NotSafe::UnregisteredValidator(*public_key))
+}
+
+/// Check a block proposal from `validator_pubkey` for slash safety.
+pub fn check_block_proposal(
+    txn: &dyn StorageTransaction,
+    validator_pubkey: &PublicKeyBytes,
+    slot: Slot,
+    signing_root: SigningRoot,
+) -> Result<Safe, NotSafe> {
+    let validator_id = get_validator_id(txn, validator_pubkey)?;
+
+    if let Some(existing_block) = txn.block_at_slot(validator_id, slot)? {
+        if existing_block.signing_root == signing_root {
+            // Same slot and same hash -> we're re-broadcasting a previously signed block
+            return Ok(Safe::SameData);
+        } else {
+            // Same epoch but not the same hash -> it's a DoubleBlockProposal
+            return Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
+                existing_block,
+            )));
+        }
+    }
+
+    if let Some(min_slot) = txn.min_block_slot(validator_id)? {
+        if slot <= min_slot {
+            return Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound {
+                    block_slot: slot,
+                    bound_slot: min_slot,
+                },
+            ));
+        }
+    }
+
+    Ok(Safe::Valid)
+}
+
+/// Check an attestation from `validator_pubkey` for slash safety.
+pub fn check_attestation(
+    txn: &dyn StorageTransaction,
+    validator_pubkey: &PublicKeyBytes,
+    att_source_epoch: Epoch,
+    att_target_epoch: Epoch,
+    att_signing_root: SigningRoot,
+) -> Result<Safe, NotSafe> {
+    // Although it's not required to avoid slashing, we disallow attestations
+    // which are obviously invalid by virtue of their source epoch exceeding their target.
+    if att_source_epoch > att_target_epoch {
+        return Err(NotSafe::InvalidAttestation(
+            InvalidAttestation::SourceExceedsTarget,
+        ));
+    }
+
+    let validator_id = get_validator_id(txn, validator_pubkey)?;
+
+    // Check for a double vote. Namely, an existing attestation with the same target epoch,
+    // and a different signing root.
+    if let Some(existing_attestation) =
+        txn.attestation_with_target(validator_id, att_target_epoch)?
+    {
+        // If the new attestation is identical to the existing attestation, then we already
+        // know that it is safe, and can return immediately.
+        if existing_attestation.signing_root == att_signing_root {
+            return Ok(Safe::SameData);
+        // Otherwise if the hashes are different, this is a double vote.
+        } else {
+            return Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                existing_attestation,
+            )));
+        }
+    }
+
+    // Check that no previous vote is surrounding `attestation`.
+    // If there is a surrounding attestation, we only return the most recent one.
+    if let Some(prev) =
+        txn.surrounding_attestation(validator_id, att_source_epoch, att_target_epoch)?
+    {
+        return Err(NotSafe::InvalidAttestation(
+            InvalidAttestation::PrevSurroundsNew { prev },
+        ));
+    }
+
+    // Check that no previous vote is surrounded by `attestation`.
+    // If there is a surrounded attestation, we only return the most recent one.
+    if let Some(prev) =
+        txn.surrounded_attestation(validator_id, att_source_epoch, att_target_epoch)?
+    {
+        return Err(NotSafe::InvalidAttestation(
+            InvalidAttestation::NewSurroundsPrev { prev },
+        ));
+    }
+
+    // Check lower bounds: ensure that source is greater than or equal to min source,
+    // and target is greater than min target. This allows pruning, and compatibility
+    // with the interchange format.
+    if let Some(min_source) = txn.min_attestation_source(validator_id)? {
+        if att_source_epoch < min_source {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: att_source_epoch,
+                    bound_epoch: min_source,
+                },
+            ));
+        }
+    }
+
+    if let Some(min_target) = txn.min_attestation_target(validator_id)? {
+        if att_target_epoch <= min_target {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: att_target_epoch,
+                    bound_epoch: min_target,
+                },
+            ));
+        }
+    }
+
+    // Everything has been checked, return Valid
+    Ok(Safe::Valid)
+}
+
+/// Check a block proposal for slash safety, and if it is safe, record it in `txn`.
+pub fn check_and_insert_block(
+    txn: &mut dyn StorageTransaction,
+    validator_pubkey: &PublicKeyBytes,
+    slot: Slot,
+    signing_root: SigningRoot,
+) -> Result<Safe, NotSafe> {
+    let safe = check_block_proposal(txn, validator_pubkey, slot, signing_root)?;
+
+    if safe != Safe::SameData {
+        let validator_id = get_validator_id(txn, validator_pubkey)?;
+        txn.insert_block(validator_id, slot, signing_root)?;
+    }
+    Ok(safe)
+}
+
+/// Check an attestation for slash safety, and if it is safe, record it in `txn`.
+pub fn check_and_insert_attestation(
+    txn: &mut dyn StorageTransaction,
+    validator_pubkey: &PublicKeyBytes,
+    att_source_epoch: Epoch,
+    att_target_epoch: Epoch,
+    att_signing_root: SigningRoot,
+) -> Result<Safe, NotSafe> {
+    let safe = check_attestation(
+        txn,
+        validator_pubkey,
+        att_source_epoch,
+        att_target_epoch,
+        att_signing_root,
+    )?;
+
+    if safe != Safe::SameData {
+        let validator_id = get_validator_id(txn, validator_pubkey)?;
+        txn.insert_attestation(
+            validator_id,
+            att_source_epoch,
+            att_target_epoch,
+            att_signing_root,
+        )?;
+    }
+    Ok(safe)
+}
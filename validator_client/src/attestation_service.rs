@@ -1,12 +1,14 @@
 use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::slot_timings::SlotTimings;
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
     http_metrics::metrics,
     validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
+use eth2::types::StateId;
 use futures::future::FutureExt;
-use slog::{crit, error, info, trace};
+use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -14,8 +16,7 @@ use std::sync::Arc;
 use tokio::time::{sleep, sleep_until, Duration, Instant};
 use tree_hash::TreeHash;
 use types::{
-    AggregateSignature, Attestation, AttestationData, BitList, ChainSpec, CommitteeIndex, EthSpec,
-    Slot,
+    AggregateSignature, Attestation, AttestationData, BitList, CommitteeIndex, Epoch, EthSpec, Slot,
 };
 
 /// Builds an `AttestationService`.
@@ -23,6 +24,7 @@ pub struct AttestationServiceBuilder<T, E: EthSpec> {
     duties_service: Option<Arc<DutiesService<T, E>>>,
     validator_store: Option<ValidatorStore<T, E>>,
     slot_clock: Option<T>,
+    slot_timings: Option<SlotTimings>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
 }
@@ -33,6 +35,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             duties_service: None,
             validator_store: None,
             slot_clock: None,
+            slot_timings: None,
             beacon_nodes: None,
             context: None,
         }
@@ -53,6 +56,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn slot_timings(mut self, slot_timings: SlotTimings) -> Self {
+        self.slot_timings = Some(slot_timings);
+        self
+    }
+
     pub fn beacon_nodes(mut self, beacon_nodes: Arc<BeaconNodeFallback<T, E>>) -> Self {
         self.beacon_nodes = Some(beacon_nodes);
         self
@@ -75,6 +83,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 slot_clock: self
                     .slot_clock
                     .ok_or("Cannot build AttestationService without slot_clock")?,
+                slot_timings: self
+                    .slot_timings
+                    .ok_or("Cannot build AttestationService without slot_timings")?,
                 beacon_nodes: self
                     .beacon_nodes
                     .ok_or("Cannot build AttestationService without beacon_nodes")?,
@@ -91,11 +102,13 @@ pub struct Inner<T, E: EthSpec> {
     duties_service: Arc<DutiesService<T, E>>,
     validator_store: ValidatorStore<T, E>,
     slot_clock: T,
+    slot_timings: SlotTimings,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators at the attestation offset into each
+/// slot (1/3rd of the way through, by default).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -122,10 +135,11 @@ impl<T, E: EthSpec> Deref for AttestationService<T, E> {
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     /// Starts the service which periodically produces attestations.
-    pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
+    pub fn start_update_service(self) -> Result<(), String> {
         let log = self.context.log().clone();
 
-        let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+        let slot_duration = self.slot_timings.slot_duration;
+        let attestation_offset = self.slot_timings.attestation;
         let duration_to_next_slot = self
             .slot_clock
             .duration_to_next_slot()
@@ -134,7 +148,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         info!(
             log,
             "Attestation production service started";
-            "next_update_millis" => duration_to_next_slot.as_millis()
+            "next_update_millis" => duration_to_next_slot.as_millis(),
+            "attestation_offset_millis" => attestation_offset.as_millis(),
+            "aggregation_offset_millis" => self.slot_timings.aggregation.as_millis(),
         );
 
         let executor = self.context.executor.clone();
@@ -142,10 +158,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         let interval_fut = async move {
             loop {
                 if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
-                    sleep(duration_to_next_slot + slot_duration / 3).await;
+                    sleep(duration_to_next_slot + attestation_offset).await;
                     let log = self.context.log();
 
-                    if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
+                    if let Err(e) = self.spawn_attestation_tasks() {
                         crit!(
                             log,
                             "Failed to spawn attestation tasks";
@@ -172,18 +188,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
     /// For each each required attestation, spawn a new task that downloads, signs and uploads the
     /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
+    fn spawn_attestation_tasks(&self) -> Result<(), String> {
         let slot = self.slot_clock.now().ok_or("Failed to read slot clock")?;
         let duration_to_next_slot = self
             .slot_clock
             .duration_to_next_slot()
             .ok_or("Unable to determine duration to next slot")?;
 
-        // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
+        // If a validator needs to publish an aggregate attestation, they must do so at the
+        // aggregation offset into the slot (2/3rds by default). This delay triggers at this time.
         let aggregate_production_instant = Instant::now()
-            + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
+            + (duration_to_next_slot + self.slot_timings.aggregation)
+                .checked_sub(self.slot_timings.slot_duration)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
@@ -197,6 +213,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 map
             });
 
+        // A passive validator client in an active/passive pair must not attest.
+        let duties_by_committee_index = if self.validator_store.signing_enabled() {
+            duties_by_committee_index
+        } else {
+            debug!(
+                self.context.log(),
+                "Skipping attestations without failover lease";
+                "slot" => slot,
+            );
+            HashMap::new()
+        };
+
         // For each committee index for this slot:
         //
         // - Create and publish an `Attestation` for all required validators.
@@ -277,7 +305,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         // If an attestation was produced, make an aggregate.
         if let Some(attestation_data) = attestation_opt {
             // First, wait until the `aggregation_production_instant` (2/3rds
-            // of the way though the slot). As verified in the
+            // of the way though the slot, by default). As verified in the
             // `delay_triggers_when_in_the_past` test, this code will still run
             // even if the instant has already elapsed.
             sleep_until(aggregate_production_instant).await;
@@ -548,22 +576,44 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
     /// Spawn a blocking task to run the slashing protection pruning process.
     ///
-    /// Start the task at `pruning_instant` to avoid interference with other tasks.
+    /// Start the task at `pruning_instant` to avoid interference with other tasks. Since pruning is
+    /// relative to the finalized epoch, the beacon node is only queried once per epoch, and not at
+    /// all if pruning is disabled.
     fn spawn_slashing_protection_pruning_task(&self, slot: Slot, pruning_instant: Instant) {
+        if !self.validator_store.slashing_protection_pruning_enabled()
+            || slot % E::slots_per_epoch() != 0
+        {
+            return;
+        }
+
         let attestation_service = self.clone();
         let executor = self.inner.context.executor.clone();
-        let current_epoch = slot.epoch(E::slots_per_epoch());
+        let log = self.context.log().clone();
 
         // Wait for `pruning_instant` in a regular task, and then switch to a blocking one.
         self.inner.context.executor.spawn(
             async move {
                 sleep_until(pruning_instant).await;
 
+                let finalized_epoch =
+                    match get_finalized_epoch(&*attestation_service.beacon_nodes).await {
+                        Ok(epoch) => epoch,
+                        Err(e) => {
+                            warn!(
+                                log,
+                                "Skipping slashing protection pruning";
+                                "reason" => "unable to fetch finalized epoch",
+                                "error" => e,
+                            );
+                            return;
+                        }
+                    };
+
                 executor.spawn_blocking(
                     move || {
                         attestation_service
                             .validator_store
-                            .prune_slashing_protection_db(current_epoch, false)
+                            .prune_slashing_protection_db(finalized_epoch, false)
                     },
                     "slashing_protection_pruning",
                 )
@@ -573,6 +623,23 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     }
 }
 
+/// Returns the finalized epoch of the head state of the first available beacon node.
+pub async fn get_finalized_epoch<T: SlotClock, E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<T, E>,
+) -> Result<Epoch, String> {
+    beacon_nodes
+        .first_success(RequireSynced::No, |beacon_node| async move {
+            beacon_node
+                .get_beacon_states_finality_checkpoints(StateId::Head)
+                .await
+                .map_err(|e| format!("{:?}", e))?
+                .ok_or_else(|| "head state not found".to_string())
+                .map(|response| response.data.finalized.epoch)
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
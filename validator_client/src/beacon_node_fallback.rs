@@ -3,10 +3,17 @@
 //! succeed.
 
 use crate::check_synced::check_synced;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use crate::http_metrics::metrics::{
+    inc_counter_vec, set_gauge, BEACON_NODES_AVAILABLE, BEACON_NODES_PRIMARY_INDEX,
+    BEACON_NODES_SYNCED, BEACON_NODES_TOTAL, ENDPOINT_ERRORS, ENDPOINT_REQUESTS,
+};
+use crate::request_cache::RequestCache;
 use environment::RuntimeContext;
+use eth2::types::GenesisData;
 use eth2::BeaconNodeHttpClient;
 use futures::future;
+use parking_lot::Mutex;
+use rand::Rng;
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt;
@@ -16,7 +23,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::{sync::RwLock, time::sleep};
-use types::{ChainSpec, EthSpec};
+use types::{ChainSpec, EthSpec, YamlConfig};
 
 /// The number of seconds *prior* to slot start that we will try and update the state of fallback
 /// nodes.
@@ -27,6 +34,13 @@ use types::{ChainSpec, EthSpec};
 /// having the correct nodes up and running prior to the start of the slot.
 const SLOT_LOOKAHEAD: Duration = Duration::from_secs(1);
 
+/// The minimum delay between attempts in `BeaconNodeFallback::first_success_with_retry`.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// The upper bound on the random delay added to `RETRY_BASE_DELAY`, so that many validator clients
+/// which lost their beacon nodes at the same moment don't all retry in lock-step.
+const RETRY_MAX_JITTER: Duration = Duration::from_millis(400);
+
 /// Starts a service that will routinely try and update the status of the provided `beacon_nodes`.
 ///
 /// See `SLOT_LOOKAHEAD` for information about when this should run.
@@ -121,11 +135,29 @@ pub enum CandidateError {
     NotSynced,
 }
 
+/// A summary of the status of all the candidates in a `BeaconNodeFallback`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeaconNodesStatus {
+    /// The count of candidates, regardless of their state.
+    pub total: usize,
+    /// The count of candidates that are online and compatible, but not necessarily synced.
+    pub available: usize,
+    /// The count of synced and ready candidates.
+    pub synced: usize,
+    /// The position of the candidate which requests will be sent to first, if any are synced.
+    pub primary_index: Option<usize>,
+    /// The endpoint of the candidate at `primary_index`.
+    pub primary: Option<String>,
+}
+
 /// Represents a `BeaconNodeHttpClient` inside a `BeaconNodeFallback` that may or may not be used
 /// for a query.
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    /// The spec is immutable whilst the node stays online, so avoid downloading it each time the
+    /// node is checked for compatibility.
+    config_spec: RequestCache<(), YamlConfig>,
     _phantom: PhantomData<E>,
 }
 
@@ -135,6 +167,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            config_spec: RequestCache::immutable(),
             _phantom: PhantomData,
         }
     }
@@ -150,8 +183,12 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
     }
 
     /// Indicate that `self` is offline.
+    ///
+    /// The node may have been restarted with a different configuration whilst it was offline, so
+    /// any cached spec is dropped.
     pub async fn set_offline(&self) {
-        *self.status.write().await = Err(CandidateError::Offline)
+        *self.status.write().await = Err(CandidateError::Offline);
+        self.config_spec.invalidate(&());
     }
 
     /// Perform some queries against the node to determine if it is a good candidate, updating
@@ -210,8 +247,13 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
     /// Checks if the node has the correct specification.
     async fn is_compatible(&self, spec: &ChainSpec, log: &Logger) -> Result<(), CandidateError> {
         let yaml_config = self
-            .beacon_node
-            .get_config_spec()
+            .config_spec
+            .get_or_try_fetch((), || async {
+                self.beacon_node
+                    .get_config_spec()
+                    .await
+                    .map(|response| response.data)
+            })
             .await
             .map_err(|e| {
                 error!(
@@ -221,8 +263,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
                     "endpoint" => %self.beacon_node,
                 );
                 CandidateError::Offline
-            })?
-            .data;
+            })?;
 
         let beacon_node_spec = yaml_config
             .apply_to_chain_spec::<E>(&E::default_spec())
@@ -277,6 +318,10 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 /// identical query.
 pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
+    /// Genesis can never change, so it only needs to be downloaded once.
+    genesis: RequestCache<(), GenesisData>,
+    /// The most recent result of `Self::update_status`, used to detect changes.
+    last_status: Mutex<Option<BeaconNodesStatus>>,
     slot_clock: Option<T>,
     spec: ChainSpec,
     log: Logger,
@@ -286,6 +331,8 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     pub fn new(candidates: Vec<CandidateBeaconNode<E>>, spec: ChainSpec, log: Logger) -> Self {
         Self {
             candidates,
+            genesis: RequestCache::immutable(),
+            last_status: Mutex::new(None),
             slot_clock: None,
             spec,
             log,
@@ -306,26 +353,88 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         self.candidates.len()
     }
 
-    /// The count of synced and ready candidates.
-    pub async fn num_synced(&self) -> usize {
+    /// The count of candidates that are online and compatible, but not necessarily synced.
+    pub async fn num_available(&self) -> usize {
         let mut n = 0;
         for candidate in &self.candidates {
-            if candidate.status(RequireSynced::Yes).await.is_ok() {
+            if candidate.status(RequireSynced::No).await.is_ok() {
                 n += 1
             }
         }
         n
     }
 
-    /// The count of candidates that are online and compatible, but not necessarily synced.
-    pub async fn num_available(&self) -> usize {
-        let mut n = 0;
-        for candidate in &self.candidates {
+    /// Returns a summary of the status of all candidates.
+    ///
+    /// Candidates are tried in order, so the primary is the first synced candidate.
+    pub async fn status(&self) -> BeaconNodesStatus {
+        let mut status = BeaconNodesStatus {
+            total: self.candidates.len(),
+            available: 0,
+            synced: 0,
+            primary_index: None,
+            primary: None,
+        };
+
+        for (i, candidate) in self.candidates.iter().enumerate() {
             if candidate.status(RequireSynced::No).await.is_ok() {
-                n += 1
+                status.available += 1;
+            }
+            if candidate.status(RequireSynced::Yes).await.is_ok() {
+                status.synced += 1;
+                if status.primary_index.is_none() {
+                    status.primary_index = Some(i);
+                    status.primary = Some(candidate.beacon_node.to_string());
+                }
             }
         }
-        n
+
+        status
+    }
+
+    /// As per `Self::status`, but also updates the beacon node metrics and logs any change in the
+    /// primary candidate or the number of synced candidates since the previous call.
+    pub async fn update_status(&self) -> BeaconNodesStatus {
+        let status = self.status().await;
+
+        set_gauge(&BEACON_NODES_TOTAL, status.total as i64);
+        set_gauge(&BEACON_NODES_AVAILABLE, status.available as i64);
+        set_gauge(&BEACON_NODES_SYNCED, status.synced as i64);
+        set_gauge(
+            &BEACON_NODES_PRIMARY_INDEX,
+            status.primary_index.map_or(-1, |i| i as i64),
+        );
+
+        let previous = self.last_status.lock().replace(status.clone());
+        if let Some(previous) = previous {
+            if previous.primary != status.primary {
+                warn!(
+                    self.log,
+                    "Primary beacon node changed";
+                    "previous" => previous.primary.as_deref().unwrap_or("none"),
+                    "new" => status.primary.as_deref().unwrap_or("none"),
+                );
+            }
+            if previous.synced > status.synced {
+                warn!(
+                    self.log,
+                    "Fewer synced beacon nodes";
+                    "total" => status.total,
+                    "previous_synced" => previous.synced,
+                    "synced" => status.synced,
+                );
+            } else if previous.synced < status.synced {
+                info!(
+                    self.log,
+                    "More synced beacon nodes";
+                    "total" => status.total,
+                    "previous_synced" => previous.synced,
+                    "synced" => status.synced,
+                );
+            }
+        }
+
+        status
     }
 
     /// Loop through any `self.candidates` that we don't think are online, compatible or synced and
@@ -458,4 +567,120 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // There were no candidates already ready and we were unable to make any of them ready.
         Err(AllErrored(errors))
     }
+
+    /// As per `first_success`, however if all candidates fail then retry up to `retries` more
+    /// times, waiting `RETRY_BASE_DELAY` plus some random jitter between attempts.
+    ///
+    /// Intended for requests where a late response is far more useful than no response (e.g.,
+    /// duties).
+    pub async fn first_success_with_retry<'a, F, O, Err, R>(
+        &'a self,
+        require_synced: RequireSynced,
+        retries: usize,
+        func: F,
+    ) -> Result<O, AllErrored<Err>>
+    where
+        F: Fn(&'a BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.first_success(require_synced, &func).await {
+                Ok(val) => return Ok(val),
+                Err(e) if attempt >= retries => return Err(e),
+                Err(e) => {
+                    attempt += 1;
+
+                    let jitter =
+                        rand::thread_rng().gen_range(0, RETRY_MAX_JITTER.as_millis() as u64);
+                    let delay = RETRY_BASE_DELAY + Duration::from_millis(jitter);
+
+                    debug!(
+                        self.log,
+                        "Retrying beacon node request";
+                        "error" => %e,
+                        "attempt" => attempt,
+                        "delay_ms" => delay.as_millis(),
+                    );
+
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Returns the genesis data from the first available candidate.
+    ///
+    /// The result is cached after the first successful request.
+    pub async fn genesis(&self) -> Result<GenesisData, AllErrored<eth2::Error>> {
+        self.genesis
+            .get_or_try_fetch((), || async {
+                self.first_success(RequireSynced::No, |node| async move {
+                    node.get_beacon_genesis().await
+                })
+                .await
+                .map(|response| response.data)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use eth2::{Timeouts, Url};
+    use slot_clock::TestingSlotClock;
+    use types::MainnetEthSpec;
+
+    fn fallback(n: usize) -> BeaconNodeFallback<TestingSlotClock, MainnetEthSpec> {
+        let candidates = (0..n)
+            .map(|i| {
+                let url = Url::parse(&format!("http://localhost:{}", 5052 + i)).unwrap();
+                CandidateBeaconNode::new(BeaconNodeHttpClient::new(
+                    url,
+                    Timeouts::set_all(Duration::from_secs(1)),
+                ))
+            })
+            .collect();
+        BeaconNodeFallback::new(
+            candidates,
+            MainnetEthSpec::default_spec(),
+            null_logger().unwrap(),
+        )
+    }
+
+    async fn set_status(
+        fallback: &BeaconNodeFallback<TestingSlotClock, MainnetEthSpec>,
+        i: usize,
+        status: Result<(), CandidateError>,
+    ) {
+        *fallback.candidates[i].status.write().await = status;
+    }
+
+    #[tokio::test]
+    async fn primary_is_first_synced_candidate() {
+        let fallback = fallback(3);
+
+        let status = fallback.update_status().await;
+        assert_eq!(status.total, 3);
+        assert_eq!(status.available, 0);
+        assert_eq!(status.synced, 0);
+        assert_eq!(status.primary_index, None);
+
+        set_status(&fallback, 0, Err(CandidateError::NotSynced)).await;
+        set_status(&fallback, 1, Ok(())).await;
+        set_status(&fallback, 2, Ok(())).await;
+
+        let status = fallback.update_status().await;
+        assert_eq!(status.available, 3);
+        assert_eq!(status.synced, 2);
+        assert_eq!(status.primary_index, Some(1));
+        assert_eq!(
+            status.primary,
+            Some(fallback.candidates[1].beacon_node.to_string())
+        );
+        assert_eq!(*fallback.last_status.lock(), Some(status));
+    }
 }
@@ -179,6 +179,15 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             return Ok(());
         }
 
+        if !self.validator_store.signing_enabled() {
+            debug!(
+                log,
+                "Not producing block without failover lease";
+                "slot" => slot.as_u64(),
+            );
+            return Ok(());
+        }
+
         trace!(
             log,
             "Block service update started";
@@ -78,6 +78,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                      misplace your database and then run with this flag you risk being slashed."
                 )
         )
+        .arg(
+            Arg::with_name("slashing-protection-history-epochs")
+                .long("slashing-protection-history-epochs")
+                .value_name("EPOCHS")
+                .help("Prune signed blocks and attestations from more than this many epochs \
+                    prior to the finalized epoch from the slashing protection database, keeping \
+                    at least the most recent record for each validator. By default the full \
+                    history is kept.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-auto-discover")
             .long("disable-auto-discover")
@@ -94,6 +104,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                       node is not synced.",
                 ),
         )
+        .arg(
+            Arg::with_name("use-long-timeouts")
+                .long("use-long-timeouts")
+                .help("If present, the validator client will use longer timeouts for requests \
+                        made to the beacon node. This flag is generally not recommended, \
+                        longer timeouts can cause missed duties when fallbacks are used.")
+                .takes_value(false),
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::with_name("graffiti")
@@ -110,6 +128,55 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .conflicts_with("graffiti")
         )
+        /* Intra-slot timing related arguments */
+        .arg(
+            Arg::with_name("attestation-offset-ms")
+                .long("attestation-offset-ms")
+                .value_name("MILLISECONDS")
+                .help("The number of milliseconds after the start of each slot at which \
+                    attestations are produced. Defaults to 1/3rd of the slot duration.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("aggregation-offset-ms")
+                .long("aggregation-offset-ms")
+                .value_name("MILLISECONDS")
+                .help("The number of milliseconds after the start of each slot at which \
+                    aggregate attestations are produced. Must be greater than the attestation \
+                    offset. Defaults to 2/3rds of the slot duration.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("duties-offset-ms")
+                .long("duties-offset-ms")
+                .value_name("MILLISECONDS")
+                .help("The number of milliseconds after the start of each slot at which \
+                    attester duties are polled from the beacon node. Proposer duties are always \
+                    polled at the start of the slot. Defaults to 0.")
+                .takes_value(true),
+        )
+        /* Active/passive failover */
+        .arg(
+            Arg::with_name("failover-lease-file")
+                .long("failover-lease-file")
+                .value_name("PATH")
+                .help("Path to a lease file shared with one or more other validator clients \
+                    managing the same validators. Only the validator client holding the lease \
+                    will sign messages, the others will take over if it stops renewing the \
+                    lease. Each validator client must still use its own slashing protection \
+                    database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("failover-lease-expiry")
+                .long("failover-lease-expiry")
+                .value_name("SECONDS")
+                .help("The number of seconds a failover lease remains valid without being \
+                    renewed. Another validator client may take over after this period. \
+                    Defaults to 36.")
+                .requires("failover-lease-file")
+                .takes_value(true),
+        )
         /* REST API related arguments */
         .arg(
             Arg::with_name("http")
@@ -1,4 +1,6 @@
+use crate::failover_lease::{FailoverLeaseConfig, DEFAULT_LEASE_EXPIRY};
 use crate::graffiti_file::GraffitiFile;
+use crate::slot_timings::SlotOffsets;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_required};
@@ -12,6 +14,7 @@ use slog::{info, warn, Logger};
 use std::fs;
 use std::net::Ipv4Addr;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::GRAFFITI_BYTES_LEN;
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
@@ -32,12 +35,22 @@ pub struct Config {
     pub allow_unsynced_beacon_node: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
+    /// If true, use longer timeouts for requests made to the beacon node.
+    pub use_long_timeouts: bool,
+    /// Multiplies the timeouts of requests made to the beacon node, for slow hardware.
+    pub timeout_multiplier: u32,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// The number of epochs of slashing protection history to keep, or `None` to keep it all.
+    pub slashing_protection_history_epochs: Option<u64>,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Option<Graffiti>,
     /// Graffiti file to load per validator graffitis.
     pub graffiti_file: Option<GraffitiFile>,
+    /// Overrides for the offsets into each slot at which duties are performed.
+    pub slot_offsets: SlotOffsets,
+    /// If set, only sign messages whilst holding the given active/passive failover lease.
+    pub failover_lease: Option<FailoverLeaseConfig>,
     /// Configuration for the HTTP REST API.
     pub http_api: http_api::Config,
     /// Configuration for the HTTP REST API.
@@ -61,9 +74,14 @@ impl Default for Config {
             beacon_nodes: vec![DEFAULT_BEACON_NODE.to_string()],
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
+            use_long_timeouts: false,
+            timeout_multiplier: 1,
             init_slashing_protection: false,
+            slashing_protection_history_epochs: None,
             graffiti: None,
             graffiti_file: None,
+            slot_offsets: <_>::default(),
+            failover_lease: None,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
         }
@@ -143,6 +161,22 @@ impl Config {
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
         config.init_slashing_protection = cli_args.is_present("init-slashing-protection");
+        config.use_long_timeouts = cli_args.is_present("use-long-timeouts");
+
+        if let Some(epochs) = parse_optional::<u64>(cli_args, "slashing-protection-history-epochs")?
+        {
+            if epochs == 0 {
+                return Err("--slashing-protection-history-epochs must be at least 1".to_string());
+            }
+            config.slashing_protection_history_epochs = Some(epochs);
+        }
+
+        if let Some(timeout_multiplier) = parse_optional(cli_args, "timeout-multiplier")? {
+            if timeout_multiplier == 0 {
+                return Err("--timeout-multiplier must be at least 1".to_string());
+            }
+            config.timeout_multiplier = timeout_multiplier;
+        }
 
         if let Some(graffiti_file_path) = cli_args.value_of("graffiti-file") {
             let mut graffiti_file = GraffitiFile::new(graffiti_file_path.into());
@@ -172,6 +206,36 @@ impl Config {
             }
         }
 
+        /*
+         * Intra-slot timings
+         */
+
+        if let Some(millis) = parse_optional::<u64>(cli_args, "attestation-offset-ms")? {
+            config.slot_offsets.attestation = Some(Duration::from_millis(millis));
+        }
+
+        if let Some(millis) = parse_optional::<u64>(cli_args, "aggregation-offset-ms")? {
+            config.slot_offsets.aggregation = Some(Duration::from_millis(millis));
+        }
+
+        if let Some(millis) = parse_optional::<u64>(cli_args, "duties-offset-ms")? {
+            config.slot_offsets.duties = Some(Duration::from_millis(millis));
+        }
+
+        /*
+         * Active/passive failover
+         */
+
+        if let Some(path) = parse_optional::<PathBuf>(cli_args, "failover-lease-file")? {
+            let expiry = parse_optional::<u64>(cli_args, "failover-lease-expiry")?
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_LEASE_EXPIRY);
+            if expiry.as_secs() == 0 {
+                return Err("The --failover-lease-expiry must be greater than zero".into());
+            }
+            config.failover_lease = Some(FailoverLeaseConfig { path, expiry });
+        }
+
         /*
          * Http API server
          */
@@ -227,3 +291,49 @@ impl Config {
         Ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+    use environment::null_logger;
+    use tempfile::tempdir;
+
+    /// Parses `args` as the validator client's CLI arguments, with the global
+    /// `--timeout-multiplier` flag which is defined by the `lighthouse` binary.
+    fn config_from_args(args: &[&str]) -> Result<Config, String> {
+        let dir = tempdir().unwrap();
+        let validators_dir = dir.path().join("validators");
+        let secrets_dir = dir.path().join("secrets");
+        let matches = crate::cli_app()
+            .arg(
+                Arg::with_name("timeout-multiplier")
+                    .long("timeout-multiplier")
+                    .takes_value(true),
+            )
+            .get_matches_from(
+                [
+                    "validator_client",
+                    "--validators-dir",
+                    validators_dir.to_str().unwrap(),
+                    "--secrets-dir",
+                    secrets_dir.to_str().unwrap(),
+                ]
+                .iter()
+                .chain(args),
+            );
+        Config::from_cli(&matches, &null_logger().unwrap())
+    }
+
+    #[test]
+    fn timeout_multiplier() {
+        assert_eq!(config_from_args(&[]).unwrap().timeout_multiplier, 1);
+        assert_eq!(
+            config_from_args(&["--timeout-multiplier", "3"])
+                .unwrap()
+                .timeout_multiplier,
+            3
+        );
+        assert!(config_from_args(&["--timeout-multiplier", "0"]).is_err());
+    }
+}
@@ -7,11 +7,15 @@
 //! block production.
 
 use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::request_cache::RequestCache;
+use crate::slot_timings::SlotTimings;
 use crate::{
     block_service::BlockServiceNotification, http_metrics::metrics, validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
-use eth2::types::{AttesterData, BeaconCommitteeSubscription, ProposerData, StateId, ValidatorId};
+use eth2::types::{
+    AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
+};
 use parking_lot::RwLock;
 use safe_arith::ArithError;
 use slog::{debug, error, info, warn, Logger};
@@ -32,6 +36,11 @@ const SUBSCRIPTION_BUFFER_SLOTS: u64 = 2;
 /// Only retain `HISTORICAL_DUTIES_EPOCHS` duties prior to the current epoch.
 const HISTORICAL_DUTIES_EPOCHS: u64 = 2;
 
+/// The number of times to retry downloading attester duties if all beacon nodes fail.
+///
+/// Attester duties are downloaded ahead of time, so we can afford a short (jittered) delay.
+const ATTESTER_DUTIES_RETRIES: usize = 1;
+
 #[derive(Debug)]
 pub enum Error {
     UnableToReadSlotClock,
@@ -85,6 +94,7 @@ type DependentRoot = Hash256;
 type AttesterMap = HashMap<PublicKeyBytes, HashMap<Epoch, (DependentRoot, DutyAndProof)>>;
 type ProposerMap = HashMap<Epoch, (DependentRoot, Vec<ProposerData>)>;
 type IndicesMap = HashMap<PublicKeyBytes, u64>;
+type AttesterDutiesRequests = RequestCache<(Epoch, Vec<u64>), DutiesResponse<Vec<AttesterData>>>;
 
 /// See the module-level documentation.
 pub struct DutiesService<T, E: EthSpec> {
@@ -96,10 +106,15 @@ pub struct DutiesService<T, E: EthSpec> {
     /// Maps a public key to a validator index. There is a task which ensures this map is kept
     /// up-to-date.
     pub indices: RwLock<IndicesMap>,
+    /// Deduplicates concurrent requests for identical attester duties, which may occur when a
+    /// slow beacon node causes one update to overlap with the next.
+    pub attester_duties_requests: AttesterDutiesRequests,
     /// Provides the canonical list of locally-managed validators.
     pub validator_store: ValidatorStore<T, E>,
     /// Tracks the current slot.
     pub slot_clock: T,
+    /// Determines when attester duties are polled within each slot.
+    pub slot_timings: SlotTimings,
     /// Provides HTTP access to remote beacon nodes.
     pub beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     /// Controls whether or not this function will refuse to interact with non-synced beacon nodes.
@@ -246,7 +261,7 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
         async move {
             loop {
                 if let Some(duration) = duties_service.slot_clock.duration_to_next_slot() {
-                    sleep(duration).await;
+                    sleep(duration + duties_service.slot_timings.duties).await;
                 } else {
                     // Just sleep for one slot if we are unable to read the system clock, this gives
                     // us an opportunity for the clock to eventually come good.
@@ -507,14 +522,23 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
     );
 
     let response = duties_service
-        .beacon_nodes
-        .first_success(duties_service.require_synced, |beacon_node| async move {
-            beacon_node
-                .post_validator_duties_attester(epoch, local_indices)
+        .attester_duties_requests
+        .get_or_try_fetch((epoch, local_indices.to_vec()), || async {
+            duties_service
+                .beacon_nodes
+                .first_success_with_retry(
+                    duties_service.require_synced,
+                    ATTESTER_DUTIES_RETRIES,
+                    |beacon_node| async move {
+                        beacon_node
+                            .post_validator_duties_attester(epoch, local_indices)
+                            .await
+                    },
+                )
                 .await
+                .map_err(|e| Error::FailedToDownloadAttesters(e.to_string()))
         })
-        .await
-        .map_err(|e| Error::FailedToDownloadAttesters(e.to_string()))?;
+        .await?;
 
     drop(fetch_timer);
     let _store_timer = metrics::start_timer_vec(
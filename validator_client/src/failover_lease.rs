@@ -0,0 +1,285 @@
+//! Coordinates two or more validator clients in an active/passive configuration such that only one
+//! of them signs messages at any time.
+//!
+//! The clients share a lease file (e.g., on a shared filesystem) which records the current holder
+//! and when the lease expires. The holder renews the lease periodically, whilst the others poll it
+//! and take over once it has expired (e.g., because the holder crashed or lost connectivity).
+//!
+//! To tolerate delays and modest clock skew between hosts:
+//!
+//! - The holder stops signing once half of the lease period has elapsed without a successful
+//!   renewal, whilst other clients wait for the full period before taking over.
+//! - A client which takes over the lease from another client waits for a "takeover delay" (one
+//!   epoch) before signing. Any message signed by the previous holder must therefore be from an
+//!   earlier epoch, which prevents double votes and double proposals.
+//! - A client which acquires a fresh lease waits for one renewal interval before signing, so that
+//!   any client racing to acquire the lease has a chance to observe the new holder.
+//!
+//! Reads and writes of the lease file are serialized with an OS file lock and the file is replaced
+//! atomically, however it is still important that each client uses its own slashing protection
+//! database which contains the full signing history of its validators.
+
+use environment::RuntimeContext;
+use lockfile::{Lockfile, LockfileError};
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use slog::{error, info, warn, Logger};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The default duration of a lease, three slots on mainnet.
+pub const DEFAULT_LEASE_EXPIRY: Duration = Duration::from_secs(36);
+
+/// The lease is renewed (or polled, by passive clients) this many times per lease period.
+const RENEWALS_PER_LEASE: u32 = 4;
+
+/// User-supplied configuration for the failover lease.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailoverLeaseConfig {
+    /// Path to the lease file shared by all clients.
+    pub path: PathBuf,
+    /// How long a lease lasts without being renewed.
+    pub expiry: Duration,
+}
+
+/// The contents of the lease file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LeaseRecord {
+    holder: String,
+    /// Milliseconds since the UNIX epoch.
+    expires_at: u64,
+}
+
+#[derive(Default)]
+struct LeaseState {
+    /// Set to `true` if we held the lease after the previous renewal.
+    held: bool,
+    /// The earliest time at which we may sign after acquiring the lease.
+    signing_from: Option<SystemTime>,
+    /// The time at which we must stop signing, if we're permitted to sign at all.
+    active_until: Option<SystemTime>,
+}
+
+/// See the module-level documentation.
+#[derive(Clone)]
+pub struct FailoverLease {
+    config: FailoverLeaseConfig,
+    takeover_delay: Duration,
+    holder: String,
+    state: Arc<Mutex<LeaseState>>,
+    log: Logger,
+}
+
+impl FailoverLease {
+    /// Instantiate a new lease with a random holder identity.
+    ///
+    /// The lease is not acquired until `Self::start_update_service` is called. After taking over
+    /// from another client, signing will not begin until `takeover_delay` has elapsed.
+    pub fn new(config: FailoverLeaseConfig, takeover_delay: Duration, log: Logger) -> Self {
+        Self {
+            config,
+            takeover_delay,
+            holder: format!("{:016x}", rand::random::<u64>()),
+            state: <_>::default(),
+            log,
+        }
+    }
+
+    /// Returns `true` if this client currently holds the lease and is permitted to sign.
+    pub fn is_active(&self) -> bool {
+        self.state
+            .lock()
+            .active_until
+            .map_or(false, |active_until| SystemTime::now() < active_until)
+    }
+
+    /// Starts the service which periodically acquires or renews the lease.
+    pub fn start_update_service<E: EthSpec>(self, context: &RuntimeContext<E>) {
+        let renewal_interval = self.renewal_interval();
+
+        info!(
+            self.log,
+            "Waiting for failover lease";
+            "path" => ?self.config.path,
+            "holder" => &self.holder,
+        );
+
+        let lease_fut = async move {
+            loop {
+                let was_active = self.is_active();
+                match self.renew(SystemTime::now()) {
+                    Ok(is_active) if is_active && !was_active => info!(
+                        self.log,
+                        "Acquired failover lease";
+                        "msg" => "this validator client will now sign messages",
+                        "holder" => &self.holder,
+                    ),
+                    Ok(is_active) if !is_active && was_active => warn!(
+                        self.log,
+                        "Lost failover lease";
+                        "msg" => "another validator client has taken over signing",
+                    ),
+                    Ok(_) => (),
+                    Err(e) => error!(
+                        self.log,
+                        "Failed to renew failover lease";
+                        "error" => e,
+                        "path" => ?self.config.path,
+                    ),
+                }
+
+                sleep(renewal_interval).await;
+            }
+        };
+
+        context.executor.spawn(lease_fut, "failover_lease");
+    }
+
+    fn renewal_interval(&self) -> Duration {
+        self.config.expiry / RENEWALS_PER_LEASE
+    }
+
+    /// Attempt to acquire or renew the lease at time `now`, returning `Ok(true)` if this client is
+    /// now permitted to sign.
+    fn renew(&self, now: SystemTime) -> Result<bool, String> {
+        let mut lock_path = self.config.path.clone().into_os_string();
+        lock_path.push(".lock");
+        let _lock = match Lockfile::new(lock_path.into()) {
+            Ok(lock) => lock,
+            // Another client is renewing, try again later.
+            Err(LockfileError::FileLocked(..)) => return Ok(self.is_active()),
+            Err(e) => return Err(format!("Unable to lock lease file: {:?}", e)),
+        };
+
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("System time is before the UNIX epoch: {:?}", e))?
+            .as_millis() as u64;
+
+        let mut state = self.state.lock();
+
+        let record = self.read()?;
+        let available = record.as_ref().map_or(true, |record| {
+            record.holder == self.holder || record.expires_at <= now_millis
+        });
+        if !available {
+            *state = LeaseState::default();
+            return Ok(false);
+        }
+
+        self.write(&LeaseRecord {
+            holder: self.holder.clone(),
+            expires_at: now_millis + self.config.expiry.as_millis() as u64,
+        })?;
+
+        if !state.held {
+            let took_over = record.map_or(false, |record| record.holder != self.holder);
+            let delay = if took_over {
+                self.takeover_delay
+            } else {
+                self.renewal_interval()
+            };
+            state.held = true;
+            state.signing_from = Some(now + delay);
+        }
+
+        if state
+            .signing_from
+            .map_or(false, |signing_from| now >= signing_from)
+        {
+            state.active_until = Some(now + self.config.expiry / 2);
+        }
+
+        Ok(state
+            .active_until
+            .map_or(false, |active_until| now < active_until))
+    }
+
+    fn read(&self) -> Result<Option<LeaseRecord>, String> {
+        match fs::read(&self.config.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| format!("Unable to parse lease file: {:?}", e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Unable to read lease file: {:?}", e)),
+        }
+    }
+
+    /// Atomically replace the contents of the lease file with `record`.
+    fn write(&self, record: &LeaseRecord) -> Result<(), String> {
+        let mut temp_path = self.config.path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", self.holder));
+
+        let bytes = serde_json::to_vec(record)
+            .map_err(|e| format!("Unable to serialize lease: {:?}", e))?;
+        fs::write(&temp_path, bytes)
+            .and_then(|()| fs::rename(&temp_path, &self.config.path))
+            .map_err(|e| format!("Unable to write lease file: {:?}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use tempfile::tempdir;
+
+    const TAKEOVER_DELAY: Duration = Duration::from_secs(384);
+
+    fn lease(path: PathBuf) -> FailoverLease {
+        FailoverLease::new(
+            FailoverLeaseConfig {
+                path,
+                expiry: DEFAULT_LEASE_EXPIRY,
+            },
+            TAKEOVER_DELAY,
+            null_logger().unwrap(),
+        )
+    }
+
+    #[test]
+    fn only_one_client_is_active() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lease.json");
+        let a = lease(path.clone());
+        let b = lease(path);
+        let start = SystemTime::now();
+        let interval = a.renewal_interval();
+
+        assert_eq!(a.renew(start), Ok(false), "not active until renewed");
+        assert_eq!(b.renew(start), Ok(false));
+        assert_eq!(a.renew(start + interval), Ok(true));
+        assert_eq!(b.renew(start + interval), Ok(false));
+        assert!(a.is_active());
+        assert!(!b.is_active());
+    }
+
+    #[test]
+    fn takeover_after_expiry_and_delay() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lease.json");
+        let a = lease(path.clone());
+        let b = lease(path);
+        let start = SystemTime::now();
+        let interval = a.renewal_interval();
+
+        a.renew(start).unwrap();
+        assert_eq!(a.renew(start + interval), Ok(true));
+
+        // `a` stops renewing. `b` may not take over until the lease has expired.
+        let expiry = start + interval + DEFAULT_LEASE_EXPIRY;
+        assert_eq!(b.renew(expiry - interval), Ok(false));
+        assert_eq!(b.renew(expiry), Ok(false));
+        assert_eq!(b.renew(expiry + interval), Ok(false));
+        assert_eq!(b.renew(expiry + TAKEOVER_DELAY), Ok(true));
+
+        // When `a` returns it must not reclaim the lease.
+        assert_eq!(a.renew(expiry + TAKEOVER_DELAY), Ok(false));
+        assert!(!a.is_active());
+    }
+}
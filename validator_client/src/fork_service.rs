@@ -85,6 +85,7 @@ impl<E: EthSpec> ForkServiceBuilder<slot_clock::TestingSlotClock, E> {
         );
         let candidates = vec![CandidateBeaconNode::new(eth2::BeaconNodeHttpClient::new(
             eth2::Url::parse("http://127.0.0.1").unwrap(),
+            eth2::Timeouts::set_all(std::time::Duration::from_secs(spec.seconds_per_slot)),
         ))];
         let mut beacon_nodes = BeaconNodeFallback::new(candidates, spec, log.clone());
         beacon_nodes.set_slot_clock(slot_clock);
@@ -2,7 +2,9 @@ use eth2::lighthouse_vc::{PK_LEN, SECRET_PREFIX as PK_PREFIX};
 use rand::thread_rng;
 use ring::digest::{digest, SHA256};
 use secp256k1::{Message, PublicKey, SecretKey};
-use std::fs;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
 use std::path::Path;
 use warp::Filter;
 
@@ -22,6 +24,68 @@ pub const SK_LEN: usize = 32;
 /// value in a public forum.
 pub const PK_FILENAME: &str = "api-token.txt";
 
+/// The name of the optional file which defines additional API tokens with limited permissions.
+pub const API_TOKENS_FILENAME: &str = "api-tokens.yml";
+
+/// An action which an API token may be permitted to perform.
+///
+/// The token in `PK_FILENAME` is permitted to perform all actions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiPermission {
+    /// `GET` the version, health, spec and validators.
+    Read,
+    /// `POST` new validators, either from keystores or a mnemonic.
+    ImportKeys,
+    /// `PATCH` existing validators, e.g. to enable or disable them.
+    EditValidators,
+}
+
+/// An entry in `API_TOKENS_FILENAME`.
+///
+/// The token for each entry is generated in the same way as the token in `PK_FILENAME`, and is
+/// written to `api-token-{name}.txt`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopedTokenDefinition {
+    pub name: String,
+    pub permissions: Vec<ApiPermission>,
+}
+
+/// An additional API token which may only perform the actions in `permissions`.
+struct ScopedToken {
+    pk: PublicKey,
+    sk: SecretKey,
+    permissions: Vec<ApiPermission>,
+}
+
+impl ScopedToken {
+    fn create_or_open(dir: &Path, definition: ScopedTokenDefinition) -> Result<Self, String> {
+        if definition.name.is_empty()
+            || !definition
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "invalid token name {:?} in {}, only letters, numbers, - and _ are permitted",
+                definition.name, API_TOKENS_FILENAME
+            ));
+        }
+
+        let (sk, pk) = create_or_open_keypair(
+            dir,
+            &format!("{}-{}", SK_FILENAME, definition.name),
+            &format!("api-token-{}.txt", definition.name),
+        )?;
+
+        Ok(Self {
+            pk,
+            sk,
+            permissions: definition.permissions,
+        })
+    }
+}
+
 /// Contains a `secp256k1` keypair that is saved-to/loaded-from disk on instantiation. The keypair
 /// is used for authorization/authentication for requests/responses on the HTTP API.
 ///
@@ -37,6 +101,7 @@ pub const PK_FILENAME: &str = "api-token.txt";
 pub struct ApiSecret {
     pk: PublicKey,
     sk: SecretKey,
+    scoped: Vec<ScopedToken>,
 }
 
 impl ApiSecret {
@@ -47,120 +112,62 @@ impl ApiSecret {
     ///
     /// If either the secret or public key files are missing on disk, create a new keypair and
     /// write it to disk (over-writing any existing files).
+    ///
+    /// If `dir` contains an `API_TOKENS_FILENAME`, a keypair is created or opened in the same way
+    /// for each token it defines.
     pub fn create_or_open<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
-        let sk_path = dir.as_ref().join(SK_FILENAME);
-        let pk_path = dir.as_ref().join(PK_FILENAME);
+        let (sk, pk) = create_or_open_keypair(dir.as_ref(), SK_FILENAME, PK_FILENAME)?;
 
-        if !(sk_path.exists() && pk_path.exists()) {
-            let sk = SecretKey::random(&mut thread_rng());
-            let pk = PublicKey::from_secret_key(&sk);
+        let tokens_path = dir.as_ref().join(API_TOKENS_FILENAME);
+        let scoped = if tokens_path.exists() {
+            let file = File::open(&tokens_path)
+                .map_err(|e| format!("cannot read {}: {}", API_TOKENS_FILENAME, e))?;
+            let definitions: Vec<ScopedTokenDefinition> = serde_yaml::from_reader(file)
+                .map_err(|e| format!("unable to parse {}: {}", API_TOKENS_FILENAME, e))?;
 
-            fs::write(
-                &sk_path,
-                serde_utils::hex::encode(&sk.serialize()).as_bytes(),
-            )
-            .map_err(|e| e.to_string())?;
-            fs::write(
-                &pk_path,
-                format!(
-                    "{}{}",
-                    PK_PREFIX,
-                    serde_utils::hex::encode(&pk.serialize_compressed()[..])
-                )
-                .as_bytes(),
-            )
-            .map_err(|e| e.to_string())?;
-        }
+            definitions
+                .into_iter()
+                .map(|definition| ScopedToken::create_or_open(dir.as_ref(), definition))
+                .collect::<Result<_, _>>()?
+        } else {
+            vec![]
+        };
 
-        let sk = fs::read(&sk_path)
-            .map_err(|e| format!("cannot read {}: {}", SK_FILENAME, e))
-            .and_then(|bytes| {
-                serde_utils::hex::decode(&String::from_utf8_lossy(&bytes))
-                    .map_err(|_| format!("{} should be 0x-prefixed hex", PK_FILENAME))
-            })
-            .and_then(|bytes| {
-                if bytes.len() == SK_LEN {
-                    let mut array = [0; SK_LEN];
-                    array.copy_from_slice(&bytes);
-                    SecretKey::parse(&array).map_err(|e| format!("invalid {}: {}", SK_FILENAME, e))
-                } else {
-                    Err(format!(
-                        "{} expected {} bytes not {}",
-                        SK_FILENAME,
-                        SK_LEN,
-                        bytes.len()
-                    ))
-                }
-            })?;
-
-        let pk = fs::read(&pk_path)
-            .map_err(|e| format!("cannot read {}: {}", PK_FILENAME, e))
-            .and_then(|bytes| {
-                let hex =
-                    String::from_utf8(bytes).map_err(|_| format!("{} is not utf8", SK_FILENAME))?;
-                if let Some(stripped) = hex.strip_prefix(PK_PREFIX) {
-                    serde_utils::hex::decode(stripped)
-                        .map_err(|_| format!("{} should be 0x-prefixed hex", SK_FILENAME))
-                } else {
-                    Err(format!("unable to parse {}", SK_FILENAME))
-                }
-            })
-            .and_then(|bytes| {
-                if bytes.len() == PK_LEN {
-                    let mut array = [0; PK_LEN];
-                    array.copy_from_slice(&bytes);
-                    PublicKey::parse_compressed(&array)
-                        .map_err(|e| format!("invalid {}: {}", PK_FILENAME, e))
-                } else {
-                    Err(format!(
-                        "{} expected {} bytes not {}",
-                        PK_FILENAME,
-                        PK_LEN,
-                        bytes.len()
-                    ))
-                }
-            })?;
-
-        // Ensure that the keys loaded from disk are indeed a pair.
-        if PublicKey::from_secret_key(&sk) != pk {
-            fs::remove_file(&sk_path)
-                .map_err(|e| format!("unable to remove {}: {}", SK_FILENAME, e))?;
-            fs::remove_file(&pk_path)
-                .map_err(|e| format!("unable to remove {}: {}", PK_FILENAME, e))?;
-            return Err(format!(
-                "{:?} does not match {:?} and the files have been deleted. Please try again.",
-                sk_path, pk_path
-            ));
-        }
-
-        Ok(Self { sk, pk })
-    }
-
-    /// Returns the public key of `self` as a 0x-prefixed hex string.
-    fn pubkey_string(&self) -> String {
-        serde_utils::hex::encode(&self.pk.serialize_compressed()[..])
+        Ok(Self { sk, pk, scoped })
     }
 
     /// Returns the API token.
     pub fn api_token(&self) -> String {
-        format!("{}{}", PK_PREFIX, self.pubkey_string())
+        token_string(&self.pk)
     }
 
-    /// Returns the value of the `Authorization` header which is used for verifying incoming HTTP
-    /// requests.
-    fn auth_header_value(&self) -> String {
-        format!("Basic {}", self.api_token())
+    /// Returns the token for each additional token name defined in `API_TOKENS_FILENAME`.
+    pub fn scoped_api_tokens(&self) -> Vec<String> {
+        self.scoped
+            .iter()
+            .map(|token| token_string(&token.pk))
+            .collect()
     }
 
     /// Returns a `warp` header which filters out request that have a missing or inaccurate
-    /// `Authorization` header.
-    pub fn authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
-        let expected = self.auth_header_value();
+    /// `Authorization` header, or which use a token that lacks `permission`.
+    pub fn authorization_header_filter(
+        &self,
+        permission: ApiPermission,
+    ) -> warp::filters::BoxedFilter<()> {
+        let expected = std::iter::once(auth_header_value(&self.pk))
+            .chain(
+                self.scoped
+                    .iter()
+                    .filter(|token| token.permissions.contains(&permission))
+                    .map(|token| auth_header_value(&token.pk)),
+            )
+            .collect::<Vec<_>>();
         warp::any()
             .map(move || expected.clone())
             .and(warp::filters::header::header("Authorization"))
-            .and_then(move |expected: String, header: String| async move {
-                if header == expected {
+            .and_then(move |expected: Vec<String>, header: String| async move {
+                if expected.contains(&header) {
                     Ok(())
                 } else {
                     Err(warp_utils::reject::invalid_auth(header))
@@ -170,15 +177,189 @@ impl ApiSecret {
             .boxed()
     }
 
-    /// Returns a closure which produces a signature over some bytes using the secret key in
-    /// `self`. The signature is a 32-byte hash formatted as a 0x-prefixed string.
-    pub fn signer(&self) -> impl Fn(&[u8]) -> String + Clone {
+    /// Returns a `warp` filter which produces a closure that signs some bytes using the secret key
+    /// of the token in the request's `Authorization` header, so that the response may be verified
+    /// with that token. The signature is a 32-byte hash formatted as a 0x-prefixed string.
+    pub fn signer_filter(
+        &self,
+    ) -> impl Filter<Extract = (impl Fn(&[u8]) -> String + Clone,), Error = warp::Rejection> + Clone
+    {
         let sk = self.sk.clone();
-        move |input: &[u8]| -> String {
-            let message =
-                Message::parse_slice(digest(&SHA256, input).as_ref()).expect("sha256 is 32 bytes");
-            let (signature, _) = secp256k1::sign(&message, &sk);
-            serde_utils::hex::encode(signature.serialize_der().as_ref())
+        let scoped_sks = self
+            .scoped
+            .iter()
+            .map(|token| (auth_header_value(&token.pk), token.sk.clone()))
+            .collect::<HashMap<_, _>>();
+        warp::filters::header::optional("Authorization").map(move |header: Option<String>| {
+            let sk = header
+                .and_then(|header| scoped_sks.get(&header))
+                .unwrap_or(&sk)
+                .clone();
+            signer(sk)
+        })
+    }
+}
+
+/// Returns the token for `pk`, a 0x-prefixed hex string.
+fn token_string(pk: &PublicKey) -> String {
+    format!(
+        "{}{}",
+        PK_PREFIX,
+        serde_utils::hex::encode(&pk.serialize_compressed()[..])
+    )
+}
+
+/// Returns the value of the `Authorization` header which is used for verifying incoming HTTP
+/// requests made with the token for `pk`.
+fn auth_header_value(pk: &PublicKey) -> String {
+    format!("Basic {}", token_string(pk))
+}
+
+/// Returns a closure which produces a signature over some bytes using `sk`.
+fn signer(sk: SecretKey) -> impl Fn(&[u8]) -> String + Clone {
+    move |input: &[u8]| -> String {
+        let message =
+            Message::parse_slice(digest(&SHA256, input).as_ref()).expect("sha256 is 32 bytes");
+        let (signature, _) = secp256k1::sign(&message, &sk);
+        serde_utils::hex::encode(signature.serialize_der().as_ref())
+    }
+}
+
+fn create_or_open_keypair(
+    dir: &Path,
+    sk_filename: &str,
+    pk_filename: &str,
+) -> Result<(SecretKey, PublicKey), String> {
+    let sk_path = dir.join(sk_filename);
+    let pk_path = dir.join(pk_filename);
+
+    if !(sk_path.exists() && pk_path.exists()) {
+        let sk = SecretKey::random(&mut thread_rng());
+        let pk = PublicKey::from_secret_key(&sk);
+
+        fs::write(
+            &sk_path,
+            serde_utils::hex::encode(&sk.serialize()).as_bytes(),
+        )
+        .map_err(|e| e.to_string())?;
+        fs::write(
+            &pk_path,
+            format!(
+                "{}{}",
+                PK_PREFIX,
+                serde_utils::hex::encode(&pk.serialize_compressed()[..])
+            )
+            .as_bytes(),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let sk = fs::read(&sk_path)
+        .map_err(|e| format!("cannot read {}: {}", sk_filename, e))
+        .and_then(|bytes| {
+            serde_utils::hex::decode(&String::from_utf8_lossy(&bytes))
+                .map_err(|_| format!("{} should be 0x-prefixed hex", pk_filename))
+        })
+        .and_then(|bytes| {
+            if bytes.len() == SK_LEN {
+                let mut array = [0; SK_LEN];
+                array.copy_from_slice(&bytes);
+                SecretKey::parse(&array).map_err(|e| format!("invalid {}: {}", sk_filename, e))
+            } else {
+                Err(format!(
+                    "{} expected {} bytes not {}",
+                    sk_filename,
+                    SK_LEN,
+                    bytes.len()
+                ))
+            }
+        })?;
+
+    let pk = fs::read(&pk_path)
+        .map_err(|e| format!("cannot read {}: {}", pk_filename, e))
+        .and_then(|bytes| {
+            let hex =
+                String::from_utf8(bytes).map_err(|_| format!("{} is not utf8", sk_filename))?;
+            if let Some(stripped) = hex.strip_prefix(PK_PREFIX) {
+                serde_utils::hex::decode(stripped)
+                    .map_err(|_| format!("{} should be 0x-prefixed hex", sk_filename))
+            } else {
+                Err(format!("unable to parse {}", sk_filename))
+            }
+        })
+        .and_then(|bytes| {
+            if bytes.len() == PK_LEN {
+                let mut array = [0; PK_LEN];
+                array.copy_from_slice(&bytes);
+                PublicKey::parse_compressed(&array)
+                    .map_err(|e| format!("invalid {}: {}", pk_filename, e))
+            } else {
+                Err(format!(
+                    "{} expected {} bytes not {}",
+                    pk_filename,
+                    PK_LEN,
+                    bytes.len()
+                ))
+            }
+        })?;
+
+    // Ensure that the keys loaded from disk are indeed a pair.
+    if PublicKey::from_secret_key(&sk) != pk {
+        fs::remove_file(&sk_path)
+            .map_err(|e| format!("unable to remove {}: {}", sk_filename, e))?;
+        fs::remove_file(&pk_path)
+            .map_err(|e| format!("unable to remove {}: {}", pk_filename, e))?;
+        return Err(format!(
+            "{:?} does not match {:?} and the files have been deleted. Please try again.",
+            sk_path, pk_path
+        ));
+    }
+
+    Ok((sk, pk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn scoped_token_permissions() {
+        let dir = tempdir().unwrap();
+        let definitions = vec![ScopedTokenDefinition {
+            name: "reader".into(),
+            permissions: vec![ApiPermission::Read],
+        }];
+        fs::write(
+            dir.path().join(API_TOKENS_FILENAME),
+            serde_yaml::to_string(&definitions).unwrap(),
+        )
+        .unwrap();
+
+        let secret = ApiSecret::create_or_open(dir.path()).unwrap();
+        let admin = secret.api_token();
+        let reader = secret.scoped_api_tokens().pop().unwrap();
+
+        for (permission, token, allowed) in vec![
+            (ApiPermission::Read, &admin, true),
+            (ApiPermission::ImportKeys, &admin, true),
+            (ApiPermission::Read, &reader, true),
+            (ApiPermission::ImportKeys, &reader, false),
+            (ApiPermission::EditValidators, &reader, false),
+        ] {
+            let result = warp::test::request()
+                .header("Authorization", format!("Basic {}", token))
+                .filter(&secret.authorization_header_filter(permission))
+                .await;
+            assert_eq!(result.is_ok(), allowed, "{:?} {}", permission, token);
         }
+
+        assert_eq!(
+            ApiSecret::create_or_open(dir.path())
+                .unwrap()
+                .scoped_api_tokens(),
+            vec![reader],
+            "scoped tokens are persisted"
+        );
     }
 }
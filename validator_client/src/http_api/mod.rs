@@ -27,7 +27,7 @@ use warp::{
     Filter,
 };
 
-pub use api_secret::ApiSecret;
+pub use api_secret::{ApiPermission, ApiSecret, ScopedTokenDefinition, API_TOKENS_FILENAME};
 
 #[derive(Debug)]
 pub enum Error {
@@ -124,10 +124,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         ));
     }
 
-    let authorization_header_filter = ctx.api_secret.authorization_header_filter();
+    let read_auth_filter = ctx
+        .api_secret
+        .authorization_header_filter(ApiPermission::Read);
+    let import_keys_auth_filter = ctx
+        .api_secret
+        .authorization_header_filter(ApiPermission::ImportKeys);
+    let edit_validators_auth_filter = ctx
+        .api_secret
+        .authorization_header_filter(ApiPermission::EditValidators);
     let api_token = ctx.api_secret.api_token();
-    let signer = ctx.api_secret.signer();
-    let signer = warp::any().map(move || signer.clone());
+    let signer = ctx.api_secret.signer_filter();
 
     let inner_validator_store = ctx.validator_store.clone();
     let validator_store_filter = warp::any()
@@ -466,23 +473,23 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
-    let routes = warp::any()
-        .and(authorization_header_filter)
+    let routes = warp::get()
+        .and(read_auth_filter)
         .and(
-            warp::get().and(
-                get_node_version
-                    .or(get_lighthouse_health)
-                    .or(get_lighthouse_spec)
-                    .or(get_lighthouse_validators)
-                    .or(get_lighthouse_validators_pubkey),
-            ),
+            get_node_version
+                .or(get_lighthouse_health)
+                .or(get_lighthouse_spec)
+                .or(get_lighthouse_validators)
+                .or(get_lighthouse_validators_pubkey),
         )
-        .or(warp::post().and(
+        .or(warp::post().and(import_keys_auth_filter).and(
             post_validators
                 .or(post_validators_keystore)
                 .or(post_validators_mnemonic),
         ))
-        .or(warp::patch().and(patch_validators))
+        .or(warp::patch()
+            .and(edit_validators_auth_filter)
+            .and(patch_validators))
         // Maps errors into HTTP responses.
         .recover(warp_utils::reject::handle_rejection)
         // Add a `Server` header.
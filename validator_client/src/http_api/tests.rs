@@ -2,7 +2,10 @@
 #![cfg(not(debug_assertions))]
 
 use crate::{
-    http_api::{ApiSecret, Config as HttpConfig, Context},
+    http_api::{
+        ApiPermission, ApiSecret, Config as HttpConfig, Context, ScopedTokenDefinition,
+        API_TOKENS_FILENAME,
+    },
     Config, ForkServiceBuilder, InitializedValidators, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
@@ -32,6 +35,8 @@ type E = MainnetEthSpec;
 
 struct ApiTester {
     client: ValidatorClientHttpClient,
+    /// A client using a scoped token which only has `ApiPermission::Read`.
+    read_only_client: ValidatorClientHttpClient,
     initialized_validators: Arc<RwLock<InitializedValidators>>,
     url: Url,
     _server_shutdown: oneshot::Sender<()>,
@@ -65,8 +70,19 @@ impl ApiTester {
         .await
         .unwrap();
 
+        let token_definitions = vec![ScopedTokenDefinition {
+            name: "reader".into(),
+            permissions: vec![ApiPermission::Read],
+        }];
+        std::fs::write(
+            validator_dir.path().join(API_TOKENS_FILENAME),
+            serde_yaml::to_string(&token_definitions).unwrap(),
+        )
+        .unwrap();
+
         let api_secret = ApiSecret::create_or_open(validator_dir.path()).unwrap();
         let api_pubkey = api_secret.api_token();
+        let read_only_pubkey = api_secret.scoped_api_tokens().pop().unwrap();
 
         let mut config = Config::default();
         config.validator_dir = validator_dir.path().into();
@@ -83,10 +99,12 @@ impl ApiTester {
 
         let validator_store: ValidatorStore<TestingSlotClock, E> = ValidatorStore::new(
             initialized_validators,
-            slashing_protection,
+            slashing_protection.into(),
             Hash256::repeat_byte(42),
             spec,
             fork_service.clone(),
+            None,
+            None,
             log.clone(),
         );
 
@@ -125,11 +143,14 @@ impl ApiTester {
         .unwrap();
 
         let client = ValidatorClientHttpClient::new(url.clone(), api_pubkey).unwrap();
+        let read_only_client =
+            ValidatorClientHttpClient::new(url.clone(), read_only_pubkey).unwrap();
 
         Self {
             initialized_validators,
             _validator_dir: validator_dir,
             client,
+            read_only_client,
             url,
             _server_shutdown: shutdown_tx,
         }
@@ -423,6 +444,64 @@ impl ApiTester {
 
         self
     }
+
+    /// Check that the read-only token may list validators, but may not create or edit them,
+    /// whilst the admin token may do both.
+    pub async fn test_read_only_token_permissions(self) -> Self {
+        let validators = self
+            .read_only_client
+            .get_lighthouse_validators()
+            .await
+            .unwrap()
+            .data;
+        let validator = validators.first().expect("tester should have a validator");
+        let initial_vals = self.vals_total();
+
+        let request = vec![ValidatorRequest {
+            enable: true,
+            description: "read-only".into(),
+            graffiti: None,
+            deposit_gwei: E::default_spec().max_effective_balance,
+        }];
+
+        self.read_only_client
+            .post_lighthouse_validators(request.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(self.vals_total(), initial_vals);
+
+        self.read_only_client
+            .patch_lighthouse_validators(&validator.voting_pubkey, !validator.enabled)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            self.initialized_validators
+                .read()
+                .is_enabled(&validator.voting_pubkey.decompress().unwrap())
+                .unwrap(),
+            validator.enabled
+        );
+
+        self.client
+            .post_lighthouse_validators(request)
+            .await
+            .unwrap();
+        assert_eq!(self.vals_total(), initial_vals + 1);
+
+        self.client
+            .patch_lighthouse_validators(&validator.voting_pubkey, !validator.enabled)
+            .await
+            .unwrap();
+        assert_eq!(
+            self.initialized_validators
+                .read()
+                .is_enabled(&validator.voting_pubkey.decompress().unwrap())
+                .unwrap(),
+            !validator.enabled
+        );
+
+        self
+    }
 }
 
 struct HdValidatorScenario {
@@ -564,3 +643,22 @@ fn keystore_validator_creation() {
             .assert_validators_count(2);
     });
 }
+
+#[test]
+fn read_only_token() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(async {
+        ApiTester::new(weak_runtime)
+            .await
+            .create_hd_validators(HdValidatorScenario {
+                count: 1,
+                specify_mnemonic: false,
+                key_derivation_path_offset: 0,
+                disabled: vec![],
+            })
+            .await
+            .test_read_only_token_permissions()
+            .await;
+    });
+}
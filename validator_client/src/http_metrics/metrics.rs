@@ -108,6 +108,22 @@ lazy_static::lazy_static! {
         "The number of beacon node requests for each endpoint",
         &["endpoint"]
     );
+    pub static ref BEACON_NODES_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "vc_beacon_nodes_total_count",
+        "Number of configured beacon nodes"
+    );
+    pub static ref BEACON_NODES_AVAILABLE: Result<IntGauge> = try_create_int_gauge(
+        "vc_beacon_nodes_available_count",
+        "Number of beacon nodes which are online and compatible"
+    );
+    pub static ref BEACON_NODES_SYNCED: Result<IntGauge> = try_create_int_gauge(
+        "vc_beacon_nodes_synced_count",
+        "Number of beacon nodes which are online, compatible and synced"
+    );
+    pub static ref BEACON_NODES_PRIMARY_INDEX: Result<IntGauge> = try_create_int_gauge(
+        "vc_beacon_nodes_primary_index",
+        "Position in --beacon-nodes of the node which will be used first, or -1 if none are synced"
+    );
 }
 
 pub fn gather_prometheus_metrics<T: EthSpec>(
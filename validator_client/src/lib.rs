@@ -5,15 +5,19 @@ mod check_synced;
 mod cli;
 mod config;
 mod duties_service;
+mod failover_lease;
 mod fork_service;
 mod graffiti_file;
 mod http_metrics;
 mod initialized_validators;
 mod key_cache;
 mod notifier;
+mod request_cache;
+mod slot_timings;
 mod validator_store;
 
 pub mod http_api;
+pub mod signing_hook;
 
 pub use cli::cli_app;
 pub use config::Config;
@@ -22,19 +26,22 @@ use crate::beacon_node_fallback::{
     start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode, RequireSynced,
 };
 use account_utils::validator_definitions::ValidatorDefinitions;
-use attestation_service::{AttestationService, AttestationServiceBuilder};
+use attestation_service::{get_finalized_epoch, AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
 use duties_service::DutiesService;
 use environment::RuntimeContext;
 use eth2::types::StateId;
-use eth2::{reqwest::ClientBuilder, BeaconNodeHttpClient, StatusCode, Url};
+use eth2::{reqwest::ClientBuilder, BeaconNodeHttpClient, StatusCode, Timeouts, Url};
+use failover_lease::FailoverLease;
 use fork_service::{ForkService, ForkServiceBuilder};
 use http_api::ApiSecret;
 use initialized_validators::InitializedValidators;
 use notifier::spawn_notifier;
 use parking_lot::RwLock;
-use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use request_cache::RequestCache;
+use signing_hook::SigningHook;
+use slashing_protection::{SlashingDatabase, SlashingProtection, SLASHING_PROTECTION_FILENAME};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
@@ -57,6 +64,13 @@ const WAITING_FOR_GENESIS_POLL_TIME: Duration = Duration::from_secs(12);
 
 /// The global timeout for HTTP requests to the beacon node.
 const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+/// The timeouts for specific HTTP requests involving beacon node duties, expressed as a quotient
+/// of the slot duration. These requests must complete well within a slot for the duty to be
+/// useful, so we prefer to fail quickly and fall back to another beacon node.
+const HTTP_ATTESTATION_TIMEOUT_QUOTIENT: u32 = 4;
+const HTTP_ATTESTER_DUTIES_TIMEOUT_QUOTIENT: u32 = 4;
+const HTTP_PROPOSAL_TIMEOUT_QUOTIENT: u32 = 2;
+const HTTP_PROPOSER_DUTIES_TIMEOUT_QUOTIENT: u32 = 4;
 
 #[derive(Clone)]
 pub struct ProductionValidatorClient<T: EthSpec> {
@@ -66,6 +80,7 @@ pub struct ProductionValidatorClient<T: EthSpec> {
     block_service: BlockService<SystemTimeSlotClock, T>,
     attestation_service: AttestationService<SystemTimeSlotClock, T>,
     validator_store: ValidatorStore<SystemTimeSlotClock, T>,
+    failover_lease: Option<FailoverLease>,
     http_api_listen_addr: Option<SocketAddr>,
     http_metrics_ctx: Option<Arc<http_metrics::Context<T>>>,
     config: Config,
@@ -215,16 +230,36 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .map(|s| s.parse())
             .collect::<Result<_, _>>()
             .map_err(|e| format!("Unable to parse beacon node URL: {:?}", e))?;
+
+        // Use quicker timeouts if a fallback beacon node exists.
+        let timeouts = if config.beacon_nodes.len() > 1 && !config.use_long_timeouts {
+            info!(
+                log,
+                "Fallback endpoints are available, using optimized timeouts.";
+            );
+            let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
+            Timeouts {
+                attestation: slot_duration / HTTP_ATTESTATION_TIMEOUT_QUOTIENT,
+                attester_duties: slot_duration / HTTP_ATTESTER_DUTIES_TIMEOUT_QUOTIENT,
+                proposal: slot_duration / HTTP_PROPOSAL_TIMEOUT_QUOTIENT,
+                proposer_duties: slot_duration / HTTP_PROPOSER_DUTIES_TIMEOUT_QUOTIENT,
+            }
+        } else {
+            Timeouts::set_all(HTTP_TIMEOUT)
+        }
+        .scale(config.timeout_multiplier);
+
         let beacon_nodes: Vec<BeaconNodeHttpClient> = beacon_node_urls
             .into_iter()
             .map(|url| {
                 let beacon_node_http_client = ClientBuilder::new()
-                    .timeout(HTTP_TIMEOUT)
+                    .timeout(HTTP_TIMEOUT * config.timeout_multiplier)
                     .build()
                     .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
                 Ok(BeaconNodeHttpClient::from_components(
                     url,
                     beacon_node_http_client,
+                    timeouts.clone(),
                 ))
             })
             .collect::<Result<Vec<BeaconNodeHttpClient>, String>>()?;
@@ -253,6 +288,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             Duration::from_secs(context.eth2_config.spec.seconds_per_slot),
         );
 
+        let slot_timings = config.slot_offsets.resolve(&slot_clock)?;
+
         beacon_nodes.set_slot_clock(slot_clock.clone());
         let beacon_nodes = Arc::new(beacon_nodes);
         start_fallback_updater_service(context.clone(), beacon_nodes.clone())?;
@@ -264,12 +301,23 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .log(log.clone())
             .build()?;
 
+        let failover_lease = config.failover_lease.clone().map(|lease_config| {
+            // Wait for a full epoch after taking over so that we never sign a message in the
+            // same epoch as the previous lease holder.
+            let takeover_delay = Duration::from_secs(
+                context.eth2_config.spec.seconds_per_slot * T::slots_per_epoch(),
+            );
+            FailoverLease::new(lease_config, takeover_delay, log.clone())
+        });
+
         let validator_store: ValidatorStore<SystemTimeSlotClock, T> = ValidatorStore::new(
             validators,
-            slashing_protection,
+            SlashingProtection::from(slashing_protection),
             genesis_validators_root,
             context.eth2_config.spec.clone(),
             fork_service.clone(),
+            failover_lease.clone(),
+            config.slashing_protection_history_epochs,
             log.clone(),
         );
 
@@ -282,8 +330,18 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // Perform pruning of the slashing protection database on start-up. In case the database is
         // oversized from having not been pruned (by a prior version) we don't want to prune
         // concurrently, as it will hog the lock and cause the attestation service to spew CRITs.
-        if let Some(slot) = slot_clock.now() {
-            validator_store.prune_slashing_protection_db(slot.epoch(T::slots_per_epoch()), true);
+        if validator_store.slashing_protection_pruning_enabled() {
+            match get_finalized_epoch(&*beacon_nodes).await {
+                Ok(finalized_epoch) => {
+                    validator_store.prune_slashing_protection_db(finalized_epoch, true)
+                }
+                Err(e) => warn!(
+                    log,
+                    "Skipping slashing protection pruning";
+                    "reason" => "unable to fetch finalized epoch",
+                    "error" => e,
+                ),
+            }
         }
 
         let duties_context = context.service_context("duties".into());
@@ -291,7 +349,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             attesters: <_>::default(),
             proposers: <_>::default(),
             indices: <_>::default(),
+            attester_duties_requests: RequestCache::deduplicating(),
             slot_clock: slot_clock.clone(),
+            slot_timings,
             beacon_nodes: beacon_nodes.clone(),
             validator_store: validator_store.clone(),
             require_synced: if config.allow_unsynced_beacon_node {
@@ -321,6 +381,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let attestation_service = AttestationServiceBuilder::new()
             .duties_service(duties_service.clone())
             .slot_clock(slot_clock)
+            .slot_timings(slot_timings)
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
@@ -339,12 +400,19 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             block_service,
             attestation_service,
             validator_store,
+            failover_lease,
             config,
             http_api_listen_addr: None,
             http_metrics_ctx,
         })
     }
 
+    /// Sets a hook which is called whenever a message is signed, allowing an external signing
+    /// backend (e.g., a distributed validator) to be used. Must be called before `start_service`.
+    pub fn set_signing_hook(&self, signing_hook: Arc<dyn SigningHook<T>>) {
+        self.validator_store.set_signing_hook(signing_hook);
+    }
+
     pub fn start_service(&mut self) -> Result<(), String> {
         // We use `SLOTS_PER_EPOCH` as the capacity of the block notification channel, because
         // we don't except notifications to be delayed by more than a single slot, let alone a
@@ -353,6 +421,10 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let (block_service_tx, block_service_rx) = mpsc::channel(channel_capacity);
         let log = self.context.log();
 
+        if let Some(failover_lease) = &self.failover_lease {
+            failover_lease.clone().start_update_service(&self.context);
+        }
+
         duties_service::start_update_service(self.duties_service.clone(), block_service_tx);
 
         self.fork_service
@@ -367,7 +439,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         self.attestation_service
             .clone()
-            .start_update_service(&self.context.eth2_config.spec)
+            .start_update_service()
             .map_err(|e| format!("Unable to start attestation service: {}", e))?;
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
@@ -435,13 +507,8 @@ async fn init_from_beacon_node<E: EthSpec>(
     }
 
     let genesis = loop {
-        match beacon_nodes
-            .first_success(RequireSynced::No, |node| async move {
-                node.get_beacon_genesis().await
-            })
-            .await
-        {
-            Ok(genesis) => break genesis.data,
+        match beacon_nodes.genesis().await {
+            Ok(genesis) => break genesis,
             Err(errors) => {
                 // Search for a 404 error which indicates that genesis has not yet
                 // occurred.
@@ -1,34 +1,28 @@
 use crate::{DutiesService, ProductionValidatorClient};
+use environment::slot_scheduler::{Schedule, SlotFraction};
 use slog::{error, info, Logger};
 use slot_clock::SlotClock;
-use tokio::time::{sleep, Duration};
 use types::EthSpec;
 
 /// Spawns a notifier service which periodically logs information about the node.
 pub fn spawn_notifier<T: EthSpec>(client: &ProductionValidatorClient<T>) -> Result<(), String> {
     let context = client.context.service_context("notifier".into());
-    let executor = context.executor.clone();
     let duties_service = client.duties_service.clone();
+    let log = context.log().clone();
 
-    let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
+    let half_slot = SlotFraction::new(1, 2).ok_or("Invalid notifier slot fraction")?;
+    context
+        .slot_scheduler(duties_service.slot_clock.clone())
+        .register_async(
+            "validator_notifier",
+            Schedule::EverySlot(half_slot),
+            move |_| {
+                let duties_service = duties_service.clone();
+                let log = log.clone();
+                async move { notify(&duties_service, &log).await }
+            },
+        );
 
-    let interval_fut = async move {
-        let log = context.log();
-
-        loop {
-            if let Some(duration_to_next_slot) = duties_service.slot_clock.duration_to_next_slot() {
-                sleep(duration_to_next_slot + slot_duration / 2).await;
-                notify(&duties_service, &log).await;
-            } else {
-                error!(log, "Failed to read slot clock");
-                // If we can't read the slot clock, just wait another slot.
-                sleep(slot_duration).await;
-                continue;
-            }
-        }
-    };
-
-    executor.spawn(interval_fut, "validator_notifier");
     Ok(())
 }
 
@@ -37,24 +31,23 @@ async fn notify<T: SlotClock + 'static, E: EthSpec>(
     duties_service: &DutiesService<T, E>,
     log: &Logger,
 ) {
-    let num_available = duties_service.beacon_nodes.num_available().await;
-    let num_synced = duties_service.beacon_nodes.num_synced().await;
-    let num_total = duties_service.beacon_nodes.num_total().await;
-    if num_synced > 0 {
+    let status = duties_service.beacon_nodes.update_status().await;
+    if let Some(primary) = &status.primary {
         info!(
             log,
             "Connected to beacon node(s)";
-            "total" => num_total,
-            "available" => num_available,
-            "synced" => num_synced,
+            "total" => status.total,
+            "available" => status.available,
+            "synced" => status.synced,
+            "primary" => primary,
         )
     } else {
         error!(
             log,
             "No synced beacon nodes";
-            "total" => num_total,
-            "available" => num_available,
-            "synced" => num_synced,
+            "total" => status.total,
+            "available" => status.available,
+            "synced" => status.synced,
         )
     }
 
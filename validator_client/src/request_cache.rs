@@ -0,0 +1,164 @@
+//! Provides `RequestCache`, which allows identical concurrent requests to the beacon node to be
+//! deduplicated and, optionally, for the responses of requests for immutable data (e.g., genesis)
+//! to be retained so that they are only ever made once.
+//!
+//! The first caller for some key will perform the request, whilst any other callers for the same
+//! key will wait on the first request and receive a clone of its result. If the first request
+//! fails, the next waiting caller will try the request itself.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Determines how long a response is kept after the request that produced it has completed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retention {
+    /// Only deduplicate requests which are in-flight at the same time. The response is dropped as
+    /// soon as the request completes, so a subsequent call will make a new request.
+    InFlight,
+    /// Keep the response forever. Only suitable for data which can never change.
+    Forever,
+}
+
+/// See the module-level documentation.
+pub struct RequestCache<K, V> {
+    cells: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+    retention: Retention,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> RequestCache<K, V> {
+    /// Instantiate a cache which only deduplicates concurrent requests.
+    pub fn deduplicating() -> Self {
+        Self::new(Retention::InFlight)
+    }
+
+    /// Instantiate a cache which retains responses forever.
+    pub fn immutable() -> Self {
+        Self::new(Retention::Forever)
+    }
+
+    pub fn new(retention: Retention) -> Self {
+        Self {
+            cells: Mutex::new(HashMap::new()),
+            retention,
+        }
+    }
+
+    /// Returns the value for `key`, running `func` to obtain it if there is no request in-flight
+    /// (or, for `Retention::Forever`, no prior successful request).
+    ///
+    /// If another caller is already running a request for `key`, this function will wait for that
+    /// request and return a clone of its result instead of running `func`.
+    pub async fn get_or_try_fetch<F, R, E>(&self, key: K, func: F) -> Result<V, E>
+    where
+        F: FnOnce() -> R,
+        R: Future<Output = Result<V, E>>,
+    {
+        let cell = self.cells.lock().entry(key.clone()).or_default().clone();
+
+        let result = cell.get_or_try_init(func).await.map(Clone::clone);
+
+        if self.retention == Retention::InFlight || result.is_err() {
+            let mut cells = self.cells.lock();
+            // Only remove the cell if it has not already been replaced by another caller.
+            if cells
+                .get(&key)
+                .map_or(false, |existing| Arc::ptr_eq(existing, &cell))
+            {
+                cells.remove(&key);
+            }
+        }
+
+        result
+    }
+
+    /// Drop any retained response for `key`, forcing the next call to make a new request.
+    pub fn invalidate(&self, key: &K) {
+        self.cells.lock().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::{sleep, Duration};
+
+    async fn slow_request(counter: &AtomicUsize) -> Result<usize, ()> {
+        sleep(Duration::from_millis(50)).await;
+        Ok(counter.fetch_add(1, Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn deduplicates_concurrent_requests() {
+        let cache = RequestCache::deduplicating();
+        let counter = AtomicUsize::new(0);
+
+        let (a, b) = tokio::join!(
+            cache.get_or_try_fetch(42, || slow_request(&counter)),
+            cache.get_or_try_fetch(42, || slow_request(&counter)),
+        );
+
+        assert_eq!(a, Ok(0));
+        assert_eq!(b, Ok(0));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(
+            cache.cells.lock().is_empty(),
+            "in-flight entries should be dropped"
+        );
+
+        let c = cache.get_or_try_fetch(42, || slow_request(&counter)).await;
+        assert_eq!(c, Ok(1), "completed requests should not be cached");
+    }
+
+    #[tokio::test]
+    async fn distinct_keys_are_not_deduplicated() {
+        let cache = RequestCache::deduplicating();
+        let counter = AtomicUsize::new(0);
+
+        let (a, b) = tokio::join!(
+            cache.get_or_try_fetch(1, || slow_request(&counter)),
+            cache.get_or_try_fetch(2, || slow_request(&counter)),
+        );
+
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn immutable_responses_are_retained() {
+        let cache = RequestCache::immutable();
+        let counter = AtomicUsize::new(0);
+
+        assert_eq!(
+            cache.get_or_try_fetch((), || slow_request(&counter)).await,
+            Ok(0)
+        );
+        assert_eq!(
+            cache.get_or_try_fetch((), || slow_request(&counter)).await,
+            Ok(0)
+        );
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        cache.invalidate(&());
+        assert_eq!(
+            cache.get_or_try_fetch((), || slow_request(&counter)).await,
+            Ok(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_retained() {
+        let cache = RequestCache::<(), usize>::immutable();
+
+        let result: Result<usize, &str> = cache.get_or_try_fetch((), || async { Err("bad") }).await;
+        assert_eq!(result, Err("bad"));
+        assert!(cache.cells.lock().is_empty());
+
+        let result: Result<usize, &str> = cache.get_or_try_fetch((), || async { Ok(7) }).await;
+        assert_eq!(result, Ok(7));
+    }
+}
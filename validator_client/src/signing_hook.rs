@@ -0,0 +1,77 @@
+//! Provides `SigningHook`, which allows an external signing backend to take part in each signature
+//! produced by the validator client (e.g., a distributed validator using threshold signatures).
+//!
+//! Each message is first approved by slashing protection and then signed with the local voting
+//! key which, for a distributed validator, is the key share held by this client. The hook is
+//! called immediately before signing with the full signing root and message, and may prevent the
+//! message from being signed. It is then given the local (possibly partial) signature and decides
+//! whether the validator client should:
+//!
+//! - Use a signature, which may be the local signature or a signature aggregated externally.
+//! - Withhold the message, because the partially-signed message is being aggregated and published
+//!   elsewhere.
+//!
+//! Hooks are called from the block and attestation services, so they should return quickly.
+
+use types::{
+    AggregateAndProof, AttestationData, BeaconBlock, Epoch, EthSpec, Hash256, PublicKeyBytes,
+    Signature, Slot,
+};
+
+/// A message which is about to be signed by the validator client.
+pub enum SignableMessage<'a, E: EthSpec> {
+    RandaoReveal(Epoch),
+    BeaconBlock(&'a BeaconBlock<E>),
+    AttestationData(&'a AttestationData),
+    SelectionProof(Slot),
+    AggregateAndProof(&'a AggregateAndProof<E>),
+}
+
+impl<'a, E: EthSpec> SignableMessage<'a, E> {
+    /// A short description of the message, for logging.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SignableMessage::RandaoReveal(_) => "randao_reveal",
+            SignableMessage::BeaconBlock(_) => "beacon_block",
+            SignableMessage::AttestationData(_) => "attestation",
+            SignableMessage::SelectionProof(_) => "selection_proof",
+            SignableMessage::AggregateAndProof(_) => "aggregate_and_proof",
+        }
+    }
+}
+
+/// The result of passing a locally-produced signature to `SigningHook::post_sign`.
+pub enum SigningOutcome {
+    /// Use this signature for the message.
+    Signature(Signature),
+    /// The partially-signed message has been passed to an external aggregator, so the validator
+    /// client should not publish it.
+    Withheld,
+}
+
+/// See the module-level documentation.
+pub trait SigningHook<E: EthSpec>: Send + Sync {
+    /// Called after slashing protection has approved `message`, but before it is signed. Returning
+    /// an error prevents `message` from being signed.
+    ///
+    /// Slashing protection has already recorded `message` as signed when this is called.
+    fn pre_sign(
+        &self,
+        _validator_pubkey: &PublicKeyBytes,
+        _signing_root: Hash256,
+        _message: &SignableMessage<E>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Called with the signature of `signing_root` produced by the local voting key.
+    fn post_sign(
+        &self,
+        _validator_pubkey: &PublicKeyBytes,
+        _signing_root: Hash256,
+        _message: &SignableMessage<E>,
+        signature: Signature,
+    ) -> Result<SigningOutcome, String> {
+        Ok(SigningOutcome::Signature(signature))
+    }
+}
@@ -0,0 +1,136 @@
+//! Provides the offsets into each slot at which the validator client performs its duties.
+//!
+//! By default these are derived from the slot duration by the `SlotClock` (e.g., attestations are
+//! produced 1/3rd of the way through the slot), however each may be overridden by the user.
+
+use serde_derive::{Deserialize, Serialize};
+use slot_clock::SlotClock;
+use std::time::Duration;
+
+/// User-supplied overrides for the intra-slot timings, measured from the start of the slot.
+///
+/// A `None` value indicates that the default from the `SlotClock` should be used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlotOffsets {
+    /// When to produce and publish unaggregated attestations.
+    pub attestation: Option<Duration>,
+    /// When to produce and publish aggregated attestations.
+    pub aggregation: Option<Duration>,
+    /// When to poll the beacon node for attester duties. Proposer duties are always polled at the
+    /// start of the slot, so that block production is not delayed.
+    pub duties: Option<Duration>,
+}
+
+impl SlotOffsets {
+    /// Resolve `self` against the defaults provided by `slot_clock`, ensuring that the resulting
+    /// timings occur in a sensible order within the slot.
+    pub fn resolve<T: SlotClock>(&self, slot_clock: &T) -> Result<SlotTimings, String> {
+        let slot_duration = slot_clock.slot_duration();
+
+        let timings = SlotTimings {
+            attestation: self
+                .attestation
+                .unwrap_or_else(|| slot_clock.unagg_attestation_production_delay()),
+            aggregation: self
+                .aggregation
+                .unwrap_or_else(|| slot_clock.agg_attestation_production_delay()),
+            duties: self.duties.unwrap_or_else(|| Duration::from_secs(0)),
+            slot_duration,
+        };
+
+        if timings.attestation >= timings.aggregation {
+            return Err(format!(
+                "The attestation offset ({}ms) must be less than the aggregation offset ({}ms)",
+                timings.attestation.as_millis(),
+                timings.aggregation.as_millis()
+            ));
+        }
+
+        for (name, offset) in &[
+            ("aggregation", timings.aggregation),
+            ("duties", timings.duties),
+        ] {
+            if *offset >= slot_duration {
+                return Err(format!(
+                    "The {} offset ({}ms) must be less than the slot duration ({}ms)",
+                    name,
+                    offset.as_millis(),
+                    slot_duration.as_millis()
+                ));
+            }
+        }
+
+        Ok(timings)
+    }
+}
+
+/// The resolved offsets into each slot at which duties are performed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotTimings {
+    pub attestation: Duration,
+    pub aggregation: Duration,
+    pub duties: Duration,
+    pub slot_duration: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::TestingSlotClock;
+    use types::Slot;
+
+    fn slot_clock() -> TestingSlotClock {
+        TestingSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        )
+    }
+
+    #[test]
+    fn defaults_derived_from_slot_duration() {
+        let timings = SlotOffsets::default().resolve(&slot_clock()).unwrap();
+
+        assert_eq!(timings.attestation, Duration::from_secs(4));
+        assert_eq!(timings.aggregation, Duration::from_secs(8));
+        assert_eq!(timings.duties, Duration::from_secs(0));
+        assert_eq!(timings.slot_duration, Duration::from_secs(12));
+    }
+
+    #[test]
+    fn overrides_are_applied() {
+        let offsets = SlotOffsets {
+            attestation: Some(Duration::from_millis(3_500)),
+            aggregation: None,
+            duties: Some(Duration::from_millis(500)),
+        };
+        let timings = offsets.resolve(&slot_clock()).unwrap();
+
+        assert_eq!(timings.attestation, Duration::from_millis(3_500));
+        assert_eq!(timings.aggregation, Duration::from_secs(8));
+        assert_eq!(timings.duties, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn invalid_offsets_are_rejected() {
+        let attestation_after_aggregation = SlotOffsets {
+            attestation: Some(Duration::from_secs(9)),
+            ..SlotOffsets::default()
+        };
+        assert!(attestation_after_aggregation
+            .resolve(&slot_clock())
+            .is_err());
+
+        let aggregation_after_slot = SlotOffsets {
+            aggregation: Some(Duration::from_secs(12)),
+            ..SlotOffsets::default()
+        };
+        assert!(aggregation_after_slot.resolve(&slot_clock()).is_err());
+
+        let duties_after_slot = SlotOffsets {
+            duties: Some(Duration::from_secs(13)),
+            ..SlotOffsets::default()
+        };
+        assert!(duties_after_slot.resolve(&slot_clock()).is_err());
+    }
+}
@@ -1,26 +1,25 @@
 use crate::{
-    fork_service::ForkService, http_metrics::metrics, initialized_validators::InitializedValidators,
+    failover_lease::FailoverLease,
+    fork_service::ForkService,
+    http_metrics::metrics,
+    initialized_validators::InitializedValidators,
+    signing_hook::{SignableMessage, SigningHook, SigningOutcome},
 };
 use account_utils::{validator_definitions::ValidatorDefinition, ZeroizeString};
 use parking_lot::{Mutex, RwLock};
-use slashing_protection::{NotSafe, Safe, SlashingDatabase};
-use slog::{crit, error, info, warn, Logger};
+use slashing_protection::{NotSafe, Safe, SlashingProtection};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
 use types::{
-    graffiti::GraffitiString, Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork,
-    Graffiti, Hash256, Keypair, PublicKeyBytes, SelectionProof, Signature, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedRoot, Slot,
+    graffiti::GraffitiString, AggregateAndProof, Attestation, BeaconBlock, ChainSpec, Domain,
+    Epoch, EthSpec, Fork, Graffiti, Hash256, Keypair, PublicKeyBytes, SelectionProof, Signature,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
 };
 use validator_dir::ValidatorDir;
 
-/// Number of epochs of slashing protection history to keep.
-///
-/// This acts as a maximum safe-guard against clock drift.
-const SLASHING_PROTECTION_HISTORY_EPOCHS: u64 = 512;
-
 struct LocalValidator {
     validator_dir: ValidatorDir,
     voting_keypair: Keypair,
@@ -49,36 +48,52 @@ impl PartialEq for LocalValidator {
 #[derive(Clone)]
 pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
-    slashing_protection: SlashingDatabase,
+    slashing_protection: SlashingProtection,
     slashing_protection_last_prune: Arc<Mutex<Epoch>>,
+    /// The number of epochs of slashing protection history to keep, or `None` to keep it all.
+    slashing_protection_history_epochs: Option<u64>,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
     temp_dir: Option<Arc<TempDir>>,
     fork_service: ForkService<T, E>,
+    failover_lease: Option<FailoverLease>,
+    signing_hook: Arc<RwLock<Option<Arc<dyn SigningHook<E>>>>>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         validators: InitializedValidators,
-        slashing_protection: SlashingDatabase,
+        slashing_protection: SlashingProtection,
         genesis_validators_root: Hash256,
         spec: ChainSpec,
         fork_service: ForkService<T, E>,
+        failover_lease: Option<FailoverLease>,
+        slashing_protection_history_epochs: Option<u64>,
         log: Logger,
     ) -> Self {
         Self {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
             slashing_protection_last_prune: Arc::new(Mutex::new(Epoch::new(0))),
+            slashing_protection_history_epochs,
             genesis_validators_root,
             spec: Arc::new(spec),
             log,
             temp_dir: None,
             fork_service,
+            failover_lease,
+            signing_hook: <_>::default(),
         }
     }
 
+    /// Sets the hook which is called whenever a message is signed. This applies to all clones of
+    /// `self`.
+    pub fn set_signing_hook(&self, signing_hook: Arc<dyn SigningHook<E>>) {
+        *self.signing_hook.write() = Some(signing_hook);
+    }
+
     pub fn initialized_validators(&self) -> Arc<RwLock<InitializedValidators>> {
         self.validators.clone()
     }
@@ -136,37 +151,111 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.fork_service.fork()
     }
 
-    pub fn randao_reveal(
+    /// Signs `signing_root` with the voting key of `validator_pubkey`, passing `message` through
+    /// the signing hook (if any).
+    ///
+    /// Returns `None` if the validator is unknown or if the signing hook prevented or withheld the
+    /// signature.
+    fn sign_message(
         &self,
         validator_pubkey: &PublicKeyBytes,
-        epoch: Epoch,
+        signing_root: Hash256,
+        message: SignableMessage<E>,
     ) -> Option<Signature> {
-        self.validators
+        let signing_hook = self.signing_hook.read().clone();
+
+        if let Some(signing_hook) = &signing_hook {
+            if let Err(e) = signing_hook.pre_sign(validator_pubkey, signing_root, &message) {
+                warn!(
+                    self.log,
+                    "Signing hook prevented signing";
+                    "error" => e,
+                    "message" => message.kind(),
+                    "pubkey" => ?validator_pubkey,
+                );
+                return None;
+            }
+        }
+
+        let signature = self
+            .validators
             .read()
-            .voting_keypair(validator_pubkey)
-            .map(|voting_keypair| {
-                let domain = self.spec.get_domain(
-                    epoch,
-                    Domain::Randao,
-                    &self.fork(),
-                    self.genesis_validators_root,
+            .voting_keypair(validator_pubkey)?
+            .sk
+            .sign(signing_root);
+
+        let signing_hook = match signing_hook {
+            Some(signing_hook) => signing_hook,
+            None => return Some(signature),
+        };
+
+        match signing_hook.post_sign(validator_pubkey, signing_root, &message, signature) {
+            Ok(SigningOutcome::Signature(signature)) => Some(signature),
+            Ok(SigningOutcome::Withheld) => {
+                debug!(
+                    self.log,
+                    "Signing hook withheld signature";
+                    "message" => message.kind(),
+                    "pubkey" => ?validator_pubkey,
                 );
-                let message = epoch.signing_root(domain);
+                None
+            }
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Signing hook failed";
+                    "error" => e,
+                    "message" => message.kind(),
+                    "pubkey" => ?validator_pubkey,
+                );
+                None
+            }
+        }
+    }
 
-                voting_keypair.sk.sign(message)
-            })
+    pub fn randao_reveal(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        epoch: Epoch,
+    ) -> Option<Signature> {
+        let domain = self.spec.get_domain(
+            epoch,
+            Domain::Randao,
+            &self.fork(),
+            self.genesis_validators_root,
+        );
+        let signing_root = epoch.signing_root(domain);
+
+        self.sign_message(
+            validator_pubkey,
+            signing_root,
+            SignableMessage::RandaoReveal(epoch),
+        )
     }
 
     pub fn graffiti(&self, validator_pubkey: &PublicKeyBytes) -> Option<Graffiti> {
         self.validators.read().graffiti(validator_pubkey)
     }
 
+    /// Returns `false` if an active/passive failover lease is configured but not currently held by
+    /// this validator client, in which case no blocks, attestations or aggregates will be signed.
+    pub fn signing_enabled(&self) -> bool {
+        self.failover_lease
+            .as_ref()
+            .map_or(true, FailoverLease::is_active)
+    }
+
     pub fn sign_block(
         &self,
         validator_pubkey: &PublicKeyBytes,
         block: BeaconBlock<E>,
         current_slot: Slot,
     ) -> Option<SignedBeaconBlock<E>> {
+        if !self.signing_enabled() {
+            debug!(self.log, "Not signing block without failover lease"; "slot" => block.slot);
+            return None;
+        }
+
         // Make sure the block slot is not higher than the current slot to avoid potential attacks.
         if block.slot > current_slot {
             warn!(
@@ -196,17 +285,19 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         match slashing_status {
             // We can safely sign this block.
             Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let voting_keypair = validators.voting_keypair(validator_pubkey)?;
+                let signing_root = block.signing_root(domain);
+                let signature = self.sign_message(
+                    validator_pubkey,
+                    signing_root,
+                    SignableMessage::BeaconBlock(&block),
+                )?;
 
                 metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::SUCCESS]);
 
-                Some(block.sign(
-                    &voting_keypair.sk,
-                    &fork,
-                    self.genesis_validators_root,
-                    &self.spec,
-                ))
+                Some(SignedBeaconBlock {
+                    message: block,
+                    signature,
+                })
             }
             Ok(Safe::SameData) => {
                 warn!(
@@ -245,6 +336,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         attestation: &mut Attestation<E>,
         current_epoch: Epoch,
     ) -> Option<()> {
+        if !self.signing_enabled() {
+            debug!(
+                self.log,
+                "Not signing attestation without failover lease";
+                "slot" => attestation.data.slot,
+            );
+            return None;
+        }
+
         // Make sure the target epoch is not higher than the current epoch to avoid potential attacks.
         if attestation.data.target.epoch > current_epoch {
             return None;
@@ -268,17 +368,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         match slashing_status {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let voting_keypair = validators.voting_keypair(validator_pubkey)?;
+                let signing_root = attestation.data.signing_root(domain);
+                let signature = self.sign_message(
+                    validator_pubkey,
+                    signing_root,
+                    SignableMessage::AttestationData(&attestation.data),
+                )?;
 
                 attestation
-                    .sign(
-                        &voting_keypair.sk,
-                        validator_committee_position,
-                        &fork,
-                        self.genesis_validators_root,
-                        &self.spec,
-                    )
+                    .add_signature(&signature, validator_committee_position)
                     .map_err(|e| {
                         error!(
                             self.log,
@@ -343,20 +441,36 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         aggregate: Attestation<E>,
         selection_proof: SelectionProof,
     ) -> Option<SignedAggregateAndProof<E>> {
-        let validators = self.validators.read();
-        let voting_keypair = &validators.voting_keypair(validator_pubkey)?;
-
-        metrics::inc_counter_vec(&metrics::SIGNED_AGGREGATES_TOTAL, &[metrics::SUCCESS]);
+        if !self.signing_enabled() {
+            debug!(
+                self.log,
+                "Not signing aggregate without failover lease";
+                "slot" => aggregate.data.slot,
+            );
+            return None;
+        }
 
-        Some(SignedAggregateAndProof::from_aggregate(
-            validator_index,
+        let message = AggregateAndProof {
+            aggregator_index: validator_index,
             aggregate,
-            Some(selection_proof),
-            &voting_keypair.sk,
+            selection_proof: selection_proof.into(),
+        };
+        let domain = self.spec.get_domain(
+            message.aggregate.data.slot.epoch(E::slots_per_epoch()),
+            Domain::AggregateAndProof,
             &self.fork(),
             self.genesis_validators_root,
-            &self.spec,
-        ))
+        );
+        let signing_root = message.signing_root(domain);
+        let signature = self.sign_message(
+            validator_pubkey,
+            signing_root,
+            SignableMessage::AggregateAndProof(&message),
+        )?;
+
+        metrics::inc_counter_vec(&metrics::SIGNED_AGGREGATES_TOTAL, &[metrics::SUCCESS]);
+
+        Some(SignedAggregateAndProof { message, signature })
     }
 
     /// Produces a `SelectionProof` for the `slot`, signed by with corresponding secret key to
@@ -366,32 +480,49 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: &PublicKeyBytes,
         slot: Slot,
     ) -> Option<SelectionProof> {
-        let validators = self.validators.read();
-        let voting_keypair = &validators.voting_keypair(validator_pubkey)?;
+        let domain = self.spec.get_domain(
+            slot.epoch(E::slots_per_epoch()),
+            Domain::SelectionProof,
+            &self.fork(),
+            self.genesis_validators_root,
+        );
+        let signing_root = slot.signing_root(domain);
+        let signature = self.sign_message(
+            validator_pubkey,
+            signing_root,
+            SignableMessage::SelectionProof(slot),
+        )?;
 
         metrics::inc_counter_vec(&metrics::SIGNED_SELECTION_PROOFS_TOTAL, &[metrics::SUCCESS]);
 
-        Some(SelectionProof::new::<E>(
-            slot,
-            &voting_keypair.sk,
-            &self.fork(),
-            self.genesis_validators_root,
-            &self.spec,
-        ))
+        Some(SelectionProof::from(signature))
     }
 
-    /// Prune the slashing protection database so that it remains performant.
+    /// Returns `true` if old records are pruned from the slashing protection database.
+    pub fn slashing_protection_pruning_enabled(&self) -> bool {
+        self.slashing_protection_history_epochs.is_some()
+    }
+
+    /// Prune the slashing protection database so that it remains performant, keeping the
+    /// configured number of epochs of history prior to `finalized_epoch`.
+    ///
+    /// Messages from prior to the finalized epoch can never be included on chain, so the margin
+    /// is based on finalization rather than the wall clock, which may drift.
     ///
     /// This function will only do actual pruning periodically, so it should usually be
     /// cheap to call. The `first_run` flag can be used to print a more verbose message when pruning
     /// runs.
-    pub fn prune_slashing_protection_db(&self, current_epoch: Epoch, first_run: bool) {
-        // Attempt to prune every SLASHING_PROTECTION_HISTORY_EPOCHs, with a tolerance for
-        // missing the epoch that aligns exactly.
+    pub fn prune_slashing_protection_db(&self, finalized_epoch: Epoch, first_run: bool) {
+        let history_epochs = if let Some(history_epochs) = self.slashing_protection_history_epochs {
+            history_epochs
+        } else {
+            return;
+        };
+
+        // Attempt to prune every `history_epochs`, with a tolerance for missing the epoch that
+        // aligns exactly.
         let mut last_prune = self.slashing_protection_last_prune.lock();
-        if current_epoch / SLASHING_PROTECTION_HISTORY_EPOCHS
-            <= *last_prune / SLASHING_PROTECTION_HISTORY_EPOCHS
-        {
+        if finalized_epoch / history_epochs <= *last_prune / history_epochs {
             return;
         }
 
@@ -399,16 +530,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             info!(
                 self.log,
                 "Pruning slashing protection DB";
-                "epoch" => current_epoch,
+                "finalized_epoch" => finalized_epoch,
                 "msg" => "pruning may take several minutes the first time it runs"
             );
         } else {
-            info!(self.log, "Pruning slashing protection DB"; "epoch" => current_epoch);
+            info!(self.log, "Pruning slashing protection DB"; "finalized_epoch" => finalized_epoch);
         }
 
         let _timer = metrics::start_timer(&metrics::SLASHING_PROTECTION_PRUNE_TIMES);
 
-        let new_min_target_epoch = current_epoch.saturating_sub(SLASHING_PROTECTION_HISTORY_EPOCHS);
+        let new_min_target_epoch = finalized_epoch.saturating_sub(history_epochs);
         let new_min_slot = new_min_target_epoch.start_slot(E::slots_per_epoch());
 
         let validators = self.validators.read();
@@ -436,8 +567,180 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return;
         }
 
-        *last_prune = current_epoch;
+        *last_prune = finalized_epoch;
 
         info!(self.log, "Completed pruning of slashing protection DB");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fork_service::ForkServiceBuilder;
+    use account_utils::validator_definitions::ValidatorDefinitions;
+    use environment::null_logger;
+    use eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf},
+        KeystoreBuilder, DKLEN,
+    };
+    use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+    use slot_clock::TestingSlotClock;
+    use std::fs::File;
+    use tempfile::tempdir;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    const PASSWORD: &str = "password";
+
+    /// A hook which records the messages it is given, and which may veto or withhold them.
+    #[derive(Default)]
+    struct TestHook {
+        veto: bool,
+        withhold: bool,
+        pre_signed: Mutex<Vec<&'static str>>,
+        post_signed: Mutex<Vec<&'static str>>,
+    }
+
+    impl SigningHook<E> for TestHook {
+        fn pre_sign(
+            &self,
+            _validator_pubkey: &PublicKeyBytes,
+            _signing_root: Hash256,
+            message: &SignableMessage<E>,
+        ) -> Result<(), String> {
+            self.pre_signed.lock().push(message.kind());
+            if self.veto {
+                Err("vetoed".into())
+            } else {
+                Ok(())
+            }
+        }
+
+        fn post_sign(
+            &self,
+            _validator_pubkey: &PublicKeyBytes,
+            _signing_root: Hash256,
+            message: &SignableMessage<E>,
+            signature: Signature,
+        ) -> Result<SigningOutcome, String> {
+            self.post_signed.lock().push(message.kind());
+            if self.withhold {
+                Ok(SigningOutcome::Withheld)
+            } else {
+                Ok(SigningOutcome::Signature(signature))
+            }
+        }
+    }
+
+    /// Returns a store with a single validator, using `dir` for the validator directory.
+    async fn store_with_validator(
+        dir: &TempDir,
+    ) -> (ValidatorStore<TestingSlotClock, E>, PublicKeyBytes) {
+        let log = null_logger().unwrap();
+        let spec = E::default_spec();
+
+        let validator_defs = ValidatorDefinitions::open_or_create(dir.path()).unwrap();
+        let initialized_validators =
+            InitializedValidators::from_definitions(validator_defs, dir.path().into(), log.clone())
+                .await
+                .unwrap();
+        let slashing_protection =
+            SlashingDatabase::open_or_create(&dir.path().join(SLASHING_PROTECTION_FILENAME))
+                .unwrap();
+        let fork_service = ForkServiceBuilder::testing_only(spec.clone(), log.clone())
+            .build()
+            .unwrap();
+
+        let store = ValidatorStore::new(
+            initialized_validators,
+            slashing_protection.into(),
+            Hash256::repeat_byte(42),
+            spec,
+            fork_service,
+            None,
+            None,
+            log,
+        );
+
+        // Use a cheap KDF so that the keystore decrypts quickly.
+        let keypair = Keypair::random();
+        let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+            .unwrap()
+            .kdf(Kdf::Pbkdf2(Pbkdf2 {
+                dklen: DKLEN,
+                c: 2,
+                prf: Prf::HmacSha256,
+                salt: vec![42; 32].into(),
+            }))
+            .build()
+            .unwrap();
+        let keystore_path = dir.path().join("voting-keystore.json");
+        keystore
+            .to_json_writer(File::create(&keystore_path).unwrap())
+            .unwrap();
+
+        store
+            .add_validator_keystore(keystore_path, PASSWORD.to_string().into(), true, None)
+            .await
+            .unwrap();
+
+        (store, keypair.pk.compress())
+    }
+
+    #[tokio::test]
+    async fn signing_hook_passes_signature() {
+        let dir = tempdir().unwrap();
+        let (store, pubkey) = store_with_validator(&dir).await;
+        let hook = Arc::new(TestHook::default());
+        store.set_signing_hook(hook.clone());
+
+        assert!(store.randao_reveal(&pubkey, Epoch::new(1)).is_some());
+        assert_eq!(*hook.pre_signed.lock(), vec!["randao_reveal"]);
+        assert_eq!(*hook.post_signed.lock(), vec!["randao_reveal"]);
+    }
+
+    #[tokio::test]
+    async fn signing_hook_vetoes_signature() {
+        let dir = tempdir().unwrap();
+        let (store, pubkey) = store_with_validator(&dir).await;
+        let hook = Arc::new(TestHook {
+            veto: true,
+            ..TestHook::default()
+        });
+        store.set_signing_hook(hook.clone());
+
+        assert!(store.randao_reveal(&pubkey, Epoch::new(1)).is_none());
+        assert!(store
+            .produce_selection_proof(&pubkey, Slot::new(8))
+            .is_none());
+        assert_eq!(
+            *hook.pre_signed.lock(),
+            vec!["randao_reveal", "selection_proof"]
+        );
+        assert!(
+            hook.post_signed.lock().is_empty(),
+            "a vetoed message should not be signed"
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_hook_withholds_signature() {
+        let dir = tempdir().unwrap();
+        let (store, pubkey) = store_with_validator(&dir).await;
+        let hook = Arc::new(TestHook {
+            withhold: true,
+            ..TestHook::default()
+        });
+        store.set_signing_hook(hook.clone());
+
+        assert!(store.randao_reveal(&pubkey, Epoch::new(1)).is_none());
+        assert!(store
+            .produce_selection_proof(&pubkey, Slot::new(8))
+            .is_none());
+        assert_eq!(
+            *hook.post_signed.lock(),
+            vec!["randao_reveal", "selection_proof"]
+        );
+    }
+}